@@ -1,9 +1,10 @@
-use ncm_api_rs::Query;
-
-use crate::api::{Album, AlbumDetail, Artist, Song, client::client};
+use crate::api::{
+    Album, AlbumDetail, Artist, Song,
+    client::{client, query},
+};
 
 pub async fn get_album_detail(id: u64) -> anyhow::Result<AlbumDetail> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
 
     match client().album(&query).await {
         Ok(resp) => {
@@ -53,7 +54,7 @@ pub async fn get_album_detail(id: u64) -> anyhow::Result<AlbumDetail> {
 }
 
 pub async fn album_subscribe(id: u64, subscribe: bool) -> anyhow::Result<()> {
-    let query = Query::new()
+    let query = query()
         .param("id", &id.to_string())
         .param("t", if subscribe { "1" } else { "0" });
 
@@ -1,9 +1,11 @@
 //! AMLL (Apple Music style) TTML lyric source for Netease song IDs.
 
+use crate::api::client::service_url;
+
 const BASE_URL: &str = "https://amlldb.bikonoo.com/ncm-lyrics";
 
 pub async fn fetch_amll_ttml(id: u64) -> anyhow::Result<Option<String>> {
-    let url = format!("{BASE_URL}/{id}.ttml");
+    let url = format!("{}/{id}.ttml", service_url(BASE_URL));
     log::debug!("[lyrics][amll] requesting song_id={id}");
     let response = reqwest::get(url).await?;
     let status = response.status();
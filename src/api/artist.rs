@@ -1,9 +1,11 @@
-use ncm_api_rs::Query;
-
-use crate::api::{Album, Artist, ArtistDetail, Mv, Song, client::client};
+use crate::api::{
+    Album, Artist, ArtistDetail, ArtistSongOrder, Mv, Song,
+    client::{client, query},
+    parse::parse_song,
+};
 
 pub async fn get_artist_detail(id: u64) -> anyhow::Result<ArtistDetail> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
     match client().artist_detail(&query).await {
         Ok(resp) => {
             let body = &resp.body;
@@ -115,36 +117,13 @@ pub async fn get_artist_detail(id: u64) -> anyhow::Result<ArtistDetail> {
 }
 
 pub async fn get_artist_song(id: u64) -> anyhow::Result<Vec<Song>> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
     match client().artists(&query).await {
         Ok(resp) => {
             let hot_songs = resp.body["hotSongs"].as_array().unwrap();
 
             // 获取 data 对象
-            let data = hot_songs
-                .iter()
-                .map(|s| Song {
-                    id: s["id"].as_u64().unwrap(),
-                    name: s["name"].as_str().unwrap().to_string(),
-                    cover_url: s["al"]["picUrl"].as_str().unwrap().to_string(),
-                    artists: s["ar"]
-                        .as_array()
-                        .unwrap()
-                        .iter()
-                        .map(|a| Artist {
-                            id: a["id"].as_u64().unwrap(),
-                            name: a["name"].as_str().unwrap().to_string(),
-                            avatar: None,
-                        })
-                        .collect(),
-                    album: Album {
-                        id: s["al"]["id"].as_u64().unwrap(),
-                        name: s["al"]["name"].as_str().unwrap().to_string(),
-                        cover_url: s["al"]["picUrl"].as_str().unwrap().to_string(),
-                    },
-                    duration: s["dt"].as_u64().unwrap(),
-                })
-                .collect();
+            let data = hot_songs.iter().map(parse_song).collect();
 
             Ok(data)
         }
@@ -155,22 +134,66 @@ pub async fn get_artist_song(id: u64) -> anyhow::Result<Vec<Song>> {
     }
 }
 
-pub async fn get_artist_album(id: u64) -> anyhow::Result<Vec<Album>> {
-    let query = Query::new().param("id", &id.to_string());
+/// 歌手全部歌曲（分页，可按热度/时间排序）
+/// 返回 (歌曲列表, 是否还有更多)
+pub async fn get_artist_songs(
+    id: u64,
+    order: ArtistSongOrder,
+    offset: usize,
+    limit: usize,
+) -> anyhow::Result<(Vec<Song>, bool)> {
+    let query = query()
+        .param("id", &id.to_string())
+        .param("order", &order.to_string())
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
+    match client().artist_songs(&query).await {
+        Ok(resp) => {
+            let songs = resp.body["songs"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .map(parse_song)
+                .collect();
+            let more = resp.body["more"].as_bool().unwrap_or_default();
+            Ok((songs, more))
+        }
+        Err(e) => {
+            eprintln!("获取歌手全部歌曲失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// 歌手专辑（分页），返回 (专辑列表, 是否还有更多)
+pub async fn get_artist_album(
+    id: u64,
+    offset: usize,
+    limit: usize,
+) -> anyhow::Result<(Vec<Album>, bool)> {
+    let query = query()
+        .param("id", &id.to_string())
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
     match client().artist_album(&query).await {
         Ok(resp) => {
-            let hot_albums = resp.body["hotAlbums"].as_array().unwrap();
+            let hot_albums = resp.body["hotAlbums"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
 
             let data = hot_albums
                 .iter()
                 .map(|a| Album {
-                    id: a["id"].as_u64().unwrap(),
-                    name: a["name"].as_str().unwrap().to_string(),
-                    cover_url: a["picUrl"].as_str().unwrap().to_string(),
+                    id: a["id"].as_u64().unwrap_or(0),
+                    name: a["name"].as_str().unwrap_or("").to_string(),
+                    cover_url: a["picUrl"].as_str().unwrap_or("").to_string(),
                 })
                 .collect();
+            let more = resp.body["more"].as_bool().unwrap_or_default();
 
-            Ok(data)
+            Ok((data, more))
         }
         Err(e) => {
             eprintln!("获取歌手专辑列表失败: {}", e);
@@ -179,12 +202,20 @@ pub async fn get_artist_album(id: u64) -> anyhow::Result<Vec<Album>> {
     }
 }
 
-pub async fn get_artist_mv(id: u64) -> anyhow::Result<Vec<Mv>> {
-    let query = Query::new().param("id", &id.to_string());
+/// 歌手 MV（分页），返回 (MV 列表, 是否还有更多)
+pub async fn get_artist_mv(
+    id: u64,
+    offset: usize,
+    limit: usize,
+) -> anyhow::Result<(Vec<Mv>, bool)> {
+    let query = query()
+        .param("id", &id.to_string())
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
     match client().artist_mv(&query).await {
         Ok(resp) => {
             // 注意：根据官方文档 /artist/mv 接口，MV 列表的字段名通常是 "mvs"
-            let mvs = resp.body["mvs"].as_array().unwrap();
+            let mvs = resp.body["mvs"].as_array().cloned().unwrap_or_default();
 
             let data = mvs
                 .iter()
@@ -200,8 +231,9 @@ pub async fn get_artist_mv(id: u64) -> anyhow::Result<Vec<Mv>> {
                     play_count: m["playCount"].as_u64().unwrap_or(0),
                 })
                 .collect();
+            let has_more = resp.body["hasMore"].as_bool().unwrap_or_default();
 
-            Ok(data)
+            Ok((data, has_more))
         }
         Err(e) => {
             eprintln!("获取歌手 MV 列表失败: {}", e);
@@ -209,3 +241,43 @@ pub async fn get_artist_mv(id: u64) -> anyhow::Result<Vec<Mv>> {
         }
     }
 }
+
+/// 关注/取消关注歌手
+pub async fn artist_subscribe(id: u64, follow: bool) -> anyhow::Result<()> {
+    let query = query()
+        .param("id", &id.to_string())
+        .param("t", if follow { "1" } else { "0" });
+
+    match client().artist_sub(&query).await {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            eprintln!("关注/取消关注歌手失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// 相似歌手
+pub async fn get_simi_artist(id: u64) -> anyhow::Result<Vec<Artist>> {
+    let query = query().param("id", &id.to_string());
+    match client().simi_artist(&query).await {
+        Ok(resp) => {
+            let artists = resp.body["artists"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .map(|a| Artist {
+                    id: a["id"].as_u64().unwrap_or(0),
+                    name: a["name"].as_str().unwrap_or("").to_string(),
+                    avatar: a["picUrl"].as_str().map(|s| s.to_string()),
+                })
+                .collect();
+            Ok(artists)
+        }
+        Err(e) => {
+            eprintln!("获取相似歌手失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
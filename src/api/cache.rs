@@ -0,0 +1,233 @@
+//! 接口响应缓存（stale-while-revalidate）
+//!
+//! 解析后的结果以 JSON 存在缓存目录的 SQLite 中。[`swr`] 先把缓存交给页面立即渲染，
+//! 超过 TTL 再联网刷新，数据有变化时再推送一次；联网失败但有缓存时不报错，离线也能浏览。
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Serialize, de::DeserializeOwned};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::APP_NAME;
+
+/// 各接口的缓存有效期：过期前直接使用缓存，不联网
+pub mod ttl {
+    use std::time::Duration;
+
+    pub const HOME_BLOCK: Duration = Duration::from_secs(30 * 60);
+    pub const RECOMMEND_PLAYLIST: Duration = Duration::from_secs(60 * 60);
+    pub const TOPLIST: Duration = Duration::from_secs(60 * 60);
+    pub const USER_PLAYLIST: Duration = Duration::from_secs(5 * 60);
+    pub const PLAYLIST_DETAIL: Duration = Duration::from_secs(10 * 60);
+}
+
+/// 缓存键
+pub mod key {
+    pub const HOME_BLOCK: &str = "home_block";
+    pub const HOME_RADAR: &str = "home_radar";
+    pub const RECOMMEND_PLAYLIST: &str = "recommend_playlist";
+    pub const TOPLIST: &str = "toplist";
+    pub const USER_PLAYLIST_PREFIX: &str = "user_playlist:";
+    pub const PLAYLIST_DETAIL_PREFIX: &str = "playlist_detail:";
+
+    pub fn user_playlist(uid: u64) -> String {
+        format!("{USER_PLAYLIST_PREFIX}{uid}")
+    }
+
+    pub fn playlist_detail(id: u64) -> String {
+        format!("{PLAYLIST_DETAIL_PREFIX}{id}")
+    }
+}
+
+fn store() -> Option<&'static Mutex<Connection>> {
+    static STORE: OnceLock<Option<Mutex<Connection>>> = OnceLock::new();
+    STORE
+        .get_or_init(|| match open(cache_path()) {
+            Ok(conn) => Some(Mutex::new(conn)),
+            Err(e) => {
+                log::error!("接口缓存打开失败，将始终联网获取: {e}");
+                None
+            }
+        })
+        .as_ref()
+}
+
+fn cache_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_NAME)
+        .join("api_cache.db")
+}
+
+fn open(path: PathBuf) -> anyhow::Result<Connection> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    init(Connection::open(path)?)
+}
+
+fn init(conn: Connection) -> anyhow::Result<Connection> {
+    conn.execute_batch(
+        "PRAGMA journal_mode=WAL;
+         PRAGMA synchronous=NORMAL;
+         CREATE TABLE IF NOT EXISTS responses (
+            key TEXT PRIMARY KEY,
+            body TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL
+         );",
+    )?;
+    Ok(conn)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// 读取缓存：(JSON, 距上次获取的时长)
+fn load(conn: &Connection, key: &str) -> Option<(String, Duration)> {
+    let row = conn
+        .query_row(
+            "SELECT body, fetched_at FROM responses WHERE key = ?1",
+            params![key],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+        )
+        .optional();
+    match row {
+        Ok(row) => row.map(|(body, fetched_at)| {
+            let age = (unix_now() - fetched_at).max(0) as u64;
+            (body, Duration::from_secs(age))
+        }),
+        Err(e) => {
+            log::error!("读取接口缓存失败 {key}: {e}");
+            None
+        }
+    }
+}
+
+fn save(conn: &Connection, key: &str, body: &str) {
+    if let Err(e) = conn.execute(
+        "INSERT INTO responses (key, body, fetched_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(key) DO UPDATE SET body = ?2, fetched_at = ?3",
+        params![key, body, unix_now()],
+    ) {
+        log::error!("写入接口缓存失败 {key}: {e}");
+    }
+}
+
+/// 先推送缓存，过期再联网刷新，结果有变化时再推送一次。
+///
+/// `emit` 可能被调用 0 ~ 2 次；只有既没有缓存、联网又失败时才返回错误。
+pub async fn swr<T, F, Fut>(
+    key: &str,
+    ttl: Duration,
+    fetch: F,
+    mut emit: impl FnMut(T),
+) -> anyhow::Result<()>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let cached = store().and_then(|conn| load(&conn.lock().unwrap(), key));
+    let mut cached_body = None;
+    if let Some((body, age)) = cached {
+        match serde_json::from_str::<T>(&body) {
+            Ok(data) => {
+                emit(data);
+                if age < ttl {
+                    return Ok(());
+                }
+                cached_body = Some(body);
+            }
+            // 结构升级后旧缓存无法解析，当作没有缓存
+            Err(e) => log::warn!("接口缓存 {key} 无法解析，已忽略: {e}"),
+        }
+    }
+
+    let data = match fetch().await {
+        Ok(data) => data,
+        Err(e) if cached_body.is_some() => {
+            log::warn!("刷新 {key} 失败，继续使用缓存: {e}");
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    };
+
+    let body = match serde_json::to_string(&data) {
+        Ok(body) => body,
+        Err(e) => {
+            log::error!("序列化接口缓存 {key} 失败: {e}");
+            emit(data);
+            return Ok(());
+        }
+    };
+    if let Some(conn) = store() {
+        save(&conn.lock().unwrap(), key, &body);
+    }
+    if cached_body.as_deref() != Some(body.as_str()) {
+        emit(data);
+    }
+    Ok(())
+}
+
+/// 丢弃某个接口的缓存，用于本地修改了数据（如歌单增删歌曲）之后
+pub fn invalidate(key: &str) {
+    if let Some(conn) = store() {
+        if let Err(e) = conn
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM responses WHERE key = ?1", params![key])
+        {
+            log::error!("删除接口缓存失败 {key}: {e}");
+        }
+    }
+}
+
+/// 丢弃以 prefix 开头的所有缓存
+pub fn invalidate_prefix(prefix: &str) {
+    if let Some(conn) = store() {
+        if let Err(e) = conn.lock().unwrap().execute(
+            "DELETE FROM responses WHERE substr(key, 1, length(?1)) = ?1",
+            params![prefix],
+        ) {
+            log::error!("删除接口缓存失败 {prefix}: {e}");
+        }
+    }
+}
+
+/// 清空全部接口缓存，切换账号时使用
+pub fn clear() {
+    if let Some(conn) = store() {
+        if let Err(e) = conn.lock().unwrap().execute("DELETE FROM responses", []) {
+            log::error!("清空接口缓存失败: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_responses_round_trip_with_age() {
+        let conn = init(Connection::open_in_memory().unwrap()).unwrap();
+        assert!(load(&conn, "toplist").is_none());
+
+        save(&conn, "toplist", "[1,2,3]");
+        let (body, age) = load(&conn, "toplist").unwrap();
+        assert_eq!(body, "[1,2,3]");
+        assert!(age < ttl::TOPLIST);
+
+        conn.execute(
+            "UPDATE responses SET fetched_at = fetched_at - 7200 WHERE key = 'toplist'",
+            [],
+        )
+        .unwrap();
+        let (_, age) = load(&conn, "toplist").unwrap();
+        assert!(age >= ttl::TOPLIST);
+    }
+}
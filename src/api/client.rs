@@ -1,19 +1,23 @@
-use ncm_api_rs::{ApiClient, create_client};
+use ncm_api_rs::{ApiClient, Query, create_client};
 use once_cell::sync::Lazy;
 use std::sync::RwLock;
 
-use crate::api::{
-    ApiClientExt, SoundQuality, album_subscribe, get_album_detail, get_artist_album,
-    get_artist_detail, get_artist_mv, get_artist_song, get_home_block,
-    get_home_category_daily_song_list, get_lryic, get_playlist_detail, get_recommend_playlist,
-    get_recommend_song, get_song_comments, get_song_detail, get_song_url, get_user_detail,
-    get_user_info, get_user_playlist, get_user_playlist_collected, get_user_playlist_created,
-    get_user_sub_album, get_user_subcount, is_like_song, like_song, playlist_create,
-    playlist_delete, playlist_subscribe, playlist_track_add, playlist_track_del,
-};
+use crate::api::ApiClientExt;
+
+/// 设置后所有接口（网易云 / QQ 音乐 / AMLL）都改发到这个地址，用于本地 mock 服务器或反向代理
+pub const BASE_URL_ENV: &str = "LINN_API_BASE_URL";
 
 static CLIENT: Lazy<RwLock<Option<ApiClient>>> = Lazy::new(|| RwLock::new(None));
 
+static BASE_URL: Lazy<RwLock<Option<String>>> = Lazy::new(|| {
+    RwLock::new(
+        std::env::var(BASE_URL_ENV)
+            .ok()
+            .map(|url| url.trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty()),
+    )
+});
+
 pub fn init_client(cookie: String) {
     let client = create_client(Some(cookie));
 
@@ -34,382 +38,45 @@ pub fn client_ext() -> impl ApiClientExt {
     client()
 }
 
-#[tokio::test]
-async fn test_init_client() {
-    init_client("MUSIC_A_T=1628302039878; MUSIC_R_T=1628302040015; MUSIC_R_U=00236B77FCA4628CDCF272DA9C15003D5625364D3CF83134A651ECA863E4E450AB1F9FE6AD2D3FAE60EC080DA0E16D8F5AFB8A871D7F8D775B2F64C3C883E111C03C8821B4449EFA1D677C5EE50978A86B; NMTID=00OJQaCFkk3ieiCe0XTuG1gnl2rEPAAAAGdbBUBAw; __csrf=a1a3d17aaafeeb01ea60cf5871667fba; MUSIC_U=005D16BA9075E5D9A048A0F7962D4C91FA1AEB0F61D1E69F3B7734E57AB813091A11E5A04165D52A6419E40048509923D5460F18BEC14FBC83A56E77BD27DD892328AD08D8C12C824EDF0F154EDA47FF32A41C75257DAFBA7C7A22BC4C4482C94B17828718E6822BFCC4DA07043E3F71C640F4D2F41DFF9B3A67DA615CD38A1E7BC135089E8EE5E73438EA3FAC2AB441092900C3F5ECD6CB8A09B0456597D32B228064168E04FF074199C19A9EC7164CAEA611C99C611E5043EE29A744FD8B4D0D6BBF41045385C1744FFF5FA06C10169DD11B83FE5E4AA6F87284B6EEF2C915DB7856DA8CE1FEC337A6EC6662A2195F9B328B04B592587866C2B4F86BB89022F0DAEABE29DD8FEF0C61D92BE4D12CA312FE3833C9B8D053E326BA5B44EA8BAFA65BA396D7BFA87FF174733D3F44D8A40A3AA931E258985D6552C8CF0F97F30BA1E91BCB9F94AF5FFF958F608F3BB4A762450222DD094C55262805A809B9430734DD501D646E7561F43F5E69C812F85231; Max-Age=2147483647; Expires=Mon, 26 Apr 2094 11:07:36 GMT; Path=/wapi/clientlog".to_string());
-
-    // test_playlist_detail().await;
-    // test_recommend_playlist().await;
-    // test_song_url().await;
-    // test_song_detail().await;
-
-    // test_album_detail().await;
-    // test_recommend_songs().await;
-    // test_lyric().await;
-    // test_user_info().await;
-    // test_user_subcount().await;
-    // test_user_playlist().await;
-    // test_user_sub_album().await;
-    // test_user_detail().await;
-    // test_artist_detail().await;
-    // test_artist_song().await;
-    // test_artist_album().await;
-    // test_artist_mv().await;
-
-    // test_playlist_create_and_delete().await;
-    // test_playlist_subscribe().await;
-    // test_like_song().await;
-    // test_collect_song().await;
-    // test_album_sub().await;
-    // test_music_comment().await;
-    // test_home_block().await;
-    // test_home_category().await;
-    // test_lyric().await;
-
-    // search API
-    // test_suggest_structure().await;
-    // test_suggest_typed().await;
-    // test_suggest_raw().await;
-    // test_search_typed().await;
-    // test_picid_verify().await;
-}
-
-/// 验证 suggest 返回的歌曲 id 是否与真实歌曲 id 一致（能否直接播放）
-#[allow(dead_code)]
-async fn test_suggest_id_vs_song() {
-    use crate::api::{get_song_detail, search_suggest};
-    match search_suggest("周杰伦").await {
-        Ok(suggest) => {
-            println!("suggest songs vs detail:");
-            for s in suggest.songs.iter().take(5) {
-                let detail = get_song_detail(vec![s.id]).await;
-                match detail {
-                    Ok(ds) => {
-                        let d = &ds[0];
-                        println!(
-                            "  suggest id={} 显示名={} 详情名:{} 封面匹配={}",
-                            s.id,
-                            s.name,
-                            d.name,
-                            d.cover_url == s.cover_url
-                        );
-                    }
-                    Err(e) => println!("  suggest id={} 名={} detail失败: {}", s.id, s.name, e),
-                }
-            }
-        }
-        Err(e) => eprintln!("搜索建议失败: {}", e),
-    }
-}
-
-#[allow(dead_code)]
-
-async fn test_suggest_raw() {
-    use crate::api::search_suggest;
-    let suggest = search_suggest("周杰伦").await.unwrap();
-    let _ = suggest;
-    eprintln!("(raw shown by suggestion test)");
-}
-
-#[allow(dead_code)]
-async fn test_picid_verify() {
-    use crate::api::{get_song_detail, pic_url_from_id, search_suggest};
-    let suggest = search_suggest("周杰伦").await.unwrap();
-    for s in suggest.songs.iter().take(3) {
-        println!("suggest 歌: {} cover={}", s.name, s.cover_url);
-        let detail = get_song_detail(vec![s.id]).await.unwrap();
-        println!(
-            "详情 cover_url={} 一致={}",
-            detail[0].cover_url,
-            detail[0].cover_url == s.cover_url
-        );
-    }
-    println!("构造URL: {}", pic_url_from_id(109951172453712025));
-}
-
-#[allow(dead_code)]
-async fn test_suggest_typed() {
-    use crate::api::search_suggest;
-    match search_suggest("周杰伦").await {
-        Ok(suggest) => {
-            println!(
-                "建议: songs={} artists={} albums={}",
-                suggest.songs.len(),
-                suggest.artists.len(),
-                suggest.albums.len(),
-            );
-            for s in suggest.songs.iter().take(3) {
-                println!(
-                    "  song id={} name={} cover={:?} album_id={} album_name={}",
-                    s.id, s.name, s.cover_url, s.album.id, s.album.name
-                );
-            }
-        }
-        Err(e) => eprintln!("搜索建议失败: {}", e),
-    }
-}
-
-async fn test_search_typed() {
-    use crate::api::{search_albums, search_artists, search_playlists, search_songs};
-
-    match search_songs("周杰伦", 3, 0).await {
-        Ok(result) => {
-            println!("歌曲总数: {}", result.total);
-            for s in result.items {
-                println!(
-                    "song id={} name={} cover={:?} album_id={} album={:?}",
-                    s.id, s.name, s.cover_url, s.album.id, s.album.name
-                );
-            }
-        }
-        Err(e) => eprintln!("歌曲搜索失败: {}", e),
-    }
-    match search_playlists("周杰伦", 3, 0).await {
-        Ok(result) => println!("歌单数: {} 结果: {:#?}", result.total, result.items),
-        Err(e) => eprintln!("歌单搜索失败: {}", e),
-    }
-    match search_artists("周杰伦", 3, 0).await {
-        Ok(result) => println!("歌手数: {} 结果: {:#?}", result.total, result.items),
-        Err(e) => eprintln!("歌手搜索失败: {}", e),
-    }
-    match search_albums("周杰伦", 3, 0).await {
-        Ok(result) => println!("专辑数: {} 结果: {:#?}", result.total, result.items),
-        Err(e) => eprintln!("专辑搜索失败: {}", e),
-    }
-}
-
-#[allow(dead_code)]
-async fn test_home_block() {
-    match get_home_block().await {
-        Ok(home_block) => println!("home_block: {:#?}", home_block),
-        Err(e) => eprintln!("Error fetching recommend playlists: {}", e),
-    }
-}
+/// 网易云接口之外的直连请求（如 NOS 上传）共用的 HTTP 客户端
+static HTTP: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
 
-async fn test_home_category() {
-    match get_home_category_daily_song_list(vec![1840647840, 2106445921, 2693587200], 1000, 10015)
-        .await
-    {
-        Ok(home_block) => println!("home_block: {:#?}", home_block),
-        Err(e) => eprintln!("Error fetching recommend playlists: {}", e),
+/// 直连请求的客户端：与网易云接口一致，设置了代理时经代理发出
+pub fn http_client(proxy: Option<&str>) -> reqwest::Result<reqwest::Client> {
+    match proxy {
+        Some(url) => reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all(url)?)
+            .build(),
+        None => Ok(HTTP.clone()),
     }
 }
 
-async fn test_music_comment() {
-    match get_song_comments(1969519579).await {
-        Ok(comments) => println!("Comments: {:?}", comments),
-        Err(e) => eprintln!("Error fetching song comments: {}", e),
-    }
+/// 测试中指向本地 mock 服务器，None 恢复各服务的默认地址
+#[cfg(test)]
+pub(crate) fn set_base_url(url: Option<String>) {
+    *BASE_URL.write().unwrap() = url.map(|url| url.trim_end_matches('/').to_string());
 }
 
-async fn test_album_sub() {
-    match album_subscribe(32311, true).await {
-        Ok(_) => println!("Album subscribed successfully!"),
-        Err(e) => eprintln!("Error subscribing to album: {}", e),
-    }
-
-    match album_subscribe(32311, false).await {
-        Ok(_) => println!("Album unsubscribed successfully!"),
-        Err(e) => eprintln!("Error unsubscribing to album: {}", e),
-    }
+pub fn base_url() -> Option<String> {
+    BASE_URL.read().unwrap().clone()
 }
 
-async fn test_collect_song() {
-    match playlist_track_add(17922927485, 1969519579).await {
-        Ok(_) => println!("Song collected successfully!"),
-        Err(e) => eprintln!("Error collecting song: {}", e),
-    }
-
-    match playlist_track_del(17922927485, 1969519579).await {
-        Ok(_) => println!("Song uncollected successfully!"),
-        Err(e) => eprintln!("Error uncollecting song: {}", e),
+/// 网易云接口的请求参数，覆盖了服务器地址时带上 domain
+pub fn query() -> Query {
+    Query {
+        domain: base_url(),
+        ..Query::new()
     }
 }
-async fn test_like_song() {
-    match like_song(1969519579, true).await {
-        Ok(_) => println!("Song liked successfully!"),
-        Err(e) => eprintln!("Error liking song: {}", e),
-    }
-
-    match is_like_song(1969519579).await {
-        Ok(liked) => println!("Song is liked: {}", liked),
-        Err(e) => eprintln!("Error checking song like status: {}", e),
-    }
 
-    match like_song(1969519579, false).await {
-        Ok(_) => println!("Song unliked successfully!"),
-        Err(e) => eprintln!("Error unliking song: {}", e),
-    }
-
-    match is_like_song(1969519579).await {
-        Ok(liked) => println!("Song is liked: {}", liked),
-        Err(e) => eprintln!("Error checking song like status: {}", e),
-    }
-}
-
-async fn test_playlist_create_and_delete() {
-    match playlist_create("test").await {
-        Ok(id) => {
-            println!("Playlist created with ID: {}", id);
-            match playlist_delete(id).await {
-                Ok(_) => println!("Playlist deleted successfully!"),
-                Err(e) => eprintln!("Error deleting playlist: {}", e),
-            }
-        }
-        Err(e) => eprintln!("Error creating playlist: {}", e),
-    }
-}
-
-async fn test_playlist_subscribe() {
-    match playlist_subscribe(2226641834, true).await {
-        Ok(_) => println!("Playlist subscribed successfully!"),
-        Err(e) => eprintln!("Error subscribing to playlist: {}", e),
-    }
-
-    match playlist_subscribe(2226641834, false).await {
-        Ok(_) => println!("Playlist unsubscribed successfully!"),
-        Err(e) => eprintln!("Error unsubscribing to playlist: {}", e),
-    }
-}
-
-async fn test_artist_mv() {
-    match get_artist_mv(11972054).await {
-        Ok(mvs) => println!("Mvs: {:?}", mvs),
-        Err(e) => eprintln!("Error fetching artist mvs: {}", e),
-    }
-}
-async fn test_artist_album() {
-    match get_artist_album(11972054).await {
-        Ok(albums) => println!("Albums: {:?}", albums),
-        Err(e) => eprintln!("Error fetching artist albums: {}", e),
-    }
-}
-
-async fn test_artist_song() {
-    match get_artist_song(11972054).await {
-        Ok(songs) => println!("Songs: {:?}", songs),
-        Err(e) => eprintln!("Error fetching artist songs: {}", e),
-    }
-}
-async fn test_artist_detail() {
-    match get_artist_detail(11972054).await {
-        Ok(detail) => println!("Artist Detail: {:?}", detail),
-        Err(e) => eprintln!("Error fetching artist detail: {}", e),
-    }
-}
-
-async fn test_playlist_detail() {
-    match get_playlist_detail(3136952023).await {
-        Ok(detail) => println!("Playlist Detail: {:?}", detail),
-        Err(e) => eprintln!("Error fetching playlist detail: {}", e),
-    }
-}
-
-async fn test_recommend_playlist() {
-    match get_recommend_playlist().await {
-        Ok(playlists) => println!("Recommended Playlists: {:?}", playlists),
-        Err(e) => eprintln!("Error fetching recommended playlists: {}", e),
-    }
-}
-
-async fn test_song_url() {
-    match get_song_url(1969519579, SoundQuality::Standard).await {
-        Ok(url) => println!("Song URL: {}", url),
-        Err(e) => eprintln!("Error fetching song URL: {}", e),
-    }
-}
-
-async fn test_song_detail() {
-    match get_song_detail(vec![3363002263, 2003647821]).await {
-        Ok(songs) => println!("Songs: {:?}", songs),
-        Err(e) => eprintln!("Error fetching song details: {}", e),
-    }
-}
-
-async fn test_album_detail() {
-    match get_album_detail(32311).await {
-        Ok(detail) => println!("Album Detail: {:?}", detail),
-        Err(e) => eprintln!("Error fetching album detail: {}", e),
-    }
-}
-
-async fn test_recommend_songs() {
-    match get_recommend_song().await {
-        Ok(songs) => println!("Recommended Songs: {:?}", songs),
-        Err(e) => eprintln!("Error fetching recommended songs: {}", e),
-    }
-}
-
-async fn test_lyric() {
-    let ids: &[u64] = &[
-        1357628744, 28018262, 31830616, 2015622697, 31830614, 1382838382, 31830620, 1491007768,
-        1897601224, 458231455, 557579433, 28152560, 2036444271, 1386747757, 33367335, 1479312520,
-        33367332, 1332957194, 539717755, 30245063, 1911198850, 34204085, 1884490658, 30854807,
-        2037944267, 1487502645, 2023989553, 825345, 825646, 1911323571, 1406598531, 1481127185,
-        28018264, 825343, 536622304, 435948605, 557583472, 496869422, 22709625, 1956287463,
-        557583473, 672188, 34509092, 1357953768, 426881506, 32405870, 478303470, 463842649,
-        426881487, 426881480, 29732992,
-    ];
-    for &id in ids {
-        match get_lryic(id).await {
-            Ok(lyric) => {
-                let has_lyric = lyric.lyric.as_ref().map_or(false, |l| !l.is_empty());
-                let has_tlyric = lyric.tlyric.as_ref().map_or(false, |l| !l.is_empty());
-                let has_yrc = lyric.yrc.as_ref().map_or(false, |l| !l.is_empty());
-                if has_lyric || has_tlyric || has_yrc {
-                    println!("=== SONG {} ===", id);
-                    println!("  is_pure_music: {}", lyric.is_pure_music);
-                    println!("  lyric:  {}", lyric.lyric.as_deref().unwrap_or("<none>"));
-                    println!("  tlyric: {}", lyric.tlyric.as_deref().unwrap_or("<none>"));
-                    println!("  yrc:    {}", lyric.yrc.as_deref().unwrap_or("<none>"));
-                }
-            }
-            Err(e) => eprintln!("Error fetching lyric for {}: {}", id, e),
-        }
-    }
-}
-
-async fn test_user_info() {
-    match get_user_info().await {
-        Ok(info) => println!("User Info: {:?}", info),
-        Err(e) => eprintln!("Error fetching user info: {}", e),
-    }
-}
-
-async fn test_user_subcount() {
-    match get_user_subcount().await {
-        Ok(counts) => println!("User Counts: {:?}", counts),
-        Err(e) => eprintln!("Error fetching user counts: {}", e),
-    }
-}
-
-async fn test_user_playlist() {
-    match get_user_playlist(5128948380).await {
-        Ok(playlists) => println!("User Playlists: {:?}", playlists),
-        Err(e) => eprintln!("Error fetching user playlists: {}", e),
-    }
-
-    match get_user_playlist_created(5128948380).await {
-        Ok(playlists) => println!("User Created Playlists: {:?}", playlists),
-        Err(e) => eprintln!("Error fetching user created playlists: {}", e),
-    }
-
-    match get_user_playlist_collected(5128948380).await {
-        Ok(playlists) => println!("User Collected Playlists: {:?}", playlists),
-        Err(e) => eprintln!("Error fetching user collected playlists: {}", e),
-    }
-}
-
-async fn test_user_sub_album() {
-    match get_user_sub_album().await {
-        Ok(albums) => println!("User Subscribed Albums: {:?}", albums),
-        Err(e) => eprintln!("Error fetching user subscribed albums: {}", e),
-    }
-}
-
-async fn test_user_detail() {
-    match get_user_detail(5128948380).await {
-        Ok(details) => println!("User Details: {:?}", details),
-        Err(e) => eprintln!("Error fetching user details: {}", e),
-    }
+/// 第三方服务的完整 URL：覆盖了服务器地址时只保留原 URL 的路径部分
+pub fn service_url(default: &str) -> String {
+    let Some(base) = base_url() else {
+        return default.to_string();
+    };
+    let path = default
+        .split_once("://")
+        .and_then(|(_, rest)| rest.find('/').map(|i| &rest[i..]))
+        .unwrap_or("");
+    format!("{base}{path}")
 }
@@ -1,6 +1,7 @@
-use ncm_api_rs::Query;
-
-use crate::api::{Comment, CommentFloor, MusicComment, UserInfo, client::client};
+use crate::api::{
+    Comment, CommentFloor, CommentType, MusicComment, UserInfo,
+    client::{client, query},
+};
 
 fn parse_comment(c: &serde_json::Value) -> Comment {
     let fallback = serde_json::Map::new();
@@ -48,16 +49,19 @@ fn parse_comment(c: &serde_json::Value) -> Comment {
 }
 
 /// 新版评论（/api/v2/resource/comments）
+/// resource: 评论所属资源类型（歌曲 / MV / 歌单 / 专辑）
 /// sort_type: 99 = 推荐, 2 = 热度, 3 = 时间
 /// cursor: 从上一页响应中回传（推荐/热度由服务端通过 pageNo 计算，时间排序需回传 cursor）
 /// 返回 (评论列表, 是否还有更多, 下一页 cursor)
-pub async fn get_song_comments_new(
+pub async fn get_comments_new(
+    resource: CommentType,
     id: u64,
     page_no: i64,
     sort_type: i64,
     cursor: &str,
 ) -> anyhow::Result<(Vec<Comment>, bool, String)> {
-    let query = Query::new()
+    let query = query()
+        .param("type", resource.code())
         .param("id", &id.to_string())
         .param("pageNo", &page_no.to_string())
         .param("sortType", &sort_type.to_string());
@@ -82,8 +86,8 @@ pub async fn get_song_comments_new(
         }
         Err(e) => {
             eprintln!(
-                "获取新版评论失败， song id: {}, sort_type: {}, {}",
-                id, sort_type, e
+                "获取新版评论失败， {:?} id: {}, sort_type: {}, {}",
+                resource, id, sort_type, e
             );
             Err(e.into())
         }
@@ -92,11 +96,13 @@ pub async fn get_song_comments_new(
 
 /// 楼中楼回复
 pub async fn get_comment_floor(
+    resource: CommentType,
     id: u64,
     parent_comment_id: u64,
     time: i64,
 ) -> anyhow::Result<CommentFloor> {
-    let query = Query::new()
+    let query = query()
+        .param("type", resource.code())
         .param("id", &id.to_string())
         .param("parentCommentId", &parent_comment_id.to_string())
         .param("time", &time.to_string())
@@ -126,39 +132,16 @@ pub async fn get_comment_floor(
         }
         Err(e) => {
             eprintln!(
-                "获取楼层评论失败，song id: {}, parent: {}, {}",
-                id, parent_comment_id, e
+                "获取楼层评论失败，{:?} id: {}, parent: {}, {}",
+                resource, id, parent_comment_id, e
             );
             Err(e.into())
         }
     }
 }
 
-/// MV 评论（/comment/mv）
-pub async fn get_mv_comments(id: u64) -> anyhow::Result<Vec<Comment>> {
-    let query = Query::new()
-        .param("id", &id.to_string())
-        .param("limit", "30");
-    match client().comment_mv(&query).await {
-        Ok(resp) => {
-            let comments = resp.body["comments"]
-                .as_array()
-                .cloned()
-                .unwrap_or_default()
-                .iter()
-                .map(parse_comment)
-                .collect();
-            Ok(comments)
-        }
-        Err(e) => {
-            eprintln!("获取 MV 评论失败， mv id: {}, {}", id, e);
-            Err(e.into())
-        }
-    }
-}
-
 pub async fn get_song_comments(id: u64) -> anyhow::Result<MusicComment> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
     match client().comment_music(&query).await {
         Ok(resp) => {
             let hot_comment = resp.body["hotComments"]
@@ -1,10 +1,12 @@
 use std::future::Future;
 
 use chrono::Local;
-use ncm_api_rs::error::Result;
+use ncm_api_rs::error::{NcmError, Result};
 use ncm_api_rs::{ApiClient, ApiResponse, CryptoType, Query, RequestOption};
 use serde_json::json;
 
+use crate::api::client::{http_client, service_url};
+
 pub trait ApiClientExt {
     fn home_recommend_resource(
         &self,
@@ -19,6 +21,17 @@ pub trait ApiClientExt {
         &self,
         query: &Query,
     ) -> impl Future<Output = Result<ApiResponse>> + Send;
+
+    /// 上传歌单封面：申请 NOS token → 上传图片 → 更新歌单封面
+    fn playlist_cover_upload(
+        &self,
+        query: &Query,
+        img_data: Vec<u8>,
+    ) -> impl Future<Output = Result<ApiResponse>> + Send;
+
+    /// 播放记录上报（weblog）：action 为 startplay 或 play，
+    /// play 时带上来源歌单 sourceid、听歌秒数 time 和结束原因 end
+    fn play_log(&self, query: &Query) -> impl Future<Output = Result<ApiResponse>> + Send;
 }
 
 impl ApiClientExt for ApiClient {
@@ -99,4 +112,147 @@ impl ApiClientExt for ApiClient {
 
         self.request("/api/pc/recent/listen/list", data, option)
     }
+
+    fn playlist_cover_upload(
+        &self,
+        query: &Query,
+        img_data: Vec<u8>,
+    ) -> impl Future<Output = Result<ApiResponse>> + Send {
+        let pid = query.get_or("id", "0");
+        let option = |crypto| RequestOption {
+            crypto,
+            cookie: query.cookie.clone(),
+            ua: query.ua.clone(),
+            proxy: query.proxy.clone(),
+            real_ip: query.real_ip.clone(),
+            random_cn_ip: query.random_cn_ip,
+            e_r: query.e_r,
+            domain: query.domain.clone(),
+            check_token: false,
+        };
+        let token_option = option(CryptoType::Weapi);
+        let update_option = option(CryptoType::Weapi);
+        let proxy = query.proxy.clone();
+
+        async move {
+            let (ext, mimetype) = image_format(&img_data)
+                .ok_or_else(|| NcmError::InvalidParam("封面只支持 JPEG / PNG 图片".to_string()))?;
+
+            // 1. 申请上传 token
+            let token_data = json!({
+                "bucket": "yyimgs",
+                "ext": ext,
+                "filename": format!("cover.{ext}"),
+                "local": false,
+                "nos_product": 0,
+                "return_body": "{\"code\":200,\"size\":\"$(ObjectSize)\"}",
+                "type": "other"
+            });
+            let token_res = self
+                .request("/api/nos/token/alloc", token_data, token_option)
+                .await?;
+            let result = &token_res.body["result"];
+            let (Some(object_key), Some(token)) =
+                (result["objectKey"].as_str(), result["token"].as_str())
+            else {
+                return Err(NcmError::Unknown(format!(
+                    "申请封面上传 token 失败: {}",
+                    token_res.body
+                )));
+            };
+            let doc_id = result["docId"].clone();
+
+            // 2. 上传图片到 NOS
+            let upload_url = format!(
+                "https://nosup-hz1.127.net/yyimgs/{}?offset=0&complete=true&version=1.0",
+                object_key
+            );
+            let upload = http_client(proxy.as_deref())
+                .map_err(|e| NcmError::Unknown(format!("代理设置无效: {e}")))?
+                .post(service_url(&upload_url))
+                .header("x-nos-token", token)
+                .header("Content-Type", mimetype)
+                .body(img_data)
+                .send()
+                .await
+                .map_err(|e| NcmError::Unknown(format!("封面上传失败: {e}")))?;
+            if !upload.status().is_success() {
+                return Err(NcmError::Unknown(format!(
+                    "封面上传失败: HTTP {}",
+                    upload.status()
+                )));
+            }
+
+            // 3. 更新歌单封面
+            let update_data = json!({
+                "id": pid,
+                "coverImgId": doc_id
+            });
+            let mut res = self
+                .request("/api/playlist/cover/update", update_data, update_option)
+                .await?;
+            if let Some(obj) = res.body.as_object_mut() {
+                obj.insert(
+                    "url_pre".to_string(),
+                    json!(format!("https://p1.music.126.net/{}", object_key)),
+                );
+            }
+            Ok(res)
+        }
+    }
+
+    fn play_log(&self, query: &Query) -> impl Future<Output = Result<ApiResponse>> + Send {
+        let action = query.get_or("action", "play");
+        let id = query.get_or("id", "0");
+        let source_id = query.get_or("sourceid", "");
+        let log = if action == "startplay" {
+            json!({
+                "id": id,
+                "sourceId": source_id,
+                "type": "song",
+                "content": "",
+                "mainsite": "1"
+            })
+        } else {
+            json!({
+                "download": 0,
+                "end": query.get_or("end", "playend"),
+                "id": id,
+                "sourceId": source_id,
+                "time": query.get_or("time", "0").parse::<u64>().unwrap_or(0),
+                "type": "song",
+                "wifi": 0,
+                "source": "list",
+                "mainsite": 1,
+                "content": ""
+            })
+        };
+        let data = json!({
+            "logs": json!([{ "action": action, "json": log }]).to_string()
+        });
+
+        let option = RequestOption {
+            crypto: CryptoType::Weapi,
+            cookie: query.cookie.clone(),
+            ua: query.ua.clone(),
+            proxy: query.proxy.clone(),
+            real_ip: query.real_ip.clone(),
+            random_cn_ip: query.random_cn_ip,
+            e_r: query.e_r,
+            domain: query.domain.clone(),
+            check_token: false,
+        };
+        self.request("/api/feedback/weblog", data, option)
+    }
+}
+
+/// 按文件头识别封面图片格式，返回 (扩展名, MIME)
+fn image_format(data: &[u8]) -> Option<(&'static str, &'static str)> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("jpg", "image/jpeg"))
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("png", "image/png"))
+    } else {
+        None
+    }
 }
@@ -1,58 +1,14 @@
 //! 发现页（Explore）相关接口
 
-use ncm_api_rs::Query;
-
-use crate::api::{Album, Artist, Mv, Playlist, Song, client::client};
-
-fn parse_song(value: &serde_json::Value) -> Song {
-    Song {
-        id: value["id"].as_u64().unwrap_or(0),
-        name: value["name"].as_str().unwrap_or("").to_string(),
-        cover_url: value["al"]["picUrl"]
-            .as_str()
-            .or_else(|| value["album"]["picUrl"].as_str())
-            .unwrap_or("")
-            .to_string(),
-        artists: value["ar"]
-            .as_array()
-            .filter(|arr| !arr.is_empty())
-            .or_else(|| value["artists"].as_array())
-            .map(|arr| {
-                arr.iter()
-                    .map(|a| Artist {
-                        id: a["id"].as_u64().unwrap_or(0),
-                        name: a["name"].as_str().unwrap_or("").to_string(),
-                        avatar: None,
-                    })
-                    .collect()
-            })
-            .unwrap_or_default(),
-        album: Album {
-            id: value["al"]["id"]
-                .as_u64()
-                .or_else(|| value["album"]["id"].as_u64())
-                .unwrap_or(0),
-            name: value["al"]["name"]
-                .as_str()
-                .or_else(|| value["album"]["name"].as_str())
-                .unwrap_or("")
-                .to_string(),
-            cover_url: value["al"]["picUrl"]
-                .as_str()
-                .or_else(|| value["album"]["picUrl"].as_str())
-                .unwrap_or("")
-                .to_string(),
-        },
-        duration: value["dt"]
-            .as_u64()
-            .or_else(|| value["duration"].as_u64())
-            .unwrap_or(0),
-    }
-}
+use crate::api::{
+    MusicArea, Mv, Playlist, Song,
+    client::{client, query},
+    parse::parse_song,
+};
 
 /// 排行榜列表（含更新频率），只保留经典全局榜
 pub async fn get_toplist() -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new();
+    let query = query();
     match client().toplist_detail(&query).await {
         Ok(resp) => {
             let list = resp.body["list"].as_array().cloned().unwrap_or_default();
@@ -74,6 +30,7 @@ pub async fn get_toplist() -> anyhow::Result<Vec<Playlist>> {
                     creator_id: 0,
                     description: item["updateFrequency"].as_str().unwrap_or("").to_string(),
                     play_count: 0,
+                    liked_songs: false,
                 });
             }
             Ok(result)
@@ -87,7 +44,7 @@ pub async fn get_toplist() -> anyhow::Result<Vec<Playlist>> {
 
 /// 获取单个榜单的歌曲列表
 pub async fn get_toplist_songs(id: u64) -> anyhow::Result<Vec<Song>> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
     match client().top_list(&query).await {
         Ok(resp) => {
             let tracks = resp.body["playlist"]["tracks"]
@@ -103,9 +60,9 @@ pub async fn get_toplist_songs(id: u64) -> anyhow::Result<Vec<Song>> {
     }
 }
 
-/// 新歌速递
-pub async fn get_new_songs() -> anyhow::Result<Vec<Song>> {
-    let query = Query::new().param("type", "0");
+/// 新歌速递（按地区）
+pub async fn get_new_songs(area: MusicArea) -> anyhow::Result<Vec<Song>> {
+    let query = query().param("type", area.song_type());
     match client().top_song(&query).await {
         Ok(resp) => {
             let songs = resp.body["data"].as_array().cloned().unwrap_or_default();
@@ -118,15 +75,20 @@ pub async fn get_new_songs() -> anyhow::Result<Vec<Song>> {
     }
 }
 
-/// 新碟上架
-pub async fn get_new_albums() -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new().param("area", "ALL").param("limit", "50");
-    match client().top_album(&query).await {
+/// 新碟上架（按地区分页），返回 (专辑, 是否还有更多)
+pub async fn get_new_albums(
+    area: MusicArea,
+    offset: u32,
+    limit: u32,
+) -> anyhow::Result<(Vec<Playlist>, bool)> {
+    let query = query()
+        .param("area", area.album_area())
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
+    match client().album_new(&query).await {
         Ok(resp) => {
-            let albums = resp.body["monthData"]
-                .as_array()
-                .cloned()
-                .unwrap_or_default();
+            let albums = resp.body["albums"].as_array().cloned().unwrap_or_default();
+            let total = resp.body["total"].as_u64().unwrap_or(0);
             let mut result = Vec::new();
             for item in &albums {
                 let id = item["id"].as_u64().unwrap_or(0);
@@ -138,12 +100,14 @@ pub async fn get_new_albums() -> anyhow::Result<Vec<Playlist>> {
                     name: item["name"].as_str().unwrap_or("").to_string(),
                     cover_url: item["picUrl"].as_str().unwrap_or("").to_string(),
                     creator_name: item["artist"]["name"].as_str().unwrap_or("").to_string(),
-                    creator_id: 0,
+                    creator_id: item["artist"]["id"].as_u64().unwrap_or(0),
                     description: String::new(),
                     play_count: 0,
+                    liked_songs: false,
                 });
             }
-            Ok(result)
+            let has_more = (offset as u64 + albums.len() as u64) < total;
+            Ok((result, has_more))
         }
         Err(e) => {
             eprintln!("获取新碟上架失败: {e}");
@@ -152,10 +116,13 @@ pub async fn get_new_albums() -> anyhow::Result<Vec<Playlist>> {
     }
 }
 
-/// 最新 MV
-pub async fn get_new_mvs() -> anyhow::Result<Vec<Mv>> {
-    let query = Query::new().param("limit", "12");
-    match client().mv_first(&query).await {
+/// 最新 MV（分页），返回 (MV, 是否还有更多)
+pub async fn get_new_mvs(offset: u32, limit: u32) -> anyhow::Result<(Vec<Mv>, bool)> {
+    let query = query()
+        .param("order", "最新")
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
+    match client().mv_all(&query).await {
         Ok(resp) => {
             let list = resp.body["data"].as_array().cloned().unwrap_or_default();
             let mut result = Vec::new();
@@ -172,7 +139,7 @@ pub async fn get_new_mvs() -> anyhow::Result<Vec<Mv>> {
                     play_count: item["playCount"].as_u64().unwrap_or(0),
                 });
             }
-            Ok(result)
+            Ok((result, resp.body["hasMore"].as_bool().unwrap_or(false)))
         }
         Err(e) => {
             eprintln!("获取最新 MV 失败: {e}");
@@ -180,62 +147,99 @@ pub async fn get_new_mvs() -> anyhow::Result<Vec<Mv>> {
         }
     }
 }
+
+/// 歌单广场的热门分类标签（华语、流行、摇滚……）
+pub async fn get_playlist_hot_tags() -> anyhow::Result<Vec<String>> {
+    let query = query();
+    match client().playlist_hot(&query).await {
+        Ok(resp) => {
+            let tags = resp.body["tags"].as_array().cloned().unwrap_or_default();
+            Ok(tags
+                .iter()
+                .filter_map(|t| t["name"].as_str())
+                .map(|name| name.to_string())
+                .collect())
+        }
+        Err(e) => {
+            eprintln!("获取歌单分类失败: {e}");
+            Err(e.into())
+        }
+    }
+}
+
+/// 歌单广场：按分类分页获取歌单，返回 (歌单, 是否还有更多)
+pub async fn get_category_playlists(
+    cat: &str,
+    offset: u32,
+    limit: u32,
+) -> anyhow::Result<(Vec<Playlist>, bool)> {
+    let query = query()
+        .param("cat", cat)
+        .param("offset", &offset.to_string())
+        .param("limit", &limit.to_string());
+    match client().top_playlist(&query).await {
+        Ok(resp) => {
+            let list = resp.body["playlists"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            let result = list
+                .iter()
+                .map(|pl| Playlist {
+                    id: pl["id"].as_u64().unwrap_or(0),
+                    name: pl["name"].as_str().unwrap_or("").to_string(),
+                    cover_url: pl["coverImgUrl"].as_str().unwrap_or("").to_string(),
+                    creator_name: pl["creator"]["nickname"].as_str().unwrap_or("").to_string(),
+                    creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
+                    description: pl["description"].as_str().unwrap_or("").to_string(),
+                    play_count: pl["playCount"].as_u64().unwrap_or(0),
+                    liked_songs: false,
+                })
+                .collect();
+            Ok((result, resp.body["more"].as_bool().unwrap_or(false)))
+        }
+        Err(e) => {
+            eprintln!("获取分类歌单失败: {e}");
+            Err(e.into())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::init_client;
-    use relm4::gtk::gio::prelude::SettingsExt;
+    use crate::api::mock::serve;
 
     #[tokio::test]
     async fn test_explore_apis() {
-        use crate::APPLICATION_ID;
-        unsafe {
-            let _ = std::process::Command::new("glib-compile-schemas")
-                .arg("data")
-                .status();
-            std::env::set_var("GSETTINGS_SCHEMA_DIR", "data");
-        }
-        let settings = relm4::gtk::gio::Settings::new(APPLICATION_ID);
-        init_client(settings.string("cookie").to_string());
+        serve();
 
+        // id 为 0 的榜单被过滤，coverImgUrl 缺失时用 coverUrl
         let toplists = get_toplist().await.unwrap();
-        println!("toplists={}", toplists.len());
-        assert!(!toplists.is_empty(), "toplist 解析为空");
-        assert!(toplists[0].cover_url.contains("http"));
+        assert_eq!(toplists.len(), 2);
+        assert_eq!(toplists[0].description, "每天更新");
+        assert!(toplists[1].cover_url.contains("http"));
 
         let songs = get_toplist_songs(toplists[0].id).await.unwrap();
-        println!(
-            "toplist_songs={} first={:?}",
-            songs.len(),
-            songs.first().map(|s| (&s.name, &s.cover_url))
-        );
-        assert!(!songs.is_empty());
-
-        let news = get_new_songs().await.unwrap();
-        println!(
-            "new_songs={} first={:?}",
-            news.len(),
-            news.first().map(|s| (&s.name, &s.cover_url))
-        );
-        assert!(!news.is_empty());
+        assert_eq!(songs[0].name, "若月亮没来");
+
+        let news = get_new_songs(MusicArea::All).await.unwrap();
+        assert_eq!(news.len(), 1);
         assert!(news[0].cover_url.contains("http"));
 
-        let albums = get_new_albums().await.unwrap();
-        println!(
-            "new_albums={} first={:?}",
-            albums.len(),
-            albums
-                .first()
-                .map(|a| (&a.name, &a.creator_name, &a.cover_url))
-        );
-        assert!(!albums.is_empty());
-
-        let mvs = get_new_mvs().await.unwrap();
-        println!(
-            "new_mvs={} first={:?}",
-            mvs.len(),
-            mvs.first().map(|m| (&m.name, m.play_count))
-        );
-        assert!(!mvs.is_empty());
+        let (albums, more) = get_new_albums(MusicArea::All, 0, 30).await.unwrap();
+        assert_eq!(albums[0].creator_name, "陈奕迅");
+        assert!(more);
+
+        let (mvs, more) = get_new_mvs(0, 12).await.unwrap();
+        assert_eq!(mvs[0].play_count, 93021345);
+        assert!(more);
+
+        let tags = get_playlist_hot_tags().await.unwrap();
+        assert_eq!(tags, ["华语", "流行", "摇滚"]);
+
+        let (playlists, more) = get_category_playlists(&tags[0], 0, 30).await.unwrap();
+        assert_eq!(playlists[0].creator_name, "云音乐私人推荐");
+        assert!(more);
     }
 }
@@ -1,10 +1,12 @@
-use ncm_api_rs::Query;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 
 use crate::api::{
-    LyricDetail, Song, amll::fetch_amll_ttml, client::client, qqmusic::fetch_qq_lyric_for_song,
+    LyricDetail, Song,
+    amll::fetch_amll_ttml,
+    client::{client, query},
+    qqmusic::fetch_qq_lyric_for_song,
 };
 use crate::utils::ttml::is_ttml;
 
@@ -14,7 +16,7 @@ const MAX_LYRIC_CACHE_ENTRIES: usize = 128;
 
 pub async fn get_lryic(id: u64) -> anyhow::Result<LyricDetail> {
     log::debug!("[lyrics][ncm] requesting song_id={id}");
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
 
     match client().lyric_new(&query).await {
         Ok(resp) => {
@@ -0,0 +1,233 @@
+//! 测试用的本地接口服务器
+//!
+//! 按路径返回 `fixtures/` 下录制的响应，网易云、QQ 音乐、AMLL 共用一个端口。
+//! 网易云 weapi / eapi 的请求体是加密的，只能按路径区分接口；
+//! QQ 音乐的搜索和歌词共用一个路径，按明文请求体里的 method 区分。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, OnceLock};
+
+use crate::api::client::{init_client, set_base_url};
+use crate::api::qqmusic::encode_lyric;
+
+/// fixtures 中 AMLL 有逐字歌词的歌曲
+pub const AMLL_SONG_ID: u64 = 186016;
+
+/// QQ 音乐歌词接口返回的 QRC 原文（响应里是加密后的 hex）
+pub const QQ_QRC: &str =
+    "[29350,3050]故(29350,380)事(29730,380)的(30110,380)小(30490,380)黄(30870,380)花(31250,1150)\n";
+
+struct Route {
+    path: String,
+    /// 同一路径下按请求体区分（QQ 音乐）
+    body_contains: Option<&'static str>,
+    body: String,
+}
+
+impl Route {
+    fn ok(path: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            body_contains: None,
+            body: body.into(),
+        }
+    }
+
+    fn when(mut self, body_contains: &'static str) -> Self {
+        self.body_contains = Some(body_contains);
+        self
+    }
+}
+
+fn routes() -> Vec<Route> {
+    let qq_lyric = serde_json::json!({
+        "code": 0,
+        "music.musichallSong.PlayLyricInfo.GetPlayLyricInfo": {
+            "code": 0,
+            "data": { "songID": 97773, "lyric": encode_lyric(QQ_QRC), "trans": "" }
+        }
+    });
+    vec![
+        Route::ok(
+            "/api/v6/playlist/detail",
+            include_str!("fixtures/playlist_detail.json"),
+        ),
+        Route::ok(
+            "/api/v3/song/detail",
+            include_str!("fixtures/song_detail.json"),
+        ),
+        Route::ok(
+            "/api/song/enhance/player/url/v1",
+            include_str!("fixtures/song_url.json"),
+        ),
+        Route::ok("/api/song/lyric/v1", include_str!("fixtures/lyric.json")),
+        Route::ok(
+            "/api/cloudsearch/pc",
+            include_str!("fixtures/cloudsearch.json"),
+        ),
+        Route::ok(
+            "/api/search/suggest/web",
+            include_str!("fixtures/search_suggest.json"),
+        ),
+        Route::ok(
+            "/api/v1/resource/comments/R_SO_4_186016",
+            include_str!("fixtures/comment_music.json"),
+        ),
+        Route::ok(
+            "/api/v2/resource/comments",
+            include_str!("fixtures/comment_new.json"),
+        ),
+        Route::ok(
+            "/api/pc/daily/rcmd/block",
+            include_str!("fixtures/home_block.json"),
+        ),
+        Route::ok(
+            "/api/v1/discovery/recommend/resource",
+            include_str!("fixtures/recommend_resource.json"),
+        ),
+        Route::ok(
+            "/api/toplist/detail",
+            include_str!("fixtures/toplist_detail.json"),
+        ),
+        Route::ok(
+            "/api/playlist/v4/detail",
+            include_str!("fixtures/toplist_songs.json"),
+        ),
+        Route::ok(
+            "/api/v1/discovery/new/songs",
+            include_str!("fixtures/new_songs.json"),
+        ),
+        Route::ok("/api/album/new", include_str!("fixtures/album_new.json")),
+        Route::ok("/api/mv/all", include_str!("fixtures/mv_all.json")),
+        Route::ok(
+            "/api/v1/play/record",
+            include_str!("fixtures/user_record.json"),
+        ),
+        Route::ok(
+            "/api/playmode/intelligence/list",
+            include_str!("fixtures/intelligence_list.json"),
+        ),
+        Route::ok(
+            "/api/feedback/weblog",
+            r#"{"code":200,"data":"success","message":""}"#,
+        ),
+        Route::ok(
+            "/api/playlist/hottags",
+            include_str!("fixtures/playlist_hottags.json"),
+        ),
+        Route::ok(
+            "/api/playlist/list",
+            include_str!("fixtures/playlist_list.json"),
+        ),
+        Route::ok(
+            "/cgi-bin/musicu.fcg",
+            include_str!("fixtures/qq_search.json"),
+        )
+        .when("DoSearchForQQMusicLite"),
+        Route::ok("/cgi-bin/musicu.fcg", qq_lyric.to_string()).when("GetPlayLyricInfo"),
+        Route::ok(
+            format!("/ncm-lyrics/{AMLL_SONG_ID}.ttml"),
+            include_str!("fixtures/amll_lyric.ttml"),
+        ),
+    ]
+}
+
+/// 启动 mock 服务器并让所有接口指向它；多个测试共用同一个实例
+pub fn serve() {
+    static SERVER: OnceLock<()> = OnceLock::new();
+    SERVER.get_or_init(|| {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("mock server bind");
+        let addr = listener.local_addr().unwrap();
+        let routes = Arc::new(routes());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = routes.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = handle(stream, &routes) {
+                        eprintln!("mock server: {e}");
+                    }
+                });
+            }
+        });
+        set_base_url(Some(format!("http://{addr}")));
+        init_client(String::new());
+    });
+}
+
+/// 网易云的 uri 在 weapi / eapi 下会换前缀，统一还原成 /api/...
+fn normalize_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or(path);
+    for prefix in ["/weapi/", "/eapi/"] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            return format!("/api/{rest}");
+        }
+    }
+    path.to_string()
+}
+
+/// 读到的一次请求
+pub struct Request {
+    /// 原样的请求路径（含查询串）
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+/// 读取一次 HTTP/1.1 请求，只关心路径和按 Content-Length 给出的请求体
+pub fn read_request(stream: &TcpStream) -> std::io::Result<Request> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or("/")
+        .to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Request { path, body })
+}
+
+/// 写回 JSON 响应并关闭连接；`status` 形如 `200 OK`
+pub fn respond(mut stream: &TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body.as_bytes())
+}
+
+fn handle(stream: TcpStream, routes: &[Route]) -> std::io::Result<()> {
+    let request = read_request(&stream)?;
+    let path = normalize_path(&request.path);
+    let body = String::from_utf8_lossy(&request.body);
+
+    let route = routes.iter().find(|route| {
+        route.path == path
+            && route
+                .body_contains
+                .is_none_or(|needle| body.contains(needle))
+    });
+    let (status, body) = match route {
+        Some(route) => ("200 OK", route.body.as_str()),
+        None => ("404 Not Found", r#"{"code":404,"msg":"no fixture"}"#),
+    };
+    respond(&stream, status, body)
+}
+
+#[path = "mock_tests.rs"]
+mod tests;
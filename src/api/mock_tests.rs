@@ -0,0 +1,247 @@
+use super::{AMLL_SONG_ID, QQ_QRC, serve};
+use crate::api::amll::fetch_amll_ttml;
+use crate::api::qqmusic::fetch_qq_lyric_for_song;
+use crate::api::{
+    Album, Artist, CommentType, HomeBlockType, PlayEndReason, PlayReport, RecordRange, Song,
+    SoundQuality, get_comments_new, get_home_block, get_intelligence_list, get_lryic,
+    get_lyric_for_song, get_playlist_detail, get_playlist_track_all, get_recommend_playlist,
+    get_song_comments, get_song_detail, get_song_url, get_user_record, pic_url_from_id,
+    report_play, search_songs, search_suggest,
+};
+
+fn qing_tian() -> Song {
+    Song {
+        id: 186016,
+        name: "晴天".into(),
+        cover_url: String::new(),
+        artists: vec![Artist {
+            id: 6452,
+            name: "周杰伦".into(),
+            avatar: None,
+        }],
+        album: Album {
+            id: 18905,
+            name: "叶惠美".into(),
+            cover_url: String::new(),
+        },
+        duration: 269000,
+    }
+}
+
+#[tokio::test]
+async fn playlist_detail_parses_tracks_and_ids() {
+    serve();
+    let detail = get_playlist_detail(3778678).await.unwrap();
+    assert_eq!(detail.id, 3778678);
+    assert_eq!(detail.name, "热歌榜");
+    assert_eq!(detail.creator_name, "网易云音乐");
+    assert_eq!(detail.creator_id, 1);
+    assert_eq!(detail.tags, ["华语", "流行"]);
+    assert_eq!(detail.play_count, 13804823040);
+    assert!(!detail.liked_songs);
+    // trackIds 比 tracks 多，剩下的分页再取
+    assert_eq!(detail.track_ids, [186016, 2116402049, 1901371647]);
+    assert_eq!(detail.tracks.len(), 2);
+
+    let song = &detail.tracks[0];
+    assert_eq!(song.name, "晴天");
+    assert_eq!(song.duration, 269000);
+    assert_eq!(song.artists[0].name, "周杰伦");
+    assert_eq!(song.album.id, 18905);
+    assert_eq!(song.album.cover_url, song.cover_url);
+
+    // picUrl 为空时由 picId 生成封面地址
+    let song = &detail.tracks[1];
+    assert_eq!(song.artists.len(), 2);
+    assert_eq!(song.cover_url, pic_url_from_id(109951169373523398));
+}
+
+#[tokio::test]
+async fn playlist_pages_and_song_detail_parse_songs() {
+    serve();
+    let page = get_playlist_track_all(&[186016, 2116402049, 1901371647], 2, 10)
+        .await
+        .unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].name, "孤勇者");
+    assert!(
+        get_playlist_track_all(&[186016], 1, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let songs = get_song_detail(vec![1901371647]).await.unwrap();
+    assert_eq!(songs[0].id, 1901371647);
+    assert_eq!(songs[0].artists[0].name, "陈奕迅");
+    assert!(songs[0].cover_url.starts_with("https://"));
+}
+
+#[tokio::test]
+async fn song_url_is_returned() {
+    serve();
+    let url = get_song_url(186016, SoundQuality::ExHigh).await.unwrap();
+    assert_eq!(url, "http://m701.music.126.net/20261018/186016.mp3");
+}
+
+#[tokio::test]
+async fn ncm_lyric_keeps_every_track() {
+    serve();
+    let lyric = get_lryic(186016).await.unwrap();
+    assert!(lyric.lyric.unwrap().contains("[00:29.35]故事的小黄花"));
+    assert!(lyric.yrc.unwrap().starts_with("[29350,3050]"));
+    assert_eq!(lyric.tlyric.as_deref(), Some(""));
+    assert!(!lyric.is_pure_music);
+}
+
+#[tokio::test]
+async fn search_parses_songs_and_suggestions() {
+    serve();
+    let result = search_songs("周杰伦", 30, 0).await.unwrap();
+    assert_eq!(result.total, 300);
+    assert_eq!(result.items.len(), 2);
+    assert_eq!(result.items[0].album.name, "叶惠美");
+    assert_eq!(result.items[1].cover_url, pic_url_from_id(7746059418324672));
+
+    let suggest = search_suggest("周杰伦").await.unwrap();
+    assert_eq!(suggest.songs[0].id, 186016);
+    assert_eq!(suggest.songs[0].duration, 269000);
+    assert_eq!(
+        suggest.songs[0].cover_url,
+        pic_url_from_id(109951163200249252)
+    );
+    assert_eq!(suggest.artists[0].name, "周杰伦");
+    assert!(suggest.artists[0].avatar.is_some());
+    assert_eq!(suggest.albums[0].id, 18905);
+}
+
+#[tokio::test]
+async fn comments_parse_users_and_replies() {
+    serve();
+    let comments = get_song_comments(186016).await.unwrap();
+    assert_eq!(comments.song_id, 186016);
+    let hot = &comments.hot_comments[0];
+    assert_eq!(hot.id, 3011594);
+    assert_eq!(hot.liked_count, 1281045);
+    assert_eq!(hot.reply_count, 3210);
+    assert_eq!(hot.user.name, "风吹过的夏天");
+    assert_eq!(hot.time_str, "2015-01-01");
+
+    let reply = &comments.comments[0];
+    assert_eq!(reply.be_replied.len(), 1);
+    assert_eq!(reply.be_replied[0].id, 3011594);
+    assert_eq!(reply.be_replied[0].user.id, 12021);
+
+    let (list, has_more, cursor) = get_comments_new(CommentType::Song, 186016, 1, 3, "")
+        .await
+        .unwrap();
+    assert!(has_more);
+    assert_eq!(cursor, "1760745600000");
+    assert_eq!(list[0].time, 1760745600000);
+    assert!(list[0].be_replied.is_empty());
+}
+
+#[tokio::test]
+async fn home_blocks_map_resource_types() {
+    serve();
+    let blocks = get_home_block().await.unwrap();
+    let types: Vec<_> = blocks.iter().map(|block| &block.type_).collect();
+    assert!(matches!(types[0], HomeBlockType::Daily));
+    assert!(matches!(
+        types[1],
+        HomeBlockType::DailyCategory { tag_id: 10015, category_id: 1000, song_id }
+            if song_id == &[186016, 185811]
+    ));
+    assert!(matches!(types[2], HomeBlockType::Playlist(3136952023)));
+    assert!(matches!(types[3], HomeBlockType::Fm));
+    assert!(matches!(types[4], HomeBlockType::Queue(ids) if ids == &[186016, 185811]));
+    assert!(matches!(types[5], HomeBlockType::Artist(ids) if ids == &[6452, 5781]));
+    assert!(matches!(types[6], HomeBlockType::Unknown));
+    assert_eq!(blocks[2].title, "私人雷达");
+    assert_eq!(blocks[2].sub_title, "你的专属歌单");
+
+    let playlists = get_recommend_playlist().await.unwrap();
+    assert_eq!(playlists[0].play_count, 30491210);
+    assert_eq!(playlists[0].description, "根据你收藏的单曲推荐");
+}
+
+#[tokio::test]
+async fn qq_lyric_is_matched_and_decrypted() {
+    serve();
+    let lyric = fetch_qq_lyric_for_song(&qing_tian()).await.unwrap();
+    assert_eq!(lyric.yrc.as_deref(), Some(QQ_QRC));
+    assert_eq!(lyric.lyric.as_deref(), Some(QQ_QRC));
+    assert!(lyric.ytlrc.is_none());
+    assert!(!lyric.is_pure_music);
+}
+
+#[tokio::test]
+async fn amll_ttml_is_optional() {
+    serve();
+    let ttml = fetch_amll_ttml(AMLL_SONG_ID).await.unwrap().unwrap();
+    assert!(ttml.starts_with("<tt"));
+    assert!(fetch_amll_ttml(1).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn lyric_sources_follow_priority() {
+    serve();
+    // AMLL 有逐字 TTML 时优先使用
+    let lyric = get_lyric_for_song(&qing_tian()).await.unwrap();
+    assert!(lyric.lyric.unwrap().starts_with("<tt"));
+    assert!(lyric.yrc.is_none());
+
+    // 没有 AMLL 时使用网易云逐字歌词
+    let song = Song {
+        id: 185811,
+        ..qing_tian()
+    };
+    let lyric = get_lyric_for_song(&song).await.unwrap();
+    assert!(lyric.yrc.unwrap().starts_with("[29350,3050](29350,380,0)"));
+}
+
+#[tokio::test]
+async fn play_reports_are_accepted() {
+    serve();
+    report_play(PlayReport::Start {
+        song_id: 186016,
+        source_id: 3778678,
+    })
+    .await
+    .unwrap();
+    report_play(PlayReport::End {
+        song_id: 186016,
+        source_id: 0,
+        played_secs: 269,
+        reason: PlayEndReason::Finished,
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn user_record_reads_the_requested_range() {
+    serve();
+    let week = get_user_record(1, RecordRange::Week).await.unwrap();
+    assert_eq!(week.len(), 2);
+    assert_eq!(week[0].song.name, "晴天");
+    assert_eq!(week[0].song.artists[0].name, "周杰伦");
+    assert_eq!(week[0].song.album.id, 18905);
+    assert_eq!(week[1].score, 45);
+
+    let all = get_user_record(1, RecordRange::AllTime).await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].play_count, 328);
+}
+
+#[tokio::test]
+async fn intelligence_list_reads_song_info() {
+    serve();
+    let songs = get_intelligence_list(186016, 3778678, 186016)
+        .await
+        .unwrap();
+    assert_eq!(songs.len(), 2);
+    assert_eq!(songs[0].id, qing_tian().id);
+    assert_eq!(songs[1].name, "稻香");
+    assert_eq!(songs[1].album.name, "魔杰座");
+}
@@ -5,13 +5,17 @@
 pub mod album;
 pub mod amll;
 pub mod artist;
+pub mod cache;
 pub mod client;
 pub mod comment;
 pub mod custom_api;
 pub mod explore;
 pub mod lyric;
+#[cfg(test)]
+pub(crate) mod mock;
 pub mod model;
 pub mod mv;
+pub mod parse;
 pub mod playlist;
 pub mod qqmusic;
 pub mod recommend;
@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
-#[derive(Debug, Clone, Default, PartialEq)]
+/// 「我喜欢的音乐」歌单的 specialType
+pub const LIKED_SONGS_SPECIAL_TYPE: u64 = 5;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Playlist {
     pub id: u64,
     pub name: String,
@@ -10,8 +13,11 @@ pub struct Playlist {
     pub creator_id: u64,
     pub description: String,
     pub play_count: u64,
+    /// 用户的「我喜欢的音乐」，心动模式只能从这里开始
+    #[serde(default)]
+    pub liked_songs: bool,
 }
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct HomeBlock {
     pub type_: HomeBlockType,
     pub title: String,
@@ -20,7 +26,7 @@ pub struct HomeBlock {
     pub color: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum HomeBlockType {
     Playlist(u64),
     Artist(Vec<u64>),
@@ -49,6 +55,7 @@ impl Playlist {
             creator_id: 0,
             description: "根据你的音乐口味生成, 每日6:00更新".into(),
             play_count: 0,
+            liked_songs: false,
         }
     }
 
@@ -62,6 +69,7 @@ impl Playlist {
             creator_id: artist_id,
             description: format!("{}最火的歌曲", artist_name),
             play_count: 0,
+            liked_songs: false,
         }
     }
 
@@ -75,11 +83,26 @@ impl Playlist {
             creator_id: 0,
             description: String::new(),
             play_count: 0,
+            liked_songs: false,
+        }
+    }
+
+    // 心动模式：仍指向「我喜欢的音乐」，点击歌单名回到原歌单
+    pub fn from_intelligence(playlist_id: u64, seed: &Song) -> Self {
+        Self {
+            id: playlist_id,
+            name: "心动模式".into(),
+            cover_url: seed.cover_url.clone(),
+            creator_name: "网易云音乐".into(),
+            creator_id: 0,
+            description: format!("从「{}」开始的推荐", seed.name),
+            play_count: 0,
+            liked_songs: true,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistDetail {
     pub id: u64,
     pub name: String,
@@ -87,12 +110,16 @@ pub struct PlaylistDetail {
     pub creator_name: String,
     pub creator_id: u64,
     pub description: String,
+    pub tags: Vec<String>,
     pub play_count: u64,
+    /// 是否为用户的「我喜欢的音乐」，可以从这里开启心动模式
+    #[serde(default)]
+    pub liked_songs: bool,
     pub tracks: Vec<Song>,
     pub track_ids: Vec<u64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Song {
     pub id: u64,
     pub name: String,
@@ -102,7 +129,7 @@ pub struct Song {
     pub duration: u64,
 }
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Artist {
     pub id: u64,
     pub name: String,
@@ -126,7 +153,95 @@ pub struct ArtistDetail {
     pub mv_size: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+/// 歌手全部歌曲的排序方式（对应 `/artist/songs` 的 order 参数）
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ArtistSongOrder {
+    #[strum(serialize = "hot")]
+    Hot,
+    #[strum(serialize = "time")]
+    Time,
+}
+
+/// 听歌排行的统计范围（对应 `/user/record` 的 type 参数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordRange {
+    Week,
+    AllTime,
+}
+
+impl RecordRange {
+    pub fn record_type(&self) -> &'static str {
+        match self {
+            RecordRange::Week => "1",
+            RecordRange::AllTime => "0",
+        }
+    }
+
+    /// 响应中排行列表所在的字段
+    pub fn data_key(&self) -> &'static str {
+        match self {
+            RecordRange::Week => "weekData",
+            RecordRange::AllTime => "allData",
+        }
+    }
+}
+
+/// 听歌排行中的一首歌；score 是相对第一名的比例（0–100）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayRecord {
+    pub song: Song,
+    pub play_count: u64,
+    pub score: u64,
+}
+
+/// 新歌速递 / 新碟上架的地区筛选
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum MusicArea {
+    #[strum(serialize = "全部")]
+    All,
+    #[strum(serialize = "华语")]
+    Chinese,
+    #[strum(serialize = "欧美")]
+    Western,
+    #[strum(serialize = "日本")]
+    Japanese,
+    #[strum(serialize = "韩国")]
+    Korean,
+}
+
+impl MusicArea {
+    pub const ALL: [MusicArea; 5] = [
+        MusicArea::All,
+        MusicArea::Chinese,
+        MusicArea::Western,
+        MusicArea::Japanese,
+        MusicArea::Korean,
+    ];
+
+    /// `/top/song` 的 type（areaId）参数
+    pub fn song_type(&self) -> &'static str {
+        match self {
+            MusicArea::All => "0",
+            MusicArea::Chinese => "7",
+            MusicArea::Western => "96",
+            MusicArea::Japanese => "8",
+            MusicArea::Korean => "16",
+        }
+    }
+
+    /// `/album/new` 的 area 参数
+    pub fn album_area(&self) -> &'static str {
+        match self {
+            MusicArea::All => "ALL",
+            MusicArea::Chinese => "ZH",
+            MusicArea::Western => "EA",
+            MusicArea::Japanese => "JP",
+            MusicArea::Korean => "KR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Album {
     pub id: u64,
     pub name: String,
@@ -156,6 +271,7 @@ pub struct UserDetails {
     pub id: u64,
     pub name: String,
     pub avatar_url: String,
+    pub signature: String,
     pub follows: String,
     pub followeds: String,
     pub vip_type: String,
@@ -186,6 +302,8 @@ pub struct MvDetail {
     pub play_count: u64,
     pub brief_desc: String,
     pub artists: Vec<Artist>,
+    /// 可选的分辨率（如 1080、720），从高到低
+    pub resolutions: Vec<u32>,
 }
 
 #[derive(Display, Clone, PartialEq)]
@@ -254,6 +372,27 @@ pub struct Comment {
     pub time_str: String,
 }
 
+/// 评论所属的资源类型（对应 `/comment/new` 的 `type` 参数）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentType {
+    Song,
+    Mv,
+    Playlist,
+    Album,
+}
+
+impl CommentType {
+    /// 资源类型编号：0 = 歌曲, 1 = MV, 2 = 歌单, 3 = 专辑
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommentType::Song => "0",
+            CommentType::Mv => "1",
+            CommentType::Playlist => "2",
+            CommentType::Album => "3",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct MusicComment {
     pub song_id: u64,
@@ -2,13 +2,17 @@ use std::sync::OnceLock;
 use std::time::Duration;
 
 use moka::future::Cache;
-use ncm_api_rs::Query;
 
-use crate::api::{Artist, Mv, MvDetail, client::client, get_artist_mv};
+use crate::api::{
+    Artist, Mv, MvDetail,
+    client::{client, query},
+    get_artist_mv,
+};
 
-static MV_URL_CACHE: OnceLock<Cache<u64, String>> = OnceLock::new();
+/// 键为 (MV id, 分辨率)
+static MV_URL_CACHE: OnceLock<Cache<(u64, u32), String>> = OnceLock::new();
 
-fn mv_url_cache() -> &'static Cache<u64, String> {
+fn mv_url_cache() -> &'static Cache<(u64, u32), String> {
     MV_URL_CACHE.get_or_init(|| {
         Cache::builder()
             .max_capacity(200)
@@ -17,19 +21,21 @@ fn mv_url_cache() -> &'static Cache<u64, String> {
     })
 }
 
-/// 获取 MV 播放地址
-pub async fn get_mv_url(id: u64) -> anyhow::Result<String> {
-    if let Some(url) = mv_url_cache().get(&id).await {
+/// 获取 MV 播放地址，resolution 取自 [`MvDetail::resolutions`]
+pub async fn get_mv_url(id: u64, resolution: u32) -> anyhow::Result<String> {
+    if let Some(url) = mv_url_cache().get(&(id, resolution)).await {
         return Ok(url);
     }
 
-    let query = Query::new().param("id", &id.to_string()).param("r", "1080");
+    let query = query()
+        .param("id", &id.to_string())
+        .param("r", &resolution.to_string());
 
     match client().mv_url(&query).await {
         Ok(resp) => {
             if let Some(url) = resp.body["data"]["url"].as_str() {
                 let url = url.to_string();
-                mv_url_cache().insert(id, url.clone()).await;
+                mv_url_cache().insert((id, resolution), url.clone()).await;
                 Ok(url)
             } else {
                 Err(anyhow::anyhow!("该 MV 需要 VIP 或暂无播放资源"))
@@ -44,7 +50,7 @@ pub async fn get_mv_url(id: u64) -> anyhow::Result<String> {
 
 /// 获取 MV 详情
 pub async fn get_mv_detail(id: u64) -> anyhow::Result<MvDetail> {
-    let query = Query::new().param("mvid", &id.to_string());
+    let query = query().param("mvid", &id.to_string());
     match client().mv_detail(&query).await {
         Ok(resp) => {
             let data = resp.body["data"].as_object().unwrap();
@@ -80,6 +86,7 @@ pub async fn get_mv_detail(id: u64) -> anyhow::Result<MvDetail> {
                             .collect()
                     })
                     .unwrap_or_default(),
+                resolutions: parse_resolutions(data.get("brs")),
             })
         }
         Err(e) => {
@@ -89,6 +96,22 @@ pub async fn get_mv_detail(id: u64) -> anyhow::Result<MvDetail> {
     }
 }
 
+/// `brs` 形如 `[{"br": 1080, "size": ...}, ...]`
+fn parse_resolutions(brs: Option<&serde_json::Value>) -> Vec<u32> {
+    let mut resolutions: Vec<u32> = brs
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|br| br["br"].as_u64())
+                .map(|br| br as u32)
+                .collect()
+        })
+        .unwrap_or_default();
+    resolutions.sort_unstable_by(|a, b| b.cmp(a));
+    resolutions.dedup();
+    resolutions
+}
+
 /// 获取相关 MV
 ///
 /// 网易云 `/weapi/discovery/simiMV` 接口已失效（忽略 mvid 一律返回固定列表），
@@ -100,6 +123,6 @@ pub async fn get_simi_mv(id: u64) -> anyhow::Result<Vec<Mv>> {
         return Ok(vec![]);
     }
 
-    let mvs = get_artist_mv(artist_id).await?;
+    let (mvs, _) = get_artist_mv(artist_id, 0, 30).await?;
     Ok(mvs.into_iter().filter(|m| m.id != id).take(12).collect())
 }
@@ -0,0 +1,52 @@
+//! 各接口共用的 JSON 解析
+
+use crate::api::{Album, Artist, Song, pic_url_from_id};
+
+/// 解析接口返回的歌曲对象。
+/// 新接口用 ar / al / dt，旧接口（搜索、新歌速递等）用 artists / album / duration，两种都认；
+/// 没有 picUrl 时由 picId 生成封面地址
+pub fn parse_song(value: &serde_json::Value) -> Song {
+    let album = if value["al"].is_object() {
+        &value["al"]
+    } else {
+        &value["album"]
+    };
+    let cover_url = match album["picUrl"].as_str().filter(|url| !url.is_empty()) {
+        Some(url) => url.to_string(),
+        None => album["picId"]
+            .as_u64()
+            .filter(|&id| id != 0)
+            .map(pic_url_from_id)
+            .unwrap_or_default(),
+    };
+    let artists = value["ar"]
+        .as_array()
+        .filter(|arr| !arr.is_empty())
+        .or_else(|| value["artists"].as_array())
+        .map(|arr| {
+            arr.iter()
+                .map(|a| Artist {
+                    id: a["id"].as_u64().unwrap_or(0),
+                    name: a["name"].as_str().unwrap_or("").to_string(),
+                    avatar: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Song {
+        id: value["id"].as_u64().unwrap_or(0),
+        name: value["name"].as_str().unwrap_or("").to_string(),
+        cover_url: cover_url.clone(),
+        artists,
+        album: Album {
+            id: album["id"].as_u64().unwrap_or(0),
+            name: album["name"].as_str().unwrap_or("").to_string(),
+            cover_url,
+        },
+        duration: value["dt"]
+            .as_u64()
+            .or_else(|| value["duration"].as_u64())
+            .unwrap_or(0),
+    }
+}
@@ -1,50 +1,8 @@
-use ncm_api_rs::Query;
-
-use crate::api::{Album, Artist, PlaylistDetail, Song, client::client};
-
-fn parse_song(value: &serde_json::Value) -> Song {
-    use crate::api::pic_url_from_id;
-
-    let artists = value["ar"].as_array().cloned().unwrap_or_default();
-    let alnum = value["al"].as_object().cloned().unwrap_or_default();
-    let pic_url = alnum
-        .get("picUrl")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    let pic_id = alnum.get("picId").and_then(|v| v.as_u64()).unwrap_or(0);
-    let cover_url = if pic_url.is_empty() && pic_id != 0 {
-        pic_url_from_id(pic_id)
-    } else {
-        pic_url
-    };
-    let artist_list = artists
-        .iter()
-        .map(|artist| Artist {
-            id: artist["id"].as_u64().unwrap_or(0),
-            name: artist["name"].as_str().unwrap_or("").to_string(),
-            avatar: None,
-            // cover_url: artist["picUrl"].as_str().unwrap_or("").to_string(),
-        })
-        .collect::<Vec<_>>();
-
-    Song {
-        id: value["id"].as_u64().unwrap_or(0),
-        name: value["name"].as_str().unwrap_or("").to_string(),
-        cover_url: cover_url.clone(),
-        artists: artist_list,
-        album: Album {
-            id: alnum.get("id").and_then(|v| v.as_u64()).unwrap_or(0),
-            name: alnum
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            cover_url,
-        },
-        duration: value["dt"].as_u64().unwrap_or(0),
-    }
-}
+use crate::api::{
+    ApiClientExt, LIKED_SONGS_SPECIAL_TYPE, PlaylistDetail, Song, cache,
+    client::{client, client_ext, query},
+    parse::parse_song,
+};
 
 // 分页获取歌单歌曲：直接用已知的 track_ids 切片 + 只请求一次歌曲详情接口，
 // 避免每次分页都重新请求歌单详情（/playlist/track/all 内部会先拉一次完整详情）
@@ -63,7 +21,7 @@ pub async fn get_playlist_track_all(
         .map(|id| id.to_string())
         .collect::<Vec<_>>()
         .join(",");
-    let query = Query::new().param("ids", &ids);
+    let query = query().param("ids", &ids);
 
     match client().song_detail(&query).await {
         Ok(resp) => {
@@ -78,7 +36,7 @@ pub async fn get_playlist_track_all(
 }
 
 pub async fn get_playlist_detail(id: u64) -> anyhow::Result<PlaylistDetail> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
 
     match client().playlist_detail(&query).await {
         Ok(resp) => {
@@ -97,7 +55,17 @@ pub async fn get_playlist_detail(id: u64) -> anyhow::Result<PlaylistDetail> {
                 creator_name: pl["creator"]["nickname"].as_str().unwrap_or("").to_string(),
                 creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
                 description: pl["description"].as_str().unwrap_or("").to_string(),
+                tags: pl["tags"]
+                    .as_array()
+                    .map(|tags| {
+                        tags.iter()
+                            .filter_map(|t| t.as_str())
+                            .map(|t| t.to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
                 play_count: pl["playCount"].as_u64().unwrap_or(0),
+                liked_songs: pl["specialType"].as_u64() == Some(LIKED_SONGS_SPECIAL_TYPE),
                 tracks: track_list,
                 track_ids: track_id_list,
             })
@@ -111,12 +79,15 @@ pub async fn get_playlist_detail(id: u64) -> anyhow::Result<PlaylistDetail> {
 
 // 收藏/取消收藏歌单
 pub async fn playlist_subscribe(id: u64, subscribe: bool) -> anyhow::Result<()> {
-    let query = Query::new()
+    let query = query()
         .param("id", &id.to_string())
         .param("t", if subscribe { "1" } else { "0" });
 
     match client().playlist_subscribe(&query).await {
-        Ok(_) => Ok(()),
+        Ok(_) => {
+            cache::invalidate_prefix(cache::key::USER_PLAYLIST_PREFIX);
+            Ok(())
+        }
         Err(e) => {
             eprintln!("操作歌单失败: {}", e);
             Err(e.into())
@@ -126,11 +97,12 @@ pub async fn playlist_subscribe(id: u64, subscribe: bool) -> anyhow::Result<()>
 
 // 新建歌单
 pub async fn playlist_create(name: &str) -> anyhow::Result<u64> {
-    let query = Query::new().param("name", name);
+    let query = query().param("name", name);
 
     match client().playlist_create(&query).await {
         Ok(resp) => {
             let id = resp.body["id"].as_u64().unwrap_or(0);
+            cache::invalidate_prefix(cache::key::USER_PLAYLIST_PREFIX);
             Ok(id)
         }
         Err(e) => {
@@ -142,10 +114,13 @@ pub async fn playlist_create(name: &str) -> anyhow::Result<u64> {
 
 // 删除歌单
 pub async fn playlist_delete(id: u64) -> anyhow::Result<()> {
-    let query = Query::new().param("id", &id.to_string());
+    let query = query().param("id", &id.to_string());
 
     match client().playlist_delete(&query).await {
-        Ok(_) => Ok(()),
+        Ok(_) => {
+            cache::invalidate_prefix(cache::key::USER_PLAYLIST_PREFIX);
+            Ok(())
+        }
         Err(e) => {
             eprintln!("删除歌单失败: {}", e);
             Err(e.into())
@@ -155,12 +130,16 @@ pub async fn playlist_delete(id: u64) -> anyhow::Result<()> {
 
 // 喜欢音乐
 pub async fn like_song(id: u64, like: bool) -> anyhow::Result<()> {
-    let query = Query::new()
+    let query = query()
         .param("id", &id.to_string())
         .param("like", if like { "true" } else { "false" });
 
     match client().like(&query).await {
-        Ok(_) => Ok(()),
+        Ok(_) => {
+            // 这里拿不到「我喜欢的音乐」的歌单 id，只能让所有歌单详情失效
+            cache::invalidate_prefix(cache::key::PLAYLIST_DETAIL_PREFIX);
+            Ok(())
+        }
         Err(e) => {
             eprintln!("操作喜欢失败: {}", e);
             Err(e.into())
@@ -170,7 +149,7 @@ pub async fn like_song(id: u64, like: bool) -> anyhow::Result<()> {
 
 // 是否喜欢音乐
 pub async fn is_like_song(id: u64) -> anyhow::Result<bool> {
-    let query = Query::new().param("ids", &format!("[{}]", id));
+    let query = query().param("ids", &format!("[{}]", id));
 
     match client().song_like_check(&query).await {
         Ok(resp) => {
@@ -184,9 +163,39 @@ pub async fn is_like_song(id: u64) -> anyhow::Result<bool> {
     }
 }
 
+/// 心动模式：以喜欢的音乐中的 seed_id 为种子，从 start_id 往后推荐一批歌曲。
+/// 返回的列表可能包含种子本身
+pub async fn get_intelligence_list(
+    seed_id: u64,
+    playlist_id: u64,
+    start_id: u64,
+) -> anyhow::Result<Vec<Song>> {
+    let query = query()
+        .param("id", &seed_id.to_string())
+        .param("pid", &playlist_id.to_string())
+        .param("sid", &start_id.to_string());
+
+    match client().playmode_intelligence_list(&query).await {
+        Ok(resp) => Ok(resp.body["data"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| parse_song(&item["songInfo"]))
+                    .filter(|song| song.id != 0)
+                    .collect()
+            })
+            .unwrap_or_default()),
+        Err(e) => {
+            eprintln!("获取心动模式列表失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
 // 添加歌曲到歌单
 pub async fn playlist_track_add(pid: u64, track_id: u64) -> anyhow::Result<()> {
-    let query = Query::new()
+    let query = query()
         .param("op", "add")
         .param("pid", &pid.to_string())
         .param("tracks", &track_id.to_string());
@@ -195,6 +204,7 @@ pub async fn playlist_track_add(pid: u64, track_id: u64) -> anyhow::Result<()> {
         Ok(resp) => {
             eprintln!("返回结果：{:?}", resp.body);
             eprintln!("添加歌曲到歌单成功");
+            cache::invalidate(&cache::key::playlist_detail(pid));
             Ok(())
         }
         Err(e) => {
@@ -204,17 +214,113 @@ pub async fn playlist_track_add(pid: u64, track_id: u64) -> anyhow::Result<()> {
     }
 }
 
-pub async fn playlist_track_del(pid: u64, track_id: u64) -> anyhow::Result<()> {
-    let query = Query::new()
+// 从歌单删除歌曲（支持多首）
+pub async fn playlist_track_del(pid: u64, track_ids: &[u64]) -> anyhow::Result<()> {
+    let tracks = track_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let query = query()
         .param("op", "del")
         .param("pid", &pid.to_string())
-        .param("tracks", &track_id.to_string());
+        .param("tracks", &tracks);
 
     match client().playlist_tracks(&query).await {
-        Ok(_) => Ok(()),
+        Ok(_) => {
+            cache::invalidate(&cache::key::playlist_detail(pid));
+            Ok(())
+        }
         Err(e) => {
             eprintln!("歌单删除歌曲失败: {}", e);
             Err(e.into())
         }
     }
 }
+
+// 重命名歌单
+pub async fn playlist_rename(id: u64, name: &str) -> anyhow::Result<()> {
+    let query = query().param("id", &id.to_string()).param("name", name);
+
+    match client().playlist_name_update(&query).await {
+        Ok(_) => {
+            cache::invalidate(&cache::key::playlist_detail(id));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("重命名歌单失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+// 更新歌单描述
+pub async fn playlist_desc_update(id: u64, desc: &str) -> anyhow::Result<()> {
+    let query = query().param("id", &id.to_string()).param("desc", desc);
+
+    match client().playlist_desc_update(&query).await {
+        Ok(_) => {
+            cache::invalidate(&cache::key::playlist_detail(id));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("更新歌单描述失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+// 更新歌单标签（网易云最多 3 个，以 ; 分隔）
+pub async fn playlist_tags_update(id: u64, tags: &[String]) -> anyhow::Result<()> {
+    let query = query()
+        .param("id", &id.to_string())
+        .param("tags", &tags.join(";"));
+
+    match client().playlist_tags_update(&query).await {
+        Ok(_) => {
+            cache::invalidate(&cache::key::playlist_detail(id));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("更新歌单标签失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+// 上传歌单封面，返回新封面地址
+pub async fn playlist_cover_update(id: u64, img_data: Vec<u8>) -> anyhow::Result<String> {
+    let query = query().param("id", &id.to_string());
+
+    match client_ext().playlist_cover_upload(&query, img_data).await {
+        Ok(resp) => {
+            cache::invalidate(&cache::key::playlist_detail(id));
+            Ok(resp.body["data"]["url"]
+                .as_str()
+                .or_else(|| resp.body["url_pre"].as_str())
+                .unwrap_or("")
+                .to_string())
+        }
+        Err(e) => {
+            eprintln!("上传歌单封面失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+// 调整歌单内歌曲顺序（传入完整的新顺序）
+pub async fn playlist_track_reorder(pid: u64, track_ids: &[u64]) -> anyhow::Result<()> {
+    let ids = serde_json::to_string(track_ids)?;
+    let query = query().param("pid", &pid.to_string()).param("ids", &ids);
+
+    match client().song_order_update(&query).await {
+        Ok(_) => {
+            cache::invalidate(&cache::key::playlist_detail(pid));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("调整歌曲顺序失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
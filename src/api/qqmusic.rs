@@ -12,7 +12,7 @@ use reqwest::header::{ACCEPT_ENCODING, CONTENT_TYPE, COOKIE, HeaderMap, HeaderVa
 use serde_json::{Value, json};
 use std::io::Read;
 
-use super::{LyricDetail, Song};
+use super::{LyricDetail, Song, client::service_url};
 
 const BASE_URL: &str = "https://u.y.qq.com/cgi-bin/musicu.fcg";
 const KEY1: &[u8; 16] = b"!@#)(NHLiuy*$%^&";
@@ -94,7 +94,7 @@ pub async fn search_qqmusic(
     });
 
     let response = reqwest::Client::new()
-        .post(BASE_URL)
+        .post(service_url(BASE_URL))
         .headers(headers())
         .body(serde_json::to_vec(&body)?)
         .send()
@@ -716,6 +716,23 @@ fn decode_lyric(encrypted_hex: &str) -> anyhow::Result<String> {
     Ok(xml.into_owned())
 }
 
+/// Inverse of `decode_lyric`, used by the mock server to serve encrypted
+/// QRC payloads.
+#[cfg(test)]
+pub(crate) fn encode_lyric(plain: &str) -> String {
+    use flate2::{Compression, write::ZlibEncoder};
+    use std::io::Write;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(plain.as_bytes()).unwrap();
+    let mut data = encoder.finish().unwrap();
+    data.resize(data.len().div_ceil(8) * 8, 0);
+    data = des_ecb(&data, KEY3, false).unwrap();
+    data = des_ecb(&data, KEY2, true).unwrap();
+    data = des_ecb(&data, KEY1, false).unwrap();
+    data.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
 /// Fetch and decode QQ lyric data for a searched song.
 pub async fn fetch_qq_lyric(song: &QqMusicSong) -> anyhow::Result<LyricDetail> {
     log::debug!("[lyrics][qq] requesting song_id={}", song.song_id);
@@ -738,7 +755,7 @@ pub async fn fetch_qq_lyric(song: &QqMusicSong) -> anyhow::Result<LyricDetail> {
         }
     });
     let response = reqwest::Client::new()
-        .post(BASE_URL)
+        .post(service_url(BASE_URL))
         .headers(headers())
         .body(serde_json::to_vec(&body)?)
         .send()
@@ -808,7 +825,7 @@ pub async fn fetch_qq_lyric_for_song(song: &Song) -> anyhow::Result<LyricDetail>
 
 #[cfg(test)]
 mod tests {
-    use super::{decode_hex, des_ecb, fetch_qq_lyric_for_song};
+    use super::{decode_hex, decode_lyric, des_ecb, encode_lyric, fetch_qq_lyric_for_song};
     use crate::api::{Artist, Song};
     use crate::utils::lyric_parse::parse_lyric;
 
@@ -828,6 +845,12 @@ mod tests {
         assert_eq!(des_ecb(&encrypted, &key16, true).unwrap(), plain);
     }
 
+    #[test]
+    fn encoded_lyric_roundtrip() {
+        let qrc = "[29350,3050]故(29350,380)事(29730,380)";
+        assert_eq!(decode_lyric(&encode_lyric(qrc)).unwrap(), qrc);
+    }
+
     /// Live regression test for the song from the reported zero-line log.
     /// Run explicitly with `cargo test -- --ignored --nocapture` when network
     /// access to QQ Music is available.
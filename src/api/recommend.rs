@@ -1,12 +1,11 @@
-use ncm_api_rs::Query;
-
 use crate::api::{
-    Album, ApiClientExt, Artist, HomeBlock, HomeBlockType, Playlist, Song,
-    client::{client, client_ext},
+    ApiClientExt, HomeBlock, HomeBlockType, Playlist, Song,
+    client::{client, client_ext, query},
+    parse::parse_song,
 };
 
 pub async fn get_recommend_playlist() -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new();
+    let query = query();
     match client().recommend_resource(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -20,6 +19,7 @@ pub async fn get_recommend_playlist() -> anyhow::Result<Vec<Playlist>> {
                         creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
                         description: pl["copywriter"].as_str().unwrap_or("").to_string(),
                         play_count: pl["playcount"].as_u64().unwrap_or(0),
+                        liked_songs: false,
                     });
                 }
             }
@@ -33,7 +33,7 @@ pub async fn get_recommend_playlist() -> anyhow::Result<Vec<Playlist>> {
 }
 
 pub async fn get_recommend_song() -> anyhow::Result<Vec<Song>> {
-    let query = Query::new();
+    let query = query();
     match client().recommend_songs(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -42,28 +42,7 @@ pub async fn get_recommend_song() -> anyhow::Result<Vec<Song>> {
                 .cloned()
                 .unwrap_or_default();
             for song in songs {
-                res.push(Song {
-                    id: song["id"].as_u64().unwrap_or(0),
-                    name: song["name"].as_str().unwrap_or("").to_string(),
-                    cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    artists: song["ar"]
-                        .as_array()
-                        .cloned()
-                        .unwrap_or_default()
-                        .iter()
-                        .map(|artist| Artist {
-                            id: artist["id"].as_u64().unwrap_or(0),
-                            name: artist["name"].as_str().unwrap_or("").to_string(),
-                            avatar: None,
-                        })
-                        .collect(),
-                    album: Album {
-                        id: song["al"]["id"].as_u64().unwrap_or(0),
-                        name: song["al"]["name"].as_str().unwrap_or("").to_string(),
-                        cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    },
-                    duration: song["dt"].as_u64().unwrap_or(0),
-                })
+                res.push(parse_song(&song));
             }
             Ok(res)
         }
@@ -75,7 +54,7 @@ pub async fn get_recommend_song() -> anyhow::Result<Vec<Song>> {
 }
 
 pub async fn get_home_block() -> anyhow::Result<Vec<HomeBlock>> {
-    let query = Query::new();
+    let query = query();
     match client_ext().home_recommend_resource(&query).await {
         Ok(resp) => {
             // eprintln!("获取首页块成功: {}", resp.body);
@@ -170,7 +149,7 @@ pub async fn get_home_category_daily_song_list(
     category_id: u64,
     tag_id: u64,
 ) -> anyhow::Result<Vec<Song>> {
-    let query = Query::new()
+    let query = query()
         .param(
             "song_ids",
             ids.iter()
@@ -190,28 +169,7 @@ pub async fn get_home_category_daily_song_list(
                 .cloned()
                 .unwrap_or_default()
                 .iter()
-                .map(|song| Song {
-                    id: song["id"].as_u64().unwrap_or(0),
-                    name: song["name"].as_str().unwrap_or("").to_string(),
-                    cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    artists: song["ar"]
-                        .as_array()
-                        .cloned()
-                        .unwrap_or_default()
-                        .iter()
-                        .map(|artist| Artist {
-                            id: artist["id"].as_u64().unwrap_or(0),
-                            name: artist["name"].as_str().unwrap_or("").to_string(),
-                            avatar: None,
-                        })
-                        .collect(),
-                    album: Album {
-                        id: song["al"]["id"].as_u64().unwrap_or(0),
-                        name: song["al"]["name"].as_str().unwrap_or("").to_string(),
-                        cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    },
-                    duration: song["dt"].as_u64().unwrap_or(0),
-                })
+                .map(parse_song)
                 .collect();
 
             Ok(songs)
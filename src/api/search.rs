@@ -1,6 +1,4 @@
-use ncm_api_rs::Query;
-
-use crate::api::{Album, Artist, Playlist, Song, client::client};
+use crate::api::{Album, Artist, Playlist, Song, client::query, parse::parse_song};
 
 /// 网易云图片 URL 生成：picId 无法直接使用，需要先加密成 CDN 路径再拼 URL。
 /// 加密方式：id 字符串与固定 key 逐字节异或 -> md5 -> base64（/ 变 _，+ 变 -），
@@ -64,7 +62,7 @@ async fn search_body(
     limit: u64,
     offset: u64,
 ) -> anyhow::Result<serde_json::Value> {
-    let query = Query::new()
+    let query = query()
         .param("keywords", keywords)
         .param("type", &search_type.code().to_string())
         .param("limit", &limit.to_string())
@@ -79,47 +77,6 @@ async fn search_body(
     }
 }
 
-fn parse_song(value: &serde_json::Value) -> Song {
-    let alnum = value["al"].as_object().cloned().unwrap_or_default();
-    let pic_url = alnum
-        .get("picUrl")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    let pic_id = alnum.get("picId").and_then(|v| v.as_u64()).unwrap_or(0);
-    let cover_url = if pic_url.is_empty() && pic_id != 0 {
-        pic_url_from_id(pic_id)
-    } else {
-        pic_url
-    };
-    Song {
-        id: value["id"].as_u64().unwrap_or(0),
-        name: value["name"].as_str().unwrap_or("").to_string(),
-        cover_url: cover_url.clone(),
-        artists: value["ar"]
-            .as_array()
-            .cloned()
-            .unwrap_or_default()
-            .iter()
-            .map(|artist| Artist {
-                id: artist["id"].as_u64().unwrap_or(0),
-                name: artist["name"].as_str().unwrap_or("").to_string(),
-                avatar: None,
-            })
-            .collect(),
-        album: Album {
-            id: alnum.get("id").and_then(|v| v.as_u64()).unwrap_or(0),
-            name: alnum
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            cover_url,
-        },
-        duration: value["dt"].as_u64().unwrap_or(0),
-    }
-}
-
 fn parse_playlist(value: &serde_json::Value) -> Playlist {
     Playlist {
         id: value["id"].as_u64().unwrap_or(0),
@@ -132,6 +89,7 @@ fn parse_playlist(value: &serde_json::Value) -> Playlist {
         creator_id: value["creator"]["userId"].as_u64().unwrap_or(0),
         description: value["description"].as_str().unwrap_or("").to_string(),
         play_count: value["playCount"].as_u64().unwrap_or(0),
+        liked_songs: false,
     }
 }
 
@@ -251,7 +209,7 @@ pub struct SearchSuggest {
 
 /// 搜索建议（search_suggest），无需指定类型
 pub async fn search_suggest(keywords: &str) -> anyhow::Result<SearchSuggest> {
-    let query = Query::new().param("keywords", keywords);
+    let query = query().param("keywords", keywords);
 
     match crate::api::client::client().search_suggest(&query).await {
         Ok(resp) => {
@@ -324,8 +282,7 @@ pub async fn search_suggest(keywords: &str) -> anyhow::Result<SearchSuggest> {
 
 #[allow(dead_code)]
 pub(crate) async fn test_suggest_structure() {
-    use ncm_api_rs::Query;
-    let query = Query::new().param("keywords", "周杰伦");
+    let query = query().param("keywords", "周杰伦");
     let mut client = crate::api::client::client();
     let resp = client.search_suggest(&query).await.unwrap();
     println!(
@@ -336,9 +293,8 @@ pub(crate) async fn test_suggest_structure() {
 
 #[allow(dead_code)]
 pub(crate) async fn test_multimatch_structure() {
-    use ncm_api_rs::Query;
     for kw in ["周杰伦", "晴天", "七里香 专辑"] {
-        let query = Query::new().param("keywords", kw);
+        let query = query().param("keywords", kw);
         let mut client = crate::api::client::client();
         let resp = client.search_multimatch(&query).await.unwrap();
         let result = resp.body["result"].as_object().cloned().unwrap_or_default();
@@ -2,9 +2,12 @@ use std::sync::OnceLock;
 use std::time::Duration;
 
 use moka::future::Cache;
-use ncm_api_rs::Query;
 
-use crate::api::{Album, Artist, Song, SoundQuality, client::client};
+use crate::api::{
+    ApiClientExt, Song, SoundQuality,
+    client::{client, client_ext, query},
+    parse::parse_song,
+};
 
 static URL_CACHE: OnceLock<Cache<(u64, String), String>> = OnceLock::new();
 
@@ -17,13 +20,50 @@ fn url_cache() -> &'static Cache<(u64, String), String> {
     })
 }
 
+/// 歌曲本身无法播放（不是网络问题），重试没有意义
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unplayable {
+    /// 无版权 / 下架 / 地区限制
+    NoCopyright,
+    /// 仅限 VIP
+    VipOnly,
+    /// 需单独购买专辑
+    Paid,
+    Unavailable,
+}
+
+impl Unplayable {
+    fn from_url_data(data: &serde_json::Value) -> Self {
+        match (data["code"].as_i64(), data["fee"].as_i64()) {
+            (Some(404), _) => Unplayable::NoCopyright,
+            (_, Some(1)) => Unplayable::VipOnly,
+            (_, Some(4)) => Unplayable::Paid,
+            _ => Unplayable::Unavailable,
+        }
+    }
+}
+
+impl std::fmt::Display for Unplayable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Unplayable::NoCopyright => "暂无版权",
+            Unplayable::VipOnly => "仅限 VIP",
+            Unplayable::Paid => "需购买专辑",
+            Unplayable::Unavailable => "暂时无法播放",
+        })
+    }
+}
+
+impl std::error::Error for Unplayable {}
+
+/// 失败时若歌曲本身不可播放，错误可 `downcast_ref::<Unplayable>()`
 pub async fn get_song_url(id: u64, quality: SoundQuality) -> anyhow::Result<String> {
     let key = (id, quality.to_string());
     if let Some(url) = url_cache().get(&key).await {
         return Ok(url);
     }
 
-    let query = Query::new()
+    let query = query()
         .param("id", &id.to_string())
         .param("level", &quality.to_string());
 
@@ -34,7 +74,7 @@ pub async fn get_song_url(id: u64, quality: SoundQuality) -> anyhow::Result<Stri
                 url_cache().insert(key, url.clone()).await;
                 Ok(url)
             } else {
-                Err(anyhow::anyhow!("未找到歌曲URL"))
+                Err(Unplayable::from_url_data(&resp.body["data"][0]).into())
             }
         }
         Err(e) => {
@@ -44,8 +84,14 @@ pub async fn get_song_url(id: u64, quality: SoundQuality) -> anyhow::Result<Stri
     }
 }
 
+/// 丢弃缓存重新获取播放链接（链接过期时使用）
+pub async fn refresh_song_url(id: u64, quality: SoundQuality) -> anyhow::Result<String> {
+    url_cache().invalidate(&(id, quality.to_string())).await;
+    get_song_url(id, quality).await
+}
+
 pub async fn get_song_detail(ids: Vec<u64>) -> anyhow::Result<Vec<Song>> {
-    let query = Query::new().param(
+    let query = query().param(
         "ids",
         ids.iter()
             .map(u64::to_string)
@@ -59,27 +105,7 @@ pub async fn get_song_detail(ids: Vec<u64>) -> anyhow::Result<Vec<Song>> {
             let songs = resp.body["songs"].as_array().unwrap();
             let mut song_lsit = Vec::new();
             for song in songs {
-                song_lsit.push(Song {
-                    id: song["id"].as_u64().unwrap_or(0),
-                    name: song["name"].as_str().unwrap_or("").to_string(),
-                    cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    artists: song["ar"]
-                        .as_array()
-                        .unwrap_or(&vec![])
-                        .iter()
-                        .map(|artist| Artist {
-                            id: artist["id"].as_u64().unwrap_or(0),
-                            name: artist["name"].as_str().unwrap_or("").to_string(),
-                            avatar: None,
-                        })
-                        .collect::<Vec<Artist>>(),
-                    album: Album {
-                        id: song["al"]["id"].as_u64().unwrap_or(0),
-                        name: song["al"]["name"].as_str().unwrap_or("").to_string(),
-                        cover_url: song["al"]["picUrl"].as_str().unwrap_or("").to_string(),
-                    },
-                    duration: song["dt"].as_u64().unwrap_or(0),
-                });
+                song_lsit.push(parse_song(song));
             }
             Ok(song_lsit)
         }
@@ -89,3 +115,68 @@ pub async fn get_song_detail(ids: Vec<u64>) -> anyhow::Result<Vec<Song>> {
         }
     }
 }
+
+/// 一首歌播放结束的原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlayEndReason {
+    /// 自然播完
+    Finished,
+    /// 用户切歌
+    Skipped,
+    /// 停止播放或退出应用
+    Interrupted,
+}
+
+impl PlayEndReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            PlayEndReason::Finished => "playend",
+            PlayEndReason::Skipped => "ui",
+            PlayEndReason::Interrupted => "interrupt",
+        }
+    }
+}
+
+/// 上报给网易云的播放记录，计入听歌排行与每日推荐；source_id 为来源歌单，0 表示没有
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayReport {
+    Start {
+        song_id: u64,
+        source_id: u64,
+    },
+    End {
+        song_id: u64,
+        source_id: u64,
+        played_secs: u64,
+        reason: PlayEndReason,
+    },
+}
+
+pub async fn report_play(report: PlayReport) -> anyhow::Result<()> {
+    let source = |id: u64| {
+        if id == 0 {
+            String::new()
+        } else {
+            id.to_string()
+        }
+    };
+    let query = match report {
+        PlayReport::Start { song_id, source_id } => query()
+            .param("action", "startplay")
+            .param("id", &song_id.to_string())
+            .param("sourceid", &source(source_id)),
+        PlayReport::End {
+            song_id,
+            source_id,
+            played_secs,
+            reason,
+        } => query()
+            .param("action", "play")
+            .param("id", &song_id.to_string())
+            .param("sourceid", &source(source_id))
+            .param("time", &played_secs.to_string())
+            .param("end", reason.as_str()),
+    };
+    client_ext().play_log(&query).await?;
+    Ok(())
+}
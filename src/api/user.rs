@@ -1,12 +1,32 @@
-use ncm_api_rs::Query;
+use ncm_api_rs::error::NcmError;
 
-use crate::api::{Album, Playlist, UserCounts, UserDetails, UserInfo, client::client};
+use crate::api::{
+    Album, Artist, LIKED_SONGS_SPECIAL_TYPE, PlayRecord, Playlist, RecordRange, UserCounts,
+    UserDetails, UserInfo,
+    client::{client, query},
+    parse::parse_song,
+};
+
+/// 对方没有公开听歌排行
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordHidden;
+
+impl std::fmt::Display for RecordHidden {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("该用户未公开听歌排行")
+    }
+}
+
+impl std::error::Error for RecordHidden {}
 
 pub async fn get_user_info() -> anyhow::Result<UserInfo> {
-    let query = Query::new();
+    let query = query();
     match client().user_account(&query).await {
         Ok(resp) => {
-            let user = resp.body["profile"].as_object().unwrap();
+            let Some(user) = resp.body["profile"].as_object() else {
+                eprintln!("获取用户详情失败: {}", resp.body);
+                return Err(anyhow::anyhow!("用户不存在"));
+            };
             Ok(UserInfo {
                 id: user["userId"].as_u64().unwrap_or(0),
                 name: user["nickname"].as_str().unwrap_or("").to_string(),
@@ -21,7 +41,7 @@ pub async fn get_user_info() -> anyhow::Result<UserInfo> {
 }
 
 pub async fn get_user_subcount() -> anyhow::Result<UserCounts> {
-    let query = Query::new();
+    let query = query();
     match client().user_subcount(&query).await {
         Ok(resp) => {
             // let subcount = ;
@@ -35,7 +55,7 @@ pub async fn get_user_subcount() -> anyhow::Result<UserCounts> {
 }
 
 pub async fn get_user_sub_album() -> anyhow::Result<Vec<Album>> {
-    let query = Query::new();
+    let query = query();
     match client().album_sublist(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -56,8 +76,32 @@ pub async fn get_user_sub_album() -> anyhow::Result<Vec<Album>> {
         }
     }
 }
+/// 关注的歌手
+pub async fn get_user_sub_artist() -> anyhow::Result<Vec<Artist>> {
+    let query = query().param("limit", "1000");
+    match client().artist_sublist(&query).await {
+        Ok(resp) => {
+            let mut res = Vec::new();
+            if let Some(artists) = resp.body["data"].as_array() {
+                for artist in artists {
+                    res.push(Artist {
+                        id: artist["id"].as_u64().unwrap_or(0),
+                        name: artist["name"].as_str().unwrap_or("").to_string(),
+                        avatar: artist["picUrl"].as_str().map(|s| s.to_string()),
+                    })
+                }
+            }
+            Ok(res)
+        }
+        Err(e) => {
+            eprintln!("获取关注歌手失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
 pub async fn get_user_playlist(uid: u64) -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new().param("uid", &uid.to_string());
+    let query = query().param("uid", &uid.to_string());
     match client().user_playlist(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -71,6 +115,7 @@ pub async fn get_user_playlist(uid: u64) -> anyhow::Result<Vec<Playlist>> {
                         creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
                         description: pl["description"].as_str().unwrap_or("").to_string(),
                         play_count: pl["playCount"].as_u64().unwrap_or(0),
+                        liked_songs: pl["specialType"].as_u64() == Some(LIKED_SONGS_SPECIAL_TYPE),
                     });
                 }
             }
@@ -84,7 +129,7 @@ pub async fn get_user_playlist(uid: u64) -> anyhow::Result<Vec<Playlist>> {
 }
 
 pub async fn get_user_playlist_created(uid: u64) -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new().param("uid", &uid.to_string());
+    let query = query().param("uid", &uid.to_string());
     match client().user_playlist_create(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -98,6 +143,7 @@ pub async fn get_user_playlist_created(uid: u64) -> anyhow::Result<Vec<Playlist>
                         creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
                         description: pl["description"].as_str().unwrap_or("").to_string(),
                         play_count: pl["playCount"].as_u64().unwrap_or(0),
+                        liked_songs: pl["specialType"].as_u64() == Some(LIKED_SONGS_SPECIAL_TYPE),
                     });
                 }
             } else {
@@ -114,7 +160,7 @@ pub async fn get_user_playlist_created(uid: u64) -> anyhow::Result<Vec<Playlist>
 }
 
 pub async fn get_user_playlist_collected(uid: u64) -> anyhow::Result<Vec<Playlist>> {
-    let query = Query::new().param("uid", &uid.to_string());
+    let query = query().param("uid", &uid.to_string());
     match client().user_playlist_collect(&query).await {
         Ok(resp) => {
             let mut res = Vec::new();
@@ -128,6 +174,7 @@ pub async fn get_user_playlist_collected(uid: u64) -> anyhow::Result<Vec<Playlis
                         creator_id: pl["creator"]["userId"].as_u64().unwrap_or(0),
                         description: pl["description"].as_str().unwrap_or("").to_string(),
                         play_count: pl["playCount"].as_u64().unwrap_or(0),
+                        liked_songs: pl["specialType"].as_u64() == Some(LIKED_SONGS_SPECIAL_TYPE),
                     });
                 }
             } else {
@@ -143,7 +190,7 @@ pub async fn get_user_playlist_collected(uid: u64) -> anyhow::Result<Vec<Playlis
 }
 
 pub async fn get_user_detail(uid: u64) -> anyhow::Result<UserDetails> {
-    let query = Query::new().param("uid", &uid.to_string());
+    let query = query().param("uid", &uid.to_string());
     match client().user_detail(&query).await {
         Ok(resp) => {
             let user = resp.body["profile"].as_object().unwrap();
@@ -152,6 +199,7 @@ pub async fn get_user_detail(uid: u64) -> anyhow::Result<UserDetails> {
                 id: user["userId"].as_u64().unwrap_or(0),
                 name: user["nickname"].as_str().unwrap_or("").to_string(),
                 avatar_url: user["avatarUrl"].as_str().unwrap_or("").to_string(),
+                signature: user["signature"].as_str().unwrap_or("").to_string(),
                 follows: user["follows"].as_u64().unwrap_or(0).to_string(),
                 followeds: user["followeds"].as_u64().unwrap_or(0).to_string(),
                 vip_type: user["vipType"].as_u64().unwrap_or(0).to_string(),
@@ -164,3 +212,31 @@ pub async fn get_user_detail(uid: u64) -> anyhow::Result<UserDetails> {
         }
     }
 }
+
+/// 听歌排行，失败时若对方未公开可 `downcast_ref::<RecordHidden>()`
+pub async fn get_user_record(uid: u64, range: RecordRange) -> anyhow::Result<Vec<PlayRecord>> {
+    let query = query()
+        .param("uid", &uid.to_string())
+        .param("type", range.record_type());
+    match client().user_record(&query).await {
+        Ok(resp) => Ok(resp.body[range.data_key()]
+            .as_array()
+            .map(|records| {
+                records
+                    .iter()
+                    .map(|record| PlayRecord {
+                        song: parse_song(&record["song"]),
+                        play_count: record["playCount"].as_u64().unwrap_or(0),
+                        score: record["score"].as_u64().unwrap_or(0),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()),
+        // 未公开时返回 code -2，被接口库归为参数错误
+        Err(NcmError::InvalidParam(_)) => Err(RecordHidden.into()),
+        Err(e) => {
+            eprintln!("获取听歌排行失败: {}", e);
+            Err(e.into())
+        }
+    }
+}
@@ -16,6 +16,7 @@ impl From<PlaylistDetail> for Playlist {
             creator_id: detail.creator_id,
             description: detail.description.clone(),
             play_count: detail.play_count,
+            liked_songs: detail.liked_songs,
         }
     }
 }
@@ -29,6 +30,7 @@ impl From<PlaylistDetail> for DetailView {
             creator: Some(p.creator_name),
             creator_id: p.creator_id,
             description: Some(p.description),
+            tags: std::mem::take(&mut p.tags),
             tracks: std::mem::take(&mut p.tracks),
             track_ids: std::mem::take(&mut p.track_ids),
         }
@@ -52,6 +54,7 @@ impl From<AlbumDetail> for DetailView {
             ),
             creator_id,
             description: Some(a.description),
+            tags: Vec::new(),
             tracks: std::mem::take(&mut a.tracks),
             track_ids,
         }
@@ -71,6 +74,7 @@ impl From<Vec<Song>> for DetailView {
             creator: Some("网易云音乐".into()),
             creator_id: 0,
             description: Some("根据你的音乐口味生成, 每日6:00更新".into()),
+            tags: Vec::new(),
             tracks: songs,
             track_ids,
         }
@@ -87,6 +91,7 @@ impl From<DetailView> for Playlist {
             creator_id: value.creator_id,
             description: value.description.unwrap_or_default(),
             play_count: 0,
+            liked_songs: false,
         }
     }
 }
@@ -105,6 +110,7 @@ impl From<AlbumDetail> for Playlist {
             creator_id: 0,
             description: value.description,
             play_count: 0,
+            liked_songs: false,
         }
     }
 }
@@ -5,12 +5,26 @@ use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
 
 use crate::APP_NAME;
+use crate::api::Song;
+use crate::player::effects::{AudioEffects, EQ_BAND_COUNT};
 use crate::player::messages::PlayMode;
+use crate::scrobble::Listen;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CollectType {
     Playlist,
     Album,
+    Artist,
+}
+
+impl CollectType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CollectType::Playlist => "playlist",
+            CollectType::Album => "album",
+            CollectType::Artist => "artist",
+        }
+    }
 }
 
 /// 上次播放会话的快照，用于启动时恢复。
@@ -24,6 +38,14 @@ pub struct SessionState {
     pub playlist_creator_name: String,
 }
 
+/// 本地播放历史中的一次播放（Unix 秒）
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayHistoryEntry {
+    pub song: Song,
+    pub played_at: i64,
+    pub played_secs: u64,
+}
+
 pub struct Db {
     conn: Connection,
 }
@@ -39,7 +61,16 @@ impl Db {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
-        let conn = Connection::open(&path)?;
+        Self::init(Connection::open(&path)?)
+    }
+
+    /// 测试用：不落盘的空数据库
+    #[cfg(test)]
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> anyhow::Result<Self> {
         conn.execute_batch(
             "PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;",
@@ -59,14 +90,38 @@ impl Db {
             )",
             [],
         )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS eq_presets (
+                name TEXT PRIMARY KEY,
+                gains TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS play_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                song TEXT NOT NULL,
+                played_at INTEGER NOT NULL,
+                played_secs INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS play_history_played_at ON play_history (played_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS scrobble_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                listen TEXT NOT NULL
+            )",
+            [],
+        )?;
         Ok(Self { conn })
     }
 
     pub fn is_collected(&self, item_id: u64, item_type: CollectType) -> bool {
-        let type_str = match item_type {
-            CollectType::Playlist => "playlist",
-            CollectType::Album => "album",
-        };
+        let type_str = item_type.as_str();
         self.conn
             .query_row(
                 "SELECT 1 FROM collected WHERE item_id = ?1 AND item_type = ?2",
@@ -77,10 +132,7 @@ impl Db {
     }
 
     pub fn set_collected(&self, item_id: u64, item_type: CollectType, collected: bool) {
-        let type_str = match item_type {
-            CollectType::Playlist => "playlist",
-            CollectType::Album => "album",
-        };
+        let type_str = item_type.as_str();
         if collected {
             let _ = self.conn.execute(
                 "INSERT OR IGNORE INTO collected (item_id, item_type) VALUES (?1, ?2)",
@@ -95,10 +147,7 @@ impl Db {
     }
 
     pub fn get_all_collected(&self, item_type: CollectType) -> HashSet<u64> {
-        let type_str = match item_type {
-            CollectType::Playlist => "playlist",
-            CollectType::Album => "album",
-        };
+        let type_str = item_type.as_str();
         let mut stmt = self
             .conn
             .prepare("SELECT item_id FROM collected WHERE item_type = ?1")
@@ -111,10 +160,7 @@ impl Db {
     }
 
     pub fn sync_collected(&self, item_type: CollectType, ids: &[u64]) {
-        let type_str = match item_type {
-            CollectType::Playlist => "playlist",
-            CollectType::Album => "album",
-        };
+        let type_str = item_type.as_str();
         let tx = self.conn.unchecked_transaction().unwrap();
         tx.execute(
             "DELETE FROM collected WHERE item_type = ?1",
@@ -161,6 +207,8 @@ impl Db {
             PlayMode::Sequential => "sequential",
             PlayMode::SingleLoop => "single_loop",
             PlayMode::Shuffle => "shuffle",
+            // 心动模式依赖当次的推荐状态，不保存
+            PlayMode::Intelligence => return,
         };
         self.set_setting("play_mode", value);
     }
@@ -173,6 +221,62 @@ impl Db {
         self.set_setting("loop_enabled", if enabled { "true" } else { "false" });
     }
 
+    pub fn get_audio_effects(&self) -> AudioEffects {
+        self.get_setting("audio_effects")
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_audio_effects(&self, effects: &AudioEffects) {
+        if let Ok(json) = serde_json::to_string(effects) {
+            self.set_setting("audio_effects", &json);
+        }
+    }
+
+    /// 用户选择的输出设备名称，None 为系统默认
+    pub fn get_output_device(&self) -> Option<String> {
+        self.get_setting("output_device")
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn set_output_device(&self, name: Option<&str>) {
+        self.set_setting("output_device", name.unwrap_or_default());
+    }
+
+    /// 用户保存的均衡器曲线，按名称排序
+    pub fn get_eq_presets(&self) -> Vec<(String, [f64; EQ_BAND_COUNT])> {
+        let Ok(mut stmt) = self
+            .conn
+            .prepare("SELECT name, gains FROM eq_presets ORDER BY name")
+        else {
+            return Vec::new();
+        };
+        stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map(|rows| {
+            rows.filter_map(|row| row.ok())
+                .filter_map(|(name, gains)| Some((name, serde_json::from_str(&gains).ok()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn save_eq_preset(&self, name: &str, gains: &[f64; EQ_BAND_COUNT]) {
+        if let Ok(json) = serde_json::to_string(gains) {
+            let _ = self.conn.execute(
+                "INSERT OR REPLACE INTO eq_presets (name, gains) VALUES (?1, ?2)",
+                params![name, json],
+            );
+        }
+    }
+
+    pub fn delete_eq_preset(&self, name: &str) {
+        let _ = self
+            .conn
+            .execute("DELETE FROM eq_presets WHERE name = ?1", params![name]);
+    }
+
     pub fn save_session(&self, session: &SessionState) {
         if let Ok(json) = serde_json::to_string(session) {
             self.set_setting("last_session", &json);
@@ -182,4 +286,79 @@ impl Db {
     pub fn load_session(&self) -> Option<SessionState> {
         serde_json::from_str::<SessionState>(self.get_setting("last_session")?.as_str()).ok()
     }
+
+    pub fn add_play(&self, song: &Song, played_at: i64, played_secs: u64) {
+        if let Ok(json) = serde_json::to_string(song) {
+            let _ = self.conn.execute(
+                "INSERT INTO play_history (song, played_at, played_secs) VALUES (?1, ?2, ?3)",
+                params![json, played_at, played_secs as i64],
+            );
+        }
+    }
+
+    /// [from, to) 时间段内的播放历史，按时间顺序
+    pub fn plays_between(&self, from: i64, to: i64) -> Vec<PlayHistoryEntry> {
+        let Ok(mut stmt) = self.conn.prepare(
+            "SELECT song, played_at, played_secs FROM play_history
+             WHERE played_at >= ?1 AND played_at < ?2 ORDER BY played_at",
+        ) else {
+            return Vec::new();
+        };
+        stmt.query_map(params![from, to], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map(|rows| {
+            rows.filter_map(|row| row.ok())
+                .filter_map(|(song, played_at, played_secs)| {
+                    Some(PlayHistoryEntry {
+                        song: serde_json::from_str(&song).ok()?,
+                        played_at,
+                        played_secs: played_secs as u64,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    /// 待提交的听歌记录入队
+    pub fn push_scrobble(&self, listen: &Listen) {
+        if let Ok(json) = serde_json::to_string(listen) {
+            let _ = self.conn.execute(
+                "INSERT INTO scrobble_queue (listen) VALUES (?1)",
+                params![json],
+            );
+        }
+    }
+
+    /// 队列中最早的若干条记录，附带行 id 供提交成功后删除
+    pub fn pending_scrobbles(&self, limit: usize) -> Vec<(i64, Listen)> {
+        let Ok(mut stmt) = self
+            .conn
+            .prepare("SELECT id, listen FROM scrobble_queue ORDER BY id LIMIT ?1")
+        else {
+            return Vec::new();
+        };
+        stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map(|rows| {
+            rows.filter_map(|row| row.ok())
+                .filter_map(|(id, json)| Some((id, serde_json::from_str(&json).ok()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub fn remove_scrobbles(&self, ids: &[i64]) {
+        for id in ids {
+            let _ = self
+                .conn
+                .execute("DELETE FROM scrobble_queue WHERE id = ?1", params![id]);
+        }
+    }
 }
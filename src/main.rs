@@ -1,4 +1,8 @@
-use crate::{api::init_client, db::Db, ui::window::Window};
+use crate::{
+    api::init_client,
+    db::Db,
+    ui::window::{WINDOW_BROKER, Window},
+};
 use relm4::{
     RelmApp,
     gtk::gio::{Settings, prelude::SettingsExt},
@@ -8,6 +12,8 @@ use std::sync::{Arc, Mutex};
 mod api;
 mod db;
 mod player;
+mod remote;
+mod scrobble;
 mod ui;
 mod utils;
 
@@ -51,5 +57,6 @@ fn main() {
     relm4::set_global_css(STYLE_CSS);
 
     let app = RelmApp::new(APPLICATION_ID);
-    app.run::<Window>((cookie, db));
+    remote::setup(&relm4::main_adw_application());
+    app.with_broker(&WINDOW_BROKER).run::<Window>((cookie, db));
 }
@@ -0,0 +1,258 @@
+//! 音效：10 段均衡器、变速（保持音调）、变调和单声道混音
+//!
+//! `GstEngine` 在创建时把 [`EffectsBin`] 设为 playbin 的 `audio-filter`，之后的调整都只改
+//! 元素属性，不重建管线，播放不会中断。缺少某个 GStreamer 插件时对应效果不可用，其余照常工作。
+//!
+//! 链尾另挂一个 `spectrum` 元素供频谱可视化使用，只在界面需要时才发送频谱消息。
+
+use gst::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const EQ_BAND_COUNT: usize = 10;
+/// `equalizer-10bands` 各频段的中心频率（Hz）
+pub const EQ_FREQUENCIES: [u32; EQ_BAND_COUNT] =
+    [29, 59, 119, 237, 474, 947, 1889, 3770, 7523, 15011];
+pub const EQ_MIN_DB: f64 = -12.0;
+pub const EQ_MAX_DB: f64 = 12.0;
+
+pub const SPEED_MIN: f64 = 0.5;
+pub const SPEED_MAX: f64 = 2.0;
+/// 变调范围（半音）
+pub const PITCH_RANGE: f64 = 12.0;
+
+/// 频谱频段数
+pub const SPECTRUM_BANDS: u32 = 32;
+/// 低于该值（dB）的频段视为静音
+const SPECTRUM_THRESHOLD_DB: i32 = -70;
+/// 频谱消息间隔：20 fps
+const SPECTRUM_INTERVAL_NS: u64 = 50_000_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioEffects {
+    pub eq_enabled: bool,
+    /// 当前曲线来自哪个预设；手动拖动过频段后为 None
+    pub eq_preset: Option<String>,
+    pub eq_gains: [f64; EQ_BAND_COUNT],
+    /// 播放速度倍率
+    pub speed: f64,
+    /// 音调偏移（半音）
+    pub pitch_semitones: f64,
+    pub mono: bool,
+}
+
+impl Default for AudioEffects {
+    fn default() -> Self {
+        Self {
+            eq_enabled: false,
+            eq_preset: Some(BUILTIN_PRESETS[0].name.to_string()),
+            eq_gains: [0.0; EQ_BAND_COUNT],
+            speed: 1.0,
+            pitch_semitones: 0.0,
+            mono: false,
+        }
+    }
+}
+
+pub struct EqPreset {
+    pub name: &'static str,
+    pub gains: [f64; EQ_BAND_COUNT],
+}
+
+pub const BUILTIN_PRESETS: &[EqPreset] = &[
+    EqPreset {
+        name: "平坦",
+        gains: [0.0; EQ_BAND_COUNT],
+    },
+    EqPreset {
+        name: "流行",
+        gains: [-1.5, 1.0, 3.0, 4.0, 3.0, 0.0, -1.0, -1.5, -1.5, -1.5],
+    },
+    EqPreset {
+        name: "摇滚",
+        gains: [5.0, 3.0, -3.0, -5.0, -2.0, 2.0, 5.0, 6.0, 6.0, 6.0],
+    },
+    EqPreset {
+        name: "古典",
+        gains: [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -4.0, -4.0, -4.0, -6.0],
+    },
+    EqPreset {
+        name: "爵士",
+        gains: [4.0, 3.0, 1.0, 2.0, -2.0, -2.0, 0.0, 1.0, 3.0, 4.0],
+    },
+    EqPreset {
+        name: "电子",
+        gains: [4.0, 3.5, 1.0, 0.0, -2.0, 2.0, 1.0, 1.0, 4.0, 5.0],
+    },
+    EqPreset {
+        name: "低音增强",
+        gains: [6.0, 5.0, 4.0, 2.5, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    },
+    EqPreset {
+        name: "人声",
+        gains: [-2.0, -3.0, -3.0, 1.0, 4.0, 4.0, 3.0, 1.0, 0.0, -2.0],
+    },
+];
+
+/// 频段的简短标签，如 `60`、`1.9k`
+pub fn band_label(freq: u32) -> String {
+    if freq >= 1000 {
+        format!("{:.1}k", freq as f64 / 1000.0).replace(".0k", "k")
+    } else {
+        freq.to_string()
+    }
+}
+
+/// 把 `spectrum` 消息里的分贝值映射到 0.0 ~ 1.0
+pub fn normalize_magnitudes(magnitudes: impl IntoIterator<Item = f32>) -> Vec<f32> {
+    let floor = SPECTRUM_THRESHOLD_DB as f32;
+    magnitudes
+        .into_iter()
+        .map(|db| ((db - floor) / -floor).clamp(0.0, 1.0))
+        .collect()
+}
+
+/// 挂在 playbin `audio-filter` 上的效果链：
+/// `audioconvert ! audioresample ! scaletempo ! audioconvert ! pitch ! audioconvert ! equalizer-10bands ! audioconvert ! audiomixmatrix ! audioconvert ! spectrum`
+pub(crate) struct EffectsBin {
+    bin: gst::Bin,
+    equalizer: Option<gst::Element>,
+    pitch: Option<gst::Element>,
+    downmix: Option<gst::Element>,
+    spectrum: Option<gst::Element>,
+}
+
+impl EffectsBin {
+    pub fn new() -> anyhow::Result<Self> {
+        let optional = |factory: &str| match gst::ElementFactory::make(factory).build() {
+            Ok(element) => Some(element),
+            Err(_) => {
+                log::warn!("GStreamer 插件 {factory} 不可用，相关音效将被禁用");
+                None
+            }
+        };
+        let convert = || gst::ElementFactory::make("audioconvert").build();
+
+        let scaletempo = optional("scaletempo");
+        let pitch = optional("pitch");
+        let equalizer = optional("equalizer-10bands");
+        // 固定双声道进出，单声道只改混音矩阵，播放中切换不用重新协商格式
+        let downmix = optional("audiomixmatrix");
+        if let Some(downmix) = &downmix {
+            downmix.set_property("in-channels", 2u32);
+            downmix.set_property("out-channels", 2u32);
+            downmix.set_property("channel-mask", STEREO_CHANNEL_MASK);
+            downmix.set_property("matrix", mix_matrix(false));
+        }
+        let spectrum = optional("spectrum");
+        if let Some(spectrum) = &spectrum {
+            spectrum.set_property("bands", SPECTRUM_BANDS);
+            spectrum.set_property("threshold", SPECTRUM_THRESHOLD_DB);
+            spectrum.set_property("interval", SPECTRUM_INTERVAL_NS);
+            spectrum.set_property("post-messages", false);
+        }
+
+        // 解码器可能输出 scaletempo 不接受的整数或非交错格式，先统一转换
+        let mut chain = vec![
+            convert()?,
+            gst::ElementFactory::make("audioresample").build()?,
+        ];
+        chain.extend(scaletempo);
+        chain.push(convert()?);
+        chain.extend(pitch.clone());
+        chain.push(convert()?);
+        chain.extend(equalizer.clone());
+        chain.push(convert()?);
+        chain.extend(downmix.clone());
+        chain.push(convert()?);
+        chain.extend(spectrum.clone());
+
+        let bin = gst::Bin::builder().name("effects").build();
+        bin.add_many(&chain)?;
+        gst::Element::link_many(&chain)?;
+
+        let sink = chain[0]
+            .static_pad("sink")
+            .expect("audio element has a sink pad");
+        let src = chain[chain.len() - 1]
+            .static_pad("src")
+            .expect("audio element has a src pad");
+        bin.add_pad(&gst::GhostPad::with_target(&sink)?)?;
+        bin.add_pad(&gst::GhostPad::with_target(&src)?)?;
+
+        Ok(Self {
+            bin,
+            equalizer,
+            pitch,
+            downmix,
+            spectrum,
+        })
+    }
+
+    /// 开关频谱消息；没有可视化界面时关闭以省去计算
+    pub fn set_spectrum_enabled(&self, enabled: bool) {
+        if let Some(spectrum) = &self.spectrum {
+            spectrum.set_property("post-messages", enabled);
+        }
+    }
+
+    pub fn element(&self) -> &gst::Element {
+        self.bin.upcast_ref()
+    }
+
+    /// 直接修改元素属性，播放中即时生效
+    pub fn apply(&self, effects: &AudioEffects) {
+        if let Some(equalizer) = &self.equalizer {
+            for (i, gain) in effects.eq_gains.iter().enumerate() {
+                let gain = if effects.eq_enabled {
+                    gain.clamp(EQ_MIN_DB, EQ_MAX_DB)
+                } else {
+                    0.0
+                };
+                equalizer.set_property(&format!("band{i}"), gain);
+            }
+        }
+        if let Some(pitch) = &self.pitch {
+            let semitones = effects.pitch_semitones.clamp(-PITCH_RANGE, PITCH_RANGE);
+            pitch.set_property("pitch", 2f64.powf(semitones / 12.0) as f32);
+        }
+        if let Some(downmix) = &self.downmix {
+            downmix.set_property("matrix", mix_matrix(effects.mono));
+        }
+    }
+}
+
+/// 左前 + 右前
+const STEREO_CHANNEL_MASK: u64 = 0x3;
+
+/// 双声道混音矩阵：单声道时两个输出声道都取左右声道的平均
+fn mix_matrix(mono: bool) -> gst::Array {
+    let (own, other) = if mono { (0.5f32, 0.5f32) } else { (1.0, 0.0) };
+    gst::Array::new([gst::Array::new([own, other]), gst::Array::new([other, own])])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_json_falls_back_to_defaults() {
+        let effects: AudioEffects = serde_json::from_str(r#"{"speed":1.5,"mono":true}"#).unwrap();
+        assert_eq!(effects.speed, 1.5);
+        assert!(effects.mono);
+        assert!(!effects.eq_enabled);
+        assert_eq!(effects.eq_gains, [0.0; EQ_BAND_COUNT]);
+
+        assert_eq!(band_label(59), "59");
+        assert_eq!(band_label(1889), "1.9k");
+        assert_eq!(band_label(15011), "15k");
+    }
+
+    #[test]
+    fn spectrum_magnitudes_are_normalized() {
+        assert_eq!(
+            normalize_magnitudes([-70.0, -35.0, 0.0, -90.0, 3.0]),
+            vec![0.0, 0.5, 1.0, 0.0, 1.0]
+        );
+    }
+}
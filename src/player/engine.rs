@@ -1,29 +1,137 @@
 use gst::ClockTime;
+use gst::prelude::*;
 use gst_play::{Play, PlayMessage, PlayState};
 
+use super::effects::{AudioEffects, EffectsBin, SPEED_MAX, SPEED_MIN, normalize_magnitudes};
 use super::messages::PlaybackState;
 
+/// 播放器对音频后端的全部要求；测试中用不发声的假实现替换 [`GstEngine`]
+pub(crate) trait AudioEngine {
+    fn play_url(&mut self, url: &str);
+    fn toggle(&mut self);
+    fn resume(&mut self);
+    fn pause(&mut self);
+    /// 停止播放，管线回到 NULL 状态
+    fn stop(&mut self);
+    /// 是否处于播放（而非暂停 / 停止）状态
+    fn is_playing(&self) -> bool;
+    fn seek(&mut self, offset_ms: u64);
+    fn set_volume(&mut self, volume: f64);
+    fn apply_effects(&mut self, effects: &AudioEffects);
+    fn set_spectrum_enabled(&mut self, enabled: bool);
+    /// 取出最新一帧频谱，丢弃积压的旧帧
+    fn take_spectrum(&self) -> Option<Vec<f32>>;
+    /// 切换输出设备（None 为系统默认）
+    fn set_output_device(&mut self, device: Option<&gst::Device>) -> anyhow::Result<()>;
+    /// 非阻塞地取出一个事件
+    fn poll(&self) -> Option<GstEvent>;
+}
+
 /// GstEngine 只管 GStreamer，事件通过回调向上汇报。
 pub(crate) struct GstEngine {
     play: Play,
-    pub is_playing: bool,
+    /// 均衡器等音效；构建失败时为 None，直接输出原始音频
+    effects: Option<EffectsBin>,
+    /// `spectrum` 元素的消息由 GstPlay 的线程转发过来，已归一化
+    spectrum_rx: flume::Receiver<Vec<f32>>,
+    is_playing: bool,
 }
 
 impl GstEngine {
     pub fn new() -> Self {
+        let play = Play::default();
+        let effects = match EffectsBin::new() {
+            Ok(effects) => {
+                play.pipeline()
+                    .set_property("audio-filter", effects.element());
+                Some(effects)
+            }
+            Err(e) => {
+                log::error!("音效链创建失败: {e}");
+                None
+            }
+        };
+        let (spectrum_tx, spectrum_rx) = flume::unbounded();
+        // gst_play 的消息总线只转发播放相关消息，元素消息需直接监听 playbin 的总线
+        play.pipeline()
+            .bus()
+            .expect("playbin has a bus")
+            .connect_message(Some("element"), move |_, msg| {
+                let Some(s) = msg.structure().filter(|s| s.name() == "spectrum") else {
+                    return;
+                };
+                if let Ok(magnitude) = s.get::<gst::List>("magnitude") {
+                    let bands = magnitude.iter().filter_map(|v| v.get::<f32>().ok());
+                    let _ = spectrum_tx.send(normalize_magnitudes(bands));
+                }
+            });
         Self {
-            play: Play::default(),
+            play,
+            effects,
+            spectrum_rx,
             is_playing: false,
         }
     }
 
-    pub fn play_url(&mut self, url: &str) {
+    fn duration_ms(&self) -> u64 {
+        self.play.duration().map_or(0, |d| d.mseconds())
+    }
+}
+
+impl AudioEngine for GstEngine {
+    fn set_spectrum_enabled(&mut self, enabled: bool) {
+        if let Some(bin) = &self.effects {
+            bin.set_spectrum_enabled(enabled);
+        }
+        if !enabled {
+            self.spectrum_rx.drain();
+        }
+    }
+
+    fn take_spectrum(&self) -> Option<Vec<f32>> {
+        self.spectrum_rx.drain().last()
+    }
+
+    fn apply_effects(&mut self, effects: &AudioEffects) {
+        if let Some(bin) = &self.effects {
+            bin.apply(effects);
+        }
+        self.play
+            .set_rate(effects.speed.clamp(SPEED_MIN, SPEED_MAX));
+    }
+
+    /// 切换输出设备（None 为系统默认）。playbin 只在启动时读取 audio-sink，
+    /// 因此需要重启管线，并恢复原来的播放位置和状态
+    fn set_output_device(&mut self, device: Option<&gst::Device>) -> anyhow::Result<()> {
+        let sink = device.map(|d| d.create_element(None)).transpose()?;
+        let uri = self.play.uri();
+        let position = self.play.position();
+
+        self.play.stop();
+        self.play
+            .pipeline()
+            .set_property("audio-sink", sink.as_ref());
+
+        if uri.is_some() {
+            if self.is_playing {
+                self.play.play();
+            } else {
+                self.play.pause();
+            }
+            if let Some(position) = position {
+                self.play.seek(position);
+            }
+        }
+        Ok(())
+    }
+
+    fn play_url(&mut self, url: &str) {
         self.play.set_uri(Some(url));
         self.play.play();
         self.is_playing = true;
     }
 
-    pub fn toggle(&mut self) {
+    fn toggle(&mut self) {
         if self.is_playing {
             self.play.pause();
             self.is_playing = false;
@@ -33,30 +141,39 @@ impl GstEngine {
         }
     }
 
-    pub fn resume(&mut self) {
+    fn resume(&mut self) {
         self.play.play();
         self.is_playing = true;
     }
 
-    pub fn pause(&mut self) {
+    fn pause(&mut self) {
         self.play.pause();
         self.is_playing = false;
     }
 
-    pub fn seek(&mut self, offset_ms: u64) {
+    fn stop(&mut self) {
+        self.play.stop();
+        self.is_playing = false;
+    }
+
+    fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    fn seek(&mut self, offset_ms: u64) {
         if offset_ms > self.duration_ms() {
             return;
         }
         self.play.seek(ClockTime::from_mseconds(offset_ms));
     }
 
-    pub fn duration_ms(&self) -> u64 {
-        self.play.duration().map_or(0, |d| d.mseconds())
+    fn set_volume(&mut self, volume: f64) {
+        self.play.set_volume(volume);
     }
 
     /// 非阻塞轮询消息总线，最多等 10ms。
     /// 返回解析好的 PlayerEvent，调用者决定怎么处理。
-    pub fn poll(&self) -> Option<GstEvent> {
+    fn poll(&self) -> Option<GstEvent> {
         let msg = self
             .play
             .message_bus()
@@ -81,7 +198,14 @@ impl GstEngine {
                     duration: dur,
                 })
             }
-            PlayMessage::Error(e) => Some(GstEvent::Error(e.details().unwrap().to_string())),
+            PlayMessage::Error(e) => {
+                // 错误文本只有概要（如 "Forbidden"），HTTP 状态码等细节在 details 里
+                let msg = match e.details() {
+                    Some(details) => format!("{}: {}", e.error(), details),
+                    None => e.error().to_string(),
+                };
+                Some(GstEvent::Error(msg))
+            }
             _ => None,
         }
     }
@@ -1,32 +1,68 @@
 use relm4::Sender;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::{
-    api::{
-        Playlist, Song, SoundQuality, get_album_detail, get_home_category_daily_song_list,
-        get_playlist_detail, get_recommend_song, get_song_detail, get_song_url, is_like_song,
-        like_song,
-    },
+    api::{PlayEndReason, PlayReport, Playlist, Song, Unplayable, like_song},
     db::Db,
     player::{
-        engine::{GstEngine, GstEvent},
+        effects::AudioEffects,
+        engine::{AudioEngine, GstEngine, GstEvent},
         messages::{
-            InternalEvent, MprisCommand, MprisUpdate, PlaybackState, PlayerCommand, PlayerEvent,
+            InternalEvent, MprisCommand, MprisUpdate, MvControl, NowPlaying, OutputDeviceList,
+            PlayMode, PlaybackState, PlayerCommand, PlayerEvent, SleepTimerStatus,
         },
         mpris,
+        output::OutputDevices,
+        play_log::{FinishedPlay, PlayLog},
         queue::{QueueItem, QueueManager},
+        recovery::{MAX_CONSECUTIVE_FAILURES, Recovery, StreamError, classify_stream_error},
+        resolver::{ApiResolver, ResolvedUrl, TrackResolver, async_runtime},
+        sleep_timer::{SleepTick, SleepTimer},
+    },
+    ui::{
+        components::image::image_manager::{ImageManager, thumbnail_url},
+        model::{PlaySource, PlaylistType},
     },
-    ui::model::{PlaySource, PlaylistType},
 };
 
-fn async_runtime() -> &'static tokio::runtime::Runtime {
-    static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
-    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().expect("Failed to create async runtime"))
+/// 切歌时预取后面几首歌的封面
+const COVER_PREFETCH_COUNT: usize = 3;
+
+/// 拖动音效滑块时设置连续变化，停下这么久后才写入数据库
+const EFFECTS_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// 心动模式下剩余不到这么多首时拉取下一批推荐
+const INTELLIGENCE_REFILL_AT: usize = 3;
+
+/// 等待退避结束后重新获取链接
+struct PendingRetry {
+    at: Instant,
+    song_id: u64,
+    resume_ms: Option<u64>,
+}
+
+/// 心动模式的续播状态
+struct Intelligence {
+    playlist_id: u64,
+    seed_id: u64,
+    fetching: bool,
+    /// 已播到队尾，下一批推荐到了接着播
+    resume_on_fetch: bool,
+}
+
+/// 进行中的 MV 播放；音乐引擎在此期间保持暂停
+struct MvSession {
+    /// 进入 MV 前音乐在播放，MV 结束后继续
+    resume_music: bool,
+    /// 上次上报的 MV 播放状态，变化时才通知
+    playing: Option<bool>,
 }
 
 pub struct PlayerFacade {
-    engine: GstEngine,
+    engine: Box<dyn AudioEngine>,
+    resolver: Box<dyn TrackResolver>,
     queue: QueueManager,
     is_waiting_to_play: bool,
     db: Arc<Mutex<Db>>,
@@ -37,6 +73,42 @@ pub struct PlayerFacade {
     /// 恢复会话但不需要自动播放时：等当前歌曲 URL 就绪后立即暂停
     pause_after_start: bool,
 
+    /// 收到 Shutdown 后置位：主循环退出并回复
+    shutdown_done: Option<flume::Sender<()>>,
+
+    /// 用户设定的音量；定时关闭淡出时以它为基准，结束后恢复
+    volume: f64,
+    sleep_timer: Option<SleepTimer>,
+    /// 上次发给 UI 的倒计时，变化时才重新发送
+    last_sleep_status: Option<SleepTimerStatus>,
+
+    /// 设备监听启动失败时为 None，始终使用系统默认设备
+    outputs: Option<OutputDevices>,
+    /// 用户选择的输出设备；断开后仍保留，重新接入时自动切回
+    preferred_output: Option<String>,
+    /// 管线当前实际使用的设备，None 为系统默认
+    active_output: Option<String>,
+
+    recovery: Recovery,
+    pending_retry: Option<PendingRetry>,
+    /// 待保存的音效设置及其保存时刻
+    pending_effects: Option<(Instant, AudioEffects)>,
+    /// 重新获取链接后要跳回的位置，等管线拿到时长后再 seek
+    pending_seek: Option<u64>,
+    /// 最近一次播放位置，出错时据此续播
+    position_ms: u64,
+    /// 当前歌曲时长，MV 结束后与 position_ms 一起补发给 UI
+    duration_ms: u64,
+
+    /// 正在播放 MV 时为 Some：状态与进度来自 MV 管线，播放控制转给它
+    mv: Option<MvSession>,
+    /// 当前歌曲的播放记录，结束时写入本地历史
+    play_log: PlayLog,
+    /// 是否向网易云上报播放记录
+    report_plays: bool,
+    /// 心动模式开启时为 Some
+    intelligence: Option<Intelligence>,
+
     cmd_rx: flume::Receiver<PlayerCommand>,
     internal_rx: flume::Receiver<InternalEvent>,
     internal_tx: flume::Sender<InternalEvent>,
@@ -53,7 +125,6 @@ impl PlayerFacade {
         db: Arc<Mutex<Db>>,
     ) -> flume::Sender<PlayerCommand> {
         let (cmd_tx, cmd_rx) = flume::unbounded::<PlayerCommand>();
-        let (internal_tx, internal_rx) = flume::unbounded::<InternalEvent>();
         let (mpris_update_tx, mpris_update_rx) = flume::unbounded::<MprisUpdate>();
         let (mpris_cmd_tx, mpris_cmd_rx) = flume::unbounded::<MprisCommand>();
 
@@ -62,64 +133,151 @@ impl PlayerFacade {
 
         let saved_play_mode = db.lock().unwrap().get_play_mode();
         let saved_loop_enabled = db.lock().unwrap().get_loop_enabled();
+        let saved_effects = db.lock().unwrap().get_audio_effects();
+        let saved_output = db.lock().unwrap().get_output_device();
 
         let mut queue = QueueManager::new();
         queue.set_play_mode(saved_play_mode);
         queue.set_loop_enabled(saved_loop_enabled);
 
         std::thread::spawn(move || {
-            PlayerFacade {
-                engine: GstEngine::new(),
+            let mut engine = GstEngine::new();
+            engine.apply_effects(&saved_effects);
+            let mut facade = PlayerFacade::new(
+                Box::new(engine),
+                Box::new(ApiResolver),
                 queue,
-                is_waiting_to_play: false,
-                restore_ui_refresh: false,
-                pause_after_start: false,
                 db,
                 cmd_rx,
-                internal_rx,
-                internal_tx,
                 event_tx,
-                mpris_tx: mpris_update_tx,
-                mpris_rx: mpris_cmd_rx,
-            }
-            .run();
+                mpris_update_tx,
+                mpris_cmd_rx,
+            );
+            facade.outputs = OutputDevices::new();
+            facade.preferred_output = saved_output;
+            facade.sync_output_device(false);
+            facade.run();
         });
 
         cmd_tx
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        engine: Box<dyn AudioEngine>,
+        resolver: Box<dyn TrackResolver>,
+        queue: QueueManager,
+        db: Arc<Mutex<Db>>,
+        cmd_rx: flume::Receiver<PlayerCommand>,
+        event_tx: Sender<PlayerEvent>,
+        mpris_tx: flume::Sender<MprisUpdate>,
+        mpris_rx: flume::Receiver<MprisCommand>,
+    ) -> Self {
+        let (internal_tx, internal_rx) = flume::unbounded::<InternalEvent>();
+        PlayerFacade {
+            engine,
+            resolver,
+            queue,
+            is_waiting_to_play: false,
+            restore_ui_refresh: false,
+            pause_after_start: false,
+            shutdown_done: None,
+            volume: 1.0,
+            sleep_timer: None,
+            last_sleep_status: None,
+            outputs: None,
+            preferred_output: None,
+            active_output: None,
+            recovery: Recovery::default(),
+            pending_retry: None,
+            pending_effects: None,
+            pending_seek: None,
+            position_ms: 0,
+            duration_ms: 0,
+            mv: None,
+            play_log: PlayLog::default(),
+            report_plays: false,
+            intelligence: None,
+            db,
+            cmd_rx,
+            internal_rx,
+            internal_tx,
+            event_tx,
+            mpris_tx,
+            mpris_rx,
+        }
+    }
+
     fn run(&mut self) {
-        loop {
-            // 1. 处理来自 UI 的指令
-            while let Ok(cmd) = self.cmd_rx.try_recv() {
-                self.handle_cmd(cmd);
-            }
+        while self.step() {}
+    }
 
-            // 2. 处理来自 MPRIS 的指令（统一转成 PlayerCommand，复用同一段逻辑）
-            while let Ok(cmd) = self.mpris_rx.try_recv() {
-                match cmd {
-                    MprisCommand::Play => {
-                        self.engine.resume();
-                    }
-                    MprisCommand::Pause => {
-                        self.engine.pause();
-                    }
-                    MprisCommand::Next => self.handle_cmd(PlayerCommand::Next),
-                    MprisCommand::Previous => self.handle_cmd(PlayerCommand::Previous),
-                    MprisCommand::Seek(ms) => self.handle_cmd(PlayerCommand::Seek(ms)),
-                }
-            }
+    /// 主循环的一轮；处理完 Shutdown 后返回 false
+    fn step(&mut self) -> bool {
+        // 1. 处理来自 UI 的指令
+        while let Ok(cmd) = self.cmd_rx.try_recv() {
+            self.handle_cmd(cmd);
+        }
+        if let Some(done) = self.shutdown_done.take() {
+            let _ = done.send(());
+            return false;
+        }
 
-            // 3. 处理异步工作线程的内部回调
-            while let Ok(ev) = self.internal_rx.try_recv() {
-                self.handle_internal(ev);
+        // 2. 处理来自 MPRIS 的指令（统一转成 PlayerCommand，复用同一段逻辑）
+        while let Ok(cmd) = self.mpris_rx.try_recv() {
+            match cmd {
+                MprisCommand::Play => self.handle_cmd(PlayerCommand::Resume),
+                MprisCommand::Pause => self.handle_cmd(PlayerCommand::Pause),
+                MprisCommand::Next => self.handle_cmd(PlayerCommand::Next),
+                MprisCommand::Previous => self.handle_cmd(PlayerCommand::Previous),
+                MprisCommand::Seek(ms) => self.handle_cmd(PlayerCommand::Seek(ms)),
+                MprisCommand::Raise => self.emit(PlayerEvent::RaiseWindow),
+                MprisCommand::Quit => self.emit(PlayerEvent::QuitRequested),
             }
+        }
 
-            // 4. 轮询 GStreamer 消息（最多阻塞 10ms）
-            if let Some(ev) = self.engine.poll() {
-                self.handle_gst(ev);
-            }
+        // 3. 处理异步工作线程的内部回调
+        while let Ok(ev) = self.internal_rx.try_recv() {
+            self.handle_internal(ev);
+        }
+
+        // 4. 轮询 GStreamer 消息（最多阻塞 10ms）
+        if let Some(ev) = self.engine.poll() {
+            self.handle_gst(ev);
+        }
+
+        // 5. 推进定时关闭
+        self.tick_sleep_timer();
+
+        // 6. 输出设备热插拔
+        if self.outputs.as_ref().is_some_and(|o| o.poll()) {
+            self.sync_output_device(true);
+        }
+
+        // 7. 转发频谱
+        if let Some(bands) = self.engine.take_spectrum() {
+            self.emit(PlayerEvent::Spectrum(Arc::new(bands)));
+        }
+
+        // 8. 退避结束的重试
+        if self
+            .pending_retry
+            .as_ref()
+            .is_some_and(|retry| retry.at <= Instant::now())
+        {
+            let retry = self.pending_retry.take().unwrap();
+            self.spawn_url_resolve(retry.song_id, retry.resume_ms);
         }
+
+        // 9. 音效设置停止变化后再保存
+        if self
+            .pending_effects
+            .as_ref()
+            .is_some_and(|(at, _)| *at <= Instant::now())
+        {
+            self.save_pending_effects();
+        }
+        true
     }
 
     fn handle_cmd(&mut self, cmd: PlayerCommand) {
@@ -128,6 +286,10 @@ impl PlayerFacade {
                 source,
                 start_index,
             } => {
+                self.leave_mv();
+                if !matches!(source, PlaySource::Intelligence { .. }) {
+                    self.leave_intelligence();
+                }
                 match source {
                     PlaySource::LazyQueue {
                         tracks,
@@ -212,32 +374,57 @@ impl PlayerFacade {
                             start_index,
                         });
                     }
+                    PlaySource::Intelligence { playlist_id, seed } => {
+                        self.start_intelligence(playlist_id, seed);
+                    }
                 }
 
                 self.is_waiting_to_play = false;
+                self.recovery.reset();
                 self.play_current();
             }
             PlayerCommand::TogglePlayPause => {
-                self.engine.toggle();
+                if !self.forward_to_mv(MvControl::Toggle) {
+                    self.engine.toggle();
+                }
             }
             PlayerCommand::Pause => {
-                self.engine.pause();
+                if !self.forward_to_mv(MvControl::Pause) {
+                    self.engine.pause();
+                }
             }
             PlayerCommand::Resume => {
-                self.engine.resume();
+                if !self.forward_to_mv(MvControl::Play) {
+                    self.engine.resume();
+                }
             }
             PlayerCommand::Seek(offset_ms) => {
-                self.engine.seek(offset_ms);
+                self.interrupt_sleep_fade();
+                if !self.forward_to_mv(MvControl::Seek(offset_ms)) {
+                    self.engine.seek(offset_ms);
+                }
+            }
+            PlayerCommand::SetVolume(volume) => {
+                let volume = volume.clamp(0.0, 1.0);
+                self.volume = volume;
+                self.engine.set_volume(volume);
+                self.emit(PlayerEvent::VolumeChanged(volume));
             }
             PlayerCommand::Next => {
+                self.interrupt_sleep_fade();
+                self.leave_mv();
                 self.is_waiting_to_play = false;
-                if self.queue.advance(false) {
-                    self.play_current();
-                } else {
-                    let _ = self.event_tx.send(PlayerEvent::EndOfQueue);
+                if !self.wait_for_intelligence() {
+                    if self.queue.advance(false) {
+                        self.play_current();
+                    } else {
+                        let _ = self.event_tx.send(PlayerEvent::EndOfQueue);
+                    }
                 }
             }
             PlayerCommand::Previous => {
+                self.interrupt_sleep_fade();
+                self.leave_mv();
                 if self.queue.go_back() {
                     self.play_current();
                 }
@@ -251,10 +438,17 @@ impl PlayerFacade {
                 });
             }
             PlayerCommand::PlayAt(index) => {
+                self.interrupt_sleep_fade();
+                self.leave_mv();
                 self.queue.play(index);
+                self.recovery.reset();
                 self.play_current();
             }
+            PlayerCommand::SetPlayMode(PlayMode::Intelligence) => {
+                self.switch_to_intelligence();
+            }
             PlayerCommand::SetPlayMode(mode) => {
+                self.intelligence = None;
                 self.queue.set_play_mode(mode);
                 self.db.lock().unwrap().set_play_mode(mode);
             }
@@ -268,6 +462,8 @@ impl PlayerFacade {
                 playlist,
                 autoplay,
             } => {
+                self.leave_mv();
+                self.leave_intelligence();
                 self.queue.load(
                     track_ids.clone(),
                     Arc::new(Vec::new()),
@@ -285,6 +481,9 @@ impl PlayerFacade {
                 let tx = self.event_tx.clone();
                 async_runtime().spawn(async move {
                     let result = like_song(song_id, liked).await;
+                    if result.is_ok() {
+                        let _ = tx.send(PlayerEvent::LikeChanged { song_id, liked });
+                    }
                     let msg = match (result.is_ok(), liked) {
                         (true, true) => "已喜欢".to_string(),
                         (true, false) => "已取消喜欢".to_string(),
@@ -293,6 +492,95 @@ impl PlayerFacade {
                     let _ = tx.send(PlayerEvent::ShowToast(msg));
                 });
             }
+            PlayerCommand::SetSleepTimer { mode, fade_secs } => {
+                self.cancel_sleep_timer();
+                self.sleep_timer = Some(SleepTimer::new(
+                    mode,
+                    Duration::from_secs(u64::from(fade_secs)),
+                    Instant::now(),
+                ));
+            }
+            PlayerCommand::CancelSleepTimer => {
+                self.cancel_sleep_timer();
+            }
+            PlayerCommand::SetAudioEffects(effects) => {
+                self.engine.apply_effects(&effects);
+                self.pending_effects = Some((Instant::now() + EFFECTS_SAVE_DELAY, effects));
+            }
+            PlayerCommand::SetSpectrumEnabled(enabled) => {
+                self.engine.set_spectrum_enabled(enabled);
+            }
+            PlayerCommand::SetPlayReport(enabled) => {
+                self.report_plays = enabled;
+            }
+            PlayerCommand::SetOutputDevice(name) => {
+                self.db.lock().unwrap().set_output_device(name.as_deref());
+                self.preferred_output = name;
+                self.sync_output_device(false);
+            }
+            PlayerCommand::StartMv(detail) => {
+                // 换一个 MV 时沿用最初记下的音乐状态
+                if self.mv.is_none() {
+                    let resume_music = self.engine.is_playing();
+                    if resume_music {
+                        self.engine.pause();
+                    }
+                    self.mv = Some(MvSession {
+                        resume_music,
+                        playing: None,
+                    });
+                }
+                let _ = self.mpris_tx.send(MprisUpdate::MvMetadata(detail.clone()));
+                self.emit(PlayerEvent::NowPlayingChanged(NowPlaying::Mv(detail)));
+            }
+            PlayerCommand::MvProgress {
+                playing,
+                position,
+                duration,
+            } => {
+                let Some(session) = self.mv.as_mut() else {
+                    return;
+                };
+                if session.playing != Some(playing) {
+                    session.playing = Some(playing);
+                    self.publish_state(if playing {
+                        PlaybackState::Playing
+                    } else {
+                        PlaybackState::Paused
+                    });
+                }
+                self.emit(PlayerEvent::TimeUpdated { position, duration });
+            }
+            PlayerCommand::EndMv => {
+                let Some(session) = self.mv.take() else {
+                    return;
+                };
+                self.emit(PlayerEvent::NowPlayingChanged(NowPlaying::Music));
+                let state = match self.queue.current() {
+                    Some(QueueItem::Full(song)) => {
+                        let _ = self.mpris_tx.send(MprisUpdate::Metadata(song.clone()));
+                        self.emit(PlayerEvent::TimeUpdated {
+                            position: self.position_ms,
+                            duration: self.duration_ms,
+                        });
+                        if session.resume_music {
+                            self.engine.resume();
+                            PlaybackState::Playing
+                        } else {
+                            PlaybackState::Paused
+                        }
+                    }
+                    _ => PlaybackState::Stopped,
+                };
+                self.publish_state(state);
+            }
+            PlayerCommand::Shutdown { done } => {
+                let finished = self.play_log.finish(PlayEndReason::Interrupted);
+                self.record_play(finished);
+                self.save_pending_effects();
+                self.engine.stop();
+                self.shutdown_done = Some(done);
+            }
         }
     }
 
@@ -319,13 +607,21 @@ impl PlayerFacade {
                 song_id,
                 url,
                 is_liked,
+                resume_ms,
             } => {
                 eprintln!("Url resolved: {:?}", song_id);
-                let is_current = self.queue.current().map_or(
-                    false,
-                    |item| matches!(item, QueueItem::Full(s) if s.id == song_id),
-                );
-                if !is_current {
+                if self.current_song_id() != Some(song_id) {
+                    return;
+                }
+
+                // 续播同一首：不通知切歌，拿到时长后跳回原位置
+                if let Some(position) = resume_ms {
+                    self.engine.play_url(&url);
+                    self.pending_seek = Some(position).filter(|p| *p > 0);
+                    self.hold_for_mv();
+                    if self.mv.is_none() {
+                        self.emit(PlayerEvent::StateChanged(PlaybackState::Playing));
+                    }
                     return;
                 }
 
@@ -339,24 +635,50 @@ impl PlayerFacade {
                     self.pause_after_start = false;
                     PlaybackState::Paused
                 } else {
+                    self.hold_for_mv();
                     PlaybackState::Playing
                 };
 
-                let _ = self.mpris_tx.send(MprisUpdate::Metadata(song.clone()));
+                let source_id = self.queue.current_playlist.as_ref().map_or(0, |p| p.id);
+                let previous =
+                    self.play_log
+                        .start(song.clone(), source_id, chrono::Utc::now().timestamp());
+                self.record_play(previous);
+                if self.report_plays {
+                    self.send_play_report(PlayReport::Start { song_id, source_id });
+                }
+
+                // MV 播放期间只更新队列位置，MPRIS 与播放状态仍显示 MV
+                if self.mv.is_none() {
+                    let _ = self.mpris_tx.send(MprisUpdate::Metadata(song.clone()));
+                }
                 self.emit(PlayerEvent::TrackChanged {
                     song,
                     current_index: self.queue.current_index.unwrap_or(0),
                     is_liked,
                 });
-                self.emit(PlayerEvent::StateChanged(state));
+                if self.mv.is_none() {
+                    self.emit(PlayerEvent::StateChanged(state));
+                }
                 // if let(Some(start_index)) = self.queue.current_index {
                 //     self.emit(PlayerEvent::SetQueue { songs: self.queue., start_index });
                 // }
             }
-            InternalEvent::UrlResolveFailed { song_id } => {
-                eprintln!("URL resolve failed for {song_id}");
-                log::warn!("URL resolve failed for {song_id}, skipping to next");
-                self.handle_cmd(PlayerCommand::Next);
+            InternalEvent::UrlResolveFailed {
+                song_id,
+                unplayable,
+                resume_ms,
+            } => {
+                if self.current_song_id() != Some(song_id) {
+                    return;
+                }
+                match unplayable {
+                    Some(reason) => {
+                        log::warn!("歌曲 {song_id} 无法播放（{reason}），跳到下一首");
+                        self.skip_failed(song_id, Some(reason.to_string()), false);
+                    }
+                    None => self.schedule_retry(song_id, resume_ms),
+                }
             }
             InternalEvent::PlaylistFetched {
                 playlist: playlist_detail,
@@ -403,6 +725,7 @@ impl PlayerFacade {
                     creator_id: 0,
                     description: String::new(),
                     play_count: 0,
+                    liked_songs: false,
                 };
                 self.handle_cmd(PlayerCommand::Play {
                     source: PlaySource::LazyQueue {
@@ -413,28 +736,81 @@ impl PlayerFacade {
                     start_index: 0,
                 });
             }
+            InternalEvent::IntelligenceFetched { seed_id, songs } => {
+                self.apply_intelligence(seed_id, songs);
+            }
         }
     }
 
     fn handle_gst(&mut self, ev: GstEvent) {
         match ev {
             GstEvent::State(state) => {
-                let _ = self
-                    .mpris_tx
-                    .send(MprisUpdate::PlaybackState(state.clone()));
-                self.emit(PlayerEvent::StateChanged(state));
+                // MV 播放期间音乐引擎的暂停不对外公布
+                if self.mv.is_none() {
+                    self.publish_state(state);
+                }
             }
             GstEvent::EndOfStream => {
-                if self.queue.advance(true) {
+                let sleep_due = self
+                    .sleep_timer
+                    .as_mut()
+                    .is_some_and(|timer| timer.on_track_end());
+                let finished = self.play_log.finish(PlayEndReason::Finished);
+                self.record_play(finished);
+                if sleep_due {
+                    // 定时已到：停在播完的这首，不再切歌
+                    self.finish_sleep_timer();
+                    self.engine.stop();
+                    return;
+                }
+                if !self.wait_for_intelligence() && self.queue.advance(true) {
                     self.play_current();
                 }
             }
             GstEvent::Position { position, duration } => {
-                self.emit(PlayerEvent::TimeUpdated { position, duration });
+                if duration > 0 {
+                    if let Some(seek) = self.pending_seek.take() {
+                        self.engine.seek(seek);
+                    }
+                }
+                if position > 0 {
+                    self.position_ms = position;
+                    self.recovery.on_success();
+                }
+                if duration > 0 {
+                    self.duration_ms = duration;
+                }
+                if let Some(timer) = self.sleep_timer.as_mut() {
+                    timer.on_position(position, duration, Instant::now());
+                }
+                if self.mv.is_none() {
+                    self.play_log.on_position(position);
+                    self.emit(PlayerEvent::TimeUpdated { position, duration });
+                }
             }
             GstEvent::Error(msg) => {
                 log::error!("GStreamer error: {msg}");
-                self.emit(PlayerEvent::Error(msg));
+                let Some(song_id) = self.current_song_id() else {
+                    self.emit(PlayerEvent::Error(msg));
+                    return;
+                };
+                let resume_ms = Some(self.position_ms);
+                match classify_stream_error(&msg) {
+                    // 链接过期无需等待，立即换新链接
+                    StreamError::Expired => {
+                        if self.recovery.next_retry().is_some() {
+                            log::warn!("歌曲 {song_id} 的播放链接已失效，重新获取");
+                            self.spawn_url_resolve(song_id, resume_ms);
+                        } else {
+                            self.skip_failed(song_id, None, false);
+                        }
+                    }
+                    StreamError::Network => self.schedule_retry(song_id, resume_ms),
+                    StreamError::Fatal => {
+                        self.emit(PlayerEvent::Error(msg));
+                        self.skip_failed(song_id, Some("播放出错".to_string()), false);
+                    }
+                }
             }
         }
     }
@@ -451,7 +827,14 @@ impl PlayerFacade {
             Some(QueueItem::Full(song)) => {
                 let song_id = song.id;
                 self.is_waiting_to_play = false;
-                self.spawn_url_resolve(song_id);
+                self.recovery.on_track_start();
+                self.pending_retry = None;
+                self.pending_seek = None;
+                self.position_ms = 0;
+                self.duration_ms = song.duration;
+                self.spawn_url_resolve(song_id, None);
+                self.prefetch_covers();
+                self.refill_intelligence();
             }
             Some(QueueItem::Id(id)) => {
                 let song_id = *id;
@@ -466,31 +849,75 @@ impl PlayerFacade {
 
     // ── 异步任务派发 ────────────────────────────────────────────────
 
-    fn spawn_url_resolve(&self, song_id: u64) {
+    /// resume_ms 不为空时是续播：丢弃缓存的旧链接重新获取
+    fn spawn_url_resolve(&self, song_id: u64, resume_ms: Option<u64>) {
         let tx = self.internal_tx.clone();
-        async_runtime().spawn(async move {
-            let url_result = get_song_url(song_id, SoundQuality::Standard).await;
-            let like_result = is_like_song(song_id).await;
-            let is_liked = like_result.unwrap_or(false);
-            match url_result {
-                Ok(url) => {
+        let resolve = self.resolver.song_url(song_id, resume_ms.is_some());
+        self.resolver.spawn(Box::pin(async move {
+            match resolve.await {
+                Ok(ResolvedUrl { url, is_liked }) => {
                     let _ = tx.send(InternalEvent::UrlResolved {
                         song_id,
                         url,
                         is_liked,
+                        resume_ms,
                     });
                 }
-                Err(_) => {
-                    let _ = tx.send(InternalEvent::UrlResolveFailed { song_id });
+                Err(e) => {
+                    log::error!("获取歌曲 {song_id} 播放链接失败: {e}");
+                    let _ = tx.send(InternalEvent::UrlResolveFailed {
+                        song_id,
+                        unplayable: e.downcast_ref::<Unplayable>().copied(),
+                        resume_ms,
+                    });
                 }
             }
-        });
+        }));
+    }
+
+    /// 结束的播放写入本地历史，并按设置上报网易云
+    fn record_play(&self, finished: Option<FinishedPlay>) {
+        let Some(finished) = finished else {
+            return;
+        };
+        self.db
+            .lock()
+            .unwrap()
+            .add_play(&finished.song, finished.played_at, finished.played_secs);
+        if self.report_plays {
+            self.send_play_report(finished.report());
+        }
+    }
+
+    fn send_play_report(&self, report: PlayReport) {
+        let send = self.resolver.report_play(report);
+        self.resolver.spawn(Box::pin(async move {
+            if let Err(e) = send.await {
+                log::warn!("上报播放记录失败: {e}");
+            }
+        }));
+    }
+
+    /// 把接下来几首的封面提前下载到磁盘缓存，切歌时封面可以立即显示
+    fn prefetch_covers(&self) {
+        let urls: Vec<String> = self
+            .queue
+            .upcoming(COVER_PREFETCH_COUNT)
+            .iter()
+            .filter(|song| !song.cover_url.is_empty())
+            // 与播放页封面使用相同尺寸，才能命中同一份缓存
+            .map(|song| thumbnail_url(&song.cover_url, 1000, 1000))
+            .collect();
+        if !urls.is_empty() {
+            async_runtime().spawn(ImageManager::global().prefetch(urls));
+        }
     }
 
     fn spawn_song_fetch(&self, ids: Vec<u64>) {
         let tx = self.internal_tx.clone();
-        async_runtime().spawn(async move {
-            match get_song_detail(ids).await {
+        let fetch = self.resolver.songs(ids);
+        self.resolver.spawn(Box::pin(async move {
+            match fetch.await {
                 Ok(songs) => {
                     let _ = tx.send(InternalEvent::SongsFetched { songs });
                 }
@@ -498,14 +925,15 @@ impl PlayerFacade {
                     log::error!("batch fetch failed: {e:?}");
                 }
             }
-        });
+        }));
     }
 
     fn spawn_playlist_fetch(&self, playlist_id: u64) {
         let tx = self.internal_tx.clone();
         eprint!("Fetching playlist {playlist_id}...");
-        async_runtime().spawn(async move {
-            match get_playlist_detail(playlist_id).await {
+        let fetch = self.resolver.playlist(playlist_id);
+        self.resolver.spawn(Box::pin(async move {
+            match fetch.await {
                 Ok(playlist) => {
                     let _ = tx.send(InternalEvent::PlaylistFetched { playlist });
                 }
@@ -513,13 +941,14 @@ impl PlayerFacade {
                     log::error!("playlist fetch failed: {e:?}");
                 }
             }
-        });
+        }));
     }
 
     fn spawn_album_fetch(&self, album_id: u64) {
         let tx = self.internal_tx.clone();
-        async_runtime().spawn(async move {
-            match get_album_detail(album_id).await {
+        let fetch = self.resolver.album(album_id);
+        self.resolver.spawn(Box::pin(async move {
+            match fetch.await {
                 Ok(album) => {
                     let _ = tx.send(InternalEvent::AlbumFetched { album });
                 }
@@ -527,13 +956,14 @@ impl PlayerFacade {
                     log::error!("album fetch failed: {e:?}");
                 }
             }
-        });
+        }));
     }
 
     fn spwa_daily_recommend_fetch(&self) {
         let tx = self.internal_tx.clone();
-        async_runtime().spawn(async move {
-            match get_recommend_song().await {
+        let fetch = self.resolver.daily_recommend();
+        self.resolver.spawn(Box::pin(async move {
+            match fetch.await {
                 Ok(songs) => {
                     let _ = tx.send(InternalEvent::DailyRecommendFetched { songs });
                 }
@@ -541,7 +971,7 @@ impl PlayerFacade {
                     log::error!("daily recommend fetch failed: {e:?}");
                 }
             }
-        });
+        }));
     }
 
     fn spawn_daily_category_fetch(
@@ -553,8 +983,9 @@ impl PlayerFacade {
         cover: String,
     ) {
         let tx = self.internal_tx.clone();
-        async_runtime().spawn(async move {
-            match get_home_category_daily_song_list(song_ids, category_id, tag_id).await {
+        let fetch = self.resolver.daily_category(song_ids, category_id, tag_id);
+        self.resolver.spawn(Box::pin(async move {
+            match fetch.await {
                 Ok(songs) => {
                     let _ = tx.send(InternalEvent::DailyCategoryFetched {
                         songs,
@@ -566,16 +997,348 @@ impl PlayerFacade {
                     log::error!("daily category fetch failed: {e:?}");
                 }
             }
+        }));
+    }
+
+    fn spawn_intelligence_fetch(&self, seed_id: u64, playlist_id: u64, start_id: u64) {
+        let tx = self.internal_tx.clone();
+        let fetch = self
+            .resolver
+            .intelligence_list(seed_id, playlist_id, start_id);
+        self.resolver.spawn(Box::pin(async move {
+            let songs = fetch.await.unwrap_or_else(|e| {
+                log::error!("intelligence list fetch failed: {e:?}");
+                Vec::new()
+            });
+            let _ = tx.send(InternalEvent::IntelligenceFetched { seed_id, songs });
+        }));
+    }
+
+    // ── 心动模式 ─────────────────────────────────────────────────────
+
+    /// 以 seed 为起点重建队列；由调用方决定是否从头播放 seed
+    fn start_intelligence(&mut self, playlist_id: u64, seed: Song) {
+        let playlist = Playlist::from_intelligence(playlist_id, &seed);
+        let tracks = Arc::new(vec![seed.clone()]);
+        self.queue
+            .load(Arc::new(vec![seed.id]), tracks.clone(), playlist.clone(), 0);
+        self.queue.set_play_mode(PlayMode::Intelligence);
+        self.intelligence = Some(Intelligence {
+            playlist_id,
+            seed_id: seed.id,
+            fetching: false,
+            resume_on_fetch: false,
+        });
+        self.emit(PlayerEvent::PlayModeChanged(PlayMode::Intelligence));
+        self.emit(PlayerEvent::SetQueue {
+            tracks,
+            playlist: Arc::new(playlist),
+            start_index: 0,
         });
     }
 
+    /// 从播放模式切换进入：以正在播放的歌为种子，不打断当前播放
+    fn switch_to_intelligence(&mut self) {
+        let playlist_id = self
+            .queue
+            .current_playlist
+            .as_ref()
+            .filter(|p| p.liked_songs)
+            .map_or(0, |p| p.id);
+        let seed = match self.queue.current() {
+            Some(QueueItem::Full(song)) if playlist_id != 0 => song.clone(),
+            _ => {
+                self.emit(PlayerEvent::ShowToast(
+                    "心动模式需要从「我喜欢的音乐」开始播放".to_string(),
+                ));
+                let mode = self.db.lock().unwrap().get_play_mode();
+                self.emit(PlayerEvent::PlayModeChanged(mode));
+                return;
+            }
+        };
+        self.start_intelligence(playlist_id, seed);
+        self.refill_intelligence();
+    }
+
+    /// 换到别的播放来源：回到保存的播放模式
+    fn leave_intelligence(&mut self) {
+        if self.intelligence.take().is_none() {
+            return;
+        }
+        let mode = self.db.lock().unwrap().get_play_mode();
+        self.queue.set_play_mode(mode);
+        self.emit(PlayerEvent::PlayModeChanged(mode));
+    }
+
+    /// 剩余歌曲不多时拉取下一批推荐，从当前歌曲往后续
+    fn refill_intelligence(&mut self) {
+        let Some(start_id) = self.current_song_id() else {
+            return;
+        };
+        if self.queue.upcoming(INTELLIGENCE_REFILL_AT).len() >= INTELLIGENCE_REFILL_AT {
+            return;
+        }
+        let Some(intelligence) = self.intelligence.as_mut().filter(|i| !i.fetching) else {
+            return;
+        };
+        intelligence.fetching = true;
+        let (seed_id, playlist_id) = (intelligence.seed_id, intelligence.playlist_id);
+        self.spawn_intelligence_fetch(seed_id, playlist_id, start_id);
+    }
+
+    /// 心动模式下已到队尾：不回绕，等下一批推荐到了再接着播
+    fn wait_for_intelligence(&mut self) -> bool {
+        if !self.queue.is_at_end() {
+            return false;
+        }
+        let Some(intelligence) = self.intelligence.as_mut() else {
+            return false;
+        };
+        intelligence.resume_on_fetch = true;
+        self.refill_intelligence();
+        self.emit(PlayerEvent::StateChanged(PlaybackState::Buffering));
+        true
+    }
+
+    fn apply_intelligence(&mut self, seed_id: u64, songs: Vec<Song>) {
+        let Some(intelligence) = self.intelligence.as_mut().filter(|i| i.seed_id == seed_id) else {
+            return;
+        };
+        intelligence.fetching = false;
+        let resume = std::mem::take(&mut intelligence.resume_on_fetch);
+
+        let mut fresh: Vec<Song> = Vec::new();
+        for song in songs {
+            if !self.queue.contains(song.id) && !fresh.iter().any(|s| s.id == song.id) {
+                fresh.push(song);
+            }
+        }
+        let extended = !fresh.is_empty();
+        if extended {
+            self.queue.append(fresh);
+            self.emit(PlayerEvent::SetQueue {
+                tracks: self.queue.get_queue(),
+                playlist: Arc::new(self.queue.current_playlist.clone().unwrap_or_default()),
+                start_index: self.queue.current_index.unwrap_or(0),
+            });
+        }
+
+        if resume {
+            if extended && self.queue.advance(false) {
+                self.play_current();
+            } else {
+                self.emit(PlayerEvent::EndOfQueue);
+            }
+        }
+    }
+
+    // ── 失败恢复 ─────────────────────────────────────────────────────
+
+    /// 网络错误：按退避时间稍后重试，次数用尽则跳过
+    fn schedule_retry(&mut self, song_id: u64, resume_ms: Option<u64>) {
+        match self.recovery.next_retry() {
+            Some(delay) => {
+                log::warn!("歌曲 {song_id} 加载失败，{} 秒后重试", delay.as_secs());
+                self.pending_retry = Some(PendingRetry {
+                    at: Instant::now() + delay,
+                    song_id,
+                    resume_ms,
+                });
+                self.emit(PlayerEvent::StateChanged(PlaybackState::Buffering));
+            }
+            None => self.skip_failed(song_id, None, true),
+        }
+    }
+
+    /// 放弃当前歌曲并跳到下一首；reason 不为空时在队列中标记为无法播放。
+    /// 连续失败达到上限时停止播放，而不是在队列里一直循环
+    fn skip_failed(&mut self, song_id: u64, reason: Option<String>, network: bool) {
+        self.pending_retry = None;
+        self.pending_seek = None;
+        if let Some(reason) = reason {
+            self.emit(PlayerEvent::TrackUnplayable { song_id, reason });
+        }
+
+        if self.recovery.on_failure() {
+            self.recovery.reset();
+            self.engine.stop();
+            let msg = if network {
+                format!(
+                    "网络连接异常，连续 {} 首歌曲加载失败，已停止播放",
+                    MAX_CONSECUTIVE_FAILURES
+                )
+            } else {
+                format!(
+                    "连续 {} 首歌曲无法播放，已停止播放",
+                    MAX_CONSECUTIVE_FAILURES
+                )
+            };
+            self.emit(PlayerEvent::ShowToast(msg));
+            self.emit(PlayerEvent::StateChanged(PlaybackState::Stopped));
+            return;
+        }
+
+        self.is_waiting_to_play = false;
+        if self.wait_for_intelligence() {
+            return;
+        }
+        if self.queue.advance(false) {
+            self.play_current();
+        } else {
+            self.emit(PlayerEvent::EndOfQueue);
+        }
+    }
+
+    // ── 定时关闭 ─────────────────────────────────────────────────────
+
+    fn tick_sleep_timer(&mut self) {
+        let Some(timer) = self.sleep_timer.as_mut() else {
+            return;
+        };
+        let now = Instant::now();
+        let tick = timer.tick(now);
+        let status = timer.status(now);
+        match tick {
+            SleepTick::Idle => {}
+            SleepTick::Fade(gain) => self.engine.set_volume(self.volume * gain),
+            SleepTick::Finished => {
+                if let Some(session) = self.mv.as_mut() {
+                    session.resume_music = false;
+                }
+                self.handle_cmd(PlayerCommand::Pause);
+                self.finish_sleep_timer();
+                return;
+            }
+        }
+        if self.last_sleep_status != Some(status) {
+            self.last_sleep_status = Some(status);
+            self.emit(PlayerEvent::SleepTimerChanged(Some(status)));
+        }
+    }
+
+    /// 按曲数定时的最后一首正在淡出时用户切歌或拖动：停止淡出并恢复音量
+    fn interrupt_sleep_fade(&mut self) {
+        if let Some(timer) = self.sleep_timer.as_mut() {
+            if timer.on_manual_change() {
+                self.engine.set_volume(self.volume);
+            }
+        }
+    }
+
+    /// 定时触发：恢复淡出前的音量并通知 UI
+    fn finish_sleep_timer(&mut self) {
+        self.cancel_sleep_timer();
+        self.emit(PlayerEvent::ShowToast("定时关闭：已暂停播放".to_string()));
+    }
+
+    fn cancel_sleep_timer(&mut self) {
+        if self.sleep_timer.take().is_some() {
+            self.engine.set_volume(self.volume);
+            self.last_sleep_status = None;
+            self.emit(PlayerEvent::SleepTimerChanged(None));
+        }
+    }
+
     // ── 工具 ─────────────────────────────────────────────────────────
 
+    fn save_pending_effects(&mut self) {
+        if let Some((_, effects)) = self.pending_effects.take() {
+            self.db.lock().unwrap().set_audio_effects(&effects);
+        }
+    }
+
+    /// 让管线使用用户选择的设备；设备不存在时退回系统默认。
+    /// notify 为 true 时（热插拔触发）用 Toast 告知切换结果
+    fn sync_output_device(&mut self, notify: bool) {
+        let Some(outputs) = &self.outputs else {
+            return;
+        };
+        let target = self
+            .preferred_output
+            .as_deref()
+            .and_then(|name| outputs.find(name).map(|device| (name.to_string(), device)));
+        let target_name = target.as_ref().map(|(name, _)| name.clone());
+
+        if target_name != self.active_output {
+            match self
+                .engine
+                .set_output_device(target.as_ref().map(|(_, device)| device))
+            {
+                Ok(()) => {
+                    let msg = match (&target_name, &self.active_output) {
+                        (Some(name), _) => Some(format!("已切换到输出设备「{}」", name)),
+                        (None, Some(old)) => {
+                            Some(format!("输出设备「{}」已断开，已切换到系统默认设备", old))
+                        }
+                        (None, None) => None,
+                    };
+                    if let Some(msg) = msg.filter(|_| notify) {
+                        self.emit(PlayerEvent::ShowToast(msg));
+                    }
+                    self.active_output = target_name;
+                }
+                Err(e) => log::error!("切换输出设备失败: {e}"),
+            }
+        }
+
+        let devices = outputs.names();
+        self.emit(PlayerEvent::OutputDevicesChanged(OutputDeviceList {
+            devices,
+            selected: self.preferred_output.clone(),
+        }));
+    }
+
+    // ── MV ───────────────────────────────────────────────────────────
+
+    /// MV 播放期间把播放控制转给 MV 管线，返回是否已转交
+    fn forward_to_mv(&self, control: MvControl) -> bool {
+        if self.mv.is_none() {
+            return false;
+        }
+        self.emit(PlayerEvent::MvControl(control));
+        true
+    }
+
+    /// 用户切回音乐（播放、切歌）：停掉 MV，音乐状态由接下来的指令决定
+    fn leave_mv(&mut self) {
+        if self.mv.take().is_none() {
+            return;
+        }
+        self.emit(PlayerEvent::MvControl(MvControl::Stop));
+        self.emit(PlayerEvent::NowPlayingChanged(NowPlaying::Music));
+        self.publish_state(PlaybackState::Paused);
+    }
+
+    /// MV 播放期间音乐链接才就绪（切歌前发出的请求、重试）：先暂停，MV 结束后继续
+    fn hold_for_mv(&mut self) {
+        if let Some(session) = self.mv.as_mut() {
+            self.engine.pause();
+            session.resume_music = true;
+        }
+    }
+
+    /// 播放状态同时通知 UI 和 MPRIS
+    fn publish_state(&self, state: PlaybackState) {
+        let _ = self.mpris_tx.send(MprisUpdate::PlaybackState(state));
+        self.emit(PlayerEvent::StateChanged(state));
+    }
+
     fn emit(&self, ev: PlayerEvent) {
         let _ = self.event_tx.send(ev);
     }
 
+    fn current_song_id(&self) -> Option<u64> {
+        match self.queue.current() {
+            Some(QueueItem::Full(song)) => Some(song.id),
+            _ => None,
+        }
+    }
+
     fn find_song(&self, song_id: u64) -> Option<Song> {
         self.queue.find_by_id(song_id)
     }
 }
+
+#[cfg(test)]
+#[path = "facade_tests.rs"]
+mod tests;
@@ -0,0 +1,834 @@
+//! PlayerFacade 场景测试：用假引擎和固定数据驱动主循环，逐轮检查发给 UI 的事件
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use super::PlayerFacade;
+use crate::api::{
+    AlbumDetail, MvDetail, PlayEndReason, PlayReport, Playlist, PlaylistDetail, Song, Unplayable,
+};
+use crate::db::Db;
+use crate::player::effects::AudioEffects;
+use crate::player::engine::{AudioEngine, GstEvent};
+use crate::player::messages::{
+    NowPlaying, PlayMode, PlaybackState, PlayerCommand, PlayerEvent, SleepTimerMode,
+};
+use crate::player::queue::QueueManager;
+use crate::player::resolver::{ResolvedUrl, TrackResolver};
+use crate::ui::model::PlaySource;
+
+#[derive(Debug, Clone, PartialEq)]
+enum EngineCall {
+    PlayUrl(String),
+    Pause,
+    Resume,
+    Stop,
+    Seek(u64),
+}
+
+/// 不发声的引擎：记录调用，事件由测试注入
+#[derive(Default)]
+struct FakeEngine {
+    calls: Arc<Mutex<Vec<EngineCall>>>,
+    events: Arc<Mutex<VecDeque<GstEvent>>>,
+    playing: bool,
+}
+
+impl FakeEngine {
+    fn record(&self, call: EngineCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+impl AudioEngine for FakeEngine {
+    fn play_url(&mut self, url: &str) {
+        self.playing = true;
+        self.record(EngineCall::PlayUrl(url.to_string()));
+    }
+
+    fn toggle(&mut self) {
+        if self.playing {
+            self.pause();
+        } else {
+            self.resume();
+        }
+    }
+
+    fn resume(&mut self) {
+        self.playing = true;
+        self.record(EngineCall::Resume);
+    }
+
+    fn pause(&mut self) {
+        self.playing = false;
+        self.record(EngineCall::Pause);
+    }
+
+    fn stop(&mut self) {
+        self.playing = false;
+        self.record(EngineCall::Stop);
+    }
+
+    fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    fn seek(&mut self, offset_ms: u64) {
+        self.record(EngineCall::Seek(offset_ms));
+    }
+
+    fn set_volume(&mut self, _volume: f64) {}
+
+    fn apply_effects(&mut self, _effects: &AudioEffects) {}
+
+    fn set_spectrum_enabled(&mut self, _enabled: bool) {}
+
+    fn take_spectrum(&self) -> Option<Vec<f32>> {
+        None
+    }
+
+    fn set_output_device(&mut self, _device: Option<&gst::Device>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn poll(&self) -> Option<GstEvent> {
+        self.events.lock().unwrap().pop_front()
+    }
+}
+
+/// 固定数据的解析器，任务同步执行，结果在同一轮主循环内处理
+#[derive(Default)]
+struct FakeResolver {
+    songs: HashMap<u64, Song>,
+    unplayable: HashMap<u64, Unplayable>,
+    /// 以 refresh=true 请求过链接的歌曲
+    refreshed: Arc<Mutex<Vec<u64>>>,
+    reports: Arc<Mutex<Vec<PlayReport>>>,
+    /// 心动模式每次请求依次返回的推荐，用完后返回空列表
+    intelligence: Mutex<VecDeque<Vec<u64>>>,
+}
+
+impl FakeResolver {
+    fn with_songs(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self {
+            songs: ids.into_iter().map(|id| (id, song(id))).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl TrackResolver for FakeResolver {
+    fn song_url(
+        &self,
+        song_id: u64,
+        refresh: bool,
+    ) -> BoxFuture<'static, anyhow::Result<ResolvedUrl>> {
+        if refresh {
+            self.refreshed.lock().unwrap().push(song_id);
+        }
+        let result = match self.unplayable.get(&song_id) {
+            Some(reason) => Err((*reason).into()),
+            None => Ok(ResolvedUrl {
+                url: url(song_id),
+                is_liked: false,
+            }),
+        };
+        Box::pin(async move { result })
+    }
+
+    fn songs(&self, ids: Vec<u64>) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        let songs = ids
+            .iter()
+            .filter_map(|id| self.songs.get(id).cloned())
+            .collect();
+        Box::pin(async move { Ok(songs) })
+    }
+
+    fn playlist(&self, id: u64) -> BoxFuture<'static, anyhow::Result<PlaylistDetail>> {
+        Box::pin(async move { anyhow::bail!("no playlist {id}") })
+    }
+
+    fn album(&self, id: u64) -> BoxFuture<'static, anyhow::Result<AlbumDetail>> {
+        Box::pin(async move { anyhow::bail!("no album {id}") })
+    }
+
+    fn daily_recommend(&self) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(async { anyhow::bail!("no daily recommend") })
+    }
+
+    fn daily_category(
+        &self,
+        _song_ids: Vec<u64>,
+        _category_id: u64,
+        _tag_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(async { anyhow::bail!("no daily category") })
+    }
+
+    fn intelligence_list(
+        &self,
+        _seed_id: u64,
+        _playlist_id: u64,
+        _start_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        let ids = self.intelligence.lock().unwrap().pop_front();
+        let songs = ids.unwrap_or_default().into_iter().map(song).collect();
+        Box::pin(async move { Ok(songs) })
+    }
+
+    fn report_play(&self, report: PlayReport) -> BoxFuture<'static, anyhow::Result<()>> {
+        self.reports.lock().unwrap().push(report);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        futures::executor::block_on(task);
+    }
+}
+
+fn song(id: u64) -> Song {
+    Song {
+        id,
+        name: format!("歌曲 {id}"),
+        ..Default::default()
+    }
+}
+
+fn mv(id: u64) -> MvDetail {
+    MvDetail {
+        id,
+        name: format!("MV {id}"),
+        ..Default::default()
+    }
+}
+
+fn url(id: u64) -> String {
+    format!("fake://{id}")
+}
+
+fn direct(ids: impl IntoIterator<Item = u64>, start_index: usize) -> PlayerCommand {
+    PlayerCommand::Play {
+        source: PlaySource::DirectTracks(Arc::new(ids.into_iter().map(song).collect())),
+        start_index,
+    }
+}
+
+/// 事件的简短描述，便于按顺序断言
+fn describe(ev: PlayerEvent) -> String {
+    match ev {
+        PlayerEvent::SetQueue {
+            tracks,
+            start_index,
+            ..
+        } => format!("SetQueue({}@{start_index})", tracks.len()),
+        PlayerEvent::TrackChanged {
+            song,
+            current_index,
+            ..
+        } => format!("TrackChanged({}@{current_index})", song.id),
+        PlayerEvent::StateChanged(state) => format!("State({state:?})"),
+        PlayerEvent::TrackUnplayable { song_id, reason } => {
+            format!("Unplayable({song_id}: {reason})")
+        }
+        PlayerEvent::TimeUpdated { position, .. } => format!("Time({position})"),
+        PlayerEvent::ShowToast(msg) => format!("Toast({msg})"),
+        PlayerEvent::Error(msg) => format!("Error({msg})"),
+        PlayerEvent::NowPlayingChanged(NowPlaying::Music) => "NowPlaying(Music)".to_string(),
+        PlayerEvent::NowPlayingChanged(NowPlaying::Mv(detail)) => {
+            format!("NowPlaying(Mv {})", detail.id)
+        }
+        other => format!("{other:?}"),
+    }
+}
+
+struct Harness {
+    facade: PlayerFacade,
+    cmd_tx: flume::Sender<PlayerCommand>,
+    events: relm4::Receiver<PlayerEvent>,
+    calls: Arc<Mutex<Vec<EngineCall>>>,
+    gst: Arc<Mutex<VecDeque<GstEvent>>>,
+    refreshed: Arc<Mutex<Vec<u64>>>,
+    reports: Arc<Mutex<Vec<PlayReport>>>,
+    _mpris_cmd_tx: flume::Sender<crate::player::messages::MprisCommand>,
+}
+
+impl Harness {
+    fn new(resolver: FakeResolver) -> Self {
+        let engine = FakeEngine::default();
+        let calls = engine.calls.clone();
+        let gst = engine.events.clone();
+        let refreshed = resolver.refreshed.clone();
+        let reports = resolver.reports.clone();
+        let (cmd_tx, cmd_rx) = flume::unbounded();
+        let (event_tx, events) = relm4::channel();
+        let (mpris_tx, _) = flume::unbounded();
+        let (mpris_cmd_tx, mpris_rx) = flume::unbounded();
+        let db = Arc::new(Mutex::new(Db::open_in_memory().unwrap()));
+        let facade = PlayerFacade::new(
+            Box::new(engine),
+            Box::new(resolver),
+            QueueManager::new(),
+            db,
+            cmd_rx,
+            event_tx,
+            mpris_tx,
+            mpris_rx,
+        );
+        Self {
+            facade,
+            cmd_tx,
+            events,
+            calls,
+            gst,
+            refreshed,
+            reports,
+            _mpris_cmd_tx: mpris_cmd_tx,
+        }
+    }
+
+    /// 发送指令并跑一轮主循环，返回这一轮发出的事件
+    fn send(&mut self, cmd: PlayerCommand) -> Vec<String> {
+        self.cmd_tx.send(cmd).unwrap();
+        self.facade.step();
+        self.drain()
+    }
+
+    /// 注入一个引擎事件并跑主循环，返回期间发出的事件。
+    /// 引擎事件在内部回调之后处理，它触发的查询结果要到下一轮才落定
+    fn gst(&mut self, ev: GstEvent) -> Vec<String> {
+        self.gst.lock().unwrap().push_back(ev);
+        self.facade.step();
+        self.facade.step();
+        self.drain()
+    }
+
+    fn drain(&self) -> Vec<String> {
+        std::iter::from_fn(|| self.events.recv().now_or_never().flatten())
+            .map(describe)
+            .collect()
+    }
+
+    fn take_calls(&self) -> Vec<EngineCall> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+
+    fn take_reports(&self) -> Vec<PlayReport> {
+        std::mem::take(&mut *self.reports.lock().unwrap())
+    }
+
+    /// 从 from 到 to 每秒上报一次进度
+    fn play_through(&mut self, from: u64, to: u64) {
+        for position in (from..=to).step_by(1000) {
+            self.gst(GstEvent::Position {
+                position,
+                duration: 200_000,
+            });
+        }
+    }
+}
+
+#[test]
+fn play_starts_requested_track() {
+    let mut h = Harness::new(FakeResolver::default());
+    assert_eq!(
+        h.send(direct([1, 2, 3], 1)),
+        ["SetQueue(3@1)", "TrackChanged(2@1)", "State(Playing)"]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::PlayUrl(url(2))]);
+
+    assert_eq!(h.send(PlayerCommand::TogglePlayPause), Vec::<String>::new());
+    assert_eq!(h.send(PlayerCommand::TogglePlayPause), Vec::<String>::new());
+    assert_eq!(h.take_calls(), [EngineCall::Pause, EngineCall::Resume]);
+
+    assert_eq!(
+        h.send(PlayerCommand::Previous),
+        ["TrackChanged(1@0)", "State(Playing)"]
+    );
+    assert_eq!(
+        h.send(PlayerCommand::PlayAt(2)),
+        ["TrackChanged(3@2)", "State(Playing)"]
+    );
+}
+
+#[test]
+fn lazy_queue_fetches_missing_tracks_before_playing_them() {
+    let mut h = Harness::new(FakeResolver::with_songs([3, 4]));
+    let events = h.send(PlayerCommand::Play {
+        source: PlaySource::LazyQueue {
+            tracks: Arc::new(vec![song(1), song(2)]),
+            track_ids: Arc::new(vec![1, 2, 3, 4]),
+            playlist: Playlist::default(),
+        },
+        start_index: 1,
+    });
+    assert_eq!(
+        events,
+        ["SetQueue(2@1)", "TrackChanged(2@1)", "State(Playing)"]
+    );
+
+    // 预加载已补全后面的歌曲，结束后直接播放
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        ["TrackChanged(3@2)", "State(Playing)"]
+    );
+    assert_eq!(h.facade.queue.get_queue().len(), 4);
+}
+
+#[test]
+fn shuffle_plays_every_track_once() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(PlayerCommand::SetLoop(false));
+    h.send(PlayerCommand::SetPlayMode(PlayMode::Shuffle));
+    assert_eq!(
+        h.send(direct(1..=6, 0)),
+        ["SetQueue(6@0)", "TrackChanged(1@0)", "State(Playing)"]
+    );
+
+    let mut played = HashSet::from([1]);
+    for _ in 0..5 {
+        let events = h.send(PlayerCommand::Next);
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert_eq!(events[1], "State(Playing)");
+        let id = h.facade.current_song_id().unwrap();
+        assert!(played.insert(id), "歌曲 {id} 重复播放");
+    }
+    assert_eq!(played.len(), 6);
+    assert_eq!(h.send(PlayerCommand::Next), ["EndOfQueue"]);
+}
+
+#[test]
+fn restore_session_stays_paused_without_autoplay() {
+    let mut h = Harness::new(FakeResolver::with_songs([1, 2, 3]));
+    let events = h.send(PlayerCommand::RestoreSession {
+        track_ids: Arc::new(vec![1, 2, 3]),
+        current_index: 1,
+        playlist: Playlist::default(),
+        autoplay: false,
+    });
+    assert_eq!(
+        events,
+        ["SetQueue(3@1)", "TrackChanged(2@1)", "State(Paused)"]
+    );
+    assert_eq!(
+        h.take_calls(),
+        [EngineCall::PlayUrl(url(2)), EngineCall::Pause]
+    );
+
+    // 只有恢复的第一首停在暂停态
+    assert_eq!(
+        h.send(PlayerCommand::Next),
+        ["TrackChanged(3@2)", "State(Playing)"]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::PlayUrl(url(3))]);
+}
+
+#[test]
+fn restore_session_with_autoplay_plays() {
+    let mut h = Harness::new(FakeResolver::with_songs([1, 2]));
+    let events = h.send(PlayerCommand::RestoreSession {
+        track_ids: Arc::new(vec![1, 2]),
+        current_index: 0,
+        playlist: Playlist::default(),
+        autoplay: true,
+    });
+    assert_eq!(
+        events,
+        ["SetQueue(2@0)", "TrackChanged(1@0)", "State(Playing)"]
+    );
+}
+
+#[test]
+fn end_of_queue_without_loop() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(PlayerCommand::SetLoop(false));
+    h.send(direct([1, 2], 1));
+    h.take_calls();
+
+    assert_eq!(h.send(PlayerCommand::Next), ["EndOfQueue"]);
+    assert_eq!(h.gst(GstEvent::EndOfStream), Vec::<String>::new());
+    assert!(h.take_calls().is_empty());
+
+    // 开启循环后回到第一首
+    h.send(PlayerCommand::SetLoop(true));
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        ["TrackChanged(1@0)", "State(Playing)"]
+    );
+}
+
+#[test]
+fn unplayable_track_is_marked_and_skipped() {
+    let mut resolver = FakeResolver::default();
+    resolver.unplayable.insert(1, Unplayable::VipOnly);
+    let mut h = Harness::new(resolver);
+    assert_eq!(
+        h.send(direct([1, 2, 3], 0)),
+        [
+            "SetQueue(3@0)",
+            "Unplayable(1: 仅限 VIP)",
+            "TrackChanged(2@1)",
+            "State(Playing)"
+        ]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::PlayUrl(url(2))]);
+
+    // 解码等无法恢复的错误同样跳过
+    assert_eq!(
+        h.gst(GstEvent::Error("Internal data stream error.".into())),
+        [
+            "Error(Internal data stream error.)",
+            "Unplayable(2: 播放出错)",
+            "TrackChanged(3@2)",
+            "State(Playing)"
+        ]
+    );
+}
+
+#[test]
+fn consecutive_failures_stop_playback() {
+    let mut resolver = FakeResolver::default();
+    for id in 1..=6 {
+        resolver.unplayable.insert(id, Unplayable::NoCopyright);
+    }
+    let mut h = Harness::new(resolver);
+    let events = h.send(direct(1..=6, 0));
+    assert_eq!(
+        events,
+        [
+            "SetQueue(6@0)",
+            "Unplayable(1: 暂无版权)",
+            "Unplayable(2: 暂无版权)",
+            "Unplayable(3: 暂无版权)",
+            "Unplayable(4: 暂无版权)",
+            "Unplayable(5: 暂无版权)",
+            "Toast(连续 5 首歌曲无法播放，已停止播放)",
+            "State(Stopped)"
+        ]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::Stop]);
+}
+
+#[test]
+fn expired_url_resumes_at_last_position() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.take_calls();
+    assert_eq!(
+        h.gst(GstEvent::Position {
+            position: 60_000,
+            duration: 200_000
+        }),
+        ["Time(60000)"]
+    );
+
+    // 换新链接续播，不算切歌
+    assert_eq!(
+        h.gst(GstEvent::Error("Forbidden (403)".into())),
+        ["State(Playing)"]
+    );
+    assert_eq!(*h.refreshed.lock().unwrap(), [1]);
+    assert_eq!(h.take_calls(), [EngineCall::PlayUrl(url(1))]);
+
+    // 拿到时长后跳回原位置
+    h.gst(GstEvent::Position {
+        position: 0,
+        duration: 200_000,
+    });
+    assert_eq!(h.take_calls(), [EngineCall::Seek(60_000)]);
+}
+
+#[test]
+fn network_error_waits_before_retry() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.take_calls();
+    assert_eq!(
+        h.gst(GstEvent::Error("Could not resolve server name.".into())),
+        ["State(Buffering)"]
+    );
+    assert!(h.facade.pending_retry.is_some());
+    assert!(h.take_calls().is_empty());
+}
+
+#[test]
+fn mv_parks_music_and_restores_it() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.gst(GstEvent::Position {
+        position: 30_000,
+        duration: 200_000,
+    });
+    h.take_calls();
+
+    assert_eq!(h.send(PlayerCommand::StartMv(mv(7))), ["NowPlaying(Mv 7)"]);
+    assert_eq!(h.take_calls(), [EngineCall::Pause]);
+    // 音乐引擎的暂停不对外公布，状态和进度来自 MV
+    assert_eq!(
+        h.gst(GstEvent::State(PlaybackState::Paused)),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        h.send(PlayerCommand::MvProgress {
+            playing: true,
+            position: 1_000,
+            duration: 90_000
+        }),
+        ["State(Playing)", "Time(1000)"]
+    );
+
+    // 播放控制转给 MV 管线
+    assert_eq!(
+        h.send(PlayerCommand::TogglePlayPause),
+        ["MvControl(Toggle)"]
+    );
+    assert_eq!(
+        h.send(PlayerCommand::Seek(5_000)),
+        ["MvControl(Seek(5000))"]
+    );
+    assert!(h.take_calls().is_empty());
+
+    // 结束后回到原来的位置继续播放
+    assert_eq!(
+        h.send(PlayerCommand::EndMv),
+        ["NowPlaying(Music)", "Time(30000)", "State(Playing)"]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::Resume]);
+}
+
+#[test]
+fn switching_back_to_music_ends_mv() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.send(PlayerCommand::Pause);
+    h.send(PlayerCommand::StartMv(mv(7)));
+    // 音乐本来就暂停着，无需再暂停
+    assert_eq!(
+        h.take_calls(),
+        [EngineCall::PlayUrl(url(1)), EngineCall::Pause]
+    );
+
+    assert_eq!(
+        h.send(PlayerCommand::Next),
+        [
+            "MvControl(Stop)",
+            "NowPlaying(Music)",
+            "State(Paused)",
+            "TrackChanged(2@1)",
+            "State(Playing)"
+        ]
+    );
+    // MV 管线随后释放，不再影响音乐
+    assert_eq!(h.send(PlayerCommand::EndMv), Vec::<String>::new());
+    assert_eq!(h.take_calls(), [EngineCall::PlayUrl(url(2))]);
+}
+
+#[test]
+fn plays_are_reported_with_listened_seconds() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(PlayerCommand::SetLoop(false));
+    h.send(PlayerCommand::SetPlayReport(true));
+    h.send(PlayerCommand::Play {
+        source: PlaySource::LazyQueue {
+            tracks: Arc::new(vec![song(1), song(2)]),
+            track_ids: Arc::new(vec![1, 2]),
+            playlist: Playlist {
+                id: 42,
+                ..Default::default()
+            },
+        },
+        start_index: 0,
+    });
+    assert_eq!(
+        h.take_reports(),
+        [PlayReport::Start {
+            song_id: 1,
+            source_id: 42
+        }]
+    );
+
+    // 拖动跳过的部分不计入听歌时长
+    h.play_through(0, 30_000);
+    h.play_through(150_000, 160_000);
+    h.send(PlayerCommand::Next);
+    assert_eq!(
+        h.take_reports(),
+        [
+            PlayReport::End {
+                song_id: 1,
+                source_id: 42,
+                played_secs: 40,
+                reason: PlayEndReason::Skipped
+            },
+            PlayReport::Start {
+                song_id: 2,
+                source_id: 42
+            }
+        ]
+    );
+
+    h.play_through(0, 200_000);
+    h.gst(GstEvent::EndOfStream);
+    assert_eq!(
+        h.take_reports(),
+        [PlayReport::End {
+            song_id: 2,
+            source_id: 42,
+            played_secs: 200,
+            reason: PlayEndReason::Finished
+        }]
+    );
+
+    // 两次播放都写入了本地历史
+    let history = h.facade.db.lock().unwrap().plays_between(0, i64::MAX);
+    let played: Vec<_> = history.iter().map(|e| (e.song.id, e.played_secs)).collect();
+    assert_eq!(played, [(1, 40), (2, 200)]);
+}
+
+#[test]
+fn plays_are_not_reported_when_disabled() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.play_through(0, 60_000);
+    h.send(PlayerCommand::Next);
+    assert!(h.take_reports().is_empty());
+    // 本地历史不受影响
+    assert_eq!(
+        h.facade.db.lock().unwrap().plays_between(0, i64::MAX).len(),
+        1
+    );
+}
+
+fn intelligence(seed: u64) -> PlayerCommand {
+    PlayerCommand::Play {
+        source: PlaySource::Intelligence {
+            playlist_id: 42,
+            seed: song(seed),
+        },
+        start_index: 0,
+    }
+}
+
+#[test]
+fn intelligence_mode_extends_the_queue_as_it_drains() {
+    let resolver = FakeResolver {
+        intelligence: Mutex::new(VecDeque::from([vec![1, 2], vec![3], vec![], vec![4]])),
+        ..Default::default()
+    };
+    let mut h = Harness::new(resolver);
+    // 推荐里包含种子本身，不重复入队
+    assert_eq!(
+        h.send(intelligence(1)),
+        [
+            "PlayModeChanged(Intelligence)",
+            "SetQueue(1@0)",
+            "TrackChanged(1@0)",
+            "State(Playing)",
+            "SetQueue(2@0)"
+        ]
+    );
+    assert_eq!(
+        h.send(PlayerCommand::Next),
+        ["TrackChanged(2@1)", "State(Playing)", "SetQueue(3@1)"]
+    );
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        ["TrackChanged(3@2)", "State(Playing)"]
+    );
+
+    // 到了队尾不回绕，等下一批到了接着播
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        [
+            "State(Buffering)",
+            "SetQueue(4@2)",
+            "TrackChanged(4@3)",
+            "State(Playing)"
+        ]
+    );
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        ["State(Buffering)", "EndOfQueue"]
+    );
+}
+
+#[test]
+fn intelligence_mode_needs_liked_songs_and_ends_with_another_source() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(PlayerCommand::SetPlayMode(PlayMode::Shuffle));
+    h.send(PlayerCommand::Play {
+        source: PlaySource::LazyQueue {
+            tracks: Arc::new(vec![song(1), song(2)]),
+            track_ids: Arc::new(vec![1, 2]),
+            playlist: Playlist {
+                id: 42,
+                ..Default::default()
+            },
+        },
+        start_index: 0,
+    });
+
+    // 普通歌单不能从播放模式开启
+    assert_eq!(
+        h.send(PlayerCommand::SetPlayMode(PlayMode::Intelligence)),
+        [
+            "Toast(心动模式需要从「我喜欢的音乐」开始播放)",
+            "PlayModeChanged(Shuffle)"
+        ]
+    );
+
+    h.send(intelligence(1));
+    // 换到别的来源后回到保存的播放模式
+    let events = h.send(direct([3, 4], 0));
+    assert_eq!(events[0], "PlayModeChanged(Shuffle)");
+}
+
+#[test]
+fn sleep_timer_at_end_of_track_stops_without_advancing() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.send(PlayerCommand::SetSleepTimer {
+        mode: SleepTimerMode::EndOfTrack,
+        fade_secs: 0,
+    });
+    h.take_calls();
+
+    assert_eq!(
+        h.gst(GstEvent::EndOfStream),
+        ["SleepTimerChanged(None)", "Toast(定时关闭：已暂停播放)"]
+    );
+    assert_eq!(h.take_calls(), [EngineCall::Stop]);
+}
+
+#[test]
+fn skipping_during_the_final_fade_cancels_it() {
+    let mut h = Harness::new(FakeResolver::default());
+    h.send(direct([1, 2], 0));
+    h.send(PlayerCommand::SetSleepTimer {
+        mode: SleepTimerMode::EndOfTrack,
+        fade_secs: 5,
+    });
+    let fading = |events: &[String], fading: bool| {
+        events
+            .iter()
+            .any(|e| e.contains(&format!("fading: {fading}")))
+    };
+
+    let events = h.gst(GstEvent::Position {
+        position: 197_000,
+        duration: 200_000,
+    });
+    assert!(fading(&events, true));
+
+    // 切到下一首后不再淡出，新歌放到快结束时才重新开始
+    let events = h.send(PlayerCommand::Next);
+    assert!(fading(&events, false));
+    let events = h.gst(GstEvent::Position {
+        position: 3_000,
+        duration: 200_000,
+    });
+    assert!(!fading(&events, true));
+    assert!(!h.take_calls().contains(&EngineCall::Pause));
+}
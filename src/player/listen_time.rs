@@ -0,0 +1,44 @@
+//! 实际收听时长：按相邻两次进度累加，拖动跳过的部分不计入，与官方客户端的统计口径一致。
+//! 播放记录和 scrobble 都用它统计。
+
+/// 相邻两次进度相差超过这么多视为拖动
+const MAX_PROGRESS_STEP_MS: u64 = 5_000;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListenTime {
+    played_ms: u64,
+    last_position: u64,
+}
+
+impl ListenTime {
+    pub fn on_position(&mut self, position: u64) {
+        let step = position.saturating_sub(self.last_position);
+        if step <= MAX_PROGRESS_STEP_MS {
+            self.played_ms += step;
+        }
+        self.last_position = position;
+    }
+
+    pub fn played_ms(&self) -> u64 {
+        self.played_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeking_is_not_counted() {
+        let mut time = ListenTime::default();
+        for position in [1_000, 2_000, 3_000] {
+            time.on_position(position);
+        }
+        // 向后拖到 60 秒，再往回拖到 10 秒
+        time.on_position(60_000);
+        time.on_position(61_000);
+        time.on_position(10_000);
+        time.on_position(11_000);
+        assert_eq!(time.played_ms(), 5_000);
+    }
+}
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use crate::{
-    api::{AlbumDetail, Playlist, PlaylistDetail, Song},
+    api::{AlbumDetail, MvDetail, Playlist, PlaylistDetail, Song, Unplayable},
+    player::effects::AudioEffects,
     ui::model::PlaySource,
 };
 
@@ -10,6 +11,70 @@ pub enum PlayMode {
     Sequential,
     SingleLoop,
     Shuffle,
+    /// 心动模式：顺序播放，队列快播完时自动续上推荐；不保存，重启后回到之前的模式
+    Intelligence,
+}
+
+/// 定时关闭方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SleepTimerMode {
+    /// N 分钟后
+    Minutes(u32),
+    /// 播完当前歌曲
+    EndOfTrack,
+    /// 播完 N 首（含当前这首）
+    Tracks(u32),
+}
+
+/// 定时关闭倒计时
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SleepTimerStatus {
+    pub remaining_secs: Option<u64>,
+    pub tracks_left: Option<u32>,
+    /// 正在淡出音量
+    pub fading: bool,
+}
+
+/// 输出设备列表
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputDeviceList {
+    pub devices: Vec<String>,
+    /// 用户选择的设备，None 为系统默认；可能当前未连接
+    pub selected: Option<String>,
+}
+
+impl OutputDeviceList {
+    /// 供界面列出的选项：系统默认 + 可用设备，所选设备未连接时附在末尾
+    pub fn choices(&self) -> Vec<(Option<String>, String)> {
+        let mut choices = vec![(None, "系统默认".to_string())];
+        choices.extend(
+            self.devices
+                .iter()
+                .map(|name| (Some(name.clone()), name.clone())),
+        );
+        if let Some(selected) = self.selected.as_ref().filter(|s| !self.devices.contains(s)) {
+            choices.push((Some(selected.clone()), format!("{}（未连接）", selected)));
+        }
+        choices
+    }
+}
+
+/// 当前在播的内容。MV 播放期间音乐队列保持暂停，MV 结束后原样恢复
+#[derive(Debug, Clone)]
+pub enum NowPlaying {
+    Music,
+    Mv(MvDetail),
+}
+
+/// MV 播放期间，播放控制由播放器转给界面线程里的 MV 管线执行
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MvControl {
+    Play,
+    Pause,
+    Toggle,
+    Seek(u64),
+    /// 用户切回音乐（播放、切歌）：暂停视频，结束 MV 会话
+    Stop,
 }
 
 /// UI 或外部调用者发给播放器的指令（只含用户意图，无内部细节）
@@ -33,6 +98,8 @@ pub enum PlayerCommand {
         song_id: u64,
         liked: bool,
     },
+    /// 设置音量（0.0 ~ 1.0）
+    SetVolume(f64),
     /// 启动时恢复上次播放：只重建队列；autoplay=false 时恢复到暂停态。
     RestoreSession {
         track_ids: Arc<Vec<u64>>,
@@ -40,6 +107,34 @@ pub enum PlayerCommand {
         playlist: Playlist,
         autoplay: bool,
     },
+    /// 设置定时关闭（覆盖已有定时），触发后在 fade_secs 秒内淡出再暂停
+    SetSleepTimer {
+        mode: SleepTimerMode,
+        fade_secs: u32,
+    },
+    CancelSleepTimer,
+    /// 更新均衡器 / 变速 / 变调 / 单声道设置，即时生效并保存
+    SetAudioEffects(AudioEffects),
+    /// 选择输出设备（按名称），None 为系统默认
+    SetOutputDevice(Option<String>),
+    /// 开关频谱数据；关闭后不再发送 `PlayerEvent::Spectrum`
+    SetSpectrumEnabled(bool),
+    /// 开关网易云播放记录上报（听歌排行、每日推荐）
+    SetPlayReport(bool),
+    /// MV 开始播放：暂停音乐并记下原来的状态，播放控制和 MPRIS 转到 MV
+    StartMv(MvDetail),
+    /// MV 管线定时上报的播放状态和进度
+    MvProgress {
+        playing: bool,
+        position: u64,
+        duration: u64,
+    },
+    /// MV 播放结束（管线释放）：恢复进入 MV 前的音乐
+    EndMv,
+    /// 退出应用前停止播放并释放 GStreamer 管线，完成后通过 `done` 回复
+    Shutdown {
+        done: flume::Sender<()>,
+    },
 }
 
 /// 播放器向 UI 发出的事件
@@ -57,6 +152,11 @@ pub enum PlayerEvent {
     },
     EndOfQueue,
     Error(String),
+    /// 歌曲无法播放（无版权 / 仅限 VIP 等），reason 用于界面提示
+    TrackUnplayable {
+        song_id: u64,
+        reason: String,
+    },
 
     SetQueue {
         tracks: Arc<Vec<Song>>,
@@ -66,6 +166,29 @@ pub enum PlayerEvent {
 
     /// 显示 Toast 消息
     ShowToast(String),
+    /// 音量变化（0.0 ~ 1.0）
+    VolumeChanged(f64),
+    /// 喜欢状态已成功同步到服务端
+    LikeChanged {
+        song_id: u64,
+        liked: bool,
+    },
+    /// 定时关闭倒计时更新；None 表示没有定时（已取消或已触发）
+    SleepTimerChanged(Option<SleepTimerStatus>),
+    /// 可用输出设备列表或所选设备变化
+    OutputDevicesChanged(OutputDeviceList),
+    /// 一帧频谱（约 20 fps），每个频段 0.0 ~ 1.0，低频在前
+    Spectrum(Arc<Vec<f32>>),
+    /// 在播内容在音乐和 MV 之间切换；MV 期间状态和进度事件来自 MV
+    NowPlayingChanged(NowPlaying),
+    /// 播放模式被播放器改变（进入或退出心动模式）
+    PlayModeChanged(PlayMode),
+    /// 由 MV 管线执行的播放控制
+    MvControl(MvControl),
+    /// MPRIS 请求显示主窗口
+    RaiseWindow,
+    /// MPRIS 请求退出应用
+    QuitRequested,
 }
 
 #[derive(Debug, Clone, PartialEq, Copy)]
@@ -86,9 +209,15 @@ pub(crate) enum InternalEvent {
         song_id: u64,
         url: String,
         is_liked: bool,
+        /// 链接过期或网络重试后重新获取：从该位置继续，不算切歌
+        resume_ms: Option<u64>,
     },
     UrlResolveFailed {
         song_id: u64,
+        /// None 表示网络等临时错误，可以重试
+        unplayable: Option<Unplayable>,
+        /// 同 `UrlResolved::resume_ms`
+        resume_ms: Option<u64>,
     },
     PlaylistFetched {
         playlist: PlaylistDetail,
@@ -104,6 +233,11 @@ pub(crate) enum InternalEvent {
         title: String,
         cover: String,
     },
+    /// 心动模式的下一批推荐；请求失败时为空
+    IntelligenceFetched {
+        seed_id: u64,
+        songs: Vec<Song>,
+    },
 }
 
 /// MPRIS 服务 → 播放器
@@ -114,10 +248,13 @@ pub enum MprisCommand {
     Next,
     Previous,
     Seek(u64),
+    Raise,
+    Quit,
 }
 
 /// 播放器 → MPRIS 服务
 pub enum MprisUpdate {
     PlaybackState(PlaybackState),
     Metadata(Song),
+    MvMetadata(MvDetail),
 }
@@ -1,10 +1,17 @@
+pub mod effects;
 mod engine;
 pub mod event_router;
 mod facade;
+pub mod listen_time;
 pub mod messages;
 pub mod mpris;
+mod output;
+mod play_log;
 pub mod player;
 mod queue;
+mod recovery;
+mod resolver;
+mod sleep_timer;
 
 pub use event_router::PlayerEventBus;
 pub use facade::PlayerFacade;
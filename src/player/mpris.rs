@@ -2,6 +2,7 @@ use flume::{Receiver, Sender};
 use mpris_server::{Metadata, PlaybackStatus, Server, Time, TrackId}; // 引入 Time 和 TrackId
 use std::sync::{Arc, Mutex};
 
+use crate::api::{MvDetail, Song};
 use crate::player::{
     messages::{MprisCommand, MprisUpdate, PlaybackState},
     player::MyPlayer,
@@ -44,38 +45,11 @@ pub fn start_mpris(update_rx: Receiver<MprisUpdate>, cmd_tx: Sender<MprisCommand
                                     .ok();
                             }
                             MprisUpdate::Metadata(song) => {
-                                let artists = song
-                                    .artists
-                                    .iter()
-                                    .map(|a| a.name.clone())
-                                    .collect::<Vec<String>>();
-
-                                let length_micros = (song.duration as i64) * 1000;
-                                let track_id = TrackId::try_from(format!(
-                                    "/com/linn/player/tracks/{}",
-                                    song.id
-                                ))
-                                .unwrap();
-
-                                let metadata = Metadata::builder()
-                                    .trackid(track_id)
-                                    .title(song.name)
-                                    .artist(artists)
-                                    .album(song.album.name)
-                                    .art_url(song.cover_url)
-                                    .length(Time::from_micros(length_micros)) // 写入时长
-                                    .build();
-
-                                // 1. 更新本地缓存
-                                *shared_metadata.lock().unwrap() = metadata.clone();
-
-                                // 2. 通知总线
-                                server
-                                    .properties_changed([mpris_server::Property::Metadata(
-                                        metadata,
-                                    )])
-                                    .await
-                                    .ok();
+                                publish_metadata(&server, &shared_metadata, song_metadata(song))
+                                    .await;
+                            }
+                            MprisUpdate::MvMetadata(mv) => {
+                                publish_metadata(&server, &shared_metadata, mv_metadata(mv)).await;
                             }
                         }
                     }
@@ -88,3 +62,55 @@ pub fn start_mpris(update_rx: Receiver<MprisUpdate>, cmd_tx: Sender<MprisCommand
         });
     });
 }
+
+async fn publish_metadata(
+    server: &Server<MyPlayer>,
+    shared_metadata: &Mutex<Metadata>,
+    metadata: Metadata,
+) {
+    // 1. 更新本地缓存
+    *shared_metadata.lock().unwrap() = metadata.clone();
+
+    // 2. 通知总线
+    server
+        .properties_changed([mpris_server::Property::Metadata(metadata)])
+        .await
+        .ok();
+}
+
+fn song_metadata(song: Song) -> Metadata {
+    let artists = song
+        .artists
+        .iter()
+        .map(|a| a.name.clone())
+        .collect::<Vec<String>>();
+
+    let length_micros = (song.duration as i64) * 1000;
+    let track_id = TrackId::try_from(format!("/com/linn/player/tracks/{}", song.id)).unwrap();
+
+    Metadata::builder()
+        .trackid(track_id)
+        .title(song.name)
+        .artist(artists)
+        .album(song.album.name)
+        .art_url(song.cover_url)
+        .length(Time::from_micros(length_micros)) // 写入时长
+        .build()
+}
+
+/// MV 详情里没有时长，只提供标题、歌手和封面
+fn mv_metadata(mv: MvDetail) -> Metadata {
+    let artists = mv
+        .artists
+        .iter()
+        .map(|a| a.name.clone())
+        .collect::<Vec<String>>();
+    let track_id = TrackId::try_from(format!("/com/linn/player/mvs/{}", mv.id)).unwrap();
+
+    Metadata::builder()
+        .trackid(track_id)
+        .title(mv.name)
+        .artist(artists)
+        .art_url(mv.cover)
+        .build()
+}
@@ -0,0 +1,62 @@
+//! 音频输出设备：通过 GStreamer 的 `DeviceMonitor` 枚举 `Audio/Sink`，并监听热插拔
+//!
+//! 设备以显示名称区分并持久化；同名设备（不同后端重复上报）只保留第一个。
+
+use gst::prelude::*;
+
+pub(crate) struct OutputDevices {
+    monitor: gst::DeviceMonitor,
+    bus: gst::Bus,
+}
+
+impl OutputDevices {
+    pub fn new() -> Option<Self> {
+        let monitor = gst::DeviceMonitor::new();
+        monitor.add_filter(Some("Audio/Sink"), None);
+        if let Err(e) = monitor.start() {
+            log::error!("音频设备监听启动失败: {e}");
+            return None;
+        }
+        let bus = monitor.bus();
+        Some(Self { monitor, bus })
+    }
+
+    /// 当前可用设备的名称，已去重
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for device in self.monitor.devices() {
+            let name = device.display_name().to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    pub fn find(&self, name: &str) -> Option<gst::Device> {
+        self.monitor
+            .devices()
+            .into_iter()
+            .find(|device| device.display_name() == name)
+    }
+
+    /// 非阻塞地取出监听消息，有设备增减时返回 true
+    pub fn poll(&self) -> bool {
+        let mut changed = false;
+        while let Some(msg) = self.bus.pop() {
+            if matches!(
+                msg.view(),
+                gst::MessageView::DeviceAdded(_) | gst::MessageView::DeviceRemoved(_)
+            ) {
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+impl Drop for OutputDevices {
+    fn drop(&mut self) {
+        self.monitor.stop();
+    }
+}
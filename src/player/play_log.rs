@@ -0,0 +1,71 @@
+//! 播放记录：每首歌结束（播完、切歌或退出）时得到实际听过的秒数，
+//! 写入本地播放历史，并按设置向网易云上报
+
+use crate::api::{PlayEndReason, PlayReport, Song};
+use crate::player::listen_time::ListenTime;
+
+/// 一次结束了的播放
+pub struct FinishedPlay {
+    pub song: Song,
+    pub source_id: u64,
+    /// 开始播放的时间（Unix 秒）
+    pub played_at: i64,
+    pub played_secs: u64,
+    pub reason: PlayEndReason,
+}
+
+impl FinishedPlay {
+    pub fn report(&self) -> PlayReport {
+        PlayReport::End {
+            song_id: self.song.id,
+            source_id: self.source_id,
+            played_secs: self.played_secs,
+            reason: self.reason,
+        }
+    }
+}
+
+struct Current {
+    song: Song,
+    source_id: u64,
+    started_at: i64,
+    listen_time: ListenTime,
+}
+
+#[derive(Default)]
+pub struct PlayLog {
+    current: Option<Current>,
+}
+
+impl PlayLog {
+    /// 开始播放一首歌；上一首还没结束时按切歌结束并返回
+    pub fn start(&mut self, song: Song, source_id: u64, now: i64) -> Option<FinishedPlay> {
+        let previous = self.finish(PlayEndReason::Skipped);
+        self.current = Some(Current {
+            song,
+            source_id,
+            started_at: now,
+            listen_time: ListenTime::default(),
+        });
+        previous
+    }
+
+    pub fn on_position(&mut self, position: u64) {
+        if let Some(current) = self.current.as_mut() {
+            current.listen_time.on_position(position);
+        }
+    }
+
+    /// 结束当前歌曲；一秒都没听的不算一次播放
+    pub fn finish(&mut self, reason: PlayEndReason) -> Option<FinishedPlay> {
+        let current = self.current.take()?;
+        let played_secs = current.listen_time.played_ms() / 1000;
+        (played_secs > 0).then_some(FinishedPlay {
+            song: current.song,
+            source_id: current.source_id,
+            played_at: current.started_at,
+            played_secs,
+            reason,
+        })
+    }
+}
@@ -19,15 +19,17 @@ impl RootInterface for MyPlayer {
     }
 
     async fn raise(&self) -> fdo::Result<()> {
+        self.cmd_tx.send(MprisCommand::Raise).ok();
         Ok(())
     }
 
     async fn quit(&self) -> fdo::Result<()> {
+        self.cmd_tx.send(MprisCommand::Quit).ok();
         Ok(())
     }
 
     async fn can_quit(&self) -> fdo::Result<bool> {
-        Ok(false)
+        Ok(true)
     }
 
     async fn fullscreen(&self) -> fdo::Result<bool> {
@@ -43,7 +45,7 @@ impl RootInterface for MyPlayer {
     }
 
     async fn can_raise(&self) -> fdo::Result<bool> {
-        Ok(false)
+        Ok(true)
     }
 
     async fn has_track_list(&self) -> fdo::Result<bool> {
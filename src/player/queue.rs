@@ -167,6 +167,44 @@ impl QueueManager {
         ids
     }
 
+    /// 按播放顺序接下来的最多 count 首已加载歌曲（不回绕）
+    pub fn upcoming(&self, count: usize) -> Vec<&Song> {
+        let Some(pos) = self
+            .current_index
+            .and_then(|ci| self.play_order.iter().position(|&i| i == ci))
+        else {
+            return Vec::new();
+        };
+        self.play_order[pos + 1..]
+            .iter()
+            .filter_map(|&idx| match self.items.get(idx) {
+                Some(QueueItem::Full(song)) => Some(song),
+                _ => None,
+            })
+            .take(count)
+            .collect()
+    }
+
+    /// 当前歌曲是否已是播放顺序中的最后一首
+    pub fn is_at_end(&self) -> bool {
+        self.current_index
+            .is_some_and(|ci| self.play_order.last() == Some(&ci))
+    }
+
+    pub fn contains(&self, song_id: u64) -> bool {
+        self.items.iter().any(|item| match item {
+            QueueItem::Full(song) => song.id == song_id,
+            QueueItem::Id(id) | QueueItem::Loading(id) => *id == song_id,
+        })
+    }
+
+    /// 在队尾追加歌曲，排在播放顺序的最后
+    pub fn append(&mut self, songs: Vec<Song>) {
+        let start = self.items.len();
+        self.items.extend(songs.into_iter().map(QueueItem::Full));
+        self.play_order.extend(start..self.items.len());
+    }
+
     fn current_waiting_id(&self) -> Option<u64> {
         self.current_index
             .and_then(|i| self.items.get(i))
@@ -0,0 +1,162 @@
+//! 播放失败恢复：区分链接过期、网络抖动和无法播放，决定重试还是跳过
+//!
+//! 链接过期（HTTP 403）时重新获取链接并从原位置继续；网络错误按 1s / 2s / 4s 退避重试；
+//! 其余错误直接跳到下一首。连续失败达到上限后停止播放，避免断网时在队列里无限循环。
+
+use std::time::Duration;
+
+/// 连续多少首失败后停止播放
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+/// 同一首歌最多重试次数
+const MAX_RETRIES: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamError {
+    /// 播放链接过期或被拒绝，换新链接即可
+    Expired,
+    /// 网络暂时不可用，稍后重试
+    Network,
+    /// 其他错误（解码失败、资源不存在等），重试无意义
+    Fatal,
+}
+
+/// 按 GStreamer 错误文本归类（souphttpsrc 的错误只有文本可用）。
+/// 文本里带着播放链接，链接中的数字和单词不参与匹配
+pub fn classify_stream_error(msg: &str) -> StreamError {
+    let words: Vec<String> = msg
+        .split_whitespace()
+        .filter(|word| !word.contains("://"))
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect();
+    let text = words.join(" ");
+    let has = |keys: &[&str]| keys.iter().any(|k| text.contains(k));
+    // 状态码须是单独的一个词，如 "Forbidden (403)"
+    if words.iter().any(|w| w == "403") || has(&["forbidden", "expired"]) {
+        StreamError::Expired
+    } else if has(&[
+        "resolve",
+        "connect",
+        "timed out",
+        "timeout",
+        "socket",
+        "network",
+        "could not read",
+    ]) {
+        StreamError::Network
+    } else {
+        StreamError::Fatal
+    }
+}
+
+/// 失败计数：每首歌的重试次数 + 跨歌曲的连续失败次数
+#[derive(Debug, Default)]
+pub(crate) struct Recovery {
+    retries: u32,
+    consecutive_failures: u32,
+}
+
+impl Recovery {
+    /// 开始播放新的一首：重试次数清零
+    pub fn on_track_start(&mut self) {
+        self.retries = 0;
+    }
+
+    /// 真正播出声音后清零连续失败
+    pub fn on_success(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    /// 用户主动选择播放时重新计数
+    pub fn reset(&mut self) {
+        self.retries = 0;
+        self.consecutive_failures = 0;
+    }
+
+    /// 申请再重试一次，返回需要等待的时间；次数用尽时返回 None
+    pub fn next_retry(&mut self) -> Option<Duration> {
+        if self.retries >= MAX_RETRIES {
+            return None;
+        }
+        let delay = Duration::from_secs(1 << self.retries);
+        self.retries += 1;
+        Some(delay)
+    }
+
+    /// 记录一首歌放弃播放；达到上限时返回 true，应停止播放
+    pub fn on_failure(&mut self) -> bool {
+        self.consecutive_failures += 1;
+        self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_errors_are_classified() {
+        assert_eq!(
+            classify_stream_error("Forbidden (403), URL: http://m701.music.126.net/x.mp3"),
+            StreamError::Expired
+        );
+        assert_eq!(
+            classify_stream_error("Could not resolve server name."),
+            StreamError::Network
+        );
+        assert_eq!(
+            classify_stream_error("Socket I/O timed out"),
+            StreamError::Network
+        );
+        assert_eq!(
+            classify_stream_error("Internal data stream error."),
+            StreamError::Fatal
+        );
+    }
+
+    #[test]
+    fn status_digits_in_the_url_are_ignored() {
+        assert_eq!(
+            classify_stream_error(
+                "Not Found: gstsouphttpsrc.c(1403): gst_soup_http_src_parse_status (): \
+                 Not Found (404), URL: http://m701.music.126.net/20250101/403ab/403.mp3"
+            ),
+            StreamError::Fatal
+        );
+        assert_eq!(
+            classify_stream_error(
+                "Internal data stream error.: URL: http://m701.music.126.net/403/x.mp3?403"
+            ),
+            StreamError::Fatal
+        );
+        assert_eq!(
+            classify_stream_error(
+                "Forbidden: gstsouphttpsrc.c(1403): gst_soup_http_src_parse_status (): \
+                 Forbidden (403), URL: http://m701.music.126.net/x.mp3"
+            ),
+            StreamError::Expired
+        );
+    }
+
+    #[test]
+    fn retries_back_off_and_failures_stop_at_limit() {
+        let mut recovery = Recovery::default();
+        assert_eq!(recovery.next_retry(), Some(Duration::from_secs(1)));
+        assert_eq!(recovery.next_retry(), Some(Duration::from_secs(2)));
+        assert_eq!(recovery.next_retry(), Some(Duration::from_secs(4)));
+        assert_eq!(recovery.next_retry(), None);
+        recovery.on_track_start();
+        assert_eq!(recovery.next_retry(), Some(Duration::from_secs(1)));
+
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            assert!(!recovery.on_failure());
+        }
+        recovery.on_success();
+        for _ in 1..MAX_CONSECUTIVE_FAILURES {
+            assert!(!recovery.on_failure());
+        }
+        assert!(recovery.on_failure());
+    }
+}
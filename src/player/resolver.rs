@@ -0,0 +1,118 @@
+//! 播放器获取歌曲数据的来源，以及播放记录的上报去向
+//!
+//! 默认的 [`ApiResolver`] 调用网易云接口并在后台运行时中执行；测试中换成固定数据、
+//! 同步执行的实现，播放器逻辑即可脱离网络和 GStreamer 运行。
+
+use futures::future::BoxFuture;
+
+use crate::api::{
+    AlbumDetail, PlayReport, PlaylistDetail, Song, SoundQuality, get_album_detail,
+    get_home_category_daily_song_list, get_intelligence_list, get_playlist_detail,
+    get_recommend_song, get_song_detail, get_song_url, is_like_song, refresh_song_url, report_play,
+};
+
+pub(crate) fn async_runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().expect("Failed to create async runtime"))
+}
+
+pub(crate) struct ResolvedUrl {
+    pub url: String,
+    pub is_liked: bool,
+}
+
+pub(crate) trait TrackResolver {
+    /// 播放链接和喜欢状态；refresh 为 true 时丢弃缓存的旧链接。
+    /// 歌曲本身无法播放时错误可 `downcast_ref::<Unplayable>()`
+    fn song_url(
+        &self,
+        song_id: u64,
+        refresh: bool,
+    ) -> BoxFuture<'static, anyhow::Result<ResolvedUrl>>;
+    fn songs(&self, ids: Vec<u64>) -> BoxFuture<'static, anyhow::Result<Vec<Song>>>;
+    fn playlist(&self, id: u64) -> BoxFuture<'static, anyhow::Result<PlaylistDetail>>;
+    fn album(&self, id: u64) -> BoxFuture<'static, anyhow::Result<AlbumDetail>>;
+    fn daily_recommend(&self) -> BoxFuture<'static, anyhow::Result<Vec<Song>>>;
+    fn daily_category(
+        &self,
+        song_ids: Vec<u64>,
+        category_id: u64,
+        tag_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>>;
+    /// 心动模式推荐，从 start_id 往后续
+    fn intelligence_list(
+        &self,
+        seed_id: u64,
+        playlist_id: u64,
+        start_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>>;
+    fn report_play(&self, report: PlayReport) -> BoxFuture<'static, anyhow::Result<()>>;
+    /// 执行查询任务，结果由任务自己经 `InternalEvent` 送回播放线程
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+}
+
+pub(crate) struct ApiResolver;
+
+impl TrackResolver for ApiResolver {
+    fn song_url(
+        &self,
+        song_id: u64,
+        refresh: bool,
+    ) -> BoxFuture<'static, anyhow::Result<ResolvedUrl>> {
+        Box::pin(async move {
+            let url = if refresh {
+                refresh_song_url(song_id, SoundQuality::Standard).await?
+            } else {
+                get_song_url(song_id, SoundQuality::Standard).await?
+            };
+            let is_liked = is_like_song(song_id).await.unwrap_or(false);
+            Ok(ResolvedUrl { url, is_liked })
+        })
+    }
+
+    fn songs(&self, ids: Vec<u64>) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(get_song_detail(ids))
+    }
+
+    fn playlist(&self, id: u64) -> BoxFuture<'static, anyhow::Result<PlaylistDetail>> {
+        Box::pin(get_playlist_detail(id))
+    }
+
+    fn album(&self, id: u64) -> BoxFuture<'static, anyhow::Result<AlbumDetail>> {
+        Box::pin(get_album_detail(id))
+    }
+
+    fn daily_recommend(&self) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(get_recommend_song())
+    }
+
+    fn daily_category(
+        &self,
+        song_ids: Vec<u64>,
+        category_id: u64,
+        tag_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(get_home_category_daily_song_list(
+            song_ids,
+            category_id,
+            tag_id,
+        ))
+    }
+
+    fn intelligence_list(
+        &self,
+        seed_id: u64,
+        playlist_id: u64,
+        start_id: u64,
+    ) -> BoxFuture<'static, anyhow::Result<Vec<Song>>> {
+        Box::pin(get_intelligence_list(seed_id, playlist_id, start_id))
+    }
+
+    fn report_play(&self, report: PlayReport) -> BoxFuture<'static, anyhow::Result<()>> {
+        Box::pin(report_play(report))
+    }
+
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        async_runtime().spawn(task);
+    }
+}
@@ -0,0 +1,149 @@
+//! 定时关闭：到点或播完指定数量的歌曲后，在淡出时长内把音量降到 0 再暂停
+//!
+//! 纯状态机，时间由调用方传入，方便 PlayerFacade 在主循环里驱动。
+
+use std::time::{Duration, Instant};
+
+use super::messages::{SleepTimerMode, SleepTimerStatus};
+
+enum Trigger {
+    /// 到达该时刻开始淡出
+    Deadline(Instant),
+    /// 还需自然播完的歌曲数（含当前这首）
+    Tracks(u32),
+}
+
+pub(crate) struct SleepTimer {
+    trigger: Trigger,
+    fade: Duration,
+    fade_started: Option<Instant>,
+}
+
+/// 每次轮询的结果
+#[derive(Debug, PartialEq)]
+pub(crate) enum SleepTick {
+    Idle,
+    /// 淡出中，值为相对原音量的增益（1.0 → 0.0）
+    Fade(f64),
+    /// 淡出结束，应暂停播放
+    Finished,
+}
+
+impl SleepTimer {
+    pub fn new(mode: SleepTimerMode, fade: Duration, now: Instant) -> Self {
+        let trigger = match mode {
+            SleepTimerMode::Minutes(min) => {
+                Trigger::Deadline(now + Duration::from_secs(u64::from(min) * 60))
+            }
+            SleepTimerMode::EndOfTrack => Trigger::Tracks(1),
+            SleepTimerMode::Tracks(n) => Trigger::Tracks(n.max(1)),
+        };
+        Self {
+            trigger,
+            fade,
+            fade_started: None,
+        }
+    }
+
+    /// 当前歌曲自然播完。返回 true 表示定时已到，不应再切到下一首
+    pub fn on_track_end(&mut self) -> bool {
+        match &mut self.trigger {
+            Trigger::Tracks(left) => {
+                *left = left.saturating_sub(1);
+                *left == 0
+            }
+            Trigger::Deadline(_) => false,
+        }
+    }
+
+    /// 按曲数定时：最后一首剩余时间不足淡出时长时开始淡出
+    pub fn on_position(&mut self, position_ms: u64, duration_ms: u64, now: Instant) {
+        if self.fade_started.is_some() || duration_ms == 0 {
+            return;
+        }
+        if matches!(self.trigger, Trigger::Tracks(1))
+            && duration_ms.saturating_sub(position_ms) <= self.fade.as_millis() as u64
+        {
+            self.fade_started = Some(now);
+        }
+    }
+
+    /// 用户切歌或拖动进度：按曲数定时时，最后一首的淡出作废，等新位置再判断。
+    /// 返回 true 表示确实中断了淡出，调用方需恢复音量
+    pub fn on_manual_change(&mut self) -> bool {
+        match self.trigger {
+            Trigger::Tracks(_) => self.fade_started.take().is_some(),
+            Trigger::Deadline(_) => false,
+        }
+    }
+
+    pub fn tick(&mut self, now: Instant) -> SleepTick {
+        let start = match (self.fade_started, &self.trigger) {
+            (Some(start), _) => start,
+            (None, Trigger::Deadline(deadline)) if now >= *deadline => {
+                self.fade_started = Some(now);
+                now
+            }
+            _ => return SleepTick::Idle,
+        };
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed >= self.fade {
+            SleepTick::Finished
+        } else {
+            SleepTick::Fade(1.0 - elapsed.as_secs_f64() / self.fade.as_secs_f64())
+        }
+    }
+
+    pub fn status(&self, now: Instant) -> SleepTimerStatus {
+        match self.trigger {
+            Trigger::Deadline(deadline) => SleepTimerStatus {
+                remaining_secs: Some(deadline.saturating_duration_since(now).as_secs()),
+                tracks_left: None,
+                fading: self.fade_started.is_some(),
+            },
+            Trigger::Tracks(left) => SleepTimerStatus {
+                remaining_secs: None,
+                tracks_left: Some(left),
+                fading: self.fade_started.is_some(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadline_fades_then_finishes() {
+        let t0 = Instant::now();
+        let mut timer = SleepTimer::new(SleepTimerMode::Minutes(1), Duration::from_secs(10), t0);
+        assert_eq!(timer.tick(t0 + Duration::from_secs(30)), SleepTick::Idle);
+        assert_eq!(
+            timer.tick(t0 + Duration::from_secs(60)),
+            SleepTick::Fade(1.0)
+        );
+        assert_eq!(
+            timer.tick(t0 + Duration::from_secs(65)),
+            SleepTick::Fade(0.5)
+        );
+        assert_eq!(
+            timer.tick(t0 + Duration::from_secs(70)),
+            SleepTick::Finished
+        );
+    }
+
+    #[test]
+    fn track_count_fades_near_end_of_last_track() {
+        let t0 = Instant::now();
+        let mut timer = SleepTimer::new(SleepTimerMode::Tracks(2), Duration::from_secs(5), t0);
+        timer.on_position(200_000, 203_000, t0);
+        assert_eq!(timer.tick(t0), SleepTick::Idle);
+        assert!(!timer.on_track_end());
+        timer.on_position(100_000, 180_000, t0);
+        assert_eq!(timer.tick(t0), SleepTick::Idle);
+        timer.on_position(176_000, 180_000, t0);
+        assert_eq!(timer.tick(t0), SleepTick::Fade(1.0));
+        assert!(timer.on_track_end());
+    }
+}
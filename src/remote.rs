@@ -0,0 +1,261 @@
+//! 命令行远程控制
+//!
+//! `linn --toggle` / `--next` / `--previous` / `--play-playlist <id>` / `--now-playing [--json]`
+//! 以及 `linn ncm://song/<id>`、`linn https://music.163.com/song?id=<id>` 这类链接都会通过 GApplication 的 `command-line` 信号
+//! 转发到已在运行的主实例，再由主窗口变成 `PlayerCommand` 或页面跳转。
+
+use std::sync::Mutex;
+
+use relm4::adw;
+use relm4::gtk::gio::prelude::{ApplicationCommandLineExt, ApplicationExt, ApplicationExtManual};
+use relm4::gtk::{gio, glib};
+use serde::Serialize;
+
+use crate::api::Song;
+use crate::ui::route::AppRoute;
+use crate::ui::window::{WINDOW_BROKER, WindowMsg};
+use crate::utils::share::{self, ShareTarget};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    TogglePlayPause,
+    Next,
+    Previous,
+    PlayPlaylist(u64),
+    /// 获取歌曲详情后单曲播放
+    PlaySong(u64),
+    Open(AppRoute),
+}
+
+impl From<ShareTarget> for RemoteCommand {
+    fn from(target: ShareTarget) -> Self {
+        // 只有单曲没有独立页面，直接播放
+        match target.route() {
+            Some(route) => RemoteCommand::Open(route),
+            None => RemoteCommand::PlaySong(target.id()),
+        }
+    }
+}
+
+/// `--now-playing` 输出的当前播放快照，由主窗口在收到播放器事件时更新
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NowPlaying {
+    pub id: u64,
+    pub name: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub cover_url: String,
+    pub position_ms: u64,
+    pub duration_ms: u64,
+    pub is_playing: bool,
+}
+
+static NOW_PLAYING: Mutex<Option<NowPlaying>> = Mutex::new(None);
+
+pub fn set_now_playing_track(song: &Song) {
+    let mut now = NOW_PLAYING.lock().unwrap();
+    let is_playing = now.as_ref().is_some_and(|n| n.is_playing);
+    *now = Some(NowPlaying {
+        id: song.id,
+        name: song.name.clone(),
+        artists: song.artists.iter().map(|a| a.name.clone()).collect(),
+        album: song.album.name.clone(),
+        cover_url: song.cover_url.clone(),
+        position_ms: 0,
+        duration_ms: song.duration,
+        is_playing,
+    });
+}
+
+pub fn set_now_playing_state(is_playing: bool) {
+    if let Some(now) = NOW_PLAYING.lock().unwrap().as_mut() {
+        now.is_playing = is_playing;
+    }
+}
+
+pub fn set_now_playing_time(position: u64, duration: u64) {
+    if let Some(now) = NOW_PLAYING.lock().unwrap().as_mut() {
+        now.position_ms = position;
+        now.duration_ms = duration;
+    }
+}
+
+fn format_time(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn now_playing_text(json: bool) -> Option<String> {
+    let now = NOW_PLAYING.lock().unwrap().clone();
+    if json {
+        return Some(serde_json::to_string(&now).unwrap_or_else(|_| "null".to_string()));
+    }
+    now.map(|n| {
+        format!(
+            "{} - {} [{}/{}]{}",
+            n.artists.join(" / "),
+            n.name,
+            format_time(n.position_ms),
+            format_time(n.duration_ms),
+            if n.is_playing { "" } else { " (暂停)" }
+        )
+    })
+}
+
+/// 解析 `ncm://<类型>/<id>` 链接
+pub fn parse_ncm_uri(uri: &str) -> Option<RemoteCommand> {
+    let rest = uri.strip_prefix("ncm://")?;
+    let mut parts = rest.trim_end_matches('/').split('/');
+    let kind = parts.next()?;
+    let id = parts.next()?.parse::<u64>().ok()?;
+    ShareTarget::from_kind(kind, id).map(RemoteCommand::from)
+}
+
+/// 注册命令行选项并处理 `command-line` 信号，需在 `RelmApp::run` 之前调用
+pub fn setup(app: &adw::Application) {
+    app.set_flags(app.flags() | gio::ApplicationFlags::HANDLES_COMMAND_LINE);
+
+    let none = glib::OptionArg::None;
+    let flags = glib::OptionFlags::NONE;
+    app.add_main_option(
+        "toggle",
+        glib::Char::from(b't'),
+        flags,
+        none,
+        "播放 / 暂停",
+        None,
+    );
+    app.add_main_option("next", glib::Char::from(b'n'), flags, none, "下一首", None);
+    app.add_main_option(
+        "previous",
+        glib::Char::from(b'p'),
+        flags,
+        none,
+        "上一首",
+        None,
+    );
+    app.add_main_option(
+        "play-playlist",
+        glib::Char::from(0),
+        flags,
+        glib::OptionArg::Int64,
+        "播放指定歌单",
+        Some("ID"),
+    );
+    app.add_main_option(
+        "now-playing",
+        glib::Char::from(0),
+        flags,
+        none,
+        "输出当前播放的歌曲",
+        None,
+    );
+    app.add_main_option(
+        "json",
+        glib::Char::from(0),
+        flags,
+        none,
+        "以 JSON 格式输出",
+        None,
+    );
+    // G_OPTION_REMAINING：其余参数（ncm:// 链接）
+    app.add_main_option(
+        "",
+        glib::Char::from(0),
+        flags,
+        glib::OptionArg::StringArray,
+        "",
+        Some("[URI…]"),
+    );
+
+    app.connect_command_line(|app, cmdline| {
+        let options = cmdline.options_dict();
+
+        if options.contains("now-playing") {
+            let json = options.contains("json");
+            return match now_playing_text(json) {
+                Some(text) => {
+                    cmdline.print_literal(&format!("{text}\n"));
+                    glib::ExitCode::SUCCESS
+                }
+                None => {
+                    cmdline.printerr_literal("当前没有在播放\n");
+                    glib::ExitCode::FAILURE
+                }
+            };
+        }
+
+        let mut commands = Vec::new();
+        if options.contains("toggle") {
+            commands.push(RemoteCommand::TogglePlayPause);
+        }
+        if options.contains("next") {
+            commands.push(RemoteCommand::Next);
+        }
+        if options.contains("previous") {
+            commands.push(RemoteCommand::Previous);
+        }
+        if let Ok(Some(id)) = options.lookup::<i64>("play-playlist") {
+            match u64::try_from(id) {
+                Ok(id) => commands.push(RemoteCommand::PlayPlaylist(id)),
+                Err(_) => {
+                    cmdline.printerr_literal(&format!("无效的歌单 ID: {id}\n"));
+                    return glib::ExitCode::FAILURE;
+                }
+            }
+        }
+        let uris = options
+            .lookup::<Vec<String>>("")
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        // 分享链接（可能是短链接）需要异步解析，交给主窗口处理
+        let mut share_links = Vec::new();
+        for uri in uris {
+            if let Some(cmd) = parse_ncm_uri(&uri) {
+                commands.push(cmd);
+            } else if share::looks_like_share_link(&uri) {
+                share_links.push(uri);
+            } else {
+                cmdline.printerr_literal(&format!("无法识别的链接: {uri}\n"));
+            }
+        }
+
+        // 纯控制命令不打扰窗口；首次启动或打开链接时显示主窗口
+        let opens_page =
+            !share_links.is_empty() || commands.iter().any(|c| matches!(c, RemoteCommand::Open(_)));
+        if !cmdline.is_remote() || commands.is_empty() || opens_page {
+            app.activate();
+        }
+        for cmd in commands {
+            WINDOW_BROKER.send(WindowMsg::Remote(cmd));
+        }
+        for link in share_links {
+            WINDOW_BROKER.send(WindowMsg::OpenShareLink(link));
+        }
+        glib::ExitCode::SUCCESS
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::model::PlaylistType;
+
+    #[test]
+    fn parse_ncm_uris() {
+        assert_eq!(
+            parse_ncm_uri("ncm://song/186016"),
+            Some(RemoteCommand::PlaySong(186016))
+        );
+        assert_eq!(
+            parse_ncm_uri("ncm://playlist/3778678/"),
+            Some(RemoteCommand::Open(AppRoute::PlaylistDetail(
+                PlaylistType::Playlist(3778678)
+            )))
+        );
+        assert_eq!(parse_ncm_uri("ncm://song/abc"), None);
+        assert_eq!(parse_ncm_uri("ncm://radio/1"), None);
+        assert_eq!(parse_ncm_uri("https://music.163.com/song?id=1"), None);
+    }
+}
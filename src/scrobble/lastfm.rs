@@ -0,0 +1,140 @@
+//! Last.fm Scrobbling API 2.0
+//!
+//! 所有请求都是带签名的 POST 表单：参数按名称排序后拼接「名称 + 值」，
+//! 末尾接上 API secret 取 md5 作为 `api_sig`。
+
+use md5::{Digest, Md5};
+use reqwest::header::CONTENT_TYPE;
+
+use super::{Listen, SubmitError};
+
+pub const DEFAULT_BASE_URL: &str = "https://ws.audioscrobbler.com/2.0";
+
+/// 重试也不会成功的错误码：方法无效、参数无效
+const REJECTED_CODES: [u64; 2] = [3, 6];
+
+pub struct LastFm {
+    base_url: String,
+    api_key: String,
+    api_secret: String,
+    session_key: String,
+    http: reqwest::Client,
+}
+
+impl LastFm {
+    pub fn new(base_url: String, api_key: String, api_secret: String, session_key: String) -> Self {
+        Self {
+            base_url,
+            api_key,
+            api_secret,
+            session_key,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn now_playing(&self, listen: &Listen) -> Result<(), SubmitError> {
+        let mut params = vec![("method".to_string(), "track.updateNowPlaying".to_string())];
+        push_listen(&mut params, listen, None);
+        self.call(params).await
+    }
+
+    pub async fn scrobble(&self, listens: &[Listen]) -> Result<(), SubmitError> {
+        let mut params = vec![("method".to_string(), "track.scrobble".to_string())];
+        for (i, listen) in listens.iter().enumerate() {
+            push_listen(&mut params, listen, Some(i));
+        }
+        self.call(params).await
+    }
+
+    async fn call(&self, mut params: Vec<(String, String)>) -> Result<(), SubmitError> {
+        params.push(("api_key".to_string(), self.api_key.clone()));
+        params.push(("sk".to_string(), self.session_key.clone()));
+        params.push(("api_sig".to_string(), sign(&params, &self.api_secret)));
+        // format 不参与签名
+        params.push(("format".to_string(), "json".to_string()));
+
+        let response = self
+            .http
+            .post(format!("{}/", self.base_url))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(form_body(&params))
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+
+        // 出错时 HTTP 状态码不一定是 4xx，以 JSON 里的 error 为准
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        match json.get("error").and_then(|v| v.as_u64()) {
+            Some(code) => {
+                let msg = format!(
+                    "Last.fm 错误 {code}: {}",
+                    json.get("message").and_then(|v| v.as_str()).unwrap_or("")
+                );
+                if REJECTED_CODES.contains(&code) {
+                    Err(SubmitError::Rejected(msg))
+                } else {
+                    Err(SubmitError::Retry(msg))
+                }
+            }
+            None if status.is_success() => Ok(()),
+            None => Err(SubmitError::from_status(status, &body)),
+        }
+    }
+}
+
+/// 批量提交时参数名带下标，如 `artist[0]`
+fn push_listen(params: &mut Vec<(String, String)>, listen: &Listen, index: Option<usize>) {
+    let name = |key: &str| match index {
+        Some(i) => format!("{key}[{i}]"),
+        None => key.to_string(),
+    };
+    params.push((name("artist"), listen.artist.clone()));
+    params.push((name("track"), listen.track.clone()));
+    if !listen.album.is_empty() {
+        params.push((name("album"), listen.album.clone()));
+    }
+    if listen.duration_ms > 0 {
+        params.push((name("duration"), (listen.duration_ms / 1000).to_string()));
+    }
+    if index.is_some() {
+        params.push((name("timestamp"), listen.started_at.to_string()));
+    }
+}
+
+fn sign(params: &[(String, String)], secret: &str) -> String {
+    let mut sorted: Vec<_> = params.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut raw = String::new();
+    for (key, value) in sorted {
+        raw.push_str(key);
+        raw.push_str(value);
+    }
+    raw.push_str(secret);
+    format!("{:x}", Md5::digest(raw.as_bytes()))
+}
+
+fn form_body(params: &[(String, String)]) -> String {
+    let mut url = reqwest::Url::parse("http://localhost/").unwrap();
+    url.query_pairs_mut().extend_pairs(params);
+    url.query().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_sorts_params_and_appends_secret() {
+        let params = vec![
+            ("sk".to_string(), "S".to_string()),
+            ("method".to_string(), "track.scrobble".to_string()),
+            ("api_key".to_string(), "K".to_string()),
+        ];
+        let expected = format!(
+            "{:x}",
+            Md5::digest("api_keyKmethodtrack.scrobbleskSsecret".as_bytes())
+        );
+        assert_eq!(sign(&params, "secret"), expected);
+    }
+}
@@ -0,0 +1,87 @@
+//! ListenBrainz `submit-listens` JSON API
+//!
+//! 用 user token 认证；「正在播放」为 `playing_now`，单条收听为 `single`，
+//! 多条（补交离线队列）为 `import`。
+
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use serde_json::{Value, json};
+
+use super::{Listen, SubmitError};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.listenbrainz.org";
+
+pub struct ListenBrainz {
+    base_url: String,
+    token: String,
+    http: reqwest::Client,
+}
+
+impl ListenBrainz {
+    pub fn new(base_url: String, token: String) -> Self {
+        Self {
+            base_url,
+            token,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn now_playing(&self, listen: &Listen) -> Result<(), SubmitError> {
+        self.submit("playing_now", vec![payload(listen, false)])
+            .await
+    }
+
+    pub async fn scrobble(&self, listens: &[Listen]) -> Result<(), SubmitError> {
+        let listen_type = if listens.len() == 1 {
+            "single"
+        } else {
+            "import"
+        };
+        let payload = listens.iter().map(|l| payload(l, true)).collect();
+        self.submit(listen_type, payload).await
+    }
+
+    async fn submit(&self, listen_type: &str, payload: Vec<Value>) -> Result<(), SubmitError> {
+        let body = json!({
+            "listen_type": listen_type,
+            "payload": payload,
+        });
+        let response = self
+            .http
+            .post(format!("{}/1/submit-listens", self.base_url))
+            .header(AUTHORIZATION, format!("Token {}", self.token))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(SubmitError::from_status(status, &body))
+    }
+}
+
+/// `playing_now` 不能带 listened_at
+fn payload(listen: &Listen, with_time: bool) -> Value {
+    let mut additional_info = json!({
+        "submission_client": crate::APP_NAME,
+        "submission_client_version": env!("CARGO_PKG_VERSION"),
+    });
+    if listen.duration_ms > 0 {
+        additional_info["duration_ms"] = json!(listen.duration_ms);
+    }
+    let mut track_metadata = json!({
+        "artist_name": listen.artist,
+        "track_name": listen.track,
+        "additional_info": additional_info,
+    });
+    if !listen.album.is_empty() {
+        track_metadata["release_name"] = json!(listen.album);
+    }
+    let mut item = json!({ "track_metadata": track_metadata });
+    if with_time {
+        item["listened_at"] = json!(listen.started_at);
+    }
+    item
+}
@@ -0,0 +1,384 @@
+//! 听歌记录同步（scrobble）
+//!
+//! 订阅 `PlayerEventBus`：切歌时发送「正在播放」，听满一半或 4 分钟后提交一次收听。
+//! 收听先写入 `Db` 的队列再发送，离线或服务暂时不可用时留在队列里定时重试。
+//! 支持 Last.fm 与 ListenBrainz 两种接口，服务器地址可在设置中修改
+//! （兼容的自建服务，或测试时的本地服务器）。
+
+mod lastfm;
+mod listenbrainz;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use relm4::gtk::gio::{self, prelude::SettingsExt};
+use serde::{Deserialize, Serialize};
+
+use crate::APPLICATION_ID;
+use crate::api::Song;
+use crate::db::Db;
+use crate::player::listen_time::ListenTime;
+use crate::player::messages::{NowPlaying, PlayerEvent};
+
+pub const SERVICE_KEY: &str = "scrobble-service";
+pub const BASE_URL_KEY: &str = "scrobble-base-url";
+pub const TOKEN_KEY: &str = "scrobble-token";
+pub const LASTFM_API_KEY_KEY: &str = "lastfm-api-key";
+pub const LASTFM_API_SECRET_KEY: &str = "lastfm-api-secret";
+
+/// 每批最多提交的条数（Last.fm 的上限）
+const BATCH_SIZE: usize = 50;
+/// 队列中有未提交的收听时，每隔这么久重试一次
+const RETRY_INTERVAL: Duration = Duration::from_secs(300);
+/// 短于 30 秒的歌曲不记录（Last.fm 规则）
+const MIN_TRACK_MS: u64 = 30_000;
+/// 听满 4 分钟即可提交，不必等到一半
+const MAX_THRESHOLD_MS: u64 = 240_000;
+
+/// 一次收听
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Listen {
+    pub artist: String,
+    pub track: String,
+    pub album: String,
+    pub duration_ms: u64,
+    /// 开始播放的时间（Unix 秒）
+    pub started_at: i64,
+}
+
+impl Listen {
+    fn from_song(song: &Song, started_at: i64) -> Self {
+        Self {
+            artist: song
+                .artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            track: song.name.clone(),
+            album: song.album.name.clone(),
+            duration_ms: song.duration,
+            started_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrobbleService {
+    LastFm,
+    ListenBrainz,
+}
+
+impl ScrobbleService {
+    /// GSettings 取值与设置页显示文字，顺序即下拉框顺序（第一项为关闭）
+    pub const CHOICES: [(Option<Self>, &'static str, &'static str); 3] = [
+        (None, "off", "关闭"),
+        (Some(Self::LastFm), "lastfm", "Last.fm"),
+        (Some(Self::ListenBrainz), "listenbrainz", "ListenBrainz"),
+    ];
+
+    pub fn from_settings(settings: &gio::Settings) -> Option<Self> {
+        let value = settings.string(SERVICE_KEY);
+        Self::CHOICES
+            .iter()
+            .find(|(_, v, _)| *v == value.as_str())
+            .and_then(|(service, _, _)| *service)
+    }
+
+    /// 服务器地址留空时使用的官方地址
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Self::LastFm => lastfm::DEFAULT_BASE_URL,
+            Self::ListenBrainz => listenbrainz::DEFAULT_BASE_URL,
+        }
+    }
+}
+
+/// 设置中的同步配置；service 为 None 时不记录
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScrobbleConfig {
+    pub service: Option<ScrobbleService>,
+    /// 空字符串时使用服务的官方地址
+    pub base_url: String,
+    /// ListenBrainz 的 user token，或 Last.fm 的 session key
+    pub token: String,
+    pub api_key: String,
+    pub api_secret: String,
+}
+
+impl ScrobbleConfig {
+    pub fn from_settings(settings: &gio::Settings) -> Self {
+        Self {
+            service: ScrobbleService::from_settings(settings),
+            base_url: settings.string(BASE_URL_KEY).trim().to_string(),
+            token: settings.string(TOKEN_KEY).trim().to_string(),
+            api_key: settings.string(LASTFM_API_KEY_KEY).trim().to_string(),
+            api_secret: settings.string(LASTFM_API_SECRET_KEY).trim().to_string(),
+        }
+    }
+
+    fn base_url(&self, service: ScrobbleService) -> String {
+        let url = if self.base_url.is_empty() {
+            service.default_base_url()
+        } else {
+            &self.base_url
+        };
+        url.trim_end_matches('/').to_string()
+    }
+}
+
+/// 提交失败的处理方式
+#[derive(Debug)]
+enum SubmitError {
+    /// 网络、服务端故障或认证失败：留在队列里，稍后（或改好配置后）重试
+    Retry(String),
+    /// 服务端拒绝这批数据（参数错误等），重试也不会成功，丢弃
+    Rejected(String),
+}
+
+impl SubmitError {
+    fn from_status(status: reqwest::StatusCode, body: &str) -> Self {
+        let msg = format!(
+            "HTTP {status}: {}",
+            body.chars().take(200).collect::<String>()
+        );
+        let retry = status.is_server_error()
+            || matches!(
+                status,
+                reqwest::StatusCode::UNAUTHORIZED
+                    | reqwest::StatusCode::FORBIDDEN
+                    | reqwest::StatusCode::TOO_MANY_REQUESTS
+            );
+        if retry {
+            Self::Retry(msg)
+        } else {
+            Self::Rejected(msg)
+        }
+    }
+}
+
+impl From<reqwest::Error> for SubmitError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Retry(e.to_string())
+    }
+}
+
+enum Client {
+    LastFm(lastfm::LastFm),
+    ListenBrainz(listenbrainz::ListenBrainz),
+}
+
+impl Client {
+    /// 配置不完整时返回 None
+    fn new(config: &ScrobbleConfig) -> Option<Self> {
+        let service = config.service?;
+        if config.token.is_empty() {
+            return None;
+        }
+        let base_url = config.base_url(service);
+        match service {
+            ScrobbleService::LastFm => {
+                if config.api_key.is_empty() || config.api_secret.is_empty() {
+                    return None;
+                }
+                Some(Self::LastFm(lastfm::LastFm::new(
+                    base_url,
+                    config.api_key.clone(),
+                    config.api_secret.clone(),
+                    config.token.clone(),
+                )))
+            }
+            ScrobbleService::ListenBrainz => Some(Self::ListenBrainz(
+                listenbrainz::ListenBrainz::new(base_url, config.token.clone()),
+            )),
+        }
+    }
+
+    async fn now_playing(&self, listen: &Listen) -> Result<(), SubmitError> {
+        match self {
+            Self::LastFm(client) => client.now_playing(listen).await,
+            Self::ListenBrainz(client) => client.now_playing(listen).await,
+        }
+    }
+
+    async fn scrobble(&self, listens: &[Listen]) -> Result<(), SubmitError> {
+        match self {
+            Self::LastFm(client) => client.scrobble(listens).await,
+            Self::ListenBrainz(client) => client.scrobble(listens).await,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    NowPlaying(Listen),
+    Scrobble(Listen),
+}
+
+/// 当前歌曲的收听进度
+struct Tracked {
+    listen: Listen,
+    /// 实际听过的时长，拖动跳过的部分不算
+    listen_time: ListenTime,
+    scrobbled: bool,
+}
+
+impl Tracked {
+    fn reached_threshold(&self) -> bool {
+        let duration = self.listen.duration_ms;
+        duration >= MIN_TRACK_MS
+            && self.listen_time.played_ms() >= (duration / 2).min(MAX_THRESHOLD_MS)
+    }
+}
+
+/// 根据播放事件决定何时发送「正在播放」、何时提交收听
+#[derive(Default)]
+struct ListenTracker {
+    current: Option<Tracked>,
+    /// 正在播放 MV：进度来自视频，不算当前歌曲的收听
+    mv_active: bool,
+}
+
+impl ListenTracker {
+    fn on_event(&mut self, event: &PlayerEvent, now: i64) -> Option<Action> {
+        match event {
+            PlayerEvent::TrackChanged { song, .. } => {
+                let listen = Listen::from_song(song, now);
+                self.current = Some(Tracked {
+                    listen: listen.clone(),
+                    listen_time: ListenTime::default(),
+                    scrobbled: false,
+                });
+                Some(Action::NowPlaying(listen))
+            }
+            PlayerEvent::NowPlayingChanged(now_playing) => {
+                self.mv_active = matches!(now_playing, NowPlaying::Mv(_));
+                None
+            }
+            PlayerEvent::TimeUpdated { position, duration } if !self.mv_active => {
+                let tracked = self.current.as_mut()?;
+                if tracked.listen.duration_ms == 0 {
+                    tracked.listen.duration_ms = *duration;
+                }
+                tracked.listen_time.on_position(*position);
+
+                if tracked.scrobbled || !tracked.reached_threshold() {
+                    return None;
+                }
+                tracked.scrobbled = true;
+                Some(Action::Scrobble(tracked.listen.clone()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 开始监听播放器事件；设置变化时立即生效
+pub fn start(events: flume::Receiver<PlayerEvent>, db: Arc<Mutex<Db>>) {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    let (config_tx, config_rx) = flume::unbounded();
+    let _ = config_tx.send(ScrobbleConfig::from_settings(&settings));
+    // 回调持有 settings 自身，使它随应用一直存活
+    settings.clone().connect_changed(None, move |_, key| {
+        let keys = [
+            SERVICE_KEY,
+            BASE_URL_KEY,
+            TOKEN_KEY,
+            LASTFM_API_KEY_KEY,
+            LASTFM_API_SECRET_KEY,
+        ];
+        if keys.contains(&key) {
+            let _ = config_tx.send(ScrobbleConfig::from_settings(&settings));
+        }
+    });
+
+    relm4::spawn(run(events, config_rx, db));
+}
+
+async fn run(
+    events: flume::Receiver<PlayerEvent>,
+    config_rx: flume::Receiver<ScrobbleConfig>,
+    db: Arc<Mutex<Db>>,
+) {
+    let mut client: Option<Arc<Client>> = None;
+    let mut tracker = ListenTracker::default();
+    let mut retry = tokio::time::interval(RETRY_INTERVAL);
+    loop {
+        tokio::select! {
+            event = events.recv_async() => {
+                let Ok(event) = event else {
+                    break;
+                };
+                let Some(action) = tracker.on_event(&event, unix_now()) else {
+                    continue;
+                };
+                // 关闭同步时不记录
+                let Some(client) = &client else {
+                    continue;
+                };
+                match action {
+                    Action::NowPlaying(listen) => {
+                        // 不等它返回，免得网络慢时积压播放事件
+                        let client = client.clone();
+                        relm4::spawn(async move {
+                            if let Err(e) = client.now_playing(&listen).await {
+                                log::warn!("发送正在播放失败: {e:?}");
+                            }
+                        });
+                    }
+                    Action::Scrobble(listen) => {
+                        db.lock().unwrap().push_scrobble(&listen);
+                        flush(client, &db).await;
+                    }
+                }
+            }
+            config = config_rx.recv_async() => {
+                let Ok(config) = config else {
+                    break;
+                };
+                client = Client::new(&config).map(Arc::new);
+                // 配置改好后立即补交积压的记录
+                if let Some(client) = &client {
+                    flush(client, &db).await;
+                }
+            }
+            _ = retry.tick() => {
+                if let Some(client) = &client {
+                    flush(client, &db).await;
+                }
+            }
+        }
+    }
+}
+
+/// 按时间顺序分批提交队列，遇到需要重试的错误就停下等下一次
+async fn flush(client: &Client, db: &Mutex<Db>) {
+    loop {
+        let batch = db.lock().unwrap().pending_scrobbles(BATCH_SIZE);
+        if batch.is_empty() {
+            return;
+        }
+        let (ids, listens): (Vec<i64>, Vec<Listen>) = batch.into_iter().unzip();
+        match client.scrobble(&listens).await {
+            Ok(()) => {}
+            Err(SubmitError::Rejected(msg)) => {
+                log::error!("听歌记录被服务端拒绝，丢弃 {} 条: {msg}", ids.len());
+            }
+            Err(SubmitError::Retry(msg)) => {
+                log::warn!("听歌记录提交失败，稍后重试: {msg}");
+                return;
+            }
+        }
+        db.lock().unwrap().remove_scrobbles(&ids);
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+#[cfg(test)]
+#[path = "scrobble_tests.rs"]
+mod tests;
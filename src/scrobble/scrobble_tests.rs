@@ -0,0 +1,177 @@
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use super::*;
+use crate::api::{Album, Artist, MvDetail, mock};
+
+fn song(id: u64, duration: u64) -> Song {
+    Song {
+        id,
+        name: format!("歌曲 {id}"),
+        artists: vec![
+            Artist {
+                name: "歌手甲".into(),
+                ..Default::default()
+            },
+            Artist {
+                name: "歌手乙".into(),
+                ..Default::default()
+            },
+        ],
+        album: Album {
+            name: "专辑".into(),
+            ..Default::default()
+        },
+        duration,
+        ..Default::default()
+    }
+}
+
+fn track_changed(song: Song) -> PlayerEvent {
+    PlayerEvent::TrackChanged {
+        song,
+        current_index: 0,
+        is_liked: false,
+    }
+}
+
+/// 从 from 到 to 每秒上报一次进度，返回期间产生的动作
+fn play(tracker: &mut ListenTracker, from: u64, to: u64, duration: u64) -> Vec<Action> {
+    (from..=to)
+        .step_by(1000)
+        .filter_map(|position| {
+            tracker.on_event(&PlayerEvent::TimeUpdated { position, duration }, 0)
+        })
+        .collect()
+}
+
+fn listen(id: u64, duration_ms: u64, started_at: i64) -> Listen {
+    Listen::from_song(&song(id, duration_ms), started_at)
+}
+
+#[test]
+fn track_change_sends_now_playing() {
+    let mut tracker = ListenTracker::default();
+    let action = tracker.on_event(&track_changed(song(1, 200_000)), 42);
+    let Some(Action::NowPlaying(listen)) = action else {
+        panic!("expected now playing, got {action:?}");
+    };
+    assert_eq!(listen.artist, "歌手甲, 歌手乙");
+    assert_eq!(listen.track, "歌曲 1");
+    assert_eq!(listen.album, "专辑");
+    assert_eq!(listen.started_at, 42);
+}
+
+#[test]
+fn scrobbles_once_after_half_the_track() {
+    let mut tracker = ListenTracker::default();
+    tracker.on_event(&track_changed(song(1, 200_000)), 7);
+
+    assert!(play(&mut tracker, 0, 99_000, 200_000).is_empty());
+    assert_eq!(
+        play(&mut tracker, 100_000, 200_000, 200_000),
+        vec![Action::Scrobble(listen(1, 200_000, 7))]
+    );
+}
+
+#[test]
+fn long_tracks_scrobble_after_four_minutes() {
+    let mut tracker = ListenTracker::default();
+    tracker.on_event(&track_changed(song(1, 600_000)), 0);
+
+    assert!(play(&mut tracker, 0, 239_000, 600_000).is_empty());
+    assert_eq!(play(&mut tracker, 240_000, 240_000, 600_000).len(), 1);
+}
+
+#[test]
+fn seeking_ahead_does_not_count_as_listening() {
+    let mut tracker = ListenTracker::default();
+    tracker.on_event(&track_changed(song(1, 200_000)), 0);
+
+    assert!(play(&mut tracker, 0, 10_000, 200_000).is_empty());
+    // 直接拖到结尾附近
+    assert!(play(&mut tracker, 190_000, 200_000, 200_000).is_empty());
+}
+
+#[test]
+fn short_tracks_are_not_scrobbled() {
+    let mut tracker = ListenTracker::default();
+    tracker.on_event(&track_changed(song(1, 20_000)), 0);
+    assert!(play(&mut tracker, 0, 20_000, 20_000).is_empty());
+}
+
+#[test]
+fn mv_progress_is_ignored() {
+    let mut tracker = ListenTracker::default();
+    tracker.on_event(&track_changed(song(1, 200_000)), 0);
+    tracker.on_event(
+        &PlayerEvent::NowPlayingChanged(NowPlaying::Mv(MvDetail::default())),
+        0,
+    );
+    assert!(play(&mut tracker, 0, 200_000, 200_000).is_empty());
+
+    tracker.on_event(&PlayerEvent::NowPlayingChanged(NowPlaying::Music), 0);
+    assert_eq!(play(&mut tracker, 0, 100_000, 200_000).len(), 1);
+}
+
+/// 按顺序返回给定状态码的本地 ListenBrainz，记录收到的请求体
+fn serve(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let bodies = received.clone();
+    std::thread::spawn(move || {
+        for (stream, status) in listener.incoming().flatten().zip(statuses) {
+            let request = mock::read_request(&stream).unwrap();
+            bodies
+                .lock()
+                .unwrap()
+                .push(serde_json::from_slice(&request.body).unwrap());
+            mock::respond(&stream, &format!("{status} X"), "").unwrap();
+        }
+    });
+    (format!("http://{addr}"), received)
+}
+
+fn listenbrainz(base_url: String) -> Client {
+    Client::new(&ScrobbleConfig {
+        service: Some(ScrobbleService::ListenBrainz),
+        base_url,
+        token: "token".into(),
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn offline_queue_is_kept_until_the_server_accepts_it() {
+    let (base_url, received) = serve(vec![503, 200]);
+    let client = listenbrainz(base_url);
+    let db = Mutex::new(Db::open_in_memory().unwrap());
+    db.lock().unwrap().push_scrobble(&listen(1, 200_000, 100));
+    db.lock().unwrap().push_scrobble(&listen(2, 200_000, 300));
+
+    flush(&client, &db).await;
+    assert_eq!(db.lock().unwrap().pending_scrobbles(BATCH_SIZE).len(), 2);
+
+    flush(&client, &db).await;
+    assert!(db.lock().unwrap().pending_scrobbles(BATCH_SIZE).is_empty());
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[1]["listen_type"], "import");
+    let payload = received[1]["payload"].as_array().unwrap();
+    assert_eq!(payload[0]["listened_at"], 100);
+    assert_eq!(payload[1]["track_metadata"]["track_name"], "歌曲 2");
+}
+
+#[tokio::test]
+async fn rejected_listens_are_dropped() {
+    let (base_url, _) = serve(vec![400]);
+    let client = listenbrainz(base_url);
+    let db = Mutex::new(Db::open_in_memory().unwrap());
+    db.lock().unwrap().push_scrobble(&listen(1, 200_000, 100));
+
+    flush(&client, &db).await;
+    assert!(db.lock().unwrap().pending_scrobbles(BATCH_SIZE).is_empty());
+}
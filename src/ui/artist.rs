@@ -1,34 +1,90 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use relm4::gtk::prelude::*;
+use relm4::prelude::FactoryVecDeque;
 use relm4::{
-    Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt, gtk,
+    Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt,
+    adw, gtk,
 };
 
 use crate::api::{
-    Album, ArtistDetail, Mv, Song, get_artist_album, get_artist_detail, get_artist_mv,
-    get_artist_song,
+    Album, Artist, ArtistDetail, ArtistSongOrder, Mv, Song, artist_subscribe, get_artist_album,
+    get_artist_detail, get_artist_mv, get_artist_song, get_artist_songs, get_simi_artist,
 };
+use crate::db::{CollectType, Db};
 use crate::ui::components::artist::album_grid::AlbumGridInput;
 use crate::ui::components::artist::mv_grid::{MvCardOutput, MvGridInput};
 use crate::ui::components::artist::song_list::SongListInput;
 use crate::ui::components::artist::{AlbumGrid, MvGrid, SongList};
+use crate::ui::components::artist_card::{
+    ArtistCard, ArtistCardInit, ArtistCardOutput, artist_row_flow_box,
+};
 use crate::ui::components::image::AsyncImage;
 use crate::ui::components::playlist_card::PlaylistCardOutput;
+use crate::ui::components::scrollable_row::ScrollableRow;
 use crate::ui::components::track_row::TrackRowOutput;
 use crate::ui::model::PlaylistType;
 use crate::ui::route::AppRoute;
 
+/// 每页歌曲数
+const SONG_PAGE_SIZE: usize = 50;
+/// 每页专辑 / MV 数
+const GRID_PAGE_SIZE: usize = 30;
+
+/// 分页状态：偏移、是否还有更多、是否正在加载
+#[derive(Debug, Default)]
+struct Paging {
+    offset: usize,
+    has_more: bool,
+    loading: bool,
+    /// 每次重置加一；结果带回发出请求时的值，对不上的是重置前的旧请求
+    generation: u64,
+}
+
+impl Paging {
+    fn reset(&mut self) {
+        self.offset = 0;
+        self.has_more = true;
+        self.loading = false;
+        self.generation += 1;
+    }
+
+    /// 是否可以发起下一页请求
+    fn can_load(&self) -> bool {
+        self.has_more && !self.loading
+    }
+}
+
+/// 分页加载的内容区
+#[derive(Debug, Clone, Copy)]
+pub enum ArtistSection {
+    Songs,
+    Albums,
+    Mvs,
+}
+
 pub struct ArtistPage {
     // 业务状态
     artist_id: u64,
     artist: ArtistDetail,
+    /// 热门 50 首，用于"播放全部"
+    top_songs_arc: Arc<Vec<Song>>,
+    /// 歌曲 tab 已加载的全部歌曲（按当前排序）
     songs_arc: Arc<Vec<Song>>,
+    song_order: ArtistSongOrder,
+    songs_paging: Paging,
+    albums_paging: Paging,
+    mvs_paging: Paging,
+    is_followed: bool,
+    has_simi: bool,
+    db: Arc<Mutex<Db>>,
 
     // 子组件控制器
     songs: Controller<SongList>,
     albums: Controller<AlbumGrid>,
     mvs: Controller<MvGrid>,
+    simi_artists: FactoryVecDeque<ArtistCard>,
+    simi_row: Controller<ScrollableRow>,
 
     bio_label: gtk::Label,
 }
@@ -36,9 +92,12 @@ pub struct ArtistPage {
 #[derive(Debug)]
 pub enum ArtistMsg {
     LoadArtistDetail(u64),
-    LoadArtistAlbums(u64),
-    LoadArtistMvs(u64),
-    LoadArtistSongs(u64),
+    LoadArtistTopSongs(u64),
+    LoadMoreSongs,
+    LoadMoreAlbums,
+    LoadMoreMvs,
+    LoadSimiArtists(u64),
+    SetSongOrder(ArtistSongOrder),
     PlayAll,
     Follow,
 
@@ -47,14 +106,37 @@ pub enum ArtistMsg {
     AlbumGridClicked(u64),
     AlbumGridPlayClicked(u64),
     MvGridClicked(u64),
+    SimiArtistClicked(u64),
 }
 
 #[derive(Debug)]
 pub enum ArtistCmdMsg {
     LoadArtistDetailed(ArtistDetail),
-    LoadArtistAlbumsed(Vec<Album>),
-    LoadArtistMvsed(Vec<Mv>),
-    LoadArtistSongsed(Vec<Song>),
+    LoadArtistTopSongsed(Vec<Song>),
+    LoadArtistSongsed {
+        generation: u64,
+        songs: Vec<Song>,
+        has_more: bool,
+    },
+    LoadArtistAlbumsed {
+        generation: u64,
+        albums: Vec<Album>,
+        has_more: bool,
+    },
+    LoadArtistMvsed {
+        generation: u64,
+        mvs: Vec<Mv>,
+        has_more: bool,
+    },
+    LoadSimiArtistsed(Vec<Artist>),
+    FollowResult {
+        success: bool,
+        followed: bool,
+    },
+    PageLoadFailed {
+        section: ArtistSection,
+        generation: u64,
+    },
 }
 #[derive(Debug)]
 pub enum ArtistPageOutput {
@@ -65,11 +147,14 @@ pub enum ArtistPageOutput {
         start_index: usize,
     },
     Navigate(AppRoute),
+    /// 关注状态变化，通知收藏页刷新关注歌手
+    FollowChanged,
+    ShowToast(String),
 }
 
 #[relm4::component(pub)]
 impl Component for ArtistPage {
-    type Init = u64;
+    type Init = (u64, Arc<Mutex<Db>>);
     type Input = ArtistMsg;
     type Output = ArtistPageOutput;
     type CommandOutput = ArtistCmdMsg;
@@ -168,7 +253,13 @@ impl Component for ArtistPage {
                             }
                         },
                         gtk::Button {
-                            set_label: "收藏/关注",
+                            #[wrap(Some)]
+                            set_child = &adw::ButtonContent {
+                                #[watch]
+                                set_label: if model.is_followed { "已关注" } else { "关注" },
+                                #[watch]
+                                set_icon_name: if model.is_followed { "heart-filled" } else { "plus-large-symbolic" },
+                            },
                             connect_clicked[sender] => move |_| {
                                 sender.input(ArtistMsg::Follow);
                             }
@@ -196,6 +287,17 @@ impl Component for ArtistPage {
                 set_transition_duration: 200,
             },
 
+            // ================= 底部相似歌手 =================
+            #[name = "simi_slot"]
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_margin_start: 24,
+                set_margin_top: 8,
+                set_margin_bottom: 16,
+                #[watch]
+                set_visible: model.has_simi,
+            },
+
             // #[name = "bio_page"]
             // gtk::ScrolledWindow {
             //     set_vexpand: true,
@@ -210,11 +312,47 @@ impl Component for ArtistPage {
             // }
         },
         #[name = "bio_label"]
-        gtk::Label {}
+        gtk::Label {},
+
+        // 歌曲 tab：排序切换 + 分页列表
+        #[name = "songs_page"]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 8,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 4,
+                set_margin_start: 24,
+
+                gtk::ToggleButton {
+                    #[watch]
+                    set_active: model.song_order == ArtistSongOrder::Hot,
+                    set_label: "热门",
+                    add_css_class: "flat",
+                    add_css_class: "comment-sort-btn",
+                    connect_clicked[sender] => move |_| {
+                        sender.input(ArtistMsg::SetSongOrder(ArtistSongOrder::Hot));
+                    },
+                },
+                gtk::ToggleButton {
+                    #[watch]
+                    set_active: model.song_order == ArtistSongOrder::Time,
+                    set_label: "最新",
+                    add_css_class: "flat",
+                    add_css_class: "comment-sort-btn",
+                    connect_clicked[sender] => move |_| {
+                        sender.input(ArtistMsg::SetSongOrder(ArtistSongOrder::Time));
+                    },
+                },
+            },
+
+            model.songs.widget(),
+        }
     }
 
     fn init(
-        artist_id: Self::Init,
+        (artist_id, db): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -238,24 +376,49 @@ impl Component for ArtistPage {
             .forward(sender.input_sender(), |msg| match msg {
                 MvCardOutput::Clicked(id) => ArtistMsg::MvGridClicked(id),
             });
+        let simi_row = ScrollableRow::new("相似歌手", 150, 160);
+        let simi_box = artist_row_flow_box();
+        simi_row.model().content_box().append(&simi_box);
+        let simi_artists =
+            FactoryVecDeque::builder()
+                .launch(simi_box)
+                .forward(sender.input_sender(), |msg| match msg {
+                    ArtistCardOutput::Clicked(id) => ArtistMsg::SimiArtistClicked(id),
+                });
+
+        let is_followed = db
+            .lock()
+            .unwrap()
+            .is_collected(artist_id, CollectType::Artist);
 
         let model = ArtistPage {
             artist_id,
             artist: ArtistDetail::default(),
+            top_songs_arc: Arc::new(Vec::new()),
+            songs_arc: Arc::new(Vec::new()),
+            song_order: ArtistSongOrder::Hot,
+            songs_paging: Paging::default(),
+            albums_paging: Paging::default(),
+            mvs_paging: Paging::default(),
+            is_followed,
+            has_simi: false,
+            db,
             songs,
             albums,
             mvs,
-            songs_arc: Arc::new(Vec::new()),
+            simi_artists,
+            simi_row,
             bio_label: gtk::Label::default(),
         };
 
         // 2. 执行 view! 宏生成 widgets 结构体
         let mut widgets = view_output!();
 
+        widgets.simi_slot.append(model.simi_row.widget());
         widgets.tab_switcher.set_stack(Some(&widgets.content_stack));
         widgets
             .content_stack
-            .add_titled(model.songs.widget(), Some("songs"), "热门单曲");
+            .add_titled(&widgets.songs_page, Some("songs"), "单曲");
         widgets
             .content_stack
             .add_titled(model.albums.widget(), Some("albums"), "所有专辑");
@@ -263,6 +426,11 @@ impl Component for ArtistPage {
             .content_stack
             .add_titled(model.mvs.widget(), Some("mvs"), "相关MV");
 
+        // 三个列表滚动到接近底部时加载下一页（无感滑动）
+        connect_load_more(model.songs.widget(), &sender, || ArtistMsg::LoadMoreSongs);
+        connect_load_more(model.albums.widget(), &sender, || ArtistMsg::LoadMoreAlbums);
+        connect_load_more(model.mvs.widget(), &sender, || ArtistMsg::LoadMoreMvs);
+
         // 手动构建 bio_page
         let bio_label = gtk::Label::builder()
             .wrap(true)
@@ -289,15 +457,36 @@ impl Component for ArtistPage {
 
     fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
         match msg {
-            ArtistMsg::PlayAll => {}
-            ArtistMsg::Follow => sender
-                .output(ArtistPageOutput::PlayQueue {
-                    artist_id: self.artist_id,
-                    artist_name: self.artist.name.clone(),
-                    songs: self.songs_arc.clone(),
-                    start_index: 0,
-                })
-                .unwrap(),
+            ArtistMsg::PlayAll => {
+                // 优先播放热门歌曲，未加载完成时退化为当前歌曲列表
+                let songs = if self.top_songs_arc.is_empty() {
+                    self.songs_arc.clone()
+                } else {
+                    self.top_songs_arc.clone()
+                };
+                if songs.is_empty() {
+                    return;
+                }
+                sender
+                    .output(ArtistPageOutput::PlayQueue {
+                        artist_id: self.artist_id,
+                        artist_name: self.artist.name.clone(),
+                        songs,
+                        start_index: 0,
+                    })
+                    .unwrap();
+            }
+            ArtistMsg::Follow => {
+                let id = self.artist_id;
+                let follow = !self.is_followed;
+                sender.command(move |out, _shutdown| async move {
+                    let result = artist_subscribe(id, follow).await;
+                    let _ = out.send(ArtistCmdMsg::FollowResult {
+                        success: result.is_ok(),
+                        followed: follow,
+                    });
+                });
+            }
             ArtistMsg::LoadArtistDetail(id) => sender.command(move |out, _shutdown| async move {
                 match get_artist_detail(id).await {
                     Ok(detail) => {
@@ -308,42 +497,122 @@ impl Component for ArtistPage {
                     }
                 }
             }),
-            ArtistMsg::LoadArtistSongs(id) => sender.command(move |out, _shutdown| async move {
+            ArtistMsg::LoadArtistTopSongs(id) => sender.command(move |out, _shutdown| async move {
                 match get_artist_song(id).await {
                     Ok(items) => {
-                        let _ = out.send(ArtistCmdMsg::LoadArtistSongsed(items));
+                        let _ = out.send(ArtistCmdMsg::LoadArtistTopSongsed(items));
                     }
                     Err(err) => {
-                        log::error!("Failed to load artist songs: {}", err);
+                        log::error!("Failed to load artist top songs: {}", err);
                     }
                 }
             }),
-            ArtistMsg::LoadArtistAlbums(id) => sender.command(move |out, _shutdown| async move {
-                match get_artist_album(id).await {
-                    Ok(items) => {
-                        let _ = out.send(ArtistCmdMsg::LoadArtistAlbumsed(items));
+            ArtistMsg::SetSongOrder(order) => {
+                if self.song_order == order {
+                    return;
+                }
+                self.song_order = order;
+                self.songs_paging.reset();
+                self.songs_arc = Arc::new(Vec::new());
+                self.songs.emit(SongListInput::SetSongs(Vec::new()));
+                sender.input(ArtistMsg::LoadMoreSongs);
+            }
+            ArtistMsg::LoadMoreSongs => {
+                if !self.songs_paging.can_load() {
+                    return;
+                }
+                self.songs_paging.loading = true;
+                let id = self.artist_id;
+                let order = self.song_order;
+                let offset = self.songs_paging.offset;
+                let generation = self.songs_paging.generation;
+                sender.command(move |out, _shutdown| async move {
+                    match get_artist_songs(id, order, offset, SONG_PAGE_SIZE).await {
+                        Ok((songs, has_more)) => {
+                            let _ = out.send(ArtistCmdMsg::LoadArtistSongsed {
+                                generation,
+                                songs,
+                                has_more,
+                            });
+                        }
+                        Err(err) => {
+                            log::error!("Failed to load artist songs: {}", err);
+                            let _ = out.send(ArtistCmdMsg::PageLoadFailed {
+                                section: ArtistSection::Songs,
+                                generation,
+                            });
+                        }
                     }
-                    Err(err) => {
-                        log::error!("Failed to load artist albums: {}", err);
+                });
+            }
+            ArtistMsg::LoadMoreAlbums => {
+                if !self.albums_paging.can_load() {
+                    return;
+                }
+                self.albums_paging.loading = true;
+                let id = self.artist_id;
+                let offset = self.albums_paging.offset;
+                let generation = self.albums_paging.generation;
+                sender.command(move |out, _shutdown| async move {
+                    match get_artist_album(id, offset, GRID_PAGE_SIZE).await {
+                        Ok((albums, has_more)) => {
+                            let _ = out.send(ArtistCmdMsg::LoadArtistAlbumsed {
+                                generation,
+                                albums,
+                                has_more,
+                            });
+                        }
+                        Err(err) => {
+                            log::error!("Failed to load artist albums: {}", err);
+                            let _ = out.send(ArtistCmdMsg::PageLoadFailed {
+                                section: ArtistSection::Albums,
+                                generation,
+                            });
+                        }
                     }
+                });
+            }
+            ArtistMsg::LoadMoreMvs => {
+                if !self.mvs_paging.can_load() {
+                    return;
                 }
-            }),
-            ArtistMsg::LoadArtistMvs(id) => sender.command(move |out, _shutdown| async move {
-                match get_artist_mv(id).await {
+                self.mvs_paging.loading = true;
+                let id = self.artist_id;
+                let offset = self.mvs_paging.offset;
+                let generation = self.mvs_paging.generation;
+                sender.command(move |out, _shutdown| async move {
+                    match get_artist_mv(id, offset, GRID_PAGE_SIZE).await {
+                        Ok((mvs, has_more)) => {
+                            let _ = out.send(ArtistCmdMsg::LoadArtistMvsed {
+                                generation,
+                                mvs,
+                                has_more,
+                            });
+                        }
+                        Err(err) => {
+                            log::error!("Failed to load artist mvs: {}", err);
+                            let _ = out.send(ArtistCmdMsg::PageLoadFailed {
+                                section: ArtistSection::Mvs,
+                                generation,
+                            });
+                        }
+                    }
+                });
+            }
+            ArtistMsg::LoadSimiArtists(id) => sender.command(move |out, _shutdown| async move {
+                match get_simi_artist(id).await {
                     Ok(items) => {
-                        let _ = out.send(ArtistCmdMsg::LoadArtistMvsed(items));
+                        let _ = out.send(ArtistCmdMsg::LoadSimiArtistsed(items));
                     }
                     Err(err) => {
-                        log::error!("Failed to load artist mvs: {}", err);
+                        log::error!("Failed to load similar artists: {}", err);
                     }
                 }
             }),
             ArtistMsg::TrackRowPlayClicked(id) => {
-                let index = self
-                    .songs_arc
-                    .iter()
-                    .position(|song| song.id == id)
-                    .unwrap();
+                let Some(index) = self.songs_arc.iter().position(|song| song.id == id) else {
+                    return;
+                };
                 sender
                     .output(ArtistPageOutput::PlayQueue {
                         artist_id: self.artist_id,
@@ -374,6 +643,11 @@ impl Component for ArtistPage {
                     .output(ArtistPageOutput::Navigate(AppRoute::Mv(id)))
                     .unwrap();
             }
+            ArtistMsg::SimiArtistClicked(id) => {
+                sender
+                    .output(ArtistPageOutput::Navigate(AppRoute::Artist(id)))
+                    .unwrap();
+            }
         }
     }
 
@@ -388,20 +662,133 @@ impl Component for ArtistPage {
                 self.artist = artist_detail;
                 self.bio_label.set_label(&self.artist.brief_desc);
                 // 触发加载其余数据
-                sender.input(ArtistMsg::LoadArtistSongs(self.artist_id));
-                sender.input(ArtistMsg::LoadArtistAlbums(self.artist_id));
-                sender.input(ArtistMsg::LoadArtistMvs(self.artist_id));
+                self.songs_paging.reset();
+                self.albums_paging.reset();
+                self.mvs_paging.reset();
+                self.songs_arc = Arc::new(Vec::new());
+                self.songs.emit(SongListInput::SetSongs(Vec::new()));
+                self.albums.emit(AlbumGridInput::SetAlbums(Vec::new()));
+                self.mvs.emit(MvGridInput::SetMvs(Vec::new()));
+                sender.input(ArtistMsg::LoadArtistTopSongs(self.artist_id));
+                sender.input(ArtistMsg::LoadMoreSongs);
+                sender.input(ArtistMsg::LoadMoreAlbums);
+                sender.input(ArtistMsg::LoadMoreMvs);
+                sender.input(ArtistMsg::LoadSimiArtists(self.artist_id));
+            }
+            ArtistCmdMsg::LoadArtistTopSongsed(songs) => {
+                self.top_songs_arc = Arc::new(songs);
+            }
+            ArtistCmdMsg::LoadArtistSongsed {
+                generation,
+                songs,
+                has_more,
+            } => {
+                // 切换排序或重新加载后，旧请求的结果直接丢弃
+                if generation != self.songs_paging.generation {
+                    return;
+                }
+                self.songs_paging.loading = false;
+                self.songs_paging.offset += songs.len();
+                self.songs_paging.has_more = has_more && !songs.is_empty();
+                let mut all = (*self.songs_arc).clone();
+                all.extend(songs.iter().cloned());
+                self.songs_arc = Arc::new(all);
+                self.songs.emit(SongListInput::AppendSongs(songs));
+            }
+            ArtistCmdMsg::LoadArtistAlbumsed {
+                generation,
+                albums,
+                has_more,
+            } => {
+                if generation != self.albums_paging.generation {
+                    return;
+                }
+                self.albums_paging.loading = false;
+                self.albums_paging.offset += albums.len();
+                self.albums_paging.has_more = has_more && !albums.is_empty();
+                self.albums.emit(AlbumGridInput::AppendAlbums(albums));
             }
-            ArtistCmdMsg::LoadArtistSongsed(songs) => {
-                self.songs_arc = Arc::new(songs.clone());
-                self.songs.emit(SongListInput::SetSongs(songs));
+            ArtistCmdMsg::LoadArtistMvsed {
+                generation,
+                mvs,
+                has_more,
+            } => {
+                if generation != self.mvs_paging.generation {
+                    return;
+                }
+                self.mvs_paging.loading = false;
+                self.mvs_paging.offset += mvs.len();
+                self.mvs_paging.has_more = has_more && !mvs.is_empty();
+                self.mvs.emit(MvGridInput::AppendMvs(mvs));
             }
-            ArtistCmdMsg::LoadArtistAlbumsed(albums) => {
-                self.albums.emit(AlbumGridInput::SetAlbums(albums));
+            ArtistCmdMsg::LoadSimiArtistsed(artists) => {
+                self.has_simi = !artists.is_empty();
+                let mut guard = self.simi_artists.guard();
+                guard.clear();
+                for artist in artists {
+                    guard.push_back(ArtistCardInit {
+                        id: artist.id,
+                        avatar_url: artist
+                            .avatar
+                            .map(|url| format!("{}?param=200y200", url))
+                            .unwrap_or_default(),
+                        name: artist.name,
+                    });
+                }
             }
-            ArtistCmdMsg::LoadArtistMvsed(mvs) => {
-                self.mvs.emit(MvGridInput::SetMvs(mvs));
+            ArtistCmdMsg::FollowResult { success, followed } => {
+                if !success {
+                    sender
+                        .output(ArtistPageOutput::ShowToast("操作失败".to_string()))
+                        .ok();
+                    return;
+                }
+                self.is_followed = followed;
+                self.db.lock().unwrap().set_collected(
+                    self.artist_id,
+                    CollectType::Artist,
+                    followed,
+                );
+                let toast = if followed {
+                    format!("已关注「{}」", self.artist.name)
+                } else {
+                    format!("已取消关注「{}」", self.artist.name)
+                };
+                sender.output(ArtistPageOutput::ShowToast(toast)).ok();
+                sender.output(ArtistPageOutput::FollowChanged).ok();
+            }
+            ArtistCmdMsg::PageLoadFailed {
+                section,
+                generation,
+            } => {
+                // 失败后允许滚动时重试；其他区的请求可能还在进行，不动它们
+                let paging = match section {
+                    ArtistSection::Songs => &mut self.songs_paging,
+                    ArtistSection::Albums => &mut self.albums_paging,
+                    ArtistSection::Mvs => &mut self.mvs_paging,
+                };
+                // 旧请求失败时新请求可能还在进行
+                if generation == paging.generation {
+                    paging.loading = false;
+                }
             }
         }
     }
 }
+
+/// 列表滚动到接近底部时发送分页消息
+fn connect_load_more(
+    scrolled: &gtk::ScrolledWindow,
+    sender: &ComponentSender<ArtistPage>,
+    msg: fn() -> ArtistMsg,
+) {
+    let scroll_sender = sender.input_sender().clone();
+    scrolled.vadjustment().connect_value_changed(move |adj| {
+        let value = adj.value();
+        let upper = adj.upper();
+        let page_size = adj.page_size();
+        if upper > 0.0 && upper - (value + page_size) < 200.0 {
+            let _ = scroll_sender.send(msg());
+        }
+    });
+}
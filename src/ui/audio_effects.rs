@@ -0,0 +1,446 @@
+//! 设置中的“音效”页：输出设备、均衡器、播放速度、变调和单声道
+//!
+//! 每次改动都通过 `AudioEffectsOutput` 交给播放器即时应用，由播放器负责保存；
+//! 自定义均衡器预设直接存入 `Db`。
+
+use std::sync::{Arc, Mutex};
+
+use relm4::adw::prelude::*;
+use relm4::gtk::glib;
+use relm4::{ComponentParts, ComponentSender, RelmWidgetExt, SimpleComponent, adw, gtk};
+
+use crate::db::Db;
+use crate::player::effects::{
+    AudioEffects, BUILTIN_PRESETS, EQ_BAND_COUNT, EQ_FREQUENCIES, EQ_MAX_DB, EQ_MIN_DB,
+    PITCH_RANGE, SPEED_MAX, SPEED_MIN, band_label,
+};
+use crate::player::messages::OutputDeviceList;
+
+const CUSTOM_LABEL: &str = "自定义";
+
+pub struct AudioEffectsPage {
+    db: Arc<Mutex<Db>>,
+    effects: AudioEffects,
+    custom_presets: Vec<(String, [f64; EQ_BAND_COUNT])>,
+    /// 预设下拉框的内容：内置预设 + 自定义预设 + “自定义”
+    preset_names: gtk::StringList,
+    band_scales: Vec<gtk::Scale>,
+    /// 输出设备下拉框的选项，与 `output_names` 一一对应
+    output_choices: Vec<Option<String>>,
+    output_names: gtk::StringList,
+    output_selected: u32,
+    /// 替换下拉框内容时选中项会跟着变动，回调只发通知，处理时再读取控件的当前选中项，
+    /// 这样 `#[watch]` 恢复后的选中项才是最终结果
+    preset_row: adw::ComboRow,
+    output_row: adw::ComboRow,
+}
+
+#[derive(Debug)]
+pub enum AudioEffectsMsg {
+    EqToggled(bool),
+    PresetSelected,
+    BandChanged(usize, f64),
+    SpeedChanged(f64),
+    PitchChanged(f64),
+    MonoToggled(bool),
+    OutputDevicesChanged(OutputDeviceList),
+    OutputDeviceSelected,
+    PromptSavePreset,
+    SavePreset(String),
+    DeletePreset,
+    Reset,
+}
+
+#[derive(Debug)]
+pub enum AudioEffectsOutput {
+    Changed(AudioEffects),
+    OutputDeviceSelected(Option<String>),
+}
+
+#[relm4::component(pub)]
+impl SimpleComponent for AudioEffectsPage {
+    type Init = Arc<Mutex<Db>>;
+    type Input = AudioEffectsMsg;
+    type Output = AudioEffectsOutput;
+
+    view! {
+        adw::PreferencesPage {
+            set_title: "音效",
+            set_icon_name: Some("audio-speakers-symbolic"),
+
+            adw::PreferencesGroup {
+                set_title: "均衡器",
+
+                adw::SwitchRow {
+                    set_title: "启用均衡器",
+
+                    #[watch]
+                    set_active: model.effects.eq_enabled,
+
+                    connect_active_notify[sender] => move |switch| {
+                        sender.input(AudioEffectsMsg::EqToggled(switch.is_active()));
+                    },
+                },
+
+                #[name(preset_row)]
+                adw::ComboRow {
+                    set_title: "预设",
+                    set_model: Some(&model.preset_names),
+
+                    #[watch]
+                    set_sensitive: model.effects.eq_enabled,
+                    #[watch]
+                    set_selected: model.preset_index(),
+
+                    connect_selected_notify[sender] => move |_| {
+                        sender.input(AudioEffectsMsg::PresetSelected);
+                    },
+                },
+
+                adw::PreferencesRow {
+                    set_activatable: false,
+
+                    #[wrap(Some)]
+                    #[name(bands_box)]
+                    set_child = &gtk::Box {
+                        set_homogeneous: true,
+                        set_margin_all: 12,
+                        set_height_request: 220,
+
+                        #[watch]
+                        set_sensitive: model.effects.eq_enabled,
+                    },
+                },
+
+                adw::ButtonRow {
+                    set_title: "保存为预设",
+                    set_start_icon_name: Some("document-save-symbolic"),
+
+                    #[watch]
+                    set_sensitive: model.effects.eq_enabled,
+
+                    connect_activated[sender] => move |_| {
+                        sender.input(AudioEffectsMsg::PromptSavePreset);
+                    },
+                },
+
+                adw::ButtonRow {
+                    set_title: "删除当前预设",
+                    set_start_icon_name: Some("user-trash-symbolic"),
+                    add_css_class: "destructive-action",
+
+                    #[watch]
+                    set_visible: model.current_custom_preset().is_some(),
+
+                    connect_activated[sender] => move |_| {
+                        sender.input(AudioEffectsMsg::DeletePreset);
+                    },
+                },
+            },
+
+            adw::PreferencesGroup {
+                set_title: "播放",
+
+                #[name(output_row)]
+                adw::ComboRow {
+                    set_title: "输出设备",
+                    set_subtitle: "所选设备断开时自动使用系统默认设备，重新接入后切回",
+                    set_model: Some(&model.output_names),
+
+                    #[watch]
+                    set_selected: model.output_selected,
+
+                    connect_selected_notify[sender] => move |_| {
+                        sender.input(AudioEffectsMsg::OutputDeviceSelected);
+                    },
+                },
+
+                adw::SpinRow {
+                    set_title: "播放速度",
+                    set_subtitle: "改变速度时保持音调不变",
+                    set_digits: 2,
+                    set_adjustment: Some(&gtk::Adjustment::new(1.0, SPEED_MIN, SPEED_MAX, 0.05, 0.25, 0.0)),
+
+                    #[watch]
+                    set_value: model.effects.speed,
+
+                    connect_value_notify[sender] => move |row| {
+                        sender.input(AudioEffectsMsg::SpeedChanged(row.value()));
+                    },
+                },
+
+                adw::SpinRow {
+                    set_title: "变调",
+                    set_subtitle: "以半音为单位升高或降低音调",
+                    set_digits: 1,
+                    set_adjustment: Some(&gtk::Adjustment::new(0.0, -PITCH_RANGE, PITCH_RANGE, 0.5, 1.0, 0.0)),
+
+                    #[watch]
+                    set_value: model.effects.pitch_semitones,
+
+                    connect_value_notify[sender] => move |row| {
+                        sender.input(AudioEffectsMsg::PitchChanged(row.value()));
+                    },
+                },
+
+                adw::SwitchRow {
+                    set_title: "单声道",
+                    set_subtitle: "将左右声道混合后输出",
+
+                    #[watch]
+                    set_active: model.effects.mono,
+
+                    connect_active_notify[sender] => move |switch| {
+                        sender.input(AudioEffectsMsg::MonoToggled(switch.is_active()));
+                    },
+                },
+
+                adw::ButtonRow {
+                    set_title: "恢复默认音效",
+                    set_start_icon_name: Some("view-refresh-symbolic"),
+
+                    connect_activated[sender] => move |_| {
+                        sender.input(AudioEffectsMsg::Reset);
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        db: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let (effects, custom_presets) = {
+            let db = db.lock().unwrap();
+            (db.get_audio_effects(), db.get_eq_presets())
+        };
+
+        let mut model = Self {
+            db,
+            effects,
+            custom_presets,
+            preset_names: gtk::StringList::new(&[]),
+            band_scales: Vec::with_capacity(EQ_BAND_COUNT),
+            output_choices: vec![None],
+            output_names: gtk::StringList::new(&["系统默认"]),
+            output_selected: 0,
+            preset_row: adw::ComboRow::default(),
+            output_row: adw::ComboRow::default(),
+        };
+        model.refresh_preset_names();
+
+        let widgets = view_output!();
+        model.preset_row = widgets.preset_row.clone();
+        model.output_row = widgets.output_row.clone();
+
+        for (i, freq) in EQ_FREQUENCIES.iter().enumerate() {
+            let scale =
+                gtk::Scale::with_range(gtk::Orientation::Vertical, EQ_MIN_DB, EQ_MAX_DB, 0.5);
+            scale.set_inverted(true);
+            scale.set_vexpand(true);
+            scale.set_draw_value(false);
+            scale.add_mark(0.0, gtk::PositionType::Right, None);
+            scale.set_value(model.effects.eq_gains[i]);
+            // change-value 只在用户拖动时触发，程序设置数值不会回调
+            let input = sender.input_sender().clone();
+            scale.connect_change_value(move |_, _, value| {
+                input.emit(AudioEffectsMsg::BandChanged(i, value));
+                glib::Propagation::Proceed
+            });
+
+            let label = gtk::Label::new(Some(&band_label(*freq)));
+            label.add_css_class("caption");
+            label.add_css_class("dim-label");
+
+            let column = gtk::Box::new(gtk::Orientation::Vertical, 6);
+            column.append(&scale);
+            column.append(&label);
+            widgets.bands_box.append(&column);
+            model.band_scales.push(scale);
+        }
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            AudioEffectsMsg::EqToggled(active) => {
+                if self.effects.eq_enabled == active {
+                    return;
+                }
+                self.effects.eq_enabled = active;
+            }
+            AudioEffectsMsg::PresetSelected => {
+                // 程序同步下拉框或选中“自定义”时不改动曲线
+                let index = self.preset_row.selected();
+                if index == self.preset_index() {
+                    return;
+                }
+                let index = index as usize;
+                let (name, gains) = if let Some(preset) = BUILTIN_PRESETS.get(index) {
+                    (preset.name.to_string(), preset.gains)
+                } else if let Some((name, gains)) =
+                    self.custom_presets.get(index - BUILTIN_PRESETS.len())
+                {
+                    (name.clone(), *gains)
+                } else {
+                    return;
+                };
+                self.effects.eq_preset = Some(name);
+                self.effects.eq_gains = gains;
+                self.sync_scales();
+            }
+            AudioEffectsMsg::BandChanged(band, value) => {
+                let value = value.clamp(EQ_MIN_DB, EQ_MAX_DB);
+                self.effects.eq_gains[band] = value;
+                self.effects.eq_preset = None;
+            }
+            AudioEffectsMsg::SpeedChanged(speed) => {
+                if (self.effects.speed - speed).abs() < f64::EPSILON {
+                    return;
+                }
+                self.effects.speed = speed;
+            }
+            AudioEffectsMsg::PitchChanged(semitones) => {
+                if (self.effects.pitch_semitones - semitones).abs() < f64::EPSILON {
+                    return;
+                }
+                self.effects.pitch_semitones = semitones;
+            }
+            AudioEffectsMsg::MonoToggled(active) => {
+                if self.effects.mono == active {
+                    return;
+                }
+                self.effects.mono = active;
+            }
+            AudioEffectsMsg::PromptSavePreset => {
+                if let Some(scale) = self.band_scales.first() {
+                    present_save_dialog(scale, sender.input_sender().clone());
+                }
+                return;
+            }
+            AudioEffectsMsg::SavePreset(name) => {
+                let name = name.trim();
+                if name.is_empty() {
+                    return;
+                }
+                // 不覆盖内置预设
+                let name = if BUILTIN_PRESETS.iter().any(|p| p.name == name) {
+                    format!("{name}（{CUSTOM_LABEL}）")
+                } else {
+                    name.to_string()
+                };
+                self.db
+                    .lock()
+                    .unwrap()
+                    .save_eq_preset(&name, &self.effects.eq_gains);
+                self.custom_presets = self.db.lock().unwrap().get_eq_presets();
+                self.effects.eq_preset = Some(name);
+                self.refresh_preset_names();
+            }
+            AudioEffectsMsg::DeletePreset => {
+                let Some(name) = self.current_custom_preset().map(str::to_string) else {
+                    return;
+                };
+                self.db.lock().unwrap().delete_eq_preset(&name);
+                self.custom_presets.retain(|(n, _)| *n != name);
+                self.effects.eq_preset = None;
+                self.refresh_preset_names();
+            }
+            AudioEffectsMsg::OutputDevicesChanged(list) => {
+                let (choices, names): (Vec<_>, Vec<_>) = list.choices().into_iter().unzip();
+                let selected = choices
+                    .iter()
+                    .position(|c| *c == list.selected)
+                    .unwrap_or(0) as u32;
+                self.output_choices = choices;
+                self.output_selected = selected;
+                let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+                self.output_names
+                    .splice(0, self.output_names.n_items(), &names);
+                return;
+            }
+            AudioEffectsMsg::OutputDeviceSelected => {
+                let index = self.output_row.selected();
+                if index == self.output_selected {
+                    return;
+                }
+                if let Some(choice) = self.output_choices.get(index as usize) {
+                    self.output_selected = index;
+                    sender
+                        .output(AudioEffectsOutput::OutputDeviceSelected(choice.clone()))
+                        .ok();
+                }
+                return;
+            }
+            AudioEffectsMsg::Reset => {
+                self.effects = AudioEffects::default();
+                self.sync_scales();
+            }
+        }
+        sender
+            .output(AudioEffectsOutput::Changed(self.effects.clone()))
+            .ok();
+    }
+}
+
+impl AudioEffectsPage {
+    fn refresh_preset_names(&self) {
+        let names = BUILTIN_PRESETS
+            .iter()
+            .map(|p| p.name)
+            .chain(self.custom_presets.iter().map(|(name, _)| name.as_str()))
+            .chain(std::iter::once(CUSTOM_LABEL))
+            .collect::<Vec<_>>();
+        self.preset_names
+            .splice(0, self.preset_names.n_items(), &names);
+    }
+
+    /// 当前曲线在下拉框中的位置，找不到对应预设时指向“自定义”
+    fn preset_index(&self) -> u32 {
+        let custom = (BUILTIN_PRESETS.len() + self.custom_presets.len()) as u32;
+        let Some(name) = &self.effects.eq_preset else {
+            return custom;
+        };
+        BUILTIN_PRESETS
+            .iter()
+            .map(|p| p.name)
+            .chain(self.custom_presets.iter().map(|(n, _)| n.as_str()))
+            .position(|n| n == name)
+            .map_or(custom, |i| i as u32)
+    }
+
+    fn current_custom_preset(&self) -> Option<&str> {
+        let name = self.effects.eq_preset.as_deref()?;
+        self.custom_presets
+            .iter()
+            .any(|(n, _)| n == name)
+            .then_some(name)
+    }
+
+    fn sync_scales(&self) {
+        for (scale, gain) in self.band_scales.iter().zip(self.effects.eq_gains) {
+            scale.set_value(gain);
+        }
+    }
+}
+
+fn present_save_dialog(parent: &impl IsA<gtk::Widget>, input: relm4::Sender<AudioEffectsMsg>) {
+    let entry = gtk::Entry::builder()
+        .placeholder_text("预设名称")
+        .activates_default(true)
+        .build();
+    let dialog = adw::AlertDialog::new(Some("保存均衡器预设"), None);
+    dialog.set_extra_child(Some(&entry));
+    dialog.add_response("cancel", "取消");
+    dialog.add_response("save", "保存");
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(Some("save"), move |_, _| {
+        let _ = input.send(AudioEffectsMsg::SavePreset(entry.text().to_string()));
+    });
+    dialog.present(Some(parent));
+}
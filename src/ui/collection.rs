@@ -1,15 +1,23 @@
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use relm4::adw;
+use relm4::adw::prelude::{AdwDialogExt, AlertDialogExt};
 use relm4::gtk::{FlowBox, prelude::*};
 use relm4::prelude::FactoryVecDeque;
-use relm4::{Component, ComponentParts, ComponentSender, RelmWidgetExt, gtk};
+use relm4::{
+    Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt, gtk,
+};
 
+use crate::api::cache::{self, key, ttl};
 use crate::api::{
-    Album, Playlist, UserDetails, UserInfo, get_user_detail, get_user_playlist, get_user_sub_album,
+    Album, Artist, Playlist, Song, UserDetails, UserInfo, get_user_detail, get_user_playlist,
+    get_user_sub_album, get_user_sub_artist, playlist_create,
 };
 use crate::db::{CollectType, Db};
+use crate::ui::components::artist_card::{ArtistCard, ArtistCardInit, ArtistCardOutput};
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::play_ranking::{PlayRanking, PlayRankingMsg, PlayRankingOutput};
 use crate::ui::components::playlist_card::{PlaylistCard, PlaylistCardInit, PlaylistCardOutput};
 use crate::ui::model::PlaylistType;
 
@@ -19,6 +27,8 @@ pub struct Collection {
     created_playlists: FactoryVecDeque<PlaylistCard>,
     collected_playlists: FactoryVecDeque<PlaylistCard>,
     albums: FactoryVecDeque<PlaylistCard>,
+    artists: FactoryVecDeque<ArtistCard>,
+    ranking: Controller<PlayRanking>,
     db: Arc<Mutex<Db>>,
 }
 
@@ -27,9 +37,15 @@ pub enum CollectionMsg {
     LoadUserPlaylist,
     LoadUserDetail,
     LoadUserSubAlbums,
+    LoadUserSubArtists,
     UpdateUserInfo(Arc<UserInfo>),
 
     CardAction(PlaylistCardOutput, PlaylistType),
+    ArtistAction(ArtistCardOutput),
+    RankingAction(PlayRankingOutput),
+
+    NewPlaylistClicked,
+    CreatePlaylist(String),
 }
 
 #[derive(Debug)]
@@ -37,12 +53,19 @@ pub enum CollectionCmdMsg {
     LoadUserPlaylisted(Vec<Playlist>),
     LoadUserDetailled(UserDetails),
     LoadUserSubAlbumed(Vec<Album>),
+    LoadUserSubArtisted(Vec<Artist>),
+    PlaylistCreated { success: bool, name: String },
+    LoadUserPlaylistFailed,
 }
 
 #[derive(Debug)]
 pub enum CollectionOutput {
     OpenPlaylistDetail(PlaylistType),
     Playlist(PlaylistType),
+    OpenArtist(u64),
+    ShowToast(String),
+    PlayTracks(Arc<Vec<Song>>, usize),
+    OpenYearReport,
 }
 
 #[relm4::component(pub)]
@@ -116,8 +139,17 @@ impl Component for Collection {
                                 set_xalign: 0.0,
                                 add_css_class: "dim-label",
                             }
-                        }
+                        },
 
+                        gtk::Button {
+                            set_label: "年度报告",
+                            set_halign: gtk::Align::Start,
+                            set_margin_top: 4,
+                            add_css_class: "pill",
+                            connect_clicked[sender] => move |_| {
+                                sender.output(CollectionOutput::OpenYearReport).ok();
+                            },
+                        },
                     }
                 },
                 gtk::Box {
@@ -147,10 +179,23 @@ impl Component for Collection {
                                 set_orientation: gtk::Orientation::Vertical,
                                 set_spacing: 12,
 
-                                gtk::Label {
-                                    set_label: "我创建的",
-                                    set_xalign: 0.0,
-                                    add_css_class: "title-2",
+                                gtk::Box {
+                                    set_orientation: gtk::Orientation::Horizontal,
+                                    set_spacing: 12,
+
+                                    gtk::Label {
+                                        set_label: "我创建的",
+                                        set_xalign: 0.0,
+                                        add_css_class: "title-2",
+                                    },
+                                    gtk::Button {
+                                        set_icon_name: "list-add-symbolic",
+                                        set_tooltip_text: Some("新建歌单"),
+                                        set_valign: gtk::Align::Center,
+                                        add_css_class: "circular",
+                                        add_css_class: "flat",
+                                        connect_clicked => CollectionMsg::NewPlaylistClicked,
+                                    },
                                 },
                                 #[name(created_flow_box)]
                                 gtk::FlowBox {
@@ -202,13 +247,33 @@ impl Component for Collection {
 
                         },
 
-                        // ----- Tab 3: 我的 MV -----
+                        // ----- Tab 3: 关注的歌手 -----
+                        add_titled[Some("artists"), "关注歌手"] = &gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_margin_top: 12,
+
+                            #[name(artist_flow_box)]
+                            gtk::FlowBox {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_row_spacing: 16,
+                                set_column_spacing: 16,
+                                set_min_children_per_line: 2,
+                                set_max_children_per_line: 100,
+                                set_selection_mode: gtk::SelectionMode::None,
+                            }
+                        },
+
+                        // ----- Tab 4: 我的 MV -----
                         add_titled[Some("mvs"), "MV"] = &gtk::Box {
                             set_orientation: gtk::Orientation::Vertical,
                             set_valign: gtk::Align::Center,
                             gtk::Label { set_label: "你还没有收藏任何 MV 哦", add_css_class: "dim-label" }
                             // 未来在这里添加 MV 的 FlowBox
-                        }
+                        },
+
+                        // ----- Tab 5: 听歌排行 -----
+                        #[local_ref]
+                        add_titled[Some("ranking"), "听歌排行"] = ranking_widget -> gtk::Box {},
                     }
                 }
             }
@@ -221,7 +286,6 @@ impl Component for Collection {
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let mut model = Self {
-            user_info,
             created_playlists: FactoryVecDeque::builder()
                 .launch(FlowBox::default())
                 .forward(sender.input_sender(), |msg| {
@@ -237,10 +301,18 @@ impl Component for Collection {
                 .forward(sender.input_sender(), |msg| {
                     CollectionMsg::CardAction(msg, PlaylistType::Album(0))
                 }),
+            artists: FactoryVecDeque::builder()
+                .launch(FlowBox::default())
+                .forward(sender.input_sender(), CollectionMsg::ArtistAction),
+            ranking: PlayRanking::builder()
+                .launch(user_info.id)
+                .forward(sender.input_sender(), CollectionMsg::RankingAction),
+            user_info,
             user_details: None,
             db,
         };
 
+        let ranking_widget = model.ranking.widget();
         let widgets = view_output!();
         model.created_playlists = FactoryVecDeque::builder()
             .launch(widgets.created_flow_box.clone())
@@ -260,14 +332,19 @@ impl Component for Collection {
                 CollectionMsg::CardAction(output, PlaylistType::Album(0))
             });
 
+        model.artists = FactoryVecDeque::builder()
+            .launch(widgets.artist_flow_box.clone())
+            .forward(sender.input_sender(), CollectionMsg::ArtistAction);
+
         sender.input(CollectionMsg::LoadUserPlaylist);
         sender.input(CollectionMsg::LoadUserDetail);
         sender.input(CollectionMsg::LoadUserSubAlbums);
+        sender.input(CollectionMsg::LoadUserSubArtists);
 
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match message {
             CollectionMsg::LoadUserPlaylist => {
                 let user_id = self.user_info.id;
@@ -275,14 +352,25 @@ impl Component for Collection {
                     return;
                 }
                 sender.command(move |out, _shutdown| async move {
-                    if let Ok(playlists) = get_user_playlist(user_id).await {
-                        let _ = out.send(CollectionCmdMsg::LoadUserPlaylisted(playlists));
+                    let result = cache::swr(
+                        &key::user_playlist(user_id),
+                        ttl::USER_PLAYLIST,
+                        || get_user_playlist(user_id),
+                        |playlists| {
+                            let _ = out.send(CollectionCmdMsg::LoadUserPlaylisted(playlists));
+                        },
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("加载用户歌单失败: {e}");
+                        let _ = out.send(CollectionCmdMsg::LoadUserPlaylistFailed);
                     }
                 });
             }
             CollectionMsg::UpdateUserInfo(user_info) => {
                 self.user_info = user_info;
                 sender.input(CollectionMsg::LoadUserPlaylist);
+                self.ranking.emit(PlayRankingMsg::Load(self.user_info.id));
             }
             CollectionMsg::CardAction(playlist_card_output, playlist_type) => {
                 match playlist_card_output {
@@ -308,6 +396,14 @@ impl Component for Collection {
                     }
                 }
             }
+            CollectionMsg::RankingAction(PlayRankingOutput::Play { songs, start_index }) => {
+                sender
+                    .output(CollectionOutput::PlayTracks(songs, start_index))
+                    .ok();
+            }
+            CollectionMsg::ArtistAction(ArtistCardOutput::Clicked(id)) => {
+                sender.output(CollectionOutput::OpenArtist(id)).unwrap();
+            }
             CollectionMsg::LoadUserDetail => {
                 let user_id = self.user_info.id;
                 sender.command(move |out, _shutdown| async move {
@@ -323,16 +419,65 @@ impl Component for Collection {
                     }
                 });
             }
+            CollectionMsg::LoadUserSubArtists => {
+                sender.command(|out, _shutdown| async move {
+                    if let Ok(artists) = get_user_sub_artist().await {
+                        let _ = out.send(CollectionCmdMsg::LoadUserSubArtisted(artists));
+                    }
+                });
+            }
+            CollectionMsg::NewPlaylistClicked => {
+                let entry = gtk::Entry::builder()
+                    .placeholder_text("歌单名称")
+                    .max_length(40)
+                    .activates_default(true)
+                    .build();
+                let alert = adw::AlertDialog::new(Some("新建歌单"), None);
+                alert.set_extra_child(Some(&entry));
+                alert.add_response("cancel", "取消");
+                alert.add_response("create", "创建");
+                alert.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+                alert.set_default_response(Some("create"));
+                alert.set_close_response("cancel");
+                let input = sender.input_sender().clone();
+                alert.connect_response(None, move |_, response| {
+                    let name = entry.text().trim().to_string();
+                    if response == "create" && !name.is_empty() {
+                        let _ = input.send(CollectionMsg::CreatePlaylist(name));
+                    }
+                });
+                alert.present(Some(root));
+            }
+            CollectionMsg::CreatePlaylist(name) => {
+                sender.command(move |out, _shutdown| async move {
+                    let success = playlist_create(&name).await.is_ok();
+                    let _ = out.send(CollectionCmdMsg::PlaylistCreated { success, name });
+                });
+            }
         }
     }
 
     fn update_cmd(
         &mut self,
         message: Self::CommandOutput,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
         _root: &Self::Root,
     ) {
         match message {
+            CollectionCmdMsg::LoadUserPlaylistFailed => {
+                sender
+                    .output(CollectionOutput::ShowToast("加载歌单失败".to_string()))
+                    .ok();
+            }
+            CollectionCmdMsg::PlaylistCreated { success, name } => {
+                let toast = if success {
+                    sender.input(CollectionMsg::LoadUserPlaylist);
+                    format!("已创建歌单「{}」", name)
+                } else {
+                    format!("创建歌单「{}」失败", name)
+                };
+                sender.output(CollectionOutput::ShowToast(toast)).ok();
+            }
             CollectionCmdMsg::LoadUserPlaylisted(playlists) => {
                 let mut created_guard = self.created_playlists.guard();
                 let mut collected_guard = self.collected_playlists.guard();
@@ -394,6 +539,28 @@ impl Component for Collection {
                     .unwrap()
                     .sync_collected(CollectType::Album, &album_ids);
             }
+            CollectionCmdMsg::LoadUserSubArtisted(artists) => {
+                let mut guard = self.artists.guard();
+                guard.clear();
+                for artist in &artists {
+                    guard.push_back(ArtistCardInit {
+                        id: artist.id,
+                        avatar_url: artist
+                            .avatar
+                            .as_ref()
+                            .map(|url| format!("{}?param=200y200", url))
+                            .unwrap_or_default(),
+                        name: artist.name.clone(),
+                    });
+                }
+                drop(guard);
+
+                let artist_ids: Vec<u64> = artists.iter().map(|a| a.id).collect();
+                self.db
+                    .lock()
+                    .unwrap()
+                    .sync_collected(CollectType::Artist, &artist_ids);
+            }
         }
     }
 }
@@ -4,19 +4,22 @@ use relm4::factory::FactoryVecDeque;
 use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 
-use crate::api::{Comment, CommentFloor, get_comment_floor, get_song_comments_new};
+use crate::api::{Comment, CommentFloor, CommentType, get_comment_floor, get_comments_new};
 use crate::ui::components::image::AsyncImage;
 use crate::ui::components::reply_row::{ReplyRow, ReplyRowInit};
+use crate::ui::route::AppRoute;
 
 #[derive(Debug, Clone)]
 pub struct CommentRowInit {
     pub comment: Comment,
-    pub song_id: u64,
+    pub resource: CommentType,
+    pub resource_id: u64,
 }
 
 pub struct CommentRow {
     comment: Comment,
-    song_id: u64,
+    resource: CommentType,
+    resource_id: u64,
     replies: Vec<Comment>,
     has_more: bool,
     expanded: bool,
@@ -31,6 +34,11 @@ pub enum CommentRowMsg {
     ToggleReplies,
 }
 
+#[derive(Debug)]
+pub enum CommentRowOutput {
+    OpenUser(u64),
+}
+
 #[derive(Debug)]
 pub enum CommentRowCmd {
     RepliesLoaded(CommentFloor),
@@ -41,7 +49,7 @@ pub enum CommentRowCmd {
 impl FactoryComponent for CommentRow {
     type Init = CommentRowInit;
     type Input = CommentRowMsg;
-    type Output = ();
+    type Output = CommentRowOutput;
     type CommandOutput = CommentRowCmd;
     type ParentWidget = gtk::ListBox;
 
@@ -65,6 +73,11 @@ impl FactoryComponent for CommentRow {
                     set_placeholder_icon: "folder-music-symbolic",
                     set_url: format!("{}?param=80y80", self.comment.user.avatar_url),
                     set_valign: gtk::Align::Start,
+                    add_controller = gtk::GestureClick {
+                        connect_released[sender, user_id = self.comment.user.id] => move |_, _, _, _| {
+                            sender.output(CommentRowOutput::OpenUser(user_id)).ok();
+                        }
+                    }
                 },
 
                 gtk::Box {
@@ -81,6 +94,12 @@ impl FactoryComponent for CommentRow {
                                 set_label: &self.comment.user.name,
                                 set_halign: gtk::Align::Start,
                                 add_css_class: "caption-heading",
+                                add_css_class: "link",
+                                add_controller = gtk::GestureClick {
+                                    connect_released[sender, user_id = self.comment.user.id] => move |_, _, _, _| {
+                                        sender.output(CommentRowOutput::OpenUser(user_id)).ok();
+                                    }
+                                }
                             },
                             gtk::Label {
                                 set_label: &self.comment.time_str,
@@ -168,7 +187,8 @@ impl FactoryComponent for CommentRow {
             .forward(&null_tx, |_| ());
         Self {
             comment: init.comment,
-            song_id: init.song_id,
+            resource: init.resource,
+            resource_id: init.resource_id,
             replies: Vec::new(),
             has_more: false,
             expanded: false,
@@ -192,11 +212,12 @@ impl FactoryComponent for CommentRow {
                 if self.loaded {
                     return;
                 }
-                let song_id = self.song_id;
+                let resource = self.resource;
+                let resource_id = self.resource_id;
                 let comment_id = self.comment.id;
                 let time = self.comment.time;
                 sender.command(move |out, _shutdown| async move {
-                    match get_comment_floor(song_id, comment_id, time).await {
+                    match get_comment_floor(resource, resource_id, comment_id, time).await {
                         Ok(floor) => {
                             let _ = out.send(CommentRowCmd::RepliesLoaded(floor));
                         }
@@ -261,13 +282,16 @@ impl FactoryComponent for CommentRow {
 
 #[derive(Debug)]
 pub enum CommentsMsg {
-    LoadComments(u64),
+    LoadComments(CommentType, u64),
     SetSort(CommentsSort),
     LoadNextPage,
+    OpenUser(u64),
 }
 
 #[derive(Debug)]
-pub enum CommentsOutput {}
+pub enum CommentsOutput {
+    Navigate(AppRoute),
+}
 
 #[derive(Debug)]
 pub enum CommentsCmdMsg {
@@ -302,7 +326,10 @@ impl CommentsSort {
 
 #[tracker::track]
 pub struct CommentsPage {
-    song_id: u64,
+    #[do_not_track]
+    resource: CommentType,
+    #[do_not_track]
+    resource_id: u64,
     is_loading: bool,
     sort: CommentsSort,
     #[do_not_track]
@@ -319,7 +346,7 @@ pub struct CommentsPage {
 
 #[relm4::component(pub)]
 impl Component for CommentsPage {
-    type Init = u64;
+    type Init = (CommentType, u64);
     type Input = CommentsMsg;
     type Output = CommentsOutput;
     type CommandOutput = CommentsCmdMsg;
@@ -407,16 +434,19 @@ impl Component for CommentsPage {
     }
 
     fn init(
-        song_id: Self::Init,
+        (resource, resource_id): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let comments = FactoryVecDeque::builder()
             .launch(gtk::ListBox::default())
-            .forward(sender.input_sender(), |_| CommentsMsg::LoadComments(0));
+            .forward(sender.input_sender(), |output| match output {
+                CommentRowOutput::OpenUser(id) => CommentsMsg::OpenUser(id),
+            });
 
         let model = Self {
-            song_id,
+            resource,
+            resource_id,
             is_loading: true,
             sort: CommentsSort::Hot,
             is_loading_more: false,
@@ -444,7 +474,7 @@ impl Component for CommentsPage {
                 }
             });
 
-        sender.input(CommentsMsg::LoadComments(song_id));
+        sender.input(CommentsMsg::LoadComments(resource, resource_id));
 
         ComponentParts { model, widgets }
     }
@@ -462,10 +492,11 @@ impl Component for CommentsPage {
                 self.has_more = false;
                 self.is_loading_more = false;
                 self.cursor.clear();
-                let id = self.song_id;
+                let resource = self.resource;
+                let id = self.resource_id;
                 let sort_type = sort.sort_type();
                 sender.command(move |out, _shutdown| async move {
-                    match get_song_comments_new(id, 1, sort_type, "").await {
+                    match get_comments_new(resource, id, 1, sort_type, "").await {
                         Ok((comments, has_more, cursor)) => {
                             let _ = out.send(CommentsCmdMsg::CommentsLoaded(
                                 comments, sort, has_more, cursor,
@@ -477,7 +508,9 @@ impl Component for CommentsPage {
                     }
                 });
             }
-            CommentsMsg::LoadComments(id) => {
+            CommentsMsg::LoadComments(resource, id) => {
+                self.resource = resource;
+                self.resource_id = id;
                 self.set_is_loading(true);
                 self.page_no = 1;
                 self.has_more = false;
@@ -487,7 +520,7 @@ impl Component for CommentsPage {
                 let sort_type = self.sort.sort_type();
                 let sort = self.sort;
                 sender.command(move |out, _shutdown| async move {
-                    match get_song_comments_new(id_clone, 1, sort_type, "").await {
+                    match get_comments_new(resource, id_clone, 1, sort_type, "").await {
                         Ok((comments, has_more, cursor)) => {
                             let _ = out.send(CommentsCmdMsg::CommentsLoaded(
                                 comments, sort, has_more, cursor,
@@ -499,13 +532,22 @@ impl Component for CommentsPage {
                     }
                 });
             }
+            CommentsMsg::OpenUser(user_id) => {
+                if user_id == 0 {
+                    return;
+                }
+                sender
+                    .output(CommentsOutput::Navigate(AppRoute::User(user_id)))
+                    .ok();
+            }
             CommentsMsg::LoadNextPage => {
                 if self.is_loading || self.is_loading_more || !self.has_more {
                     return;
                 }
                 self.is_loading_more = true;
                 self.page_no += 1;
-                let id = self.song_id;
+                let resource = self.resource;
+                let id = self.resource_id;
                 let page_no = self.page_no;
                 let sort_type = self.sort.sort_type();
                 let cursor = self.cursor.clone();
@@ -513,7 +555,7 @@ impl Component for CommentsPage {
                     "无感分页(评论): 触发加载更多, page={page_no}, sort={sort_type}, cursor={cursor:?}"
                 );
                 sender.command(move |out, _shutdown| async move {
-                    match get_song_comments_new(id, page_no, sort_type, &cursor).await {
+                    match get_comments_new(resource, id, page_no, sort_type, &cursor).await {
                         Ok((comments, has_more, next_cursor)) => {
                             let _ = out.send(CommentsCmdMsg::NextPageLoaded(
                                 comments,
@@ -548,7 +590,8 @@ impl Component for CommentsPage {
                     for c in comments {
                         guard.push_back(CommentRowInit {
                             comment: c,
-                            song_id: self.song_id,
+                            resource: self.resource,
+                            resource_id: self.resource_id,
                         });
                     }
                 }
@@ -570,7 +613,8 @@ impl Component for CommentsPage {
                     for c in comments {
                         guard.push_back(CommentRowInit {
                             comment: c,
-                            song_id: self.song_id,
+                            resource: self.resource,
+                            resource_id: self.resource_id,
                         });
                     }
                 }
@@ -17,6 +17,7 @@ pub struct AlbumGrid {
 #[derive(Debug)]
 pub enum AlbumGridInput {
     SetAlbums(Vec<Album>),
+    AppendAlbums(Vec<Album>),
 }
 
 #[relm4::component(pub)]
@@ -76,6 +77,18 @@ impl SimpleComponent for AlbumGrid {
                     });
                 }
             }
+            AlbumGridInput::AppendAlbums(albums) => {
+                let mut guard = self.albums.guard();
+                for album in albums {
+                    guard.push_back(PlaylistCardInit {
+                        id: album.id,
+                        cover_url: format!("{}?param=200y200", album.cover_url),
+                        title: album.name,
+                        subtitle: None,
+                        show_play_button: true,
+                    });
+                }
+            }
         }
     }
 }
@@ -20,6 +20,7 @@ pub struct MvGrid {
 #[derive(Debug)]
 pub enum MvGridInput {
     SetMvs(Vec<Mv>),
+    AppendMvs(Vec<Mv>),
 }
 
 #[relm4::component(pub)]
@@ -73,6 +74,12 @@ impl SimpleComponent for MvGrid {
                     guard.push_back(MvCardInit::from_duration(&mv));
                 }
             }
+            MvGridInput::AppendMvs(mvs) => {
+                let mut guard = self.mvs.guard();
+                for mv in mvs {
+                    guard.push_back(MvCardInit::from_duration(&mv));
+                }
+            }
         }
     }
 }
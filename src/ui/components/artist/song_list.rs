@@ -18,6 +18,8 @@ pub struct SongList {
 pub enum SongListInput {
     /// ArtistPage 加载完数据后调用，替换整个列表
     SetSongs(Vec<Song>),
+    /// 分页加载：追加到列表末尾
+    AppendSongs(Vec<Song>),
 }
 
 #[relm4::component(pub)]
@@ -73,6 +75,16 @@ impl SimpleComponent for SongList {
                     guard.push_back(TrackRowInit { track, index });
                 }
             }
+            SongListInput::AppendSongs(songs) => {
+                let mut guard = self.tracks.guard();
+                let base = guard.len();
+                for (index, track) in songs.into_iter().enumerate() {
+                    guard.push_back(TrackRowInit {
+                        track,
+                        index: base + index,
+                    });
+                }
+            }
         }
     }
 }
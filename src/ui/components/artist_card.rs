@@ -3,6 +3,7 @@ use relm4::gtk;
 use relm4::gtk::prelude::*;
 
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::image::image_manager::thumbnail_url;
 
 #[derive(Debug)]
 pub struct ArtistCardInit {
@@ -28,7 +29,7 @@ impl FactoryComponent for ArtistCard {
     type Input = ();
     type Output = ArtistCardOutput;
     type CommandOutput = ();
-    type ParentWidget = gtk::Box;
+    type ParentWidget = gtk::FlowBox;
 
     view! {
         gtk::Box {
@@ -47,7 +48,7 @@ impl FactoryComponent for ArtistCard {
                 set_corner_radius: 55.0,
                 set_halign: gtk::Align::Center,
                 set_valign: gtk::Align::Center,
-                set_url: self.avatar_url.clone(),
+                set_url: thumbnail_url(&self.avatar_url, 220, 220),
                 set_placeholder_icon: "avatar-default-symbolic",
                 set_fallback_icon: "image-missing-symbolic",
             },
@@ -81,3 +82,15 @@ impl FactoryComponent for ArtistCard {
 
     fn update(&mut self, _message: Self::Input, _sender: FactorySender<Self>) {}
 }
+
+/// 横向滚动行里承载歌手卡片的单行 FlowBox：每列只放一张卡，卡片从左到右排开
+pub fn artist_row_flow_box() -> gtk::FlowBox {
+    gtk::FlowBox::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .min_children_per_line(1)
+        .max_children_per_line(1)
+        .column_spacing(16)
+        .valign(gtk::Align::Start)
+        .selection_mode(gtk::SelectionMode::None)
+        .build()
+}
@@ -287,6 +287,8 @@ pub struct MeshGradientRenderer {
     old_mesh: Option<RenderMesh>,
 
     pub config: MeshConfig,
+    /// 随音乐低频变化的缩放量（对应 AMLL 的 volume），0 为静止
+    pub volume: f32,
 
     time: f32,
     trans_alpha: f32,
@@ -308,6 +310,7 @@ impl MeshGradientRenderer {
             current_mesh: None,
             old_mesh: None,
             config: MeshConfig::default(),
+            volume: 0.0,
             time: 0.0,
             trans_alpha: 1.0,
             initialized: false,
@@ -640,7 +643,7 @@ impl MeshGradientRenderer {
             if let Some(prog) = self.program {
                 gl.use_program(Some(prog));
                 set_f(gl, prog, "u_time", self.time);
-                set_f(gl, prog, "u_volume", 0.0);
+                set_f(gl, prog, "u_volume", self.volume);
                 set_f(gl, prog, "u_aspectRatio", ww as f32 / wh as f32);
 
                 // 绘制旧 Mesh（做底）
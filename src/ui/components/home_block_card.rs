@@ -3,6 +3,7 @@ use relm4::gtk;
 use relm4::gtk::prelude::*;
 
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::image::image_manager::thumbnail_url;
 
 #[derive(Debug)]
 pub struct HomeBlockCardInit {
@@ -52,7 +53,7 @@ impl FactoryComponent for HomeBlockCard {
                     set_width_request: 160,
                     set_height_request: 160,
                     set_corner_radius: 0.0,
-                    set_url: self.cover_url.clone(),
+                    set_url: thumbnail_url(&self.cover_url, 320, 320),
                     set_placeholder_icon: "folder-music-symbolic",
                     set_fallback_icon: "image-missing-symbolic",
                 },
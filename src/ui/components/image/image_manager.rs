@@ -1,15 +1,35 @@
+//! 图片缓存：内存（moka）→ 磁盘 → 网络
+//!
+//! 磁盘缓存以 URL 的 md5 命名，URL 含 `?param=WyH` 时不同尺寸各占一份。
+//! 索引存在缓存目录下的 SQLite 中，记录大小和最近访问时间，超过上限时按 LRU 淘汰。
+
+use md5::{Digest, Md5};
 use moka::future::Cache;
 use reqwest::Client;
-use std::hash::{DefaultHasher, Hash, Hasher};
-use std::path::PathBuf;
-use std::sync::OnceLock;
-use std::time::Duration;
+use rusqlite::{Connection, params};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use tokio_util::sync::CancellationToken;
 
 use crate::APP_NAME;
 
+/// 磁盘缓存上限
+pub const DISK_CACHE_LIMIT: u64 = 256 * 1024 * 1024;
+/// 超限后清理到上限的这个比例，避免每写一张图都触发淘汰
+const EVICT_TARGET_PERCENT: u64 = 90;
+
+/// 给网易云图片 URL 加上缩略图尺寸参数；已带参数或为空时原样返回
+pub fn thumbnail_url(url: &str, width: u32, height: u32) -> String {
+    if url.is_empty() || url.contains('?') {
+        url.to_string()
+    } else {
+        format!("{url}?param={width}y{height}")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum FetchError {
     Cancelled,
@@ -24,6 +44,8 @@ pub struct ImageManager {
     http_client: Client,
     // 本地磁盘缓存目录
     cache_dir: PathBuf,
+    // 磁盘缓存索引；打开失败时为 None，此时不使用磁盘缓存
+    disk_index: Option<Mutex<DiskIndex>>,
 }
 
 impl ImageManager {
@@ -43,6 +65,14 @@ impl ImageManager {
                     .expect("Failed to create image cache directory");
             }
 
+            let disk_index = match DiskIndex::open(&cache_dir) {
+                Ok(index) => Some(Mutex::new(index)),
+                Err(e) => {
+                    log::error!("图片缓存索引打开失败，将不使用磁盘缓存: {e}");
+                    None
+                }
+            };
+
             ImageManager {
                 memory_cache: Cache::builder()
                     .max_capacity(30 * 1024 * 1024)
@@ -54,6 +84,7 @@ impl ImageManager {
                     .build()
                     .expect("Failed to build reqwest client"),
                 cache_dir,
+                disk_index,
             }
         })
     }
@@ -91,13 +122,47 @@ impl ImageManager {
         }
     }
 
+    /// 预取图片到磁盘缓存（如即将播放歌曲的封面），已缓存的跳过
+    pub async fn prefetch(&self, urls: Vec<String>) {
+        for url in urls {
+            if url.is_empty() || self.memory_cache.contains_key(&url) {
+                continue;
+            }
+            if let Err(e) = self.fetch_disk_or_network(&url).await {
+                log::warn!("预取图片失败 {url}: {e:?}");
+            }
+        }
+    }
+
+    /// 磁盘缓存当前占用（字节）
+    pub fn disk_usage(&self) -> u64 {
+        self.disk_index
+            .as_ref()
+            .map_or(0, |index| index.lock().unwrap().total_size())
+    }
+
+    /// 清空磁盘和内存缓存
+    pub fn clear(&self) {
+        self.memory_cache.invalidate_all();
+        let Some(index) = &self.disk_index else {
+            return;
+        };
+        for key in index.lock().unwrap().clear() {
+            let _ = std::fs::remove_file(self.disk_path(&key));
+        }
+    }
+
     /// 二级/三级缓存的具体实现
     async fn fetch_disk_or_network(&self, url: &str) -> Result<Vec<u8>, FetchError> {
-        let file_path = self.get_disk_cache_path(url);
+        let key = cache_key(url);
+        let file_path = self.disk_path(&key);
 
         // 2. 查二级缓存 (Disk)
-        if let Ok(bytes) = fs::read(&file_path).await {
-            return Ok(bytes);
+        if let Some(index) = &self.disk_index {
+            if let Ok(bytes) = fs::read(&file_path).await {
+                index.lock().unwrap().record(&key, bytes.len() as u64);
+                return Ok(bytes);
+            }
         }
 
         // 3. 查三级缓存 (Network Request)
@@ -123,24 +188,207 @@ impl ImageManager {
 
         // 异步写入磁盘，不阻塞当前返回流 (Fire and forget)
         // 这样 UI 可以立刻拿到图片，后台慢慢存盘
-        let file_path_clone = file_path.clone();
-        let bytes_clone = bytes.clone();
-        tokio::spawn(async move {
-            if let Ok(mut file) = fs::File::create(&file_path_clone).await {
-                let _ = file.write_all(&bytes_clone).await;
-            }
-        });
+        if self.disk_index.is_some() {
+            let bytes_clone = bytes.clone();
+            tokio::spawn(async move {
+                ImageManager::global().store(key, bytes_clone).await;
+            });
+        }
 
         Ok(bytes)
     }
 
-    /// 工具方法：将 URL 转换为本地安全的文件名
-    fn get_disk_cache_path(&self, url: &str) -> PathBuf {
-        // 使用标准库简易哈希 URL 作为文件名
-        let mut hasher = DefaultHasher::new();
-        url.hash(&mut hasher);
-        let filename = format!("{:x}.img", hasher.finish());
+    /// 写入磁盘并登记索引，超出上限时淘汰最久未用的文件
+    async fn store(&self, key: String, bytes: Vec<u8>) {
+        let Some(index) = &self.disk_index else {
+            return;
+        };
+        let file_path = self.disk_path(&key);
+        let written = match fs::File::create(&file_path).await {
+            Ok(mut file) => file.write_all(&bytes).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = written {
+            log::error!("图片写入磁盘缓存失败: {e}");
+            let _ = fs::remove_file(&file_path).await;
+            return;
+        }
+
+        let evicted = {
+            let index = index.lock().unwrap();
+            index.record(&key, bytes.len() as u64);
+            index.evict(DISK_CACHE_LIMIT)
+        };
+        for key in evicted {
+            let _ = fs::remove_file(self.disk_path(&key)).await;
+        }
+    }
+
+    fn disk_path(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{key}.img"))
+    }
+}
+
+/// URL 的 md5，作为稳定的缓存文件名
+fn cache_key(url: &str) -> String {
+    format!("{:x}", Md5::digest(url.as_bytes()))
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// 磁盘缓存索引：每个文件的大小与最近访问时间
+struct DiskIndex {
+    conn: Connection,
+}
+
+impl DiskIndex {
+    /// 索引版本；旧版本没有索引，文件名也不稳定，升级时直接清掉
+    const VERSION: i32 = 1;
+
+    fn open(cache_dir: &Path) -> rusqlite::Result<Self> {
+        let conn = Connection::open(cache_dir.join("index.db"))?;
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        let index = Self::init(conn)?;
+        if version < Self::VERSION {
+            remove_legacy_files(cache_dir);
+            index
+                .conn
+                .execute_batch(&format!("PRAGMA user_version = {}", Self::VERSION))?;
+        }
+        Ok(index)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;
+             CREATE TABLE IF NOT EXISTS images (
+                key TEXT PRIMARY KEY,
+                size INTEGER NOT NULL,
+                last_access INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS images_last_access ON images (last_access);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// 新增或命中一个文件，刷新访问时间
+    fn record(&self, key: &str, size: u64) {
+        if let Err(e) = self.conn.execute(
+            "INSERT INTO images (key, size, last_access) VALUES (?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET size = ?2, last_access = ?3",
+            params![key, size as i64, unix_now()],
+        ) {
+            log::error!("更新图片缓存索引失败: {e}");
+        }
+    }
+
+    fn total_size(&self) -> u64 {
+        self.conn
+            .query_row("SELECT COALESCE(SUM(size), 0) FROM images", [], |row| {
+                row.get::<_, i64>(0)
+            })
+            .map_or(0, |size| size as u64)
+    }
+
+    /// 总大小超过 limit 时从最久未访问的开始移出索引，返回需要删除的文件
+    fn evict(&self, limit: u64) -> Vec<String> {
+        let mut total = self.total_size();
+        if total <= limit {
+            return Vec::new();
+        }
+        let target = limit * EVICT_TARGET_PERCENT / 100;
+        let mut evicted = Vec::new();
+        let Ok(mut stmt) = self
+            .conn
+            .prepare("SELECT key, size FROM images ORDER BY last_access ASC")
+        else {
+            return evicted;
+        };
+        let Ok(rows) = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        }) else {
+            return evicted;
+        };
+        for (key, size) in rows.flatten() {
+            if total <= target {
+                break;
+            }
+            total = total.saturating_sub(size as u64);
+            evicted.push(key);
+        }
+        drop(stmt);
+
+        for key in &evicted {
+            let _ = self
+                .conn
+                .execute("DELETE FROM images WHERE key = ?1", params![key]);
+        }
+        evicted
+    }
+
+    /// 清空索引，返回所有需要删除的文件
+    fn clear(&self) -> Vec<String> {
+        let keys = self
+            .conn
+            .prepare("SELECT key FROM images")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .unwrap_or_default();
+        if let Err(e) = self.conn.execute("DELETE FROM images", []) {
+            log::error!("清空图片缓存索引失败: {e}");
+        }
+        keys
+    }
+}
+
+/// 删除旧版本按 `DefaultHasher` 命名、没有索引的缓存文件
+fn remove_legacy_files(cache_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "img") {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_first() {
+        let index = DiskIndex::init(Connection::open_in_memory().unwrap()).unwrap();
+        for (key, last_access) in [("a", 1), ("b", 2), ("c", 3)] {
+            index
+                .conn
+                .execute(
+                    "INSERT INTO images (key, size, last_access) VALUES (?1, 40, ?2)",
+                    params![key, last_access],
+                )
+                .unwrap();
+        }
+        assert_eq!(index.evict(200), Vec::<String>::new());
+        assert_eq!(index.evict(100), vec!["a".to_string()]);
+        assert_eq!(index.total_size(), 80);
 
-        self.cache_dir.join(filename)
+        assert_eq!(cache_key("x"), "9dd4e461268c8034f5c8564e155c67a6");
+        assert_eq!(
+            thumbnail_url("http://p1.music.126.net/a.jpg", 200, 200),
+            "http://p1.music.126.net/a.jpg?param=200y200"
+        );
+        assert_eq!(
+            thumbnail_url("http://p1.music.126.net/a.jpg?param=64y64", 200, 200),
+            "http://p1.music.126.net/a.jpg?param=64y64"
+        );
     }
 }
@@ -7,12 +7,16 @@ pub mod home_block_card;
 pub mod image;
 pub mod mv_card;
 pub mod mv_row;
+pub mod play_ranking;
 pub mod playlist_card;
+pub mod playlist_edit_dialog;
 pub mod reply_row;
 pub mod scrollable_row;
 pub mod song_list;
 pub mod song_row;
+pub mod spectrum;
 pub mod toplist_card;
+pub mod track_editor;
 pub mod track_row;
 
 pub mod gl_bg;
@@ -0,0 +1,360 @@
+//! 听歌排行：最近一周 / 所有时间，条形长度为相对第一名的比例
+
+use std::sync::Arc;
+
+use relm4::{
+    Component, ComponentParts, ComponentSender, RelmWidgetExt,
+    factory::{DynamicIndex, FactoryComponent, FactorySender, FactoryVecDeque},
+    gtk::{self, prelude::*},
+};
+
+use crate::api::{PlayRecord, RecordHidden, RecordRange, Song, get_user_record};
+use crate::ui::components::image::AsyncImage;
+
+#[derive(Debug)]
+pub struct RankingRow {
+    rank: usize,
+    record: PlayRecord,
+}
+
+#[derive(Debug)]
+pub enum RankingRowOutput {
+    Play(usize),
+}
+
+#[relm4::factory(pub)]
+impl FactoryComponent for RankingRow {
+    type Init = (usize, PlayRecord);
+    type Input = ();
+    type Output = RankingRowOutput;
+    type CommandOutput = ();
+    type ParentWidget = gtk::ListBox;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Horizontal,
+            set_spacing: 12,
+            set_margin_all: 6,
+
+            gtk::Label {
+                set_label: &(self.rank + 1).to_string(),
+                set_width_chars: 3,
+                add_css_class: "numeric",
+                add_css_class: if self.rank < 3 { "heading" } else { "dim-label" },
+            },
+
+            AsyncImage {
+                set_width_request: 40,
+                set_height_request: 40,
+                set_corner_radius: 4.0,
+                set_url: format!("{}?param=80y80", self.record.song.cover_url),
+                set_placeholder_icon: "folder-music-symbolic",
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_valign: gtk::Align::Center,
+                set_spacing: 2,
+                set_hexpand: true,
+
+                gtk::Label {
+                    set_label: &self.record.song.name,
+                    set_halign: gtk::Align::Start,
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    add_css_class: "heading",
+                },
+                gtk::Label {
+                    set_label: &self.record.song.artists.iter()
+                        .map(|a| a.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" / "),
+                    set_halign: gtk::Align::Start,
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    add_css_class: "caption",
+                    add_css_class: "dim-label",
+                },
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_valign: gtk::Align::Center,
+                set_spacing: 2,
+                set_width_request: 160,
+
+                gtk::LevelBar {
+                    set_min_value: 0.0,
+                    set_max_value: 100.0,
+                    set_value: self.record.score as f64,
+                },
+                // 只有「所有时间」返回播放次数
+                gtk::Label {
+                    set_visible: self.record.play_count > 0,
+                    set_label: &format!("{} 次", self.record.play_count),
+                    set_halign: gtk::Align::End,
+                    add_css_class: "caption",
+                    add_css_class: "dim-label",
+                },
+            },
+
+            gtk::Button {
+                set_icon_name: "media-playback-start-symbolic",
+                set_valign: gtk::Align::Center,
+                add_css_class: "circular",
+                add_css_class: "flat",
+                set_tooltip_text: Some("播放"),
+                connect_clicked[sender, rank = self.rank] => move |_| {
+                    sender.output(RankingRowOutput::Play(rank)).ok();
+                },
+            },
+        }
+    }
+
+    fn init_model(
+        (rank, record): Self::Init,
+        _index: &DynamicIndex,
+        _sender: FactorySender<Self>,
+    ) -> Self {
+        Self { rank, record }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RankingState {
+    Loading,
+    Loaded,
+    Empty,
+    Hidden,
+    Failed,
+}
+
+pub struct PlayRanking {
+    user_id: u64,
+    range: RecordRange,
+    state: RankingState,
+    /// 当前列表中的歌曲，播放时整个排行作为队列
+    songs: Arc<Vec<Song>>,
+    rows: FactoryVecDeque<RankingRow>,
+}
+
+#[derive(Debug)]
+pub enum PlayRankingMsg {
+    Load(u64),
+    SetRange(RecordRange),
+    Play(usize),
+}
+
+#[derive(Debug)]
+pub enum PlayRankingCmd {
+    Loaded {
+        user_id: u64,
+        range: RecordRange,
+        records: Vec<PlayRecord>,
+    },
+    Hidden {
+        user_id: u64,
+        range: RecordRange,
+    },
+    Failed {
+        user_id: u64,
+        range: RecordRange,
+    },
+}
+
+#[derive(Debug)]
+pub enum PlayRankingOutput {
+    Play {
+        songs: Arc<Vec<Song>>,
+        start_index: usize,
+    },
+}
+
+#[relm4::component(pub)]
+impl Component for PlayRanking {
+    type Init = u64;
+    type Input = PlayRankingMsg;
+    type Output = PlayRankingOutput;
+    type CommandOutput = PlayRankingCmd;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 12,
+            set_margin_top: 12,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_spacing: 4,
+
+                gtk::ToggleButton {
+                    #[watch]
+                    set_active: model.range == RecordRange::Week,
+                    set_label: "最近一周",
+                    add_css_class: "flat",
+                    add_css_class: "comment-sort-btn",
+                    connect_clicked[sender] => move |_| {
+                        sender.input(PlayRankingMsg::SetRange(RecordRange::Week));
+                    },
+                },
+                gtk::ToggleButton {
+                    #[watch]
+                    set_active: model.range == RecordRange::AllTime,
+                    set_label: "所有时间",
+                    add_css_class: "flat",
+                    add_css_class: "comment-sort-btn",
+                    connect_clicked[sender] => move |_| {
+                        sender.input(PlayRankingMsg::SetRange(RecordRange::AllTime));
+                    },
+                },
+            },
+
+            gtk::Stack {
+                set_transition_type: gtk::StackTransitionType::Crossfade,
+                #[watch]
+                set_visible_child_name: match model.state {
+                    RankingState::Loading => "loading",
+                    RankingState::Loaded => "list",
+                    _ => "message",
+                },
+
+                add_named[Some("loading")] = &gtk::Spinner {
+                    set_spinning: true,
+                    set_halign: gtk::Align::Center,
+                    set_margin_top: 24,
+                    set_width_request: 32,
+                    set_height_request: 32,
+                },
+
+                #[local_ref]
+                add_named[Some("list")] = list_box -> gtk::ListBox {
+                    set_selection_mode: gtk::SelectionMode::None,
+                    set_valign: gtk::Align::Start,
+                    add_css_class: "boxed-list",
+                },
+
+                add_named[Some("message")] = &gtk::Label {
+                    #[watch]
+                    set_label: match model.state {
+                        RankingState::Hidden => "该用户未公开听歌排行",
+                        RankingState::Failed => "听歌排行加载失败",
+                        _ => "暂无听歌记录",
+                    },
+                    set_valign: gtk::Align::Start,
+                    set_margin_top: 24,
+                    add_css_class: "dim-label",
+                },
+            },
+        }
+    }
+
+    fn init(
+        user_id: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let rows = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::new())
+            .forward(sender.input_sender(), |RankingRowOutput::Play(index)| {
+                PlayRankingMsg::Play(index)
+            });
+        let model = Self {
+            user_id,
+            range: RecordRange::Week,
+            state: RankingState::Loading,
+            songs: Arc::new(Vec::new()),
+            rows,
+        };
+        let list_box = model.rows.widget();
+        let widgets = view_output!();
+
+        sender.input(PlayRankingMsg::Load(user_id));
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        match message {
+            PlayRankingMsg::Load(user_id) => {
+                self.user_id = user_id;
+                self.fetch(&sender);
+            }
+            PlayRankingMsg::SetRange(range) => {
+                if self.range != range {
+                    self.range = range;
+                    self.fetch(&sender);
+                }
+            }
+            PlayRankingMsg::Play(index) => {
+                sender
+                    .output(PlayRankingOutput::Play {
+                        songs: self.songs.clone(),
+                        start_index: index,
+                    })
+                    .ok();
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        _sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        let (user_id, range, state, records) = match message {
+            PlayRankingCmd::Loaded {
+                user_id,
+                range,
+                records,
+            } => {
+                let state = if records.is_empty() {
+                    RankingState::Empty
+                } else {
+                    RankingState::Loaded
+                };
+                (user_id, range, state, records)
+            }
+            PlayRankingCmd::Hidden { user_id, range } => {
+                (user_id, range, RankingState::Hidden, Vec::new())
+            }
+            PlayRankingCmd::Failed { user_id, range } => {
+                (user_id, range, RankingState::Failed, Vec::new())
+            }
+        };
+        // 切换用户或范围后，旧请求的结果直接丢弃
+        if user_id != self.user_id || range != self.range {
+            return;
+        }
+
+        self.state = state;
+        self.songs = Arc::new(records.iter().map(|r| r.song.clone()).collect());
+        let mut guard = self.rows.guard();
+        guard.clear();
+        for (rank, record) in records.into_iter().enumerate() {
+            guard.push_back((rank, record));
+        }
+    }
+}
+
+impl PlayRanking {
+    fn fetch(&mut self, sender: &ComponentSender<Self>) {
+        self.state = RankingState::Loading;
+        let (user_id, range) = (self.user_id, self.range);
+        if user_id == 0 {
+            return;
+        }
+        sender.oneshot_command(async move {
+            match get_user_record(user_id, range).await {
+                Ok(records) => PlayRankingCmd::Loaded {
+                    user_id,
+                    range,
+                    records,
+                },
+                Err(e) if e.downcast_ref::<RecordHidden>().is_some() => {
+                    PlayRankingCmd::Hidden { user_id, range }
+                }
+                Err(_) => PlayRankingCmd::Failed { user_id, range },
+            }
+        });
+    }
+}
@@ -1,4 +1,5 @@
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::image::image_manager::thumbnail_url;
 use relm4::factory::{DynamicIndex, FactoryComponent};
 use relm4::gtk::prelude::{BoxExt, ButtonExt, GestureSingleExt, OrientableExt, WidgetExt};
 use relm4::{gtk, prelude::*};
@@ -81,7 +82,7 @@ macro_rules! define_playlist_card {
                             set_corner_radius: 8.0,
                             set_halign: gtk::Align::Center,
                             set_valign: gtk::Align::Center,
-                            set_url: self.cover_url.clone(),
+                            set_url: thumbnail_url(&self.cover_url, 320, 320),
                             set_placeholder_icon: "folder-music-symbolic",
                             set_fallback_icon: "image-missing-symbolic",
                             add_css_class: "rounded-cover",
@@ -0,0 +1,334 @@
+//! 编辑歌单信息对话框：名称 / 描述 / 标签 / 封面
+//!
+//! 保存时只提交有变化的字段；封面先选本地图片，保存时再上传。
+
+use std::path::PathBuf;
+
+use relm4::adw;
+use relm4::adw::prelude::AdwDialogExt;
+use relm4::gtk::prelude::*;
+use relm4::gtk::{gio, glib};
+use relm4::prelude::*;
+
+use crate::api::{
+    playlist_cover_update, playlist_desc_update, playlist_rename, playlist_tags_update,
+};
+use crate::ui::components::image::AsyncImage;
+
+#[derive(Debug)]
+pub struct PlaylistEditInit {
+    pub id: u64,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub cover_url: String,
+}
+
+pub struct PlaylistEditDialog {
+    init: PlaylistEditInit,
+    cover_path: Option<PathBuf>,
+    is_saving: bool,
+    name_entry: gtk::Entry,
+    tags_entry: gtk::Entry,
+    desc_buffer: gtk::TextBuffer,
+}
+
+#[derive(Debug)]
+pub enum PlaylistEditMsg {
+    PickCover,
+    CoverPicked(PathBuf),
+    Save,
+}
+
+#[derive(Debug)]
+pub enum PlaylistEditCmdMsg {
+    Saved {
+        name: String,
+        description: String,
+        tags: Vec<String>,
+        cover_url: Option<String>,
+        failed: Vec<&'static str>,
+    },
+}
+
+#[derive(Debug)]
+pub enum PlaylistEditOutput {
+    Saved {
+        name: String,
+        description: String,
+        tags: Vec<String>,
+        cover_url: Option<String>,
+    },
+    ShowToast(String),
+}
+
+#[relm4::component(pub)]
+impl Component for PlaylistEditDialog {
+    type Init = PlaylistEditInit;
+    type Input = PlaylistEditMsg;
+    type Output = PlaylistEditOutput;
+    type CommandOutput = PlaylistEditCmdMsg;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_title: "编辑歌单",
+            set_content_width: 420,
+            set_follows_content_size: true,
+
+            #[wrap(Some)]
+            set_child = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 12,
+                set_margin_all: 20,
+
+                // 封面
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 16,
+
+                    AsyncImage {
+                        set_width_request: 96,
+                        set_height_request: 96,
+                        set_corner_radius: 8.0,
+                        set_url: format!("{}?param=200y200", model.init.cover_url),
+                        set_placeholder_icon: "folder-music-symbolic",
+                    },
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_valign: gtk::Align::Center,
+                        set_spacing: 6,
+
+                        gtk::Button {
+                            set_label: "更换封面",
+                            set_halign: gtk::Align::Start,
+                            connect_clicked => PlaylistEditMsg::PickCover,
+                        },
+                        gtk::Label {
+                            #[watch]
+                            set_label: &model.cover_path.as_ref()
+                                .and_then(|p| p.file_name())
+                                .map(|n| n.to_string_lossy().to_string())
+                                .unwrap_or_else(|| "支持 JPG / PNG".to_string()),
+                            set_halign: gtk::Align::Start,
+                            set_max_width_chars: 24,
+                            set_ellipsize: gtk::pango::EllipsizeMode::Middle,
+                            add_css_class: "caption",
+                            add_css_class: "dim-label",
+                        },
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "名称",
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "caption-heading",
+                },
+                #[name(name_entry)]
+                gtk::Entry {
+                    set_text: &model.init.name,
+                    set_max_length: 40,
+                },
+
+                gtk::Label {
+                    set_label: "标签",
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "caption-heading",
+                },
+                #[name(tags_entry)]
+                gtk::Entry {
+                    set_text: &model.init.tags.join(", "),
+                    set_placeholder_text: Some("用逗号分隔，最多 3 个（如 华语, 流行）"),
+                },
+
+                gtk::Label {
+                    set_label: "描述",
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "caption-heading",
+                },
+                gtk::ScrolledWindow {
+                    set_min_content_height: 100,
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+                    add_css_class: "card",
+
+                    #[name(desc_view)]
+                    gtk::TextView {
+                        set_wrap_mode: gtk::WrapMode::WordChar,
+                        set_margin_all: 8,
+                    },
+                },
+
+                gtk::Button {
+                    #[watch]
+                    set_label: if model.is_saving { "正在保存..." } else { "保存" },
+                    #[watch]
+                    set_sensitive: !model.is_saving,
+                    set_halign: gtk::Align::End,
+                    add_css_class: "suggested-action",
+                    add_css_class: "pill",
+                    connect_clicked => PlaylistEditMsg::Save,
+                },
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut model = Self {
+            init,
+            cover_path: None,
+            is_saving: false,
+            name_entry: gtk::Entry::default(),
+            tags_entry: gtk::Entry::default(),
+            desc_buffer: gtk::TextBuffer::default(),
+        };
+
+        let widgets = view_output!();
+        widgets.desc_view.buffer().set_text(&model.init.description);
+        model.name_entry = widgets.name_entry.clone();
+        model.tags_entry = widgets.tags_entry.clone();
+        model.desc_buffer = widgets.desc_view.buffer();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
+        match message {
+            PlaylistEditMsg::PickCover => {
+                let filter = gtk::FileFilter::new();
+                filter.set_name(Some("图片"));
+                filter.add_mime_type("image/jpeg");
+                filter.add_mime_type("image/png");
+                let filters = gio::ListStore::new::<gtk::FileFilter>();
+                filters.append(&filter);
+
+                let dialog = gtk::FileDialog::builder()
+                    .title("选择封面图片")
+                    .filters(&filters)
+                    .modal(true)
+                    .build();
+                let parent = root.root().and_downcast::<gtk::Window>();
+                let input = sender.input_sender().clone();
+                dialog.open(parent.as_ref(), None::<&gio::Cancellable>, move |result| {
+                    if let Ok(path) = result.map(|file| file.path()) {
+                        if let Some(path) = path {
+                            let _ = input.send(PlaylistEditMsg::CoverPicked(path));
+                        }
+                    }
+                });
+            }
+            PlaylistEditMsg::CoverPicked(path) => {
+                self.cover_path = Some(path);
+            }
+            PlaylistEditMsg::Save => {
+                let name = self.name_entry.text().trim().to_string();
+                if name.is_empty() {
+                    sender
+                        .output(PlaylistEditOutput::ShowToast(
+                            "歌单名称不能为空".to_string(),
+                        ))
+                        .ok();
+                    return;
+                }
+                let (start, end) = self.desc_buffer.bounds();
+                let description = self.desc_buffer.text(&start, &end, false).to_string();
+                let tags: Vec<String> = self
+                    .tags_entry
+                    .text()
+                    .split([',', '，', ';'])
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .take(3)
+                    .collect();
+
+                let id = self.init.id;
+                let name_changed = name != self.init.name;
+                let desc_changed = description != self.init.description;
+                let tags_changed = tags != self.init.tags;
+                let cover_path = self.cover_path.clone();
+                if !name_changed && !desc_changed && !tags_changed && cover_path.is_none() {
+                    root.close();
+                    return;
+                }
+
+                self.is_saving = true;
+                sender.command(move |out, _shutdown| async move {
+                    let mut failed = Vec::new();
+                    if name_changed && playlist_rename(id, &name).await.is_err() {
+                        failed.push("名称");
+                    }
+                    if desc_changed && playlist_desc_update(id, &description).await.is_err() {
+                        failed.push("描述");
+                    }
+                    if tags_changed && playlist_tags_update(id, &tags).await.is_err() {
+                        failed.push("标签");
+                    }
+                    let mut cover_url = None;
+                    if let Some(path) = cover_path {
+                        let uploaded = match tokio::fs::read(&path).await {
+                            Ok(data) => playlist_cover_update(id, data).await.ok(),
+                            Err(e) => {
+                                log::error!("读取封面图片失败: {e}");
+                                None
+                            }
+                        };
+                        match uploaded {
+                            Some(url) => cover_url = Some(url),
+                            None => failed.push("封面"),
+                        }
+                    }
+                    let _ = out.send(PlaylistEditCmdMsg::Saved {
+                        name,
+                        description,
+                        tags,
+                        cover_url,
+                        failed,
+                    });
+                });
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            PlaylistEditCmdMsg::Saved {
+                name,
+                description,
+                tags,
+                cover_url,
+                failed,
+            } => {
+                self.is_saving = false;
+                let toast = if failed.is_empty() {
+                    "歌单信息已更新".to_string()
+                } else {
+                    format!("部分修改失败：{}", failed.join("、"))
+                };
+                sender
+                    .output(PlaylistEditOutput::Saved {
+                        name,
+                        description,
+                        tags,
+                        cover_url,
+                    })
+                    .ok();
+                sender.output(PlaylistEditOutput::ShowToast(toast)).ok();
+                glib::idle_add_local_once({
+                    let root = root.clone();
+                    move || {
+                        root.close();
+                    }
+                });
+            }
+        }
+    }
+}
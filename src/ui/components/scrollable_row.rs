@@ -4,12 +4,14 @@ use relm4::prelude::*;
 
 /// 可复用的横向滚动行组件
 ///
-/// 包含：标题 + 左右箭头按钮 + 横向滚动窗口
+/// 包含：标题 + （可选）查看全部 + 左右箭头按钮 + 横向滚动窗口
 #[derive(Debug)]
 pub struct ScrollableRowInit {
     pub title: String,
     pub min_height: i32,
     pub max_height: i32,
+    /// 是否在标题栏显示“查看全部”按钮
+    pub show_more: bool,
 }
 
 impl ScrollableRowInit {
@@ -18,8 +20,14 @@ impl ScrollableRowInit {
             title: title.into(),
             min_height,
             max_height,
+            show_more: false,
         }
     }
+
+    pub fn with_more(mut self) -> Self {
+        self.show_more = true;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -28,8 +36,15 @@ pub enum ScrollableRowInput {
     ScrollRight,
 }
 
+#[derive(Debug)]
+pub enum ScrollableRowOutput {
+    /// 点击了“查看全部”
+    MoreClicked,
+}
+
 pub struct ScrollableRow {
     title: String,
+    show_more: bool,
     min_height: i32,
     max_height: i32,
     adjustment: Adjustment,
@@ -59,7 +74,7 @@ impl ScrollableRow {
 impl Component for ScrollableRow {
     type Init = ScrollableRowInit;
     type Input = ScrollableRowInput;
-    type Output = ();
+    type Output = ScrollableRowOutput;
     type CommandOutput = ();
 
     view! {
@@ -80,6 +95,16 @@ impl Component for ScrollableRow {
                     set_hexpand: true,
                 },
 
+                gtk::Button {
+                    set_label: "查看全部",
+                    set_visible: model.show_more,
+                    set_halign: gtk::Align::End,
+                    add_css_class: "flat",
+                    connect_clicked[sender] => move |_| {
+                        let _ = sender.output(ScrollableRowOutput::MoreClicked);
+                    },
+                },
+
                 gtk::Button {
                     set_icon_name: "go-previous-symbolic",
                     set_tooltip_text: Some("向左滚动"),
@@ -130,6 +155,7 @@ impl Component for ScrollableRow {
     ) -> ComponentParts<Self> {
         let mut model = Self {
             title: init.title,
+            show_more: init.show_more,
             min_height: init.min_height,
             max_height: init.max_height,
             adjustment: gtk::Adjustment::default(),
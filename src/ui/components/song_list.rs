@@ -8,7 +8,9 @@ use relm4::gtk::prelude::*;
 use relm4::{gtk, prelude::*};
 
 use crate::api::Song;
-use crate::ui::components::scrollable_row::ScrollableRow;
+use crate::ui::components::scrollable_row::{
+    ScrollableRow, ScrollableRowInit, ScrollableRowOutput,
+};
 use crate::ui::components::song_row::{SongRow, SongRowInit, SongRowOutput};
 
 #[derive(Debug)]
@@ -16,6 +18,8 @@ pub struct SongListScrollInit {
     pub title: String,
     pub min_height: i32,
     pub max_height: i32,
+    /// 是否在标题栏显示“查看全部”按钮
+    pub show_more: bool,
 }
 
 impl SongListScrollInit {
@@ -24,8 +28,14 @@ impl SongListScrollInit {
             title: title.into(),
             min_height,
             max_height,
+            show_more: false,
         }
     }
+
+    pub fn with_more(mut self) -> Self {
+        self.show_more = true;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -36,6 +46,7 @@ pub enum SongListScrollInput {
 #[derive(Debug)]
 pub enum SongListScrollOutput {
     Clicked(u64),
+    MoreClicked,
 }
 
 pub struct SongListScroll {
@@ -60,9 +71,16 @@ impl SimpleComponent for SongListScroll {
     fn init(
         init: Self::Init,
         root: Self::Root,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let scroll = ScrollableRow::new(init.title, init.min_height, init.max_height);
+        let mut row_init = ScrollableRowInit::new(init.title, init.min_height, init.max_height);
+        row_init.show_more = init.show_more;
+        let scroll =
+            ScrollableRow::builder()
+                .launch(row_init)
+                .forward(sender.output_sender(), |out| match out {
+                    ScrollableRowOutput::MoreClicked => SongListScrollOutput::MoreClicked,
+                });
         // 一列 = 行宽 240 + 列内边距 32；三列 + 两处行距 = 848，强制最小内容宽保证三列可见
         scroll
             .widgets()
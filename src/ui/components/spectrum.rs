@@ -0,0 +1,208 @@
+//! 频谱可视化：柱状 / 波形两种样式，绘制 `PlayerEvent::Spectrum` 提供的频段数据
+//!
+//! 样式取自 GSettings 的 `visualizer-style`，设置改变时自动切换，关闭时隐藏自身。
+//! 频段升高时立即跟上、回落时按帧衰减，避免 20 fps 的数据看起来一跳一跳。
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use relm4::gtk::gio::prelude::SettingsExt;
+use relm4::gtk::prelude::*;
+use relm4::gtk::{self, gdk, gio};
+
+use crate::APPLICATION_ID;
+
+pub const STYLE_KEY: &str = "visualizer-style";
+pub const BEAT_BACKGROUND_KEY: &str = "visualizer-beat-background";
+
+/// 每帧回落比例
+const DECAY: f32 = 0.82;
+const BAR_GAP: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpectrumStyle {
+    Off,
+    Bars,
+    Wave,
+}
+
+impl SpectrumStyle {
+    pub fn from_settings(settings: &gio::Settings) -> Self {
+        match settings.string(STYLE_KEY).as_str() {
+            "bars" => SpectrumStyle::Bars,
+            "wave" => SpectrumStyle::Wave,
+            _ => SpectrumStyle::Off,
+        }
+    }
+}
+
+/// 低频能量（0.0 ~ 1.0），用于节拍律动
+pub fn bass_level(bands: &[f32]) -> f32 {
+    let low = &bands[..bands.len().min(4)];
+    if low.is_empty() {
+        return 0.0;
+    }
+    low.iter().sum::<f32>() / low.len() as f32
+}
+
+struct State {
+    style: SpectrumStyle,
+    levels: Vec<f32>,
+    /// None 时跟随控件的前景色
+    color: Option<gdk::RGBA>,
+}
+
+#[derive(Clone)]
+pub struct SpectrumView {
+    area: gtk::DrawingArea,
+    state: Rc<RefCell<State>>,
+    /// 保持 changed 信号的连接
+    _settings: gio::Settings,
+}
+
+impl SpectrumView {
+    pub fn new(height: i32) -> Self {
+        let settings = gio::Settings::new(APPLICATION_ID);
+        let style = SpectrumStyle::from_settings(&settings);
+        let area = gtk::DrawingArea::builder()
+            .content_height(height)
+            .hexpand(true)
+            .can_target(false)
+            .visible(style != SpectrumStyle::Off)
+            .build();
+        let state = Rc::new(RefCell::new(State {
+            style,
+            levels: Vec::new(),
+            color: None,
+        }));
+
+        area.set_draw_func({
+            let state = state.clone();
+            move |area, cr, width, height| {
+                let state = state.borrow();
+                let color = state.color.unwrap_or_else(|| area.color());
+                draw(&state, color, cr, width as f64, height as f64);
+            }
+        });
+
+        settings.connect_changed(Some(STYLE_KEY), {
+            let area = area.clone();
+            let state = state.clone();
+            move |settings, _| {
+                let style = SpectrumStyle::from_settings(settings);
+                let mut state = state.borrow_mut();
+                state.style = style;
+                state.levels.clear();
+                area.set_visible(style != SpectrumStyle::Off);
+                area.queue_draw();
+            }
+        });
+
+        Self {
+            area,
+            state,
+            _settings: settings,
+        }
+    }
+
+    pub fn widget(&self) -> &gtk::DrawingArea {
+        &self.area
+    }
+
+    /// 固定颜色，用于全屏页这类前景色不随主题变化的场景
+    pub fn set_color(&self, color: gdk::RGBA) {
+        self.state.borrow_mut().color = Some(color);
+    }
+
+    pub fn push(&self, bands: &[f32]) {
+        let mut state = self.state.borrow_mut();
+        if state.style == SpectrumStyle::Off {
+            return;
+        }
+        if state.levels.len() != bands.len() {
+            state.levels = vec![0.0; bands.len()];
+        }
+        for (level, band) in state.levels.iter_mut().zip(bands) {
+            *level = band.max(*level * DECAY);
+        }
+        drop(state);
+        self.area.queue_draw();
+    }
+
+    /// 暂停或切歌时让频谱落回底部
+    pub fn clear(&self) {
+        self.state.borrow_mut().levels.clear();
+        self.area.queue_draw();
+    }
+}
+
+fn draw(state: &State, c: gdk::RGBA, cr: &gtk::cairo::Context, width: f64, height: f64) {
+    let count = state.levels.len();
+    if count == 0 || width <= 0.0 {
+        return;
+    }
+    match state.style {
+        SpectrumStyle::Off => {}
+        SpectrumStyle::Bars => {
+            let bar_width = ((width - BAR_GAP * (count - 1) as f64) / count as f64).max(1.0);
+            cr.set_source_rgba(
+                c.red() as f64,
+                c.green() as f64,
+                c.blue() as f64,
+                c.alpha() as f64 * 0.7,
+            );
+            for (i, level) in state.levels.iter().enumerate() {
+                let h = (*level as f64 * height).max(2.0);
+                let x = i as f64 * (bar_width + BAR_GAP);
+                rounded_bar(cr, x, height - h, bar_width, h);
+            }
+            let _ = cr.fill();
+        }
+        SpectrumStyle::Wave => {
+            let step = width / (count - 1).max(1) as f64;
+            let y = |i: usize| height - state.levels[i] as f64 * height * 0.9;
+            cr.move_to(0.0, y(0));
+            for i in 1..count {
+                // 控制点取两点水平中线，曲线在每个频点处切线水平，波形更平滑
+                let (x0, y0) = ((i - 1) as f64 * step, y(i - 1));
+                let (x1, y1) = (i as f64 * step, y(i));
+                let mx = (x0 + x1) / 2.0;
+                cr.curve_to(mx, y0, mx, y1, x1, y1);
+            }
+            cr.set_line_width(2.0);
+            cr.set_source_rgba(
+                c.red() as f64,
+                c.green() as f64,
+                c.blue() as f64,
+                c.alpha() as f64 * 0.8,
+            );
+            let _ = cr.stroke_preserve();
+            cr.line_to(width, height);
+            cr.line_to(0.0, height);
+            cr.close_path();
+            cr.set_source_rgba(
+                c.red() as f64,
+                c.green() as f64,
+                c.blue() as f64,
+                c.alpha() as f64 * 0.2,
+            );
+            let _ = cr.fill();
+        }
+    }
+}
+
+fn rounded_bar(cr: &gtk::cairo::Context, x: f64, y: f64, w: f64, h: f64) {
+    let r = (w / 2.0).min(h / 2.0).min(3.0);
+    cr.new_sub_path();
+    cr.arc(x + w - r, y + r, r, -std::f64::consts::FRAC_PI_2, 0.0);
+    cr.line_to(x + w, y + h);
+    cr.line_to(x, y + h);
+    cr.arc(
+        x + r,
+        y + r,
+        r,
+        std::f64::consts::PI,
+        3.0 * std::f64::consts::FRAC_PI_2,
+    );
+    cr.close_path();
+}
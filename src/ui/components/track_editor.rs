@@ -0,0 +1,454 @@
+//! 歌单歌曲编辑对话框：拖拽调整顺序、多选删除
+//!
+//! 打开时补齐歌单中尚未分页加载的歌曲；排序只在点击“保存排序”时整体同步到服务端。
+
+use futures::FutureExt;
+use relm4::adw;
+use relm4::adw::prelude::AdwDialogExt;
+use relm4::factory::{DynamicIndex, FactoryComponent, FactorySender, FactoryVecDeque};
+use relm4::gtk::prelude::*;
+use relm4::gtk::{gdk, glib};
+use relm4::prelude::*;
+
+use crate::api::{Song, get_playlist_track_all, playlist_track_del, playlist_track_reorder};
+use crate::ui::components::image::AsyncImage;
+
+const FETCH_BATCH: usize = 500;
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// 行组件
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug)]
+pub struct TrackEditRow {
+    song: Song,
+    selected: bool,
+    index: DynamicIndex,
+}
+
+#[derive(Debug)]
+pub enum TrackEditRowMsg {
+    SetSelected(bool),
+}
+
+#[derive(Debug)]
+pub enum TrackEditRowOutput {
+    SelectionChanged,
+    /// 把 `from` 位置的行拖到 `to` 位置
+    Move {
+        from: usize,
+        to: usize,
+    },
+}
+
+#[relm4::factory(pub)]
+impl FactoryComponent for TrackEditRow {
+    type Init = Song;
+    type Input = TrackEditRowMsg;
+    type Output = TrackEditRowOutput;
+    type CommandOutput = ();
+    type ParentWidget = gtk::ListBox;
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Horizontal,
+            set_spacing: 12,
+            set_margin_all: 6,
+
+            add_controller = gtk::DropTarget::new(glib::Type::U32, gdk::DragAction::MOVE) {
+                connect_drop[sender, index = self.index.clone()] => move |_, value, _, _| {
+                    let Ok(from) = value.get::<u32>() else {
+                        return false;
+                    };
+                    sender
+                        .output(TrackEditRowOutput::Move {
+                            from: from as usize,
+                            to: index.current_index(),
+                        })
+                        .ok();
+                    true
+                }
+            },
+
+            gtk::Image {
+                set_icon_name: Some("list-drag-handle-symbolic"),
+                set_tooltip_text: Some("拖动调整顺序"),
+                add_css_class: "dim-label",
+
+                add_controller = gtk::DragSource {
+                    set_actions: gdk::DragAction::MOVE,
+                    connect_prepare[index = self.index.clone()] => move |_, _, _| {
+                        let value = (index.current_index() as u32).to_value();
+                        Some(gdk::ContentProvider::for_value(&value))
+                    }
+                },
+            },
+
+            gtk::CheckButton {
+                set_active: self.selected,
+                connect_toggled[sender] => move |b| {
+                    sender.input(TrackEditRowMsg::SetSelected(b.is_active()));
+                }
+            },
+
+            AsyncImage {
+                set_width_request: 40,
+                set_height_request: 40,
+                set_corner_radius: 4.0,
+                set_url: format!("{}?param=100y100", self.song.cover_url),
+                set_placeholder_icon: "missing-album-symbolic",
+            },
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_valign: gtk::Align::Center,
+                set_spacing: 2,
+                set_hexpand: true,
+
+                gtk::Label {
+                    set_label: &self.song.name,
+                    set_halign: gtk::Align::Start,
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    add_css_class: "heading",
+                },
+                gtk::Label {
+                    set_label: &self.song.artists.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", "),
+                    set_halign: gtk::Align::Start,
+                    set_ellipsize: gtk::pango::EllipsizeMode::End,
+                    add_css_class: "dim-label",
+                    add_css_class: "caption",
+                }
+            },
+        }
+    }
+
+    fn init_model(song: Self::Init, index: &DynamicIndex, _sender: FactorySender<Self>) -> Self {
+        Self {
+            song,
+            selected: false,
+            index: index.clone(),
+        }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: FactorySender<Self>) {
+        match message {
+            TrackEditRowMsg::SetSelected(selected) => {
+                self.selected = selected;
+                sender.output(TrackEditRowOutput::SelectionChanged).ok();
+            }
+        }
+    }
+}
+
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+// 对话框
+// ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+
+#[derive(Debug)]
+pub struct TrackEditorInit {
+    pub playlist_id: u64,
+    pub track_ids: Vec<u64>,
+    /// 详情页已加载的歌曲（track_ids 的前缀）
+    pub tracks: Vec<Song>,
+}
+
+#[tracker::track]
+pub struct TrackEditorDialog {
+    #[do_not_track]
+    playlist_id: u64,
+    is_loading: bool,
+    is_saving: bool,
+    /// 有歌曲没能加载出来：列表不完整，保存排序会丢掉这些歌
+    load_failed: bool,
+    order_changed: bool,
+    selected_count: usize,
+    #[do_not_track]
+    rows: FactoryVecDeque<TrackEditRow>,
+}
+
+#[derive(Debug)]
+pub enum TrackEditorMsg {
+    RowAction(TrackEditRowOutput),
+    DeleteSelected,
+    SaveOrder,
+}
+
+#[derive(Debug)]
+pub enum TrackEditorCmdMsg {
+    /// complete 为 false 表示有分页加载失败，songs 只是已取到的部分
+    RestLoaded {
+        songs: Vec<Song>,
+        complete: bool,
+    },
+    Deleted {
+        ids: Vec<u64>,
+        success: bool,
+    },
+    OrderSaved(bool),
+}
+
+#[derive(Debug)]
+pub enum TrackEditorOutput {
+    /// 歌单内容已在服务端变更，父组件需重新加载
+    Changed,
+    ShowToast(String),
+}
+
+#[relm4::component(pub)]
+impl Component for TrackEditorDialog {
+    type Init = TrackEditorInit;
+    type Input = TrackEditorMsg;
+    type Output = TrackEditorOutput;
+    type CommandOutput = TrackEditorCmdMsg;
+
+    view! {
+        #[root]
+        adw::Dialog {
+            set_title: "编辑歌曲",
+            set_content_width: 520,
+            set_content_height: 600,
+
+            #[wrap(Some)]
+            set_child = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+
+                gtk::ScrolledWindow {
+                    set_vexpand: true,
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    #[local_ref]
+                    list_box -> gtk::ListBox {
+                        set_selection_mode: gtk::SelectionMode::None,
+                        set_margin_all: 8,
+                        add_css_class: "boxed-list",
+                    }
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 12,
+                    set_margin_all: 12,
+
+                    gtk::Spinner {
+                        #[watch]
+                        set_spinning: model.is_loading || model.is_saving,
+                        #[watch]
+                        set_visible: model.is_loading || model.is_saving,
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &if model.is_loading {
+                            "正在加载全部歌曲...".to_string()
+                        } else if model.load_failed {
+                            "部分歌曲加载失败，无法保存排序".to_string()
+                        } else {
+                            format!("已选 {} 首", model.selected_count)
+                        },
+                        set_hexpand: true,
+                        set_halign: gtk::Align::Start,
+                        add_css_class: "dim-label",
+                    },
+                    gtk::Button {
+                        set_label: "删除所选",
+                        add_css_class: "destructive-action",
+                        #[watch]
+                        set_sensitive: model.selected_count > 0 && !model.is_saving,
+                        connect_clicked => TrackEditorMsg::DeleteSelected,
+                    },
+                    gtk::Button {
+                        set_label: "保存排序",
+                        add_css_class: "suggested-action",
+                        #[watch]
+                        set_sensitive: model.order_changed
+                            && !model.is_loading
+                            && !model.is_saving
+                            && !model.load_failed,
+                        connect_clicked => TrackEditorMsg::SaveOrder,
+                    },
+                }
+            }
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut rows = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::new())
+            .forward(sender.input_sender(), TrackEditorMsg::RowAction);
+        {
+            let mut guard = rows.guard();
+            for song in &init.tracks {
+                guard.push_back(song.clone());
+            }
+        }
+
+        let loaded = init.tracks.len();
+        let is_loading = loaded < init.track_ids.len();
+        let model = Self {
+            playlist_id: init.playlist_id,
+            is_loading,
+            is_saving: false,
+            load_failed: false,
+            order_changed: false,
+            selected_count: 0,
+            rows,
+            tracker: 0,
+        };
+
+        let list_box = model.rows.widget();
+        let widgets = view_output!();
+
+        if is_loading {
+            let track_ids = init.track_ids;
+            sender.command(move |out, shutdown| {
+                shutdown
+                    .register(async move {
+                        let mut songs = Vec::new();
+                        let mut offset = loaded;
+                        let mut complete = true;
+                        while offset < track_ids.len() {
+                            match get_playlist_track_all(&track_ids, offset, FETCH_BATCH).await {
+                                Ok(batch) if !batch.is_empty() => songs.extend(batch),
+                                Ok(_) => {
+                                    log::error!("歌单歌曲加载中断: offset {offset} 返回空列表");
+                                    complete = false;
+                                    break;
+                                }
+                                Err(e) => {
+                                    log::error!("歌单歌曲加载失败: {e}");
+                                    complete = false;
+                                    break;
+                                }
+                            }
+                            offset += FETCH_BATCH;
+                        }
+                        let _ = out.send(TrackEditorCmdMsg::RestLoaded { songs, complete });
+                    })
+                    .drop_on_shutdown()
+                    .boxed()
+            });
+        }
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        self.reset();
+        match message {
+            TrackEditorMsg::RowAction(TrackEditRowOutput::SelectionChanged) => {
+                let count = self.rows.iter().filter(|row| row.selected).count();
+                self.set_selected_count(count);
+            }
+            TrackEditorMsg::RowAction(TrackEditRowOutput::Move { from, to }) => {
+                if from == to || self.is_saving {
+                    return;
+                }
+                let mut guard = self.rows.guard();
+                if from < guard.len() && to < guard.len() {
+                    guard.move_to(from, to);
+                    drop(guard);
+                    self.set_order_changed(true);
+                }
+            }
+            TrackEditorMsg::DeleteSelected => {
+                let ids: Vec<u64> = self
+                    .rows
+                    .iter()
+                    .filter(|row| row.selected)
+                    .map(|row| row.song.id)
+                    .collect();
+                if ids.is_empty() {
+                    return;
+                }
+                self.set_is_saving(true);
+                let pid = self.playlist_id;
+                sender.command(move |out, _shutdown| async move {
+                    let success = playlist_track_del(pid, &ids).await.is_ok();
+                    let _ = out.send(TrackEditorCmdMsg::Deleted { ids, success });
+                });
+            }
+            TrackEditorMsg::SaveOrder => {
+                if self.load_failed {
+                    return;
+                }
+                let ids: Vec<u64> = self.rows.iter().map(|row| row.song.id).collect();
+                self.set_is_saving(true);
+                let pid = self.playlist_id;
+                sender.command(move |out, _shutdown| async move {
+                    let success = playlist_track_reorder(pid, &ids).await.is_ok();
+                    let _ = out.send(TrackEditorCmdMsg::OrderSaved(success));
+                });
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        self.reset();
+        match message {
+            TrackEditorCmdMsg::RestLoaded { songs, complete } => {
+                let mut guard = self.rows.guard();
+                for song in songs {
+                    guard.push_back(song);
+                }
+                drop(guard);
+                self.set_is_loading(false);
+                if !complete {
+                    self.set_load_failed(true);
+                    sender
+                        .output(TrackEditorOutput::ShowToast(
+                            "部分歌曲加载失败，暂时无法保存排序".to_string(),
+                        ))
+                        .ok();
+                }
+            }
+            TrackEditorCmdMsg::Deleted { ids, success } => {
+                self.set_is_saving(false);
+                if !success {
+                    sender
+                        .output(TrackEditorOutput::ShowToast("删除歌曲失败".to_string()))
+                        .ok();
+                    return;
+                }
+                let mut guard = self.rows.guard();
+                for index in (0..guard.len()).rev() {
+                    if guard
+                        .get(index)
+                        .is_some_and(|row| ids.contains(&row.song.id))
+                    {
+                        guard.remove(index);
+                    }
+                }
+                drop(guard);
+                self.set_selected_count(0);
+                sender
+                    .output(TrackEditorOutput::ShowToast(format!(
+                        "已从歌单删除 {} 首歌曲",
+                        ids.len()
+                    )))
+                    .ok();
+                sender.output(TrackEditorOutput::Changed).ok();
+            }
+            TrackEditorCmdMsg::OrderSaved(success) => {
+                self.set_is_saving(false);
+                let toast = if success {
+                    self.set_order_changed(false);
+                    sender.output(TrackEditorOutput::Changed).ok();
+                    "歌曲顺序已保存"
+                } else {
+                    "保存排序失败"
+                };
+                sender
+                    .output(TrackEditorOutput::ShowToast(toast.to_string()))
+                    .ok();
+            }
+        }
+    }
+}
@@ -1,18 +1,25 @@
 //! Explore 发现页 —— 四区四形态
 //!
-//! 排行榜（网易云式并列榜卡） / 新歌速递（SongRow 分列横滚） / 新碟上架（横滚 CD 圆卡） / 最新 MV（横滚宽卡）。
+//! 排行榜（网易云式并列榜卡） / 新歌速递（SongRow 分列横滚） / 新碟上架（横滚 CD 圆卡） / 最新 MV（横滚宽卡），
+//! 每区都可“查看全部”；页面底部是歌单广场（分类标签 + 无感分页）。
+
+pub mod more;
+pub mod playlist_square;
 
 use relm4::factory::FactoryVecDeque;
 use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 
+use crate::api::cache::{self, key, ttl};
 use crate::api::{
-    Mv, Playlist, Song, get_album_detail, get_new_albums, get_new_mvs, get_new_songs, get_toplist,
-    get_toplist_songs,
+    MusicArea, Mv, Playlist, Song, get_album_detail, get_new_albums, get_new_mvs, get_new_songs,
+    get_toplist, get_toplist_songs,
 };
 use crate::ui::components::album_disc::{AlbumDisc, AlbumDiscInit, AlbumDiscOutput};
 use crate::ui::components::mv_card::{BoxMvCard, MvCardInit, MvCardOutput};
-use crate::ui::components::scrollable_row::ScrollableRow;
+use crate::ui::components::scrollable_row::{
+    ScrollableRow, ScrollableRowInit, ScrollableRowOutput,
+};
 use crate::ui::components::song_list::{
     SongListScroll, SongListScrollInit, SongListScrollInput, SongListScrollOutput,
 };
@@ -20,6 +27,11 @@ use crate::ui::components::toplist_card::{
     BoardCardData, ToplistBoard, ToplistBoardInit, ToplistBoardInput, ToplistBoardOutput,
 };
 use crate::ui::components::track_row::{TrackRow, TrackRowInit, TrackRowOutput};
+use crate::ui::model::PlaylistType;
+use crate::ui::route::AppRoute;
+
+use more::ExploreSection;
+use playlist_square::{PlaylistSquare, PlaylistSquareMsg, PlaylistSquareOutput};
 
 pub struct Explore {
     // 排行榜:并列榜卡
@@ -40,6 +52,9 @@ pub struct Explore {
     mv_cards: FactoryVecDeque<BoxMvCard>,
     _mv_row: Controller<ScrollableRow>,
 
+    // 歌单广场：分类标签 + 分页歌单
+    playlist_square: Controller<PlaylistSquare>,
+
     // 榜单详情子视图
     stack: gtk::Stack,
     ranking_title: gtk::Label,
@@ -57,6 +72,12 @@ pub enum ExploreMsg {
     /// 新碟卡点击（异步取专辑曲目后播放）
     AlbumDiscClicked(AlbumDiscOutput),
     MvClicked(MvCardOutput),
+    /// 某一区的“查看全部”
+    ViewAll(ExploreSection),
+    /// 歌单广场卡片（打开 / 播放）
+    SquareAction(PlaylistSquareOutput),
+    /// 主页面滚动到接近底部 → 歌单广场加载下一页
+    LoadMorePlaylists,
     CloseRanking,
     /// 榜单歌曲行点击播放
     RankingPlayClicked(u64),
@@ -87,6 +108,9 @@ pub enum ExploreOutput {
     PlayTracks(Vec<Song>, usize),
     /// 打开 MV 播放页
     OpenMv(u64),
+    /// 直接播放歌单 / 专辑
+    PlayPlaylist(PlaylistType),
+    Navigate(AppRoute),
 }
 
 #[relm4::component(pub)]
@@ -103,6 +127,7 @@ impl Component for Explore {
                 gtk::Stack {
                     set_transition_type: gtk::StackTransitionType::Crossfade,
 
+                #[name(main_scrolled)]
                 add_named[Some("main")] = &gtk::ScrolledWindow {
                     set_vexpand: true,
                     set_hscrollbar_policy: gtk::PolicyType::Never,
@@ -116,6 +141,25 @@ impl Component for Explore {
                         set_spacing: 16,
 
                         // ── 1. 排行榜：并列榜卡（含前三首歌） ──
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_margin_end: 16,
+
+                            gtk::Label {
+                                set_label: "排行榜",
+                                add_css_class: "title-3",
+                                set_halign: gtk::Align::Start,
+                                set_hexpand: true,
+                            },
+
+                            gtk::Button {
+                                set_label: "查看全部",
+                                set_halign: gtk::Align::End,
+                                add_css_class: "flat",
+                                connect_clicked => ExploreMsg::ViewAll(ExploreSection::Toplists),
+                            },
+                        },
+
                         #[name(toplists_slot)]
                         gtk::Box {
                             set_orientation: gtk::Orientation::Vertical,
@@ -141,6 +185,12 @@ impl Component for Explore {
                             set_orientation: gtk::Orientation::Vertical,
                             set_spacing: 8,
                         },
+
+                        // ── 5. 歌单广场：分类标签 + 分页歌单 ──
+                        #[name(square_slot)]
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                        },
                     }
                 },
 
@@ -196,13 +246,25 @@ impl Component for Explore {
     ) -> ComponentParts<Self> {
         // 新歌速递：与搜索页共用的单曲分列横滚
         let song_list = SongListScroll::builder()
-            .launch(SongListScrollInit::new("新歌速递", 230, 230))
+            .launch(SongListScrollInit::new("新歌速递", 230, 230).with_more())
             .forward(sender.input_sender(), |out| match out {
                 SongListScrollOutput::Clicked(id) => ExploreMsg::SongClicked(id),
+                SongListScrollOutput::MoreClicked => ExploreMsg::ViewAll(ExploreSection::NewSongs),
             });
         // 新碟 → 横滚 CD 圆卡行；最新 MV → 横滚宽卡行
-        let album_row = ScrollableRow::new("新碟上架", 230, 235);
-        let mv_row = ScrollableRow::new("最新 MV", 165, 170);
+        let album_row = ScrollableRow::builder()
+            .launch(ScrollableRowInit::new("新碟上架", 230, 235).with_more())
+            .forward(sender.input_sender(), |out| match out {
+                ScrollableRowOutput::MoreClicked => ExploreMsg::ViewAll(ExploreSection::NewAlbums),
+            });
+        let mv_row = ScrollableRow::builder()
+            .launch(ScrollableRowInit::new("最新 MV", 165, 170).with_more())
+            .forward(sender.input_sender(), |out| match out {
+                ScrollableRowOutput::MoreClicked => ExploreMsg::ViewAll(ExploreSection::NewMvs),
+            });
+        let playlist_square = PlaylistSquare::builder()
+            .launch(())
+            .forward(sender.input_sender(), ExploreMsg::SquareAction);
         let album_box = album_row.model().content_box();
         let mv_box = mv_row.model().content_box();
         let ranking_title = gtk::Label::new(Some("排行榜"));
@@ -221,6 +283,7 @@ impl Component for Explore {
                 .launch(mv_box)
                 .forward(sender.input_sender(), ExploreMsg::MvClicked),
             _mv_row: mv_row,
+            playlist_square,
             stack: gtk::Stack::default(),
             ranking_title,
             ranking_list: gtk::ListBox::new(),
@@ -244,6 +307,21 @@ impl Component for Explore {
         widgets.song_slot.append(model.song_list.widget());
         widgets.album_slot.append(model._album_row.widget());
         widgets.mv_slot.append(model._mv_row.widget());
+        widgets.square_slot.append(model.playlist_square.widget());
+
+        // 滚动到接近底部时触发歌单广场分页加载（无感滑动）
+        let scroll_sender = sender.input_sender().clone();
+        widgets
+            .main_scrolled
+            .vadjustment()
+            .connect_value_changed(move |adj| {
+                let value = adj.value();
+                let upper = adj.upper();
+                let page_size = adj.page_size();
+                if upper > 0.0 && upper - (value + page_size) < 200.0 {
+                    let _ = scroll_sender.send(ExploreMsg::LoadMorePlaylists);
+                }
+            });
 
         // 榜单详情工厂挂到真实 ListBox
         model.ranking_tracks = FactoryVecDeque::builder()
@@ -255,17 +333,16 @@ impl Component for Explore {
 
         // 并行加载 4 块内容
         sender.command(|out, _shutdown| async move {
-            match get_toplist().await {
-                Ok(list) => {
-                    let _ = out.send(ExploreCmdMsg::ToplistsLoaded(list));
-                }
-                Err(_) => {
-                    let _ = out.send(ExploreCmdMsg::LoadFailed);
-                }
+            let result = cache::swr(key::TOPLIST, ttl::TOPLIST, get_toplist, |list| {
+                let _ = out.send(ExploreCmdMsg::ToplistsLoaded(list));
+            })
+            .await;
+            if result.is_err() {
+                let _ = out.send(ExploreCmdMsg::LoadFailed);
             }
         });
         sender.command(|out, _shutdown| async move {
-            match get_new_songs().await {
+            match get_new_songs(MusicArea::All).await {
                 Ok(songs) => {
                     let _ = out.send(ExploreCmdMsg::NewSongsLoaded(songs));
                 }
@@ -275,8 +352,8 @@ impl Component for Explore {
             }
         });
         sender.command(|out, _shutdown| async move {
-            match get_new_albums().await {
-                Ok(albums) => {
+            match get_new_albums(MusicArea::All, 0, 12).await {
+                Ok((albums, _)) => {
                     let _ = out.send(ExploreCmdMsg::NewAlbumsLoaded(albums));
                 }
                 Err(_) => {
@@ -285,8 +362,8 @@ impl Component for Explore {
             }
         });
         sender.command(|out, _shutdown| async move {
-            match get_new_mvs().await {
-                Ok(mvs) => {
+            match get_new_mvs(0, 12).await {
+                Ok((mvs, _)) => {
                     let _ = out.send(ExploreCmdMsg::NewMvsLoaded(mvs));
                 }
                 Err(_) => {
@@ -346,6 +423,23 @@ impl Component for Explore {
                     let _ = sender.output(ExploreOutput::OpenMv(id));
                 }
             },
+            ExploreMsg::ViewAll(section) => {
+                let _ = sender.output(ExploreOutput::Navigate(AppRoute::ExploreMore(section)));
+            }
+            ExploreMsg::SquareAction(output) => {
+                let out = match output {
+                    PlaylistSquareOutput::Open(id) => ExploreOutput::Navigate(
+                        AppRoute::PlaylistDetail(PlaylistType::Playlist(id)),
+                    ),
+                    PlaylistSquareOutput::Play(id) => {
+                        ExploreOutput::PlayPlaylist(PlaylistType::Playlist(id))
+                    }
+                };
+                let _ = sender.output(out);
+            }
+            ExploreMsg::LoadMorePlaylists => {
+                self.playlist_square.emit(PlaylistSquareMsg::LoadMore);
+            }
             ExploreMsg::CloseRanking => {
                 self.stack.set_visible_child_name("main");
             }
@@ -380,6 +474,10 @@ impl Component for Explore {
                     .take(8)
                     .map(BoardCardData::from_playlist)
                     .collect();
+                // 缓存之后可能再收到一次刷新结果，替换掉旧的榜单
+                if let Some(old) = self.toplist_board.take() {
+                    self.toplists_slot.remove(old.widget());
+                }
                 let board = ToplistBoard::builder()
                     .launch(ToplistBoardInit { cards })
                     .forward(sender.input_sender(), ExploreMsg::BoardClicked);
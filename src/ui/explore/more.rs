@@ -0,0 +1,442 @@
+//! 发现页各区的“查看全部”页面
+//!
+//! 排行榜：全部榜单（ToplistCard 网格） / 新歌速递：地区筛选 + 歌曲列表 /
+//! 新碟上架：地区筛选 + 分页专辑网格 / 最新 MV：分页 MV 网格。
+
+use relm4::factory::FactoryVecDeque;
+use relm4::gtk::prelude::*;
+use relm4::prelude::*;
+use strum::Display;
+
+use crate::api::{
+    MusicArea, Mv, Playlist, Song, get_new_albums, get_new_mvs, get_new_songs, get_toplist,
+};
+use crate::ui::components::mv_card::{MvCard, MvCardInit, MvCardOutput};
+use crate::ui::components::playlist_card::{PlaylistCard, PlaylistCardInit, PlaylistCardOutput};
+use crate::ui::components::toplist_card::{
+    BoardCardData, ToplistBoard, ToplistBoardInit, ToplistBoardOutput,
+};
+use crate::ui::components::track_row::{TrackRow, TrackRowInit, TrackRowOutput};
+use crate::ui::model::PlaylistType;
+use crate::ui::route::AppRoute;
+
+const PAGE_SIZE: u32 = 30;
+
+/// 发现页中可“查看全部”的区块
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ExploreSection {
+    #[strum(serialize = "排行榜")]
+    Toplists,
+    #[strum(serialize = "新歌速递")]
+    NewSongs,
+    #[strum(serialize = "新碟上架")]
+    NewAlbums,
+    #[strum(serialize = "最新 MV")]
+    NewMvs,
+}
+
+impl ExploreSection {
+    fn has_area_filter(&self) -> bool {
+        matches!(self, ExploreSection::NewSongs | ExploreSection::NewAlbums)
+    }
+
+    fn is_paged(&self) -> bool {
+        matches!(self, ExploreSection::NewAlbums | ExploreSection::NewMvs)
+    }
+}
+
+#[derive(Debug)]
+pub enum ExploreMoreMsg {
+    SetArea(MusicArea),
+    LoadMore,
+    ToplistClicked(ToplistBoardOutput),
+    SongPlayClicked(u64),
+    AlbumCardAction(PlaylistCardOutput),
+    MvClicked(MvCardOutput),
+    Noop,
+}
+
+#[derive(Debug)]
+pub enum ExploreMoreCmdMsg {
+    ToplistsLoaded(Vec<Playlist>),
+    SongsLoaded(MusicArea, Vec<Song>),
+    AlbumsLoaded(MusicArea, Vec<Playlist>, bool),
+    MvsLoaded(Vec<Mv>, bool),
+    LoadFailed,
+}
+
+#[derive(Debug)]
+pub enum ExploreMoreOutput {
+    Navigate(AppRoute),
+    PlayTracks(Vec<Song>, usize),
+    PlayPlaylist(PlaylistType),
+}
+
+#[tracker::track]
+pub struct ExploreMorePage {
+    #[do_not_track]
+    section: ExploreSection,
+    area: MusicArea,
+    is_loading: bool,
+    #[do_not_track]
+    offset: u32,
+    #[do_not_track]
+    has_more: bool,
+    #[do_not_track]
+    songs: Vec<Song>,
+    #[do_not_track]
+    toplist_slot: gtk::Box,
+    #[do_not_track]
+    toplist_board: Option<Controller<ToplistBoard>>,
+    #[do_not_track]
+    tracks: FactoryVecDeque<TrackRow>,
+    #[do_not_track]
+    albums: FactoryVecDeque<PlaylistCard>,
+    #[do_not_track]
+    mvs: FactoryVecDeque<MvCard>,
+}
+
+#[relm4::component(pub)]
+impl Component for ExploreMorePage {
+    type Init = ExploreSection;
+    type Input = ExploreMoreMsg;
+    type Output = ExploreMoreOutput;
+    type CommandOutput = ExploreMoreCmdMsg;
+
+    view! {
+        #[root]
+        #[name(scrolled)]
+        gtk::ScrolledWindow {
+            set_vexpand: true,
+            set_hscrollbar_policy: gtk::PolicyType::Never,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 16,
+                set_margin_start: 24,
+                set_margin_end: 24,
+                set_margin_top: 16,
+                set_margin_bottom: 24,
+
+                gtk::Label {
+                    set_label: &model.section.to_string(),
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "title-1",
+                },
+
+                // 地区筛选（新歌 / 新碟）
+                #[name(area_box)]
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 4,
+                    set_visible: model.section.has_area_filter(),
+                },
+
+                #[name(toplist_slot)]
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_visible: model.section == ExploreSection::Toplists,
+                },
+
+                #[local_ref]
+                track_list -> gtk::ListBox {
+                    add_css_class: "boxed-list",
+                    set_selection_mode: gtk::SelectionMode::None,
+                    set_show_separators: true,
+                    set_visible: model.section == ExploreSection::NewSongs,
+                },
+
+                #[local_ref]
+                album_flow_box -> gtk::FlowBox {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_homogeneous: true,
+                    set_row_spacing: 16,
+                    set_column_spacing: 16,
+                    set_min_children_per_line: 1,
+                    set_max_children_per_line: 100,
+                    set_selection_mode: gtk::SelectionMode::None,
+                    set_visible: model.section == ExploreSection::NewAlbums,
+                },
+
+                #[local_ref]
+                mv_flow_box -> gtk::FlowBox {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_homogeneous: true,
+                    set_row_spacing: 16,
+                    set_column_spacing: 16,
+                    set_min_children_per_line: 1,
+                    set_max_children_per_line: 100,
+                    set_selection_mode: gtk::SelectionMode::None,
+                    set_visible: model.section == ExploreSection::NewMvs,
+                },
+
+                gtk::Spinner {
+                    #[watch]
+                    set_spinning: model.is_loading,
+                    #[watch]
+                    set_visible: model.is_loading,
+                    set_halign: gtk::Align::Center,
+                    set_margin_top: 8,
+                },
+            }
+        }
+    }
+
+    fn init(
+        section: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let tracks = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::default())
+            .forward(sender.input_sender(), |msg| match msg {
+                TrackRowOutput::PlayClicked(id) => ExploreMoreMsg::SongPlayClicked(id),
+                TrackRowOutput::MoreClicked(_) => ExploreMoreMsg::Noop,
+            });
+        let albums = FactoryVecDeque::builder()
+            .launch(gtk::FlowBox::default())
+            .forward(sender.input_sender(), ExploreMoreMsg::AlbumCardAction);
+        let mvs = FactoryVecDeque::builder()
+            .launch(gtk::FlowBox::default())
+            .forward(sender.input_sender(), ExploreMoreMsg::MvClicked);
+
+        let mut model = Self {
+            section,
+            area: MusicArea::All,
+            is_loading: false,
+            offset: 0,
+            has_more: true,
+            songs: Vec::new(),
+            toplist_slot: gtk::Box::default(),
+            toplist_board: None,
+            tracks,
+            albums,
+            mvs,
+            tracker: 0,
+        };
+
+        let track_list = model.tracks.widget();
+        let album_flow_box = model.albums.widget();
+        let mv_flow_box = model.mvs.widget();
+        let widgets = view_output!();
+        model.toplist_slot = widgets.toplist_slot.clone();
+
+        // 地区切换按钮（同组互斥）
+        let mut group: Option<gtk::ToggleButton> = None;
+        for area in MusicArea::ALL {
+            let button = gtk::ToggleButton::builder()
+                .label(area.to_string())
+                .active(area == MusicArea::All)
+                .build();
+            button.add_css_class("flat");
+            button.add_css_class("comment-sort-btn");
+            button.set_group(group.as_ref());
+            let input = sender.input_sender().clone();
+            button.connect_toggled(move |b| {
+                if b.is_active() {
+                    let _ = input.send(ExploreMoreMsg::SetArea(area));
+                }
+            });
+            widgets.area_box.append(&button);
+            group.get_or_insert(button);
+        }
+
+        // 滚动到接近底部时加载下一页（新碟 / MV）
+        if section.is_paged() {
+            let scroll_sender = sender.input_sender().clone();
+            widgets
+                .scrolled
+                .vadjustment()
+                .connect_value_changed(move |adj| {
+                    let value = adj.value();
+                    let upper = adj.upper();
+                    let page_size = adj.page_size();
+                    if upper > 0.0 && upper - (value + page_size) < 200.0 {
+                        let _ = scroll_sender.send(ExploreMoreMsg::LoadMore);
+                    }
+                });
+        }
+
+        sender.input(ExploreMoreMsg::LoadMore);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        self.reset();
+        match message {
+            ExploreMoreMsg::SetArea(area) => {
+                if self.area == area {
+                    return;
+                }
+                self.set_area(area);
+                self.offset = 0;
+                self.has_more = true;
+                self.set_is_loading(false);
+                self.tracks.guard().clear();
+                self.albums.guard().clear();
+                sender.input(ExploreMoreMsg::LoadMore);
+            }
+            ExploreMoreMsg::LoadMore => {
+                if self.is_loading || !self.has_more {
+                    return;
+                }
+                self.set_is_loading(true);
+                let area = self.area;
+                let offset = self.offset;
+                match self.section {
+                    ExploreSection::Toplists => {
+                        sender.command(|out, _shutdown| async move {
+                            match get_toplist().await {
+                                Ok(list) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::ToplistsLoaded(list));
+                                }
+                                Err(_) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::LoadFailed);
+                                }
+                            }
+                        });
+                    }
+                    ExploreSection::NewSongs => {
+                        sender.command(move |out, _shutdown| async move {
+                            match get_new_songs(area).await {
+                                Ok(songs) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::SongsLoaded(area, songs));
+                                }
+                                Err(_) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::LoadFailed);
+                                }
+                            }
+                        });
+                    }
+                    ExploreSection::NewAlbums => {
+                        sender.command(move |out, _shutdown| async move {
+                            match get_new_albums(area, offset, PAGE_SIZE).await {
+                                Ok((albums, has_more)) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::AlbumsLoaded(
+                                        area, albums, has_more,
+                                    ));
+                                }
+                                Err(_) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::LoadFailed);
+                                }
+                            }
+                        });
+                    }
+                    ExploreSection::NewMvs => {
+                        sender.command(move |out, _shutdown| async move {
+                            match get_new_mvs(offset, PAGE_SIZE).await {
+                                Ok((mvs, has_more)) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::MvsLoaded(mvs, has_more));
+                                }
+                                Err(_) => {
+                                    let _ = out.send(ExploreMoreCmdMsg::LoadFailed);
+                                }
+                            }
+                        });
+                    }
+                }
+            }
+            ExploreMoreMsg::ToplistClicked(ToplistBoardOutput::Clicked(id)) => {
+                sender
+                    .output(ExploreMoreOutput::Navigate(AppRoute::PlaylistDetail(
+                        PlaylistType::Playlist(id),
+                    )))
+                    .ok();
+            }
+            ExploreMoreMsg::SongPlayClicked(id) => {
+                let index = self.songs.iter().position(|s| s.id == id).unwrap_or(0);
+                sender
+                    .output(ExploreMoreOutput::PlayTracks(self.songs.clone(), index))
+                    .ok();
+            }
+            ExploreMoreMsg::AlbumCardAction(output) => {
+                let out = match output {
+                    PlaylistCardOutput::Clicked(id) => ExploreMoreOutput::Navigate(
+                        AppRoute::PlaylistDetail(PlaylistType::Album(id)),
+                    ),
+                    PlaylistCardOutput::ClickedPlaylist(id) => {
+                        ExploreMoreOutput::PlayPlaylist(PlaylistType::Album(id))
+                    }
+                };
+                sender.output(out).ok();
+            }
+            ExploreMoreMsg::MvClicked(MvCardOutput::Clicked(id)) => {
+                sender
+                    .output(ExploreMoreOutput::Navigate(AppRoute::Mv(id)))
+                    .ok();
+            }
+            ExploreMoreMsg::Noop => {}
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        self.reset();
+        match message {
+            ExploreMoreCmdMsg::ToplistsLoaded(list) => {
+                self.set_is_loading(false);
+                self.has_more = false;
+                let cards = list.iter().map(BoardCardData::from_playlist).collect();
+                let board = ToplistBoard::builder()
+                    .launch(ToplistBoardInit { cards })
+                    .forward(sender.input_sender(), ExploreMoreMsg::ToplistClicked);
+                self.toplist_slot.append(board.widget());
+                self.toplist_board = Some(board);
+            }
+            ExploreMoreCmdMsg::SongsLoaded(area, songs) => {
+                // 切换地区后返回的旧结果直接丢弃
+                if area != self.area {
+                    return;
+                }
+                self.set_is_loading(false);
+                self.has_more = false;
+                let mut guard = self.tracks.guard();
+                guard.clear();
+                for (index, song) in songs.iter().enumerate() {
+                    guard.push_back(TrackRowInit {
+                        track: song.clone(),
+                        index,
+                    });
+                }
+                drop(guard);
+                self.songs = songs;
+            }
+            ExploreMoreCmdMsg::AlbumsLoaded(area, albums, has_more) => {
+                if area != self.area {
+                    return;
+                }
+                self.set_is_loading(false);
+                self.offset += PAGE_SIZE;
+                self.has_more = has_more;
+                let mut guard = self.albums.guard();
+                for album in &albums {
+                    guard.push_back(
+                        PlaylistCardInit::new(
+                            album.id,
+                            format!("{}?param=200y200", album.cover_url),
+                            album.name.clone(),
+                        )
+                        .with_subtitle(album.creator_name.clone()),
+                    );
+                }
+            }
+            ExploreMoreCmdMsg::MvsLoaded(mvs, has_more) => {
+                self.set_is_loading(false);
+                self.offset += PAGE_SIZE;
+                self.has_more = has_more;
+                let mut guard = self.mvs.guard();
+                for mv in &mvs {
+                    guard.push_back(MvCardInit::from_play_count(mv));
+                }
+            }
+            ExploreMoreCmdMsg::LoadFailed => {
+                self.set_is_loading(false);
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! 歌单广场：分类标签 + 分类歌单网格（滚动到底部自动加载下一页）
+//!
+//! 标签来自热门分类（华语 / 流行 / 摇滚 …），首个标签固定为“全部”。
+//! 分页由父组件在外层滚动接近底部时发送 `PlaylistSquareMsg::LoadMore` 驱动。
+
+use relm4::factory::FactoryVecDeque;
+use relm4::gtk::prelude::*;
+use relm4::prelude::*;
+
+use crate::api::{Playlist, get_category_playlists, get_playlist_hot_tags};
+use crate::ui::components::mv_card::fmt_play_count;
+use crate::ui::components::playlist_card::{PlaylistCard, PlaylistCardInit, PlaylistCardOutput};
+
+const PAGE_SIZE: u32 = 30;
+const ALL_TAG: &str = "全部";
+
+#[derive(Debug)]
+pub enum PlaylistSquareMsg {
+    SelectTag(String),
+    LoadMore,
+    CardAction(PlaylistCardOutput),
+}
+
+#[derive(Debug)]
+pub enum PlaylistSquareCmdMsg {
+    TagsLoaded(Vec<String>),
+    PlaylistsLoaded {
+        tag: String,
+        playlists: Vec<Playlist>,
+        has_more: bool,
+    },
+    LoadFailed(String),
+}
+
+#[derive(Debug)]
+pub enum PlaylistSquareOutput {
+    /// 打开歌单详情
+    Open(u64),
+    /// 直接播放歌单
+    Play(u64),
+}
+
+#[tracker::track]
+pub struct PlaylistSquare {
+    #[do_not_track]
+    tag: String,
+    #[do_not_track]
+    offset: u32,
+    #[do_not_track]
+    has_more: bool,
+    is_loading: bool,
+    #[do_not_track]
+    tag_box: gtk::FlowBox,
+    #[do_not_track]
+    playlists: FactoryVecDeque<PlaylistCard>,
+}
+
+#[relm4::component(pub)]
+impl Component for PlaylistSquare {
+    type Init = ();
+    type Input = PlaylistSquareMsg;
+    type Output = PlaylistSquareOutput;
+    type CommandOutput = PlaylistSquareCmdMsg;
+
+    view! {
+        #[root]
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 12,
+
+            gtk::Label {
+                set_label: "歌单广场",
+                set_halign: gtk::Align::Start,
+                add_css_class: "title-3",
+            },
+
+            #[name(tag_box)]
+            gtk::FlowBox {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_selection_mode: gtk::SelectionMode::None,
+                set_row_spacing: 4,
+                set_column_spacing: 4,
+                set_min_children_per_line: 4,
+                set_max_children_per_line: 20,
+            },
+
+            #[local_ref]
+            playlist_flow_box -> gtk::FlowBox {
+                set_orientation: gtk::Orientation::Horizontal,
+                set_homogeneous: true,
+                set_row_spacing: 16,
+                set_column_spacing: 16,
+                set_min_children_per_line: 1,
+                set_max_children_per_line: 100,
+                set_selection_mode: gtk::SelectionMode::None,
+            },
+
+            gtk::Spinner {
+                #[watch]
+                set_spinning: model.is_loading,
+                #[watch]
+                set_visible: model.is_loading,
+                set_halign: gtk::Align::Center,
+                set_margin_top: 8,
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let playlists = FactoryVecDeque::builder()
+            .launch(gtk::FlowBox::default())
+            .forward(sender.input_sender(), PlaylistSquareMsg::CardAction);
+
+        let mut model = Self {
+            tag: ALL_TAG.to_string(),
+            offset: 0,
+            has_more: true,
+            is_loading: false,
+            tag_box: gtk::FlowBox::default(),
+            playlists,
+            tracker: 0,
+        };
+
+        let playlist_flow_box = model.playlists.widget();
+        let widgets = view_output!();
+        model.tag_box = widgets.tag_box.clone();
+
+        sender.command(|out, _shutdown| async move {
+            match get_playlist_hot_tags().await {
+                Ok(tags) => {
+                    let _ = out.send(PlaylistSquareCmdMsg::TagsLoaded(tags));
+                }
+                Err(_) => {
+                    let _ = out.send(PlaylistSquareCmdMsg::TagsLoaded(Vec::new()));
+                }
+            }
+        });
+        sender.input(PlaylistSquareMsg::LoadMore);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        self.reset();
+        match message {
+            PlaylistSquareMsg::SelectTag(tag) => {
+                if self.tag == tag {
+                    return;
+                }
+                self.tag = tag;
+                self.offset = 0;
+                self.has_more = true;
+                self.set_is_loading(false);
+                self.playlists.guard().clear();
+                sender.input(PlaylistSquareMsg::LoadMore);
+            }
+            PlaylistSquareMsg::LoadMore => {
+                if self.is_loading || !self.has_more {
+                    return;
+                }
+                self.set_is_loading(true);
+                let tag = self.tag.clone();
+                let offset = self.offset;
+                log::info!("无感分页(歌单广场): tag={tag}, offset={offset}");
+                sender.command(move |out, _shutdown| async move {
+                    match get_category_playlists(&tag, offset, PAGE_SIZE).await {
+                        Ok((playlists, has_more)) => {
+                            let _ = out.send(PlaylistSquareCmdMsg::PlaylistsLoaded {
+                                tag,
+                                playlists,
+                                has_more,
+                            });
+                        }
+                        Err(_) => {
+                            let _ = out.send(PlaylistSquareCmdMsg::LoadFailed(tag));
+                        }
+                    }
+                });
+            }
+            PlaylistSquareMsg::CardAction(output) => {
+                let out = match output {
+                    PlaylistCardOutput::Clicked(id) => PlaylistSquareOutput::Open(id),
+                    PlaylistCardOutput::ClickedPlaylist(id) => PlaylistSquareOutput::Play(id),
+                };
+                sender.output(out).ok();
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        self.reset();
+        match message {
+            PlaylistSquareCmdMsg::TagsLoaded(tags) => {
+                let mut group: Option<gtk::ToggleButton> = None;
+                for tag in std::iter::once(ALL_TAG.to_string()).chain(tags) {
+                    let button = gtk::ToggleButton::builder()
+                        .label(&tag)
+                        .active(tag == self.tag)
+                        .build();
+                    button.add_css_class("flat");
+                    button.add_css_class("comment-sort-btn");
+                    button.set_group(group.as_ref());
+                    let input = sender.input_sender().clone();
+                    button.connect_toggled(move |b| {
+                        if b.is_active() {
+                            let _ = input.send(PlaylistSquareMsg::SelectTag(tag.clone()));
+                        }
+                    });
+                    self.tag_box.append(&button);
+                    group.get_or_insert(button);
+                }
+            }
+            PlaylistSquareCmdMsg::PlaylistsLoaded {
+                tag,
+                playlists,
+                has_more,
+            } => {
+                // 切换标签后返回的旧结果直接丢弃
+                if tag != self.tag {
+                    return;
+                }
+                self.offset += PAGE_SIZE;
+                self.has_more = has_more;
+                self.set_is_loading(false);
+                let mut guard = self.playlists.guard();
+                for playlist in &playlists {
+                    guard.push_back(
+                        PlaylistCardInit::new(
+                            playlist.id,
+                            format!("{}?param=200y200", playlist.cover_url),
+                            playlist.name.clone(),
+                        )
+                        .with_subtitle(format!("{} 次播放", fmt_play_count(playlist.play_count))),
+                    );
+                }
+            }
+            PlaylistSquareCmdMsg::LoadFailed(tag) => {
+                if tag == self.tag {
+                    self.set_is_loading(false);
+                }
+            }
+        }
+    }
+}
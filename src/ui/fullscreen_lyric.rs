@@ -2,14 +2,18 @@
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use relm4::gtk;
+use relm4::gtk::gio::{self, prelude::SettingsExt};
 use relm4::gtk::prelude::*;
 use relm4::prelude::*;
 
+use crate::APPLICATION_ID;
 use crate::api::Song;
-use crate::ui::components::gl_bg::mesh_renderer::MeshGradientRenderer;
+use crate::ui::components::gl_bg::mesh_renderer::{MeshConfig, MeshGradientRenderer};
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::spectrum::{self, SpectrumView};
 use crate::ui::lyric::{LyricPage, LyricsMsg, LyricsOutput};
 
 pub const FULLSCREEN_CSS: &str = "
@@ -83,6 +87,7 @@ pub enum FullscreenLyricMsg {
     LyricsSeek(u64),
     SetLiked(bool),
     ToggleLike,
+    Spectrum(Arc<Vec<f32>>),
 }
 
 #[derive(Debug)]
@@ -113,6 +118,10 @@ pub struct FullscreenLyricPage {
     current_margin: Rc<std::cell::Cell<f64>>,
     target_margin: Rc<std::cell::Cell<f64>>,
     animation_start_time: Rc<std::cell::Cell<Option<u64>>>,
+    spectrum: SpectrumView,
+    settings: gio::Settings,
+    /// 平滑后的低频能量，驱动背景律动
+    beat: f32,
 }
 
 #[relm4::component(pub)]
@@ -315,6 +324,16 @@ impl SimpleComponent for FullscreenLyricPage {
                 },
             },
 
+            // 频谱（底部 overlay，设置中关闭时隐藏）
+            #[local_ref]
+            add_overlay = spectrum_widget -> gtk::DrawingArea {
+                set_valign: gtk::Align::End,
+                set_margin_start: 32,
+                set_margin_end: 32,
+                set_margin_bottom: 24,
+                set_opacity: 0.6,
+            },
+
             // 关闭按钮（右上角 overlay）
             add_overlay = &gtk::Button {
                 set_icon_name: "window-close-symbolic",
@@ -374,7 +393,12 @@ impl SimpleComponent for FullscreenLyricPage {
             current_margin: current_margin.clone(),
             target_margin: target_margin.clone(),
             animation_start_time: animation_start_time.clone(),
+            spectrum: SpectrumView::new(64),
+            settings: gio::Settings::new(APPLICATION_ID),
+            beat: 0.0,
         };
+        model.spectrum.set_color(gtk::gdk::RGBA::WHITE);
+        let spectrum_widget = model.spectrum.widget();
 
         let widgets = view_output!();
         model.progress_scale.clone_from(&widgets.progress_scale);
@@ -513,6 +537,10 @@ impl SimpleComponent for FullscreenLyricPage {
             }
             FullscreenLyricMsg::UpdatePlayback(is_playing) => {
                 self.is_playing = is_playing;
+                if !is_playing {
+                    self.spectrum.clear();
+                    self.reset_beat();
+                }
                 // 播放 → margin 收到 0（图片顶满），暂停 → margin 展到 24
                 self.target_margin.set(if is_playing { 0.0 } else { 20.0 });
                 self.animation_start_time.set(None);
@@ -542,6 +570,14 @@ impl SimpleComponent for FullscreenLyricPage {
             FullscreenLyricMsg::SetLiked(liked) => {
                 self.is_liked = liked;
             }
+            FullscreenLyricMsg::Spectrum(bands) => {
+                self.spectrum.push(&bands);
+                if self.settings.boolean(spectrum::BEAT_BACKGROUND_KEY) {
+                    self.push_beat(spectrum::bass_level(&bands));
+                } else if self.beat > 0.0 {
+                    self.reset_beat();
+                }
+            }
             FullscreenLyricMsg::ToggleLike => {
                 let new_liked = !self.is_liked;
                 self.is_liked = new_liked;
@@ -553,6 +589,30 @@ impl SimpleComponent for FullscreenLyricPage {
     }
 }
 
+impl FullscreenLyricPage {
+    /// 低频能量映射到背景：流动加快、画面轻微缩放
+    fn push_beat(&mut self, level: f32) {
+        // 上升快、回落慢，避免背景抖动
+        let rate = if level > self.beat { 0.6 } else { 0.15 };
+        self.beat += (level - self.beat) * rate;
+        self.apply_beat();
+    }
+
+    /// 暂停或关闭律动时恢复默认背景
+    fn reset_beat(&mut self) {
+        self.beat = 0.0;
+        self.apply_beat();
+    }
+
+    fn apply_beat(&self) {
+        if let Some(gs) = self.gl_state.borrow_mut().as_mut() {
+            let default = MeshConfig::default();
+            gs.renderer.config.flow_speed = default.flow_speed * (1.0 + self.beat * 3.0);
+            gs.renderer.volume = self.beat * 0.08;
+        }
+    }
+}
+
 fn create_glow_context() -> Result<glow::Context, String> {
     unsafe {
         type EglGetProcAddr =
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use crate::api::UserInfo;
 use crate::ui::components::image::AsyncImage;
 use crate::ui::route::AppRoute;
+use crate::utils::share;
 use relm4::adw::{self};
 use relm4::gtk::prelude::*;
 use relm4::{Component, ComponentParts, ComponentSender, gtk};
@@ -34,6 +35,8 @@ pub enum HeaderMsg {
     SearchAccepted,
     /// 搜索输入框内容变化
     SearchChanged,
+    /// 快捷键：聚焦搜索框并选中已有内容
+    FocusSearch,
 }
 
 // 向上层抛出的路由事件 (【修改】增加了 OpenSettings)
@@ -48,6 +51,8 @@ pub enum HeaderOutput {
     SearchSubmit(String),
     /// 搜索输入框内容变化（用于实时建议）
     SearchChanged(String),
+    /// 搜索框里粘贴的是分享链接
+    OpenShareLink(String),
 }
 
 #[relm4::component(pub)]
@@ -235,13 +240,23 @@ impl Component for Header {
             }
             HeaderMsg::SearchAccepted => {
                 let query = self.search_entry.text().to_string();
-                if !query.is_empty() {
+                if share::looks_like_share_link(&query) {
+                    self.search_entry.set_text("");
+                    sender.output(HeaderOutput::OpenShareLink(query)).unwrap();
+                } else if !query.is_empty() {
                     sender.output(HeaderOutput::SearchSubmit(query)).unwrap();
                 }
             }
             HeaderMsg::SearchChanged => {
                 let query = self.search_entry.text().to_string();
-                sender.output(HeaderOutput::SearchChanged(query)).unwrap();
+                // 分享链接不需要搜索建议
+                if !share::looks_like_share_link(&query) {
+                    sender.output(HeaderOutput::SearchChanged(query)).unwrap();
+                }
+            }
+            HeaderMsg::FocusSearch => {
+                self.search_entry.grab_focus();
+                self.search_entry.select_region(0, -1);
             }
         }
     }
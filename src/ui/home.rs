@@ -11,6 +11,7 @@ use super::components::playlist_card::{
     BoxPlaylistCard, PlaylistCard, PlaylistCardInit, PlaylistCardOutput,
 };
 use super::components::scrollable_row::ScrollableRow;
+use crate::api::cache::{self, key, ttl};
 use crate::api::{
     HomeBlock, HomeBlockType, Playlist, PlaylistDetail, Song, get_home_block, get_playlist_detail,
     get_recommend_playlist, get_song_detail,
@@ -30,6 +31,7 @@ pub struct Home {
     home_block_cards: FactoryVecDeque<HomeBlockCard>,
     home_block_row: Controller<ScrollableRow>,
     radar_row: Controller<ScrollableRow>,
+    load_failed_notified: bool,
 }
 
 #[derive(Debug)]
@@ -48,6 +50,8 @@ pub enum HomeCmdMsg {
     RadarPlaylistsLoaded(Vec<PlaylistDetail>),
     HomeBlocksLoaded(Vec<HomeBlock>),
     QueueSongsLoaded(Vec<Song>),
+    /// 某一块既没有缓存、联网又失败
+    LoadFailed,
 }
 
 #[derive(Debug)]
@@ -58,6 +62,7 @@ pub enum HomeOutput {
     Playlist(PlaylistType),
     NavigateToArtist(u64),
     PlayDirectTracks(Vec<Song>),
+    ShowToast(String),
 }
 
 #[relm4::component(pub)]
@@ -146,6 +151,7 @@ impl Component for Home {
                 }),
             home_block_row,
             radar_row,
+            load_failed_notified: false,
         };
 
         let widgets = view_output!();
@@ -181,61 +187,55 @@ impl Component for Home {
         match message {
             HomeMsg::LoadPlaylists => {
                 sender.command(|out, _shutdown| async move {
-                    match get_recommend_playlist().await {
-                        Ok(playlists) => {
+                    let result = cache::swr(
+                        key::RECOMMEND_PLAYLIST,
+                        ttl::RECOMMEND_PLAYLIST,
+                        get_recommend_playlist,
+                        |playlists| {
                             let _ = out.send(HomeCmdMsg::PlaylistsLoaded(playlists));
-                        }
-                        Err(e) => log::error!("加载推荐歌单失败: {e}"),
+                        },
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("加载推荐歌单失败: {e}");
+                        let _ = out.send(HomeCmdMsg::LoadFailed);
                     }
                 });
             }
 
             HomeMsg::LoadRadarPlaylists => {
                 sender.command(|out, _shutdown| async move {
-                    let ids = RADAR_PLAYLIST_IDS.to_vec();
-                    let results: Vec<_> =
-                        stream::iter(ids.into_iter().enumerate().map(|(i, id)| async move {
-                            let result = get_playlist_detail(id).await;
-                            (i, result)
-                        }))
-                        .buffer_unordered(CONCURRENCY_LIMIT)
-                        .collect()
-                        .await;
-
-                    let mut results = results;
-                    results.sort_by_key(|(i, _)| *i);
-                    let playlists: Vec<PlaylistDetail> =
-                        results.into_iter().filter_map(|(_, r)| r.ok()).collect();
-
-                    let _ = out.send(HomeCmdMsg::RadarPlaylistsLoaded(playlists));
+                    let result = cache::swr(
+                        key::HOME_RADAR,
+                        ttl::PLAYLIST_DETAIL,
+                        fetch_radar_playlists,
+                        |playlists| {
+                            let _ = out.send(HomeCmdMsg::RadarPlaylistsLoaded(playlists));
+                        },
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("加载雷达歌单失败: {e}");
+                        let _ = out.send(HomeCmdMsg::LoadFailed);
+                    }
                 });
             }
 
             HomeMsg::LoadHomeBlocks => {
                 sender.command(|out, _shutdown| async move {
-                    match get_home_block().await {
-                        Ok(blocks) => {
-                            let mut filtered: Vec<HomeBlock> = Vec::new();
-                            for mut block in blocks {
-                                match &block.type_ {
-                                    HomeBlockType::Fm | HomeBlockType::Unknown => continue,
-                                    _ => {}
-                                }
-
-                                let cover_url = format!("{}?param=300y300", block.cover);
-                                let token = CancellationToken::new();
-                                let color =
-                                    match ImageManager::global().fetch(cover_url, token).await {
-                                        Ok(bytes) => extract_dominant_color(&bytes),
-                                        Err(_) => "#333333".to_string(),
-                                    };
-                                block.color = color;
-
-                                filtered.push(block);
-                            }
-                            let _ = out.send(HomeCmdMsg::HomeBlocksLoaded(filtered));
-                        }
-                        Err(e) => log::error!("加载首页推荐块失败: {e}"),
+                    // 主色也一并缓存，离线时卡片颜色不变
+                    let result = cache::swr(
+                        key::HOME_BLOCK,
+                        ttl::HOME_BLOCK,
+                        fetch_home_blocks,
+                        |blocks| {
+                            let _ = out.send(HomeCmdMsg::HomeBlocksLoaded(blocks));
+                        },
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("加载首页推荐块失败: {e}");
+                        let _ = out.send(HomeCmdMsg::LoadFailed);
                     }
                 });
             }
@@ -354,6 +354,55 @@ impl Component for Home {
             HomeCmdMsg::QueueSongsLoaded(songs) => {
                 let _ = sender.output(HomeOutput::PlayDirectTracks(songs));
             }
+            HomeCmdMsg::LoadFailed => {
+                // 离线时几块会一起失败，只提示一次
+                if !self.load_failed_notified {
+                    self.load_failed_notified = true;
+                    let _ = sender.output(HomeOutput::ShowToast(
+                        "首页加载失败，请检查网络连接".to_string(),
+                    ));
+                }
+            }
         }
     }
 }
+
+/// 雷达歌单：并发获取，保持 `RADAR_PLAYLIST_IDS` 的顺序，失败的跳过
+async fn fetch_radar_playlists() -> anyhow::Result<Vec<PlaylistDetail>> {
+    let ids = RADAR_PLAYLIST_IDS.to_vec();
+    let mut results: Vec<_> = stream::iter(ids.into_iter().enumerate().map(|(i, id)| async move {
+        let result = get_playlist_detail(id).await;
+        (i, result)
+    }))
+    .buffer_unordered(CONCURRENCY_LIMIT)
+    .collect()
+    .await;
+
+    results.sort_by_key(|(i, _)| *i);
+    let playlists: Vec<PlaylistDetail> = results.into_iter().filter_map(|(_, r)| r.ok()).collect();
+    if playlists.is_empty() {
+        anyhow::bail!("雷达歌单全部获取失败");
+    }
+    Ok(playlists)
+}
+
+/// 首页推荐块：过滤掉不支持的类型，并取封面主色作为卡片背景
+async fn fetch_home_blocks() -> anyhow::Result<Vec<HomeBlock>> {
+    let mut filtered: Vec<HomeBlock> = Vec::new();
+    for mut block in get_home_block().await? {
+        match &block.type_ {
+            HomeBlockType::Fm | HomeBlockType::Unknown => continue,
+            _ => {}
+        }
+
+        let cover_url = format!("{}?param=300y300", block.cover);
+        let token = CancellationToken::new();
+        block.color = match ImageManager::global().fetch(cover_url, token).await {
+            Ok(bytes) => extract_dominant_color(&bytes),
+            Err(_) => "#333333".to_string(),
+        };
+
+        filtered.push(block);
+    }
+    Ok(filtered)
+}
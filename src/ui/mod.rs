@@ -1,5 +1,6 @@
 pub mod about;
 pub mod artist;
+pub mod audio_effects;
 pub mod collection;
 pub mod comments;
 pub mod components;
@@ -9,12 +10,18 @@ pub mod header;
 pub mod home;
 pub mod lyric;
 pub mod model;
+pub mod mv_pip;
+pub mod mv_playback;
 pub mod mv_player;
+pub mod notification;
 pub mod player;
 pub mod playlist_detail;
 pub mod queue;
 pub mod route;
 pub mod search;
 pub mod setting;
+pub mod shortcuts;
 pub mod sidebar;
+pub mod user;
 pub mod window;
+pub mod year_report;
@@ -33,6 +33,11 @@ pub enum PlaySource {
         artist_name: String,
         artist_id: u64,
     },
+    // 心动模式：从「我喜欢的音乐」里的一首歌开始，边播边续推荐
+    Intelligence {
+        playlist_id: u64,
+        seed: Song,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -43,6 +48,7 @@ pub struct DetailView {
     pub creator: Option<String>,
     pub creator_id: u64,
     pub description: Option<String>,
+    pub tags: Vec<String>,
     pub tracks: Vec<Song>,
     pub track_ids: Vec<u64>,
 }
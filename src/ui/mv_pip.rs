@@ -0,0 +1,178 @@
+//! MV 画中画窗口
+//!
+//! 接管 MV 页的播放管线，离开 MV 页浏览其它页面时视频继续播放。
+//! GTK4 没有置顶接口，窗口设为主窗口的临时窗口，始终显示在主窗口之上。
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use relm4::gtk::prelude::*;
+use relm4::gtk::{self, glib};
+use relm4::{Component, ComponentParts, ComponentSender};
+
+use crate::ui::mv_playback::MvPlayback;
+
+pub struct MvPipWindow {
+    playback: Rc<MvPlayback>,
+    is_playing: bool,
+}
+
+#[derive(Debug)]
+pub enum MvPipMsg {
+    Tick,
+    TogglePlay,
+    /// 回到 MV 页继续播放
+    Restore,
+    Close,
+}
+
+#[derive(Debug)]
+pub enum MvPipOutput {
+    Restore(u64),
+    Closed,
+}
+
+#[relm4::component(pub)]
+impl Component for MvPipWindow {
+    type Init = Rc<MvPlayback>;
+    type Input = MvPipMsg;
+    type Output = MvPipOutput;
+    type CommandOutput = ();
+
+    view! {
+        #[root]
+        gtk::Window {
+            set_title: Some(&model.playback.title()),
+            set_default_size: (400, 225),
+            set_decorated: false,
+            add_css_class: "mv-pip",
+
+            connect_close_request[sender] => move |_| {
+                sender.output(MvPipOutput::Closed).ok();
+                glib::Propagation::Proceed
+            },
+
+            // 无边框窗口：整个画面都可以拖动
+            gtk::WindowHandle {
+                gtk::Overlay {
+                    #[wrap(Some)]
+                    #[name(picture)]
+                    set_child = &gtk::Picture {
+                        set_paintable: Some(model.playback.paintable()),
+                        set_hexpand: true,
+                        set_vexpand: true,
+
+                        add_controller = gtk::GestureClick {
+                            connect_pressed[sender] => move |_, n_press, _, _| {
+                                if n_press == 2 {
+                                    sender.input(MvPipMsg::Restore);
+                                }
+                            }
+                        },
+                    },
+
+                    // 鼠标悬停时显示控制按钮
+                    add_overlay: controls = &gtk::Revealer {
+                        set_transition_type: gtk::RevealerTransitionType::Crossfade,
+                        set_halign: gtk::Align::Center,
+                        set_valign: gtk::Align::End,
+                        set_margin_bottom: 8,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_spacing: 6,
+                            add_css_class: "osd",
+                            add_css_class: "toolbar",
+
+                            gtk::Button {
+                                #[watch]
+                                set_icon_name: if model.is_playing {
+                                    "media-playback-pause-symbolic"
+                                } else {
+                                    "media-playback-start-symbolic"
+                                },
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("播放/暂停"),
+                                connect_clicked => MvPipMsg::TogglePlay,
+                            },
+                            gtk::Button {
+                                set_icon_name: "view-restore-symbolic",
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("返回 MV 页"),
+                                connect_clicked => MvPipMsg::Restore,
+                            },
+                            gtk::Button {
+                                set_icon_name: "window-close-symbolic",
+                                add_css_class: "flat",
+                                set_tooltip_text: Some("关闭"),
+                                connect_clicked => MvPipMsg::Close,
+                            },
+                        },
+                    },
+                },
+
+                add_controller = gtk::EventControllerMotion {
+                    connect_enter[controls] => move |_, _, _| {
+                        controls.set_reveal_child(true);
+                    },
+                    connect_leave[controls] => move |_| {
+                        controls.set_reveal_child(false);
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        playback: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = Self {
+            is_playing: playback.is_playing(),
+            playback,
+        };
+        let widgets = view_output!();
+
+        // 播放状态轮询：节流到 ~250ms 一次（随窗口销毁自动停止）
+        let tick_sender = sender.input_sender().clone();
+        let last_tick = Cell::new(0i64);
+        widgets.picture.add_tick_callback(move |_, clock| {
+            let now = clock.frame_time();
+            if now - last_tick.get() >= 250_000 {
+                last_tick.set(now);
+                tick_sender.emit(MvPipMsg::Tick);
+            }
+            glib::ControlFlow::Continue
+        });
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
+        match message {
+            MvPipMsg::Tick => {
+                for err in self.playback.poll() {
+                    log::error!("画中画播放出错: {err}");
+                }
+                self.is_playing = self.playback.is_playing();
+            }
+            MvPipMsg::TogglePlay => {
+                self.is_playing = self.playback.toggle();
+            }
+            MvPipMsg::Restore => {
+                sender
+                    .output(MvPipOutput::Restore(self.playback.mv_id()))
+                    .ok();
+            }
+            MvPipMsg::Close => root.close(),
+        }
+    }
+}
+
+impl MvPipWindow {
+    /// 交回管线（回到 MV 页时由新页面接管）
+    pub fn playback(&self) -> Rc<MvPlayback> {
+        self.playback.clone()
+    }
+}
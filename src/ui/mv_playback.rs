@@ -0,0 +1,254 @@
+//! MV 播放管线
+//!
+//! MV 页、全屏窗口和画中画窗口共用同一条管线和同一个 paintable，
+//! 切换显示位置时视频不中断；画中画持有引用时离开 MV 页也能继续播放。
+//! 最后一个引用释放时管线回到 NULL 状态。
+//!
+//! 播放状态经 `PlayerCommand` 上报给播放器：开始时播放器暂停并记下音乐，
+//! MPRIS 和侧栏显示 MV；播放器把媒体键等控制以 `MvControl` 转回这里。
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gst::prelude::*;
+use relm4::gtk::gdk;
+
+use crate::api::MvDetail;
+use crate::player::messages::{MvControl, PlayerCommand};
+
+/// 进度变化超过这么多（毫秒）才重新上报
+const PROGRESS_REPORT_STEP_MS: u64 = 500;
+
+pub struct MvPlayback {
+    pipeline: gst::Pipeline,
+    playbin: gst::Element,
+    paintable: gdk::Paintable,
+    detail: RefCell<MvDetail>,
+    resolution: Cell<u32>,
+    player_cmd_tx: flume::Sender<PlayerCommand>,
+    /// 已向播放器发出 StartMv 且尚未结束
+    in_session: Cell<bool>,
+    /// 上次上报的（是否在播放，位置，时长）
+    reported: Cell<Option<(bool, u64, u64)>>,
+    /// 切换清晰度后等拿到时长再跳回的位置（毫秒）
+    pending_seek: Cell<Option<u64>>,
+}
+
+impl MvPlayback {
+    pub fn new(player_cmd_tx: flume::Sender<PlayerCommand>) -> Rc<Self> {
+        let pipeline = gst::Pipeline::new();
+        let playbin = gst::ElementFactory::make("playbin3")
+            .build()
+            .expect("playbin3 element not available");
+        pipeline.add(&playbin).expect("failed to add playbin3");
+
+        let sink = gst::ElementFactory::make("gtk4paintablesink")
+            .build()
+            .expect("gtk4paintablesink element not available");
+        let paintable: gdk::Paintable = sink.property("paintable");
+        playbin.set_property("video-sink", &sink);
+
+        Rc::new(Self {
+            pipeline,
+            playbin,
+            paintable,
+            detail: RefCell::default(),
+            resolution: Cell::new(0),
+            player_cmd_tx,
+            in_session: Cell::new(false),
+            reported: Cell::new(None),
+            pending_seek: Cell::new(None),
+        })
+    }
+
+    pub fn paintable(&self) -> &gdk::Paintable {
+        &self.paintable
+    }
+
+    /// 当前载入的 MV，未载入时为 0
+    pub fn mv_id(&self) -> u64 {
+        self.detail.borrow().id
+    }
+
+    pub fn title(&self) -> String {
+        self.detail.borrow().name.clone()
+    }
+
+    pub fn resolution(&self) -> u32 {
+        self.resolution.get()
+    }
+
+    /// 从头（start_ms 为 0）或从指定位置播放新地址
+    pub fn load(&self, detail: &MvDetail, url: &str, resolution: u32, start_ms: u64) {
+        *self.detail.borrow_mut() = detail.clone();
+        self.resolution.set(resolution);
+        self.start_session();
+        self.pending_seek.set(Some(start_ms).filter(|ms| *ms > 0));
+
+        // 切换地址前先复位
+        let _ = self.pipeline.set_state(gst::State::Null);
+        self.playbin.set_property("uri", url);
+        let _ = self.pipeline.set_state(gst::State::Playing);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.pipeline.current_state() == gst::State::Playing
+    }
+
+    pub fn set_playing(&self, playing: bool) {
+        // 切回音乐后又在这里点了播放：重新接管
+        if playing && !self.in_session.get() {
+            self.start_session();
+        }
+        let state = if playing {
+            gst::State::Playing
+        } else {
+            gst::State::Paused
+        };
+        let _ = self.pipeline.set_state(state);
+    }
+
+    /// 返回切换后是否在播放
+    pub fn toggle(&self) -> bool {
+        let playing = !self.is_playing();
+        self.set_playing(playing);
+        playing
+    }
+
+    pub fn position(&self) -> u64 {
+        self.playbin
+            .query_position::<gst::ClockTime>()
+            .map_or(0, |t| t.mseconds())
+    }
+
+    pub fn duration(&self) -> u64 {
+        self.playbin
+            .query_duration::<gst::ClockTime>()
+            .map_or(0, |t| t.mseconds())
+    }
+
+    pub fn seek(&self, ms: u64) {
+        // FLUSH：立即清空缓冲快速生效；KEY_UNIT：跳到关键帧（不用 ACCURATE，
+        // 否则需精确解码中间帧导致远距离 seek 很慢）。
+        let _ = self.playbin.seek_simple(
+            gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
+            gst::ClockTime::from_mseconds(ms),
+        );
+    }
+
+    /// 音量 0.0 ~ 1.0（线性）
+    pub fn volume(&self) -> f64 {
+        self.playbin.property("volume")
+    }
+
+    pub fn set_volume(&self, volume: f64) {
+        self.playbin.set_property("volume", volume.clamp(0.0, 1.0));
+    }
+
+    /// 执行播放器转来的控制（媒体键、MPRIS、侧栏按钮）
+    pub fn control(&self, control: MvControl) {
+        match control {
+            MvControl::Play => self.set_playing(true),
+            MvControl::Pause => self.set_playing(false),
+            MvControl::Toggle => {
+                self.toggle();
+            }
+            MvControl::Seek(ms) => self.seek(ms),
+            MvControl::Stop => {
+                // 播放器已切回音乐，不再上报
+                self.in_session.set(false);
+                let _ = self.pipeline.set_state(gst::State::Paused);
+            }
+        }
+    }
+
+    /// 定时调用：执行待定的续播跳转并上报进度，处理播完，返回期间总线上的错误信息
+    pub fn poll(&self) -> Vec<String> {
+        let duration = self.duration();
+        if duration > 0 {
+            if let Some(ms) = self.pending_seek.take() {
+                self.seek(ms);
+            }
+        }
+        if self.in_session.get() {
+            let (playing, position) = (self.is_playing(), self.position());
+            let changed =
+                self.reported
+                    .get()
+                    .is_none_or(|(last_playing, last_position, last_duration)| {
+                        playing != last_playing
+                            || duration != last_duration
+                            || position.abs_diff(last_position) >= PROGRESS_REPORT_STEP_MS
+                    });
+            if changed {
+                self.reported.set(Some((playing, position, duration)));
+                self.send(PlayerCommand::MvProgress {
+                    playing,
+                    position,
+                    duration,
+                });
+            }
+        }
+
+        let mut errors = Vec::new();
+        if let Some(bus) = self.pipeline.bus() {
+            while let Some(msg) = bus.pop() {
+                match msg.view() {
+                    gst::message::MessageView::Error(error) => {
+                        errors.push(error.error().message().to_string());
+                    }
+                    gst::message::MessageView::Eos(_) => self.finish(),
+                    _ => {}
+                }
+            }
+        }
+        errors
+    }
+}
+
+impl MvPlayback {
+    /// 自然播完：交还给音乐，停在开头，再点播放从头重播
+    fn finish(&self) {
+        let _ = self.pipeline.set_state(gst::State::Paused);
+        self.seek(0);
+        if self.in_session.replace(false) {
+            self.send(PlayerCommand::EndMv);
+        }
+    }
+
+    fn start_session(&self) {
+        let detail = self.detail.borrow().clone();
+        if detail.id == 0 {
+            return;
+        }
+        // 载入新地址（换 MV、换清晰度）时也重新发送，更新 MPRIS 信息
+        self.in_session.set(true);
+        self.reported.set(None);
+        self.send(PlayerCommand::StartMv(detail));
+    }
+
+    fn send(&self, cmd: PlayerCommand) {
+        if let Err(e) = self.player_cmd_tx.send(cmd) {
+            log::error!("MV 播放状态上报失败: {e}");
+        }
+    }
+}
+
+impl std::fmt::Debug for MvPlayback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MvPlayback")
+            .field("mv_id", &self.mv_id())
+            .field("resolution", &self.resolution.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for MvPlayback {
+    fn drop(&mut self) {
+        // GStreamer 要求元素销毁前必须回到 NULL 状态，否则 dispose 时会卡死
+        let _ = self.pipeline.set_state(gst::State::Null);
+        if self.in_session.get() {
+            self.send(PlayerCommand::EndMv);
+        }
+    }
+}
@@ -2,38 +2,52 @@
 //!
 //! 使用 GStreamer `playbin3` + `gtk4paintablesink` 播放视频，渲染到 `gtk::Picture`。
 //! 不走 `gtk::Video`/`gtk::MediaFile`（其内置 GtkGstSink 渲染路径在部分环境存在性能问题）。
+//! 管线由 [`MvPlayback`] 持有，全屏窗口与画中画窗口共用同一个 paintable。
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::Duration;
 
-use gst::prelude::*;
-use relm4::factory::FactoryVecDeque;
+use relm4::gtk::gio::prelude::SettingsExt;
 use relm4::gtk::prelude::*;
-use relm4::gtk::{self, gdk, glib};
+use relm4::gtk::{self, gdk, gio, glib};
 use relm4::prelude::*;
 use relm4::{Component, ComponentController, ComponentParts, ComponentSender, Controller};
 
-use crate::api::{Mv, MvDetail, get_mv_comments, get_mv_detail, get_mv_url, get_simi_mv};
-use crate::ui::comments::{CommentRow, CommentRowInit};
+use crate::APPLICATION_ID;
+use crate::api::{CommentType, Mv, MvDetail, get_mv_detail, get_mv_url, get_simi_mv};
+use crate::ui::comments::{CommentsMsg, CommentsOutput, CommentsPage};
 use crate::ui::components::mv_row::{MvList, MvListInput, MvRowOutput};
+use crate::ui::mv_playback::MvPlayback;
 use crate::ui::route::AppRoute;
+use crate::ui::window::{SEEK_STEP_MS, VOLUME_STEP};
+
+/// 偏好的 MV 分辨率（GSettings）
+const MV_RESOLUTION_KEY: &str = "mv-resolution";
+/// 全屏时鼠标静止多久后隐藏控制栏
+const CONTROLS_HIDE_DELAY: Duration = Duration::from_secs(3);
 
 pub struct MvPlayerPage {
     mv_id: u64,
     detail: MvDetail,
-    pipeline: gst::Pipeline,
-    playbin: gst::Element,
+    playback: Rc<MvPlayback>,
+    settings: gio::Settings,
     is_playing: bool,
     position: u64,
     duration: u64,
+    volume: f64,
     progress_scale: gtk::Scale,
     time_label: gtk::Label,
     seek_handler_id: Option<glib::SignalHandlerId>,
     is_seeking: Rc<Cell<bool>>,
     /// 正在进行的 seek 目标位置（毫秒），用于 seek 完成检测
     seek_target: Option<u64>,
+    quality_dropdown: gtk::DropDown,
+    quality_handler_id: Option<glib::SignalHandlerId>,
+    volume_scale: gtk::Scale,
+    fullscreen: Option<FullscreenVideo>,
     simi_list: Controller<MvList>,
-    comments: FactoryVecDeque<CommentRow>,
+    comments: Controller<CommentsPage>,
 }
 
 #[derive(Debug)]
@@ -42,20 +56,37 @@ pub enum MvPlayerMsg {
     Tick,
     TogglePlay,
     Seek(u64),
+    /// 方向键快进/快退（毫秒）
+    SeekBy(i64),
+    SetVolume(f64),
+    /// 方向键调节音量
+    ChangeVolume(f64),
+    /// 清晰度下拉框的选中项，下标对应 `detail.resolutions`
+    SelectResolution(u32),
+    ToggleFullscreen,
+    /// 全屏窗口已关闭（Esc、按钮或窗口管理器）
+    FullscreenClosed,
+    /// 转到画中画窗口继续播放
+    OpenPip,
     SimiMvClicked(u64),
     ArtistClicked,
-    /// 占位消息（评论行无输出）
-    Noop,
+    /// 评论区内的跳转（例如点击评论者头像）
+    CommentsNavigate(AppRoute),
 }
 
 #[derive(Debug)]
 pub enum MvPlayerCmdMsg {
     Loaded {
         id: u64,
-        url: String,
+        /// 播放地址与分辨率；接管画中画的管线时为空，不重新载入
+        url: Option<(String, u32)>,
         detail: MvDetail,
         simi: Vec<Mv>,
-        comments: Vec<crate::api::Comment>,
+    },
+    ResolutionLoaded {
+        id: u64,
+        resolution: u32,
+        url: String,
     },
     LoadFailed(String),
 }
@@ -64,11 +95,14 @@ pub enum MvPlayerCmdMsg {
 pub enum MvPlayerOutput {
     Navigate(AppRoute),
     ShowToast(String),
+    /// 在画中画窗口中继续播放这条管线
+    OpenPip(Rc<MvPlayback>),
 }
 
 #[relm4::component(pub)]
 impl Component for MvPlayerPage {
-    type Init = u64;
+    /// MV id，以及播放用的管线（可能接管自画中画或上一个 MV 页，正在播放别的 MV）
+    type Init = (u64, Rc<MvPlayback>);
     type Input = MvPlayerMsg;
     type Output = MvPlayerOutput;
     type CommandOutput = MvPlayerCmdMsg;
@@ -101,6 +135,15 @@ impl Component for MvPlayerPage {
                         set_halign: gtk::Align::Fill,
                         set_valign: gtk::Align::Fill,
                         add_css_class: "mv-video",
+
+                        // 双击进入全屏
+                        add_controller = gtk::GestureClick {
+                            connect_pressed[sender] => move |_, n_press, _, _| {
+                                if n_press == 2 {
+                                    sender.input(MvPlayerMsg::ToggleFullscreen);
+                                }
+                            }
+                        },
                     },
 
                     gtk::Box {
@@ -136,31 +179,57 @@ impl Component for MvPlayerPage {
                             add_css_class: "caption",
                             add_css_class: "dim-label",
                         },
-                    },
-
-                    // 评论紧贴进度条下方
-                    gtk::Box {
-                        set_orientation: gtk::Orientation::Vertical,
-                        set_vexpand: true,
 
-                        gtk::Label {
-                            set_label: "评论",
-                            add_css_class: "heading",
-                            set_halign: gtk::Align::Start,
-                            set_margin_top: 8,
-                            set_margin_bottom: 4,
+                        #[name(quality_dropdown)]
+                        gtk::DropDown {
+                            set_visible: false,
+                            set_valign: gtk::Align::Center,
+                            set_tooltip_text: Some("清晰度"),
                         },
 
-                        gtk::ScrolledWindow {
-                            set_vexpand: true,
-                            set_hscrollbar_policy: gtk::PolicyType::Never,
+                        gtk::Image {
+                            #[watch]
+                            set_icon_name: Some(if model.volume > 0.0 {
+                                "audio-volume-high-symbolic"
+                            } else {
+                                "audio-volume-muted-symbolic"
+                            }),
+                        },
 
-                            #[local_ref]
-                            comments_list -> gtk::ListBox {
-                                set_selection_mode: gtk::SelectionMode::None,
-                                set_show_separators: true,
+                        #[name(volume_scale)]
+                        gtk::Scale {
+                            set_orientation: gtk::Orientation::Horizontal,
+                            set_range: (0.0, 1.0),
+                            set_value: model.volume,
+                            set_draw_value: false,
+                            set_width_request: 100,
+                            set_tooltip_text: Some("音量"),
+                            connect_value_changed[sender] => move |scale| {
+                                sender.input(MvPlayerMsg::SetVolume(scale.value()));
                             },
                         },
+
+                        gtk::Button {
+                            set_icon_name: "window-new-symbolic",
+                            add_css_class: "flat",
+                            set_tooltip_text: Some("画中画"),
+                            connect_clicked => MvPlayerMsg::OpenPip,
+                        },
+
+                        gtk::Button {
+                            set_icon_name: "view-fullscreen-symbolic",
+                            add_css_class: "flat",
+                            set_tooltip_text: Some("全屏"),
+                            connect_clicked => MvPlayerMsg::ToggleFullscreen,
+                        },
+                    },
+
+                    // 评论紧贴进度条下方（复用评论页：热门/最新排序 + 无感分页）
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_vexpand: true,
+
+                        model.comments.widget(),
                     },
                 },
 
@@ -230,7 +299,7 @@ impl Component for MvPlayerPage {
     }
 
     fn init(
-        init: Self::Init,
+        (init, playback): Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -241,31 +310,21 @@ impl Component for MvPlayerPage {
                     MvRowOutput::Clicked(id) => MvPlayerMsg::SimiMvClicked(id),
                 });
 
-        let comments = FactoryVecDeque::builder()
-            .launch(gtk::ListBox::new())
-            .forward(sender.input_sender(), |_| MvPlayerMsg::Noop);
-
-        // ── GStreamer pipeline ────────────────────────────────
-        let pipeline = gst::Pipeline::new();
-        let playbin = gst::ElementFactory::make("playbin3")
-            .build()
-            .expect("playbin3 element not available");
-        pipeline.add(&playbin).expect("failed to add playbin3");
-
-        let sink = gst::ElementFactory::make("gtk4paintablesink")
-            .build()
-            .expect("gtk4paintablesink element not available");
-        let paintable: gdk::Paintable = sink.property("paintable");
-        playbin.set_property("video-sink", &sink);
+        let comments = CommentsPage::builder()
+            .launch((CommentType::Mv, init))
+            .forward(sender.input_sender(), |msg| match msg {
+                CommentsOutput::Navigate(route) => MvPlayerMsg::CommentsNavigate(route),
+            });
 
         let is_seeking = Rc::new(Cell::new(false));
 
         let mut model = Self {
             mv_id: init,
             detail: MvDetail::default(),
-            pipeline,
-            playbin,
-            is_playing: false,
+            is_playing: playback.is_playing(),
+            volume: playback.volume(),
+            playback,
+            settings: gio::Settings::new(APPLICATION_ID),
             position: 0,
             duration: 0,
             progress_scale: gtk::Scale::default(),
@@ -273,27 +332,22 @@ impl Component for MvPlayerPage {
             seek_handler_id: None,
             is_seeking: is_seeking.clone(),
             seek_target: None,
+            quality_dropdown: gtk::DropDown::default(),
+            quality_handler_id: None,
+            volume_scale: gtk::Scale::default(),
+            fullscreen: None,
             simi_list,
             comments,
         };
 
-        let comments_list = model.comments.widget();
         let widgets = view_output!();
 
-        widgets.video_picture.set_paintable(Some(&paintable));
+        widgets
+            .video_picture
+            .set_paintable(Some(model.playback.paintable()));
 
         // 位置轮询：tick 节流到 ~100ms 发一次（随 widget 销毁自动停止）
-        let tick_sender = sender.input_sender().clone();
-        let last_tick = Rc::new(Cell::new(0i64));
-        let last_tick_clone = last_tick.clone();
-        widgets.video_picture.add_tick_callback(move |_, clock| {
-            let now = clock.frame_time();
-            if now - last_tick_clone.get() >= 100_000 {
-                last_tick_clone.set(now);
-                tick_sender.emit(MvPlayerMsg::Tick);
-            }
-            gtk::glib::ControlFlow::Continue
-        });
+        add_tick_sender(&widgets.video_picture, sender.input_sender().clone());
 
         // 进度条拖动
         let scale = widgets.progress_scale.clone();
@@ -306,71 +360,68 @@ impl Component for MvPlayerPage {
             gtk::glib::Propagation::Proceed
         });
 
+        // 清晰度切换
+        let quality_sender = sender.input_sender().clone();
+        let quality_handler_id = widgets.quality_dropdown.connect_selected_notify(move |dd| {
+            quality_sender.emit(MvPlayerMsg::SelectResolution(dd.selected()));
+        });
+
         model.progress_scale = scale;
         model.time_label = widgets.time_label.clone();
         model.seek_handler_id = Some(seek_handler_id);
+        model.quality_dropdown = widgets.quality_dropdown.clone();
+        model.quality_handler_id = Some(quality_handler_id);
+        model.volume_scale = widgets.volume_scale.clone();
 
         sender.input(MvPlayerMsg::Load(init));
 
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match message {
             MvPlayerMsg::Load(id) => {
                 self.mv_id = id;
+                // 接管的管线已在播放这个 MV，只补齐页面信息
+                let reuse = self.playback.mv_id() == id;
+                let preferred = self.settings.uint(MV_RESOLUTION_KEY);
                 sender.command(move |out, _shutdown| async move {
-                    let url_fut = get_mv_url(id);
-                    let detail_fut = get_mv_detail(id);
-                    let simi_fut = get_simi_mv(id);
-                    let comments_fut = get_mv_comments(id);
-
-                    match futures::future::join4(url_fut, detail_fut, simi_fut, comments_fut).await
-                    {
-                        (Ok(url), Ok(detail), simi, comments) => {
-                            let _ = out.send(MvPlayerCmdMsg::Loaded {
-                                id,
-                                url,
-                                detail,
-                                simi: simi.unwrap_or_default(),
-                                comments: comments.unwrap_or_default(),
-                            });
-                        }
-                        (Err(e), _, _, _) => {
-                            let _ = out.send(MvPlayerCmdMsg::LoadFailed(e.to_string()));
-                        }
-                        _ => {
-                            let _ =
-                                out.send(MvPlayerCmdMsg::LoadFailed("MV 信息加载失败".to_string()));
+                    // 先拿详情：可选分辨率在详情里
+                    let (detail, simi) =
+                        futures::future::join(get_mv_detail(id), get_simi_mv(id)).await;
+                    let Ok(detail) = detail else {
+                        let _ = out.send(MvPlayerCmdMsg::LoadFailed("MV 信息加载失败".to_string()));
+                        return;
+                    };
+                    let url = if reuse {
+                        None
+                    } else {
+                        let resolution = pick_resolution(&detail.resolutions, preferred);
+                        match get_mv_url(id, resolution).await {
+                            Ok(url) => Some((url, resolution)),
+                            Err(e) => {
+                                let _ = out.send(MvPlayerCmdMsg::LoadFailed(e.to_string()));
+                                return;
+                            }
                         }
-                    }
+                    };
+                    let _ = out.send(MvPlayerCmdMsg::Loaded {
+                        id,
+                        url,
+                        detail,
+                        simi: simi.unwrap_or_default(),
+                    });
                 });
             }
             MvPlayerMsg::Tick => {
                 // 播放错误（总线消息）
-                if let Some(bus) = self.pipeline.bus() {
-                    while let Some(msg) = bus.pop() {
-                        if let gst::message::MessageView::Error(error) = msg.view() {
-                            let err = error.error();
-                            sender
-                                .output(MvPlayerOutput::ShowToast(err.message().to_string()))
-                                .ok();
-                        }
-                    }
+                for err in self.playback.poll() {
+                    sender.output(MvPlayerOutput::ShowToast(err)).ok();
                 }
 
-                // 播放状态
-                self.is_playing = self.pipeline.current_state() == gst::State::Playing;
-
-                // 位置 / 时长（毫秒）
-                self.position = self
-                    .playbin
-                    .query_position::<gst::ClockTime>()
-                    .map_or(0, |t| t.mseconds());
-                self.duration = self
-                    .playbin
-                    .query_duration::<gst::ClockTime>()
-                    .map_or(0, |t| t.mseconds());
+                self.is_playing = self.playback.is_playing();
+                self.position = self.playback.position();
+                self.duration = self.playback.duration();
 
                 // seek 完成检测：当前位置已到达目标附近
                 if self.is_seeking.get()
@@ -386,38 +437,93 @@ impl Component for MvPlayerPage {
                 }
                 // seek 期间保留用户拖动的位置，不覆盖
                 if !self.is_seeking.get() {
-                    if let Some(id) = &self.seek_handler_id {
-                        self.progress_scale.block_signal(id);
-                    }
-                    self.progress_scale.set_value(self.position as f64);
-                    if let Some(id) = &self.seek_handler_id {
-                        self.progress_scale.unblock_signal(id);
-                    }
+                    self.set_progress_value(self.position);
                 }
                 self.time_label
                     .set_label(&format_time(self.position, self.duration));
+                if let Some(fullscreen) = &self.fullscreen {
+                    fullscreen.update(self.is_playing, self.position, self.duration);
+                }
             }
             MvPlayerMsg::TogglePlay => {
-                let next = if self.is_playing {
-                    gst::State::Paused
-                } else {
-                    gst::State::Playing
-                };
-                let _ = self.pipeline.set_state(next);
-                self.is_playing = next == gst::State::Playing;
+                self.is_playing = self.playback.toggle();
             }
             MvPlayerMsg::Seek(ms) => {
+                // UI 归零问题由 seeking 保护（seek 期间不更新进度条）解决
                 self.is_seeking.set(true);
                 self.seek_target = Some(ms);
-                // FLUSH：立即清空缓冲快速生效；KEY_UNIT：跳到关键帧（不用 ACCURATE，
-                // 否则需精确解码中间帧导致远距离 seek 很慢）。
-                // UI 归零问题由 seeking 保护（seek 期间不更新进度条）解决。
-                let _ = self.playbin.seek_simple(
-                    gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT,
-                    gst::ClockTime::from_mseconds(ms),
-                );
+                self.playback.seek(ms);
+            }
+            MvPlayerMsg::SeekBy(delta_ms) => {
+                if self.duration == 0 {
+                    return;
+                }
+                let target =
+                    (self.position as i64 + delta_ms).clamp(0, self.duration as i64) as u64;
+                self.position = target;
+                self.set_progress_value(target);
+                sender.input(MvPlayerMsg::Seek(target));
+            }
+            MvPlayerMsg::SetVolume(volume) => {
+                self.volume = volume.clamp(0.0, 1.0);
+                self.playback.set_volume(self.volume);
+            }
+            MvPlayerMsg::ChangeVolume(delta) => {
+                // 经由音量条的 value-changed 回到 SetVolume
+                self.volume_scale
+                    .set_value((self.volume + delta).clamp(0.0, 1.0));
+            }
+            MvPlayerMsg::SelectResolution(index) => {
+                let Some(&resolution) = self.detail.resolutions.get(index as usize) else {
+                    return;
+                };
+                if resolution == self.playback.resolution() {
+                    return;
+                }
+                if let Err(e) = self.settings.set_uint(MV_RESOLUTION_KEY, resolution) {
+                    log::error!("保存 MV 清晰度失败: {e}");
+                }
+                let id = self.mv_id;
+                sender.command(move |out, _shutdown| async move {
+                    let msg = match get_mv_url(id, resolution).await {
+                        Ok(url) => MvPlayerCmdMsg::ResolutionLoaded {
+                            id,
+                            resolution,
+                            url,
+                        },
+                        Err(e) => MvPlayerCmdMsg::LoadFailed(e.to_string()),
+                    };
+                    let _ = out.send(msg);
+                });
+            }
+            MvPlayerMsg::ToggleFullscreen => match self.fullscreen.take() {
+                Some(fullscreen) => fullscreen.window.close(),
+                None => {
+                    let parent = root.root().and_downcast::<gtk::Window>();
+                    self.fullscreen = Some(FullscreenVideo::new(
+                        &self.playback,
+                        parent.as_ref(),
+                        sender.input_sender(),
+                    ));
+                }
+            },
+            MvPlayerMsg::FullscreenClosed => {
+                self.fullscreen = None;
+            }
+            MvPlayerMsg::OpenPip => {
+                if self.playback.mv_id() != self.mv_id {
+                    return;
+                }
+                if let Some(fullscreen) = self.fullscreen.take() {
+                    fullscreen.window.close();
+                }
+                sender
+                    .output(MvPlayerOutput::OpenPip(self.playback.clone()))
+                    .ok();
             }
             MvPlayerMsg::SimiMvClicked(id) => {
+                self.comments
+                    .emit(CommentsMsg::LoadComments(CommentType::Mv, id));
                 sender.input(MvPlayerMsg::Load(id));
             }
             MvPlayerMsg::ArtistClicked => {
@@ -427,7 +533,9 @@ impl Component for MvPlayerPage {
                         .ok();
                 }
             }
-            MvPlayerMsg::Noop => {}
+            MvPlayerMsg::CommentsNavigate(route) => {
+                sender.output(MvPlayerOutput::Navigate(route)).ok();
+            }
         }
     }
 
@@ -443,50 +551,292 @@ impl Component for MvPlayerPage {
                 url,
                 detail,
                 simi,
-                comments,
             } => {
                 self.mv_id = id;
                 self.detail = detail;
-                self.position = 0;
-                self.duration = 0;
-                self.progress_scale.set_value(0.0);
-                self.time_label.set_label("00:00 / 00:00");
 
-                // 切换 MV 时先复位，再设置新 URI 并播放
-                let _ = self.pipeline.set_state(gst::State::Null);
-                self.playbin.set_property("uri", &url);
-                let _ = self.pipeline.set_state(gst::State::Playing);
-                self.is_playing = true;
+                if let Some((url, resolution)) = url {
+                    self.position = 0;
+                    self.duration = 0;
+                    self.set_progress_value(0);
+                    self.time_label.set_label("00:00 / 00:00");
+                    self.playback.load(&self.detail, &url, resolution, 0);
+                    self.is_playing = true;
+                }
+                self.sync_quality_dropdown();
 
                 // 相关 MV（ListBox）
                 self.simi_list.emit(MvListInput::SetMvs(simi));
-
-                // 评论区（ListBox）
-                {
-                    let mut guard = self.comments.guard();
-                    guard.clear();
-                    for c in comments {
-                        guard.push_back(CommentRowInit {
-                            comment: c,
-                            song_id: id,
-                        });
-                    }
+            }
+            MvPlayerCmdMsg::ResolutionLoaded {
+                id,
+                resolution,
+                url,
+            } => {
+                if id != self.mv_id {
+                    return;
+                }
+                // 从当前位置续播，保持原来的播放/暂停状态
+                let was_playing = self.playback.is_playing();
+                let position = self.playback.position();
+                self.playback.load(&self.detail, &url, resolution, position);
+                if !was_playing {
+                    self.playback.set_playing(false);
                 }
             }
             MvPlayerCmdMsg::LoadFailed(err) => {
+                self.sync_quality_dropdown();
                 sender.output(MvPlayerOutput::ShowToast(err)).ok();
             }
         }
     }
 }
 
+impl MvPlayerPage {
+    pub fn playback(&self) -> Rc<MvPlayback> {
+        self.playback.clone()
+    }
+
+    fn set_progress_value(&self, ms: u64) {
+        if let Some(id) = &self.seek_handler_id {
+            self.progress_scale.block_signal(id);
+        }
+        self.progress_scale.set_value(ms as f64);
+        if let Some(id) = &self.seek_handler_id {
+            self.progress_scale.unblock_signal(id);
+        }
+    }
+
+    /// 按详情里的分辨率重建下拉框，选中正在播放的那一项
+    fn sync_quality_dropdown(&self) {
+        let labels: Vec<String> = self
+            .detail
+            .resolutions
+            .iter()
+            .map(|r| format!("{r}P"))
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let selected = self
+            .detail
+            .resolutions
+            .iter()
+            .position(|r| *r == self.playback.resolution())
+            .unwrap_or(0);
+
+        if let Some(id) = &self.quality_handler_id {
+            self.quality_dropdown.block_signal(id);
+        }
+        self.quality_dropdown
+            .set_model(Some(&gtk::StringList::new(&labels)));
+        self.quality_dropdown.set_selected(selected as u32);
+        if let Some(id) = &self.quality_handler_id {
+            self.quality_dropdown.unblock_signal(id);
+        }
+        self.quality_dropdown.set_visible(!labels.is_empty());
+    }
+}
+
 impl Drop for MvPlayerPage {
     fn drop(&mut self) {
-        // GStreamer 要求元素销毁前必须回到 NULL 状态，否则 dispose 时会卡死
-        let _ = self.pipeline.set_state(gst::State::Null);
+        // 管线由 MvPlayback 负责复位；画中画仍持有时继续播放
+        if let Some(fullscreen) = self.fullscreen.take() {
+            fullscreen.window.close();
+        }
+    }
+}
+
+/// 真全屏播放窗口：与页面共用 paintable，鼠标静止一段时间后隐藏控制栏和指针。
+/// 单独的顶层窗口收不到主窗口的快捷键，按键在这里直接处理
+struct FullscreenVideo {
+    window: gtk::Window,
+    play_button: gtk::Button,
+    time_label: gtk::Label,
+}
+
+impl FullscreenVideo {
+    fn new(
+        playback: &MvPlayback,
+        parent: Option<&gtk::Window>,
+        input: &relm4::Sender<MvPlayerMsg>,
+    ) -> Self {
+        let picture = gtk::Picture::for_paintable(playback.paintable());
+        picture.set_hexpand(true);
+        picture.set_vexpand(true);
+
+        let play_button = gtk::Button::from_icon_name("media-playback-pause-symbolic");
+        play_button.add_css_class("flat");
+        play_button.set_tooltip_text(Some("播放/暂停"));
+        let exit_button = gtk::Button::from_icon_name("view-restore-symbolic");
+        exit_button.add_css_class("flat");
+        exit_button.set_tooltip_text(Some("退出全屏"));
+        let time_label = gtk::Label::new(Some("00:00 / 00:00"));
+        time_label.add_css_class("caption");
+
+        let bar = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        bar.add_css_class("osd");
+        bar.add_css_class("toolbar");
+        bar.append(&play_button);
+        bar.append(&time_label);
+        bar.append(&exit_button);
+
+        let revealer = gtk::Revealer::builder()
+            .transition_type(gtk::RevealerTransitionType::Crossfade)
+            .halign(gtk::Align::Center)
+            .valign(gtk::Align::End)
+            .margin_bottom(32)
+            .child(&bar)
+            .build();
+
+        let overlay = gtk::Overlay::new();
+        overlay.set_child(Some(&picture));
+        overlay.add_overlay(&revealer);
+
+        let window = gtk::Window::builder()
+            .title(playback.title())
+            .child(&overlay)
+            .build();
+        window.add_css_class("mv-fullscreen");
+        window.set_transient_for(parent);
+
+        {
+            let input = input.clone();
+            play_button.connect_clicked(move |_| input.emit(MvPlayerMsg::TogglePlay));
+        }
+        {
+            let input = input.clone();
+            exit_button.connect_clicked(move |_| input.emit(MvPlayerMsg::ToggleFullscreen));
+        }
+        {
+            let input = input.clone();
+            window.connect_close_request(move |_| {
+                input.emit(MvPlayerMsg::FullscreenClosed);
+                glib::Propagation::Proceed
+            });
+        }
+
+        // 双击退出全屏
+        let click = gtk::GestureClick::new();
+        {
+            let input = input.clone();
+            click.connect_pressed(move |_, n_press, _, _| {
+                if n_press == 2 {
+                    input.emit(MvPlayerMsg::ToggleFullscreen);
+                }
+            });
+        }
+        picture.add_controller(click);
+
+        // 捕获阶段处理，避免空格同时触发获得焦点的按钮
+        let keys = gtk::EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        {
+            let input = input.clone();
+            keys.connect_key_pressed(move |_, key, _, _| {
+                let msg = match key {
+                    gdk::Key::Escape | gdk::Key::F11 | gdk::Key::f => MvPlayerMsg::ToggleFullscreen,
+                    gdk::Key::space => MvPlayerMsg::TogglePlay,
+                    gdk::Key::Left => MvPlayerMsg::SeekBy(-SEEK_STEP_MS),
+                    gdk::Key::Right => MvPlayerMsg::SeekBy(SEEK_STEP_MS),
+                    gdk::Key::Up => MvPlayerMsg::ChangeVolume(VOLUME_STEP),
+                    gdk::Key::Down => MvPlayerMsg::ChangeVolume(-VOLUME_STEP),
+                    _ => return glib::Propagation::Proceed,
+                };
+                input.emit(msg);
+                glib::Propagation::Stop
+            });
+        }
+        window.add_controller(keys);
+
+        // 鼠标移动时显示控制栏，静止后隐藏
+        let hide_timer: Rc<RefCell<Option<glib::SourceId>>> = Rc::default();
+        let reveal = {
+            let window = window.downgrade();
+            let revealer = revealer.downgrade();
+            move || {
+                let (Some(window), Some(revealer)) = (window.upgrade(), revealer.upgrade()) else {
+                    return;
+                };
+                revealer.set_reveal_child(true);
+                window.set_cursor(None);
+                if let Some(source) = hide_timer.borrow_mut().take() {
+                    source.remove();
+                }
+                let timer = hide_timer.clone();
+                let source = glib::timeout_add_local_once(
+                    CONTROLS_HIDE_DELAY,
+                    glib::clone!(
+                        #[weak]
+                        window,
+                        #[weak]
+                        revealer,
+                        move || {
+                            timer.borrow_mut().take();
+                            revealer.set_reveal_child(false);
+                            window.set_cursor_from_name(Some("none"));
+                        }
+                    ),
+                );
+                *hide_timer.borrow_mut() = Some(source);
+            }
+        };
+        reveal();
+        let motion = gtk::EventControllerMotion::new();
+        // 隐藏指针等也会产生原地的 motion 事件，只认真正的移动
+        let last_pointer = Cell::new((f64::NAN, f64::NAN));
+        motion.connect_motion(move |_, x, y| {
+            let (last_x, last_y) = last_pointer.replace((x, y));
+            if (x - last_x).abs() > 1.0 || (y - last_y).abs() > 1.0 || last_x.is_nan() {
+                reveal();
+            }
+        });
+        window.add_controller(motion);
+
+        // 主窗口被遮住时可能不再出帧，全屏窗口自己驱动进度刷新
+        add_tick_sender(&picture, input.clone());
+
+        window.fullscreen();
+        window.present();
+
+        Self {
+            window,
+            play_button,
+            time_label,
+        }
+    }
+
+    fn update(&self, is_playing: bool, position: u64, duration: u64) {
+        self.play_button.set_icon_name(if is_playing {
+            "media-playback-pause-symbolic"
+        } else {
+            "media-playback-start-symbolic"
+        });
+        self.time_label.set_label(&format_time(position, duration));
     }
 }
 
+/// 每 ~100ms 发一次 Tick（随 widget 销毁自动停止）
+fn add_tick_sender(widget: &impl IsA<gtk::Widget>, sender: relm4::Sender<MvPlayerMsg>) {
+    let last_tick = Cell::new(0i64);
+    widget.add_tick_callback(move |_, clock| {
+        let now = clock.frame_time();
+        if now - last_tick.get() >= 100_000 {
+            last_tick.set(now);
+            sender.emit(MvPlayerMsg::Tick);
+        }
+        gtk::glib::ControlFlow::Continue
+    });
+}
+
+/// 不超过偏好值的最高分辨率；都高于偏好时取最低的。resolutions 从高到低
+fn pick_resolution(resolutions: &[u32], preferred: u32) -> u32 {
+    resolutions
+        .iter()
+        .copied()
+        .find(|r| *r <= preferred)
+        .or(resolutions.last().copied())
+        .unwrap_or(preferred)
+}
+
 fn format_play_count(count: u64) -> String {
     if count >= 100_000_000 {
         format!("{:.1} 亿次播放", count as f64 / 100_000_000.0)
@@ -504,3 +854,17 @@ fn format_time(position: u64, duration: u64) -> String {
     }
     format!("{} / {}", fmt(position), fmt(duration))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::pick_resolution;
+
+    #[test]
+    fn resolution_falls_back_to_nearest_lower() {
+        assert_eq!(pick_resolution(&[1080, 720, 480], 1080), 1080);
+        assert_eq!(pick_resolution(&[1080, 720, 480], 900), 720);
+        assert_eq!(pick_resolution(&[720, 480], 240), 480);
+        // 详情里没有分辨率时按偏好请求
+        assert_eq!(pick_resolution(&[], 720), 720);
+    }
+}
@@ -0,0 +1,141 @@
+//! 切歌时的桌面通知
+//!
+//! 订阅 `PlayerEventBus`，在 `TrackChanged` 时发送带封面的 `gio::Notification`，
+//! 通知上的 上一首 / 下一首 / 喜欢 按钮通过 `app.*` 动作转回 `PlayerCommand`。
+//! 主窗口处于焦点时不打扰；可在设置中关闭。
+
+use flume::{Receiver, Sender};
+use relm4::adw;
+use relm4::gtk::gio::prelude::{ActionMapExtManual, ApplicationExt, SettingsExt};
+use relm4::gtk::glib::prelude::ToVariant;
+use relm4::gtk::prelude::GtkWindowExt;
+use relm4::gtk::{gio, glib};
+use tokio_util::sync::CancellationToken;
+
+use crate::APPLICATION_ID;
+use crate::api::Song;
+use crate::player::messages::{PlayerCommand, PlayerEvent};
+use crate::ui::components::image::image_manager::ImageManager;
+
+/// 同一个 id 的通知会互相替换，避免切歌时堆满通知中心
+const NOTIFICATION_ID: &str = "track-changed";
+const SETTINGS_KEY: &str = "track-notifications";
+
+/// 注册通知按钮用到的应用动作，并开始监听播放器事件
+pub fn start(
+    app: &adw::Application,
+    window: &adw::ApplicationWindow,
+    events: Receiver<PlayerEvent>,
+    cmd_tx: Sender<PlayerCommand>,
+) {
+    register_actions(app, cmd_tx);
+
+    let app = app.clone();
+    let window = window.clone();
+    let settings = gio::Settings::new(APPLICATION_ID);
+    glib::MainContext::default().spawn_local(async move {
+        while let Ok(event) = events.recv_async().await {
+            match event {
+                PlayerEvent::TrackChanged { song, is_liked, .. } => {
+                    if !settings.boolean(SETTINGS_KEY) || window.is_active() {
+                        continue;
+                    }
+                    let icon = fetch_cover(&song.cover_url).await;
+                    // 封面下载期间窗口可能已获得焦点
+                    if window.is_active() {
+                        continue;
+                    }
+                    let notification = build_notification(&song, is_liked, icon);
+                    app.send_notification(Some(NOTIFICATION_ID), &notification);
+                }
+                PlayerEvent::EndOfQueue => app.withdraw_notification(NOTIFICATION_ID),
+                _ => {}
+            }
+        }
+    });
+}
+
+fn register_actions(app: &adw::Application, cmd_tx: Sender<PlayerCommand>) {
+    let previous = gio::ActionEntry::builder("notification-previous")
+        .activate({
+            let cmd_tx = cmd_tx.clone();
+            move |_: &adw::Application, _, _| {
+                let _ = cmd_tx.send(PlayerCommand::Previous);
+            }
+        })
+        .build();
+    let next = gio::ActionEntry::builder("notification-next")
+        .activate({
+            let cmd_tx = cmd_tx.clone();
+            move |_: &adw::Application, _, _| {
+                let _ = cmd_tx.send(PlayerCommand::Next);
+            }
+        })
+        .build();
+    // 参数为 (歌曲 id, 目标喜欢状态)
+    let like = gio::ActionEntry::builder("notification-like")
+        .parameter_type(Some(glib::VariantTy::new("(tb)").unwrap()))
+        .activate(move |_: &adw::Application, _, param| {
+            if let Some((song_id, liked)) = param.and_then(|p| p.get::<(u64, bool)>()) {
+                let _ = cmd_tx.send(PlayerCommand::LikeSong { song_id, liked });
+            }
+        })
+        .build();
+    app.add_action_entries([previous, next, like]);
+}
+
+fn build_notification(
+    song: &Song,
+    is_liked: bool,
+    icon: Option<gio::BytesIcon>,
+) -> gio::Notification {
+    let artists = song
+        .artists
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let body = if song.album.name.is_empty() {
+        artists
+    } else {
+        format!("{} · {}", artists, song.album.name)
+    };
+
+    let notification = gio::Notification::new(&song.name);
+    notification.set_body(Some(&body));
+    notification.set_category(Some("x-gnome.music"));
+    notification.set_priority(gio::NotificationPriority::Low);
+    if let Some(icon) = icon {
+        notification.set_icon(&icon);
+    }
+    notification.add_button("上一首", "app.notification-previous");
+    notification.add_button("下一首", "app.notification-next");
+    notification.add_button_with_target_value(
+        if is_liked { "取消喜欢" } else { "喜欢" },
+        "app.notification-like",
+        Some(&(song.id, !is_liked).to_variant()),
+    );
+    notification
+}
+
+async fn fetch_cover(cover_url: &str) -> Option<gio::BytesIcon> {
+    if cover_url.is_empty() {
+        return None;
+    }
+    let url = format!("{}?param=128y128", cover_url);
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let res = ImageManager::global()
+            .fetch(url, CancellationToken::new())
+            .await;
+        let _ = sender.send(res);
+    });
+    match receiver.await {
+        Ok(Ok(bytes)) => Some(gio::BytesIcon::new(&glib::Bytes::from_owned(bytes))),
+        Ok(Err(e)) => {
+            log::error!("通知封面加载失败: {:?}", e);
+            None
+        }
+        Err(_) => None,
+    }
+}
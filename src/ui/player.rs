@@ -1,28 +1,49 @@
 use std::sync::Arc;
 
 use relm4::gtk::Orientation;
+use relm4::gtk::gio::{self, prelude::SettingsExt};
 use relm4::gtk::{self, glib, prelude::*};
 use relm4::prelude::*;
 
-use crate::api::{Artist, Playlist, Song};
+use crate::APPLICATION_ID;
+use crate::api::{Artist, CommentType, MvDetail, Playlist, Song};
 use crate::player::PlayMode;
+use crate::player::messages::{NowPlaying, OutputDeviceList, SleepTimerMode, SleepTimerStatus};
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::spectrum::SpectrumView;
 use crate::ui::model::PlaylistType;
 use crate::ui::route::AppRoute;
+use crate::utils::share;
 
 #[tracker::track]
 pub struct PlayerPage {
     song: Song,
+    /// 正在播放的 MV，期间封面和标题显示 MV 的信息
+    #[no_eq]
+    mv: Option<MvDetail>,
     is_playing: bool,
     is_liked: bool,
     playlist: Arc<Playlist>,
     position: u64,
+    duration: u64,
     volume: f64,
     play_mode: PlayMode,
     loop_enabled: bool,
+    sleep_status: Option<SleepTimerStatus>,
     #[do_not_track]
     progress_scale: gtk::Scale,
     #[do_not_track]
+    sleep_popover: gtk::Popover,
+    #[do_not_track]
+    sleep_tracks_spin: gtk::SpinButton,
+    #[do_not_track]
+    spectrum: SpectrumView,
+    #[do_not_track]
+    output_popover: gtk::Popover,
+    /// 输出设备弹出框中的选项列表，设备变化时重建
+    #[do_not_track]
+    output_list: gtk::Box,
+    #[do_not_track]
     seek_handler_id: Option<glib::SignalHandlerId>,
 }
 
@@ -40,11 +61,19 @@ pub enum PlayerPageOutput {
     OpenArtistDialog(Vec<Artist>),
     ToggleLike(u64, bool),
     CollectSong(u64),
+    ShowToast(String),
+    SetSleepTimer {
+        mode: SleepTimerMode,
+        fade_secs: u32,
+    },
+    CancelSleepTimer,
+    SetOutputDevice(Option<String>),
 }
 
 #[derive(Debug)]
 pub enum PlayerPageMsg {
     UpdateTrack(Song),
+    NowPlayingChanged(NowPlaying),
     UpdatePlayback(bool),
     SetQueue {
         tracks: Arc<Vec<Song>>,
@@ -68,7 +97,18 @@ pub enum PlayerPageMsg {
     PlaylistClicked,
     CollectClicked,
     CommentClicked,
+    CopyShareLink,
+    SetSleepTimer(SleepTimerMode),
+    /// 按数量定时：数量取自弹出框里的输入框
+    SetSleepTimerTracks,
+    CancelSleepTimer,
+    SleepTimerChanged(Option<SleepTimerStatus>),
+    OutputDevicesChanged(OutputDeviceList),
+    SelectOutputDevice(Option<String>),
+    Spectrum(Arc<Vec<f32>>),
     SetLiked(bool),
+    /// 播放器改变了播放模式（进入 / 退出心动模式）
+    SetPlayMode(PlayMode),
     Navigate(AppRoute),
 }
 
@@ -139,12 +179,19 @@ impl Component for PlayerPage {
                 set_width_request: 320,
                 set_height_request: 320,
                 set_corner_radius: 32.0,
-                #[track = "model.changed(PlayerPage::song())"]
-                set_url: format!("{}?param=1000y1000", model.song.cover_url.clone()),
+                #[track = "model.changed(PlayerPage::song() | PlayerPage::mv())"]
+                set_url: format!("{}?param=1000y1000", model.cover_url()),
                 set_placeholder_icon: "folder-music-symbolic",
                 add_css_class: "card",
             },
 
+            // 频谱（设置中关闭时隐藏）
+            #[local_ref]
+            spectrum_widget -> gtk::DrawingArea {
+                set_width_request: 320,
+                set_hexpand: false,
+            },
+
             // ================= 3. 歌手和专辑信息 =================
             gtk::Box {
                 set_orientation: Orientation::Vertical,
@@ -152,8 +199,8 @@ impl Component for PlayerPage {
                 set_halign: gtk::Align::Center,
 
                 gtk::Label {
-                    #[track = "model.changed(PlayerPage::song())"]
-                    set_label: &model.song.name,
+                    #[track = "model.changed(PlayerPage::song() | PlayerPage::mv())"]
+                    set_label: model.title(),
                     add_css_class: "title-1",
                     set_ellipsize: gtk::pango::EllipsizeMode::End,
                     set_width_chars: 20,
@@ -163,14 +210,14 @@ impl Component for PlayerPage {
                     set_orientation: Orientation::Vertical,
                     set_align: gtk::Align::Center,
                     gtk::Label {
-                        #[track = "model.changed(PlayerPage::song())"]
-                        set_label: &model.song.artists.iter().take(2).map(|artist| artist.name.clone()).collect::<Vec<_>>().join(" / "),
+                        #[track = "model.changed(PlayerPage::song() | PlayerPage::mv())"]
+                        set_label: &model.artists().iter().take(2).map(|artist| artist.name.clone()).collect::<Vec<_>>().join(" / "),
                         set_ellipsize: gtk::pango::EllipsizeMode::End,
                         set_max_width_chars: 20,
                     },
                     gtk::Label {
-                        #[track = "model.changed(PlayerPage::song())"]
-                        set_label: &model.song.album.name,
+                        #[track = "model.changed(PlayerPage::song() | PlayerPage::mv())"]
+                        set_label: if model.mv.is_some() { "MV" } else { &model.song.album.name },
                         set_ellipsize: gtk::pango::EllipsizeMode::End,
                         set_max_width_chars: 20,
                     },
@@ -229,6 +276,134 @@ impl Component for PlayerPage {
                     add_css_class: "flat",
                     set_tooltip_text: Some("Collect to playlist"),
                     connect_clicked => PlayerPageMsg::CollectClicked,
+                },
+
+                // 复制分享链接
+                gtk::Button {
+                    set_icon_name: "edit-copy-symbolic",
+                    add_css_class: "flat",
+                    set_tooltip_text: Some("复制分享链接"),
+                    connect_clicked => PlayerPageMsg::CopyShareLink,
+                },
+
+                // 定时关闭：有定时时在图标旁显示倒计时
+                gtk::MenuButton {
+                    add_css_class: "flat",
+                    set_tooltip_text: Some("定时关闭"),
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_spacing: 4,
+                        gtk::Image {
+                            set_icon_name: Some("alarm-symbolic"),
+                        },
+                        gtk::Label {
+                            #[track = "model.changed(PlayerPage::sleep_status())"]
+                            set_label: &model.sleep_status.map(sleep_status_text).unwrap_or_default(),
+                            #[track = "model.changed(PlayerPage::sleep_status())"]
+                            set_visible: model.sleep_status.is_some(),
+                            add_css_class: "caption",
+                            add_css_class: "numeric",
+                        },
+                    },
+                    #[wrap(Some)]
+                    set_popover: sleep_popover = &gtk::Popover {
+                        gtk::Box {
+                            set_orientation: Orientation::Vertical,
+                            set_spacing: 2,
+                            set_margin_all: 6,
+
+                            gtk::Label {
+                                set_label: "定时关闭",
+                                add_css_class: "heading",
+                                set_margin_bottom: 4,
+                            },
+                            gtk::Button {
+                                set_label: "15 分钟",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::Minutes(15)),
+                            },
+                            gtk::Button {
+                                set_label: "30 分钟",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::Minutes(30)),
+                            },
+                            gtk::Button {
+                                set_label: "45 分钟",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::Minutes(45)),
+                            },
+                            gtk::Button {
+                                set_label: "60 分钟",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::Minutes(60)),
+                            },
+                            gtk::Button {
+                                set_label: "90 分钟",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::Minutes(90)),
+                            },
+                            gtk::Button {
+                                set_label: "播完当前歌曲",
+                                add_css_class: "flat",
+                                connect_clicked => PlayerPageMsg::SetSleepTimer(SleepTimerMode::EndOfTrack),
+                            },
+                            gtk::Box {
+                                set_spacing: 6,
+                                set_margin_all: 4,
+
+                                gtk::Label {
+                                    set_label: "播完",
+                                },
+                                #[name(sleep_tracks_spin)]
+                                gtk::SpinButton::with_range(1.0, 50.0, 1.0) {
+                                    set_value: 3.0,
+                                },
+                                gtk::Label {
+                                    set_label: "首",
+                                    set_hexpand: true,
+                                    set_halign: gtk::Align::Start,
+                                },
+                                gtk::Button {
+                                    set_label: "开始",
+                                    connect_clicked => PlayerPageMsg::SetSleepTimerTracks,
+                                },
+                            },
+                            gtk::Button {
+                                set_label: "取消定时",
+                                add_css_class: "flat",
+                                add_css_class: "error",
+                                #[track = "model.changed(PlayerPage::sleep_status())"]
+                                set_visible: model.sleep_status.is_some(),
+                                connect_clicked => PlayerPageMsg::CancelSleepTimer,
+                            },
+                        }
+                    },
+                },
+
+                // 输出设备
+                gtk::MenuButton {
+                    set_icon_name: "audio-speakers-symbolic",
+                    add_css_class: "flat",
+                    set_tooltip_text: Some("输出设备"),
+                    #[wrap(Some)]
+                    set_popover: output_popover = &gtk::Popover {
+                        gtk::Box {
+                            set_orientation: Orientation::Vertical,
+                            set_spacing: 2,
+                            set_margin_all: 6,
+
+                            gtk::Label {
+                                set_label: "输出设备",
+                                add_css_class: "heading",
+                                set_margin_bottom: 4,
+                            },
+                            #[name(output_list)]
+                            gtk::Box {
+                                set_orientation: Orientation::Vertical,
+                                set_spacing: 2,
+                            },
+                        }
+                    },
                 }
             },
 
@@ -270,8 +445,8 @@ impl Component for PlayerPage {
 
                     #[wrap(Some)]
                     set_end_widget = &gtk::Label {
-                        #[track = "model.changed(PlayerPage::song())"]
-                        set_label: &format_time(model.song.duration),
+                        #[track = "model.changed(PlayerPage::duration())"]
+                        set_label: &format_time(model.duration),
                         add_css_class: "caption",
                         add_css_class: "dim-label",
                     }
@@ -288,19 +463,22 @@ impl Component for PlayerPage {
                 set_halign: gtk::Align::Center,
                 set_margin_top: 8,
 
-                // 播放模式（左）：顺序→单曲循环→随机，3态切换
+                // 播放模式（左）：顺序→单曲循环→随机→心动模式（仅歌单），循环切换
                 gtk::Button {
                     #[track = "model.changed(PlayerPage::play_mode())"]
                     set_icon_name: match model.play_mode {
                         PlayMode::Sequential => "media-playlist-consecutive-symbolic",
                         PlayMode::SingleLoop => "media-playlist-repeat-song-symbolic",
-                        PlayMode::Shuffle => "media-playlist-shuffle-symbolic"
+                        PlayMode::Shuffle => "media-playlist-shuffle-symbolic",
+                        PlayMode::Intelligence => "heart-filled",
                     },
                     add_css_class: "flat",
+                    #[track = "model.changed(PlayerPage::play_mode())"]
                     set_tooltip_text: Some(match model.play_mode {
                         PlayMode::Sequential => "顺序播放",
                         PlayMode::SingleLoop => "单曲循环",
                         PlayMode::Shuffle => "随机播放",
+                        PlayMode::Intelligence => "心动模式",
                     }),
                     set_size_request: (36, 36),
                     connect_clicked => PlayerPageMsg::ToggleMode,
@@ -356,18 +534,32 @@ impl Component for PlayerPage {
     ) -> ComponentParts<Self> {
         let mut model = Self {
             song: Song::default(),
+            mv: None,
             is_playing: false,
             is_liked: false,
             position: 0,
+            duration: 0,
             volume: 0.8,
             play_mode: PlayMode::Sequential,
             loop_enabled: true,
+            sleep_status: None,
             tracker: 0,
             playlist: Arc::new(Playlist::default()),
             progress_scale: gtk::Scale::default(), // 临时占位
             seek_handler_id: None,
+            sleep_popover: gtk::Popover::default(),
+            sleep_tracks_spin: gtk::SpinButton::default(),
+            output_popover: gtk::Popover::default(),
+            output_list: gtk::Box::default(),
+            spectrum: SpectrumView::new(40),
         };
+        let spectrum_widget = model.spectrum.widget();
         let widgets = view_output!();
+        model.sleep_popover = widgets.sleep_popover.clone();
+        model.sleep_tracks_spin = widgets.sleep_tracks_spin.clone();
+        model.output_popover = widgets.output_popover.clone();
+        model.output_list = widgets.output_list.clone();
+        model.rebuild_output_list(&OutputDeviceList::default(), &sender);
 
         // 绑定进度条信号，存储信号 ID 用于后续阻塞
         let scale = widgets.progress_scale.clone();
@@ -382,17 +574,40 @@ impl Component for PlayerPage {
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         self.reset();
         match message {
             PlayerPageMsg::UpdateTrack(song) => {
+                self.spectrum.clear();
+                if self.mv.is_none() {
+                    self.set_duration(song.duration);
+                }
                 self.set_song(song);
             }
+            PlayerPageMsg::NowPlayingChanged(now_playing) => {
+                self.spectrum.clear();
+                match now_playing {
+                    NowPlaying::Music => {
+                        self.set_duration(self.song.duration);
+                        self.set_mv(None);
+                    }
+                    NowPlaying::Mv(detail) => self.set_mv(Some(detail)),
+                }
+            }
             PlayerPageMsg::UpdatePlayback(is_playing) => {
+                if !is_playing {
+                    self.spectrum.clear();
+                }
                 self.set_is_playing(is_playing);
             }
+            PlayerPageMsg::Spectrum(bands) => {
+                self.spectrum.push(&bands);
+            }
             PlayerPageMsg::UpdateProgress { position, duration } => {
                 self.set_position(position);
+                if duration > 0 && duration != self.duration {
+                    self.set_duration(duration);
+                }
                 // 阻塞信号，避免程序设置值时触发 Seek 消息
                 if let Some(handler_id) = &self.seek_handler_id {
                     self.progress_scale.block_signal(handler_id);
@@ -432,7 +647,9 @@ impl Component for PlayerPage {
                 let next = match self.play_mode {
                     PlayMode::Sequential => PlayMode::SingleLoop,
                     PlayMode::SingleLoop => PlayMode::Shuffle,
-                    PlayMode::Shuffle => PlayMode::Sequential,
+                    // 心动模式只能以「我喜欢的音乐」为来源
+                    PlayMode::Shuffle if self.playlist.liked_songs => PlayMode::Intelligence,
+                    PlayMode::Shuffle | PlayMode::Intelligence => PlayMode::Sequential,
                 };
                 self.set_play_mode(next);
                 sender.output(PlayerPageOutput::SetMode(next)).unwrap();
@@ -450,9 +667,7 @@ impl Component for PlayerPage {
             }
             PlayerPageMsg::ArtistClicked => {
                 sender
-                    .output(PlayerPageOutput::OpenArtistDialog(
-                        self.song.artists.clone(),
-                    ))
+                    .output(PlayerPageOutput::OpenArtistDialog(self.artists().to_vec()))
                     .unwrap();
             }
             PlayerPageMsg::AlbumClicked => {
@@ -476,12 +691,55 @@ impl Component for PlayerPage {
             }
             PlayerPageMsg::CommentClicked => {
                 sender
-                    .output(PlayerPageOutput::Navigate(AppRoute::Comments(self.song.id)))
+                    .output(PlayerPageOutput::Navigate(AppRoute::Comments(
+                        CommentType::Song,
+                        self.song.id,
+                    )))
+                    .unwrap();
+            }
+            PlayerPageMsg::CopyShareLink => {
+                if self.song.id == 0 {
+                    return;
+                }
+                root.clipboard()
+                    .set_text(&share::song_share_url(&self.song));
+                sender
+                    .output(PlayerPageOutput::ShowToast("已复制分享链接".to_string()))
+                    .unwrap();
+            }
+            PlayerPageMsg::SetSleepTimer(mode) => {
+                self.sleep_popover.popdown();
+                let fade_secs = gio::Settings::new(APPLICATION_ID).uint("sleep-fade-seconds");
+                sender
+                    .output(PlayerPageOutput::SetSleepTimer { mode, fade_secs })
+                    .unwrap();
+            }
+            PlayerPageMsg::SetSleepTimerTracks => {
+                let count = self.sleep_tracks_spin.value_as_int().max(1) as u32;
+                sender.input(PlayerPageMsg::SetSleepTimer(SleepTimerMode::Tracks(count)));
+            }
+            PlayerPageMsg::CancelSleepTimer => {
+                self.sleep_popover.popdown();
+                sender.output(PlayerPageOutput::CancelSleepTimer).unwrap();
+            }
+            PlayerPageMsg::SleepTimerChanged(status) => {
+                self.set_sleep_status(status);
+            }
+            PlayerPageMsg::OutputDevicesChanged(list) => {
+                self.rebuild_output_list(&list, &sender);
+            }
+            PlayerPageMsg::SelectOutputDevice(name) => {
+                self.output_popover.popdown();
+                sender
+                    .output(PlayerPageOutput::SetOutputDevice(name))
                     .unwrap();
             }
             PlayerPageMsg::SetLiked(liked) => {
                 self.set_is_liked(liked);
             }
+            PlayerPageMsg::SetPlayMode(mode) => {
+                self.set_play_mode(mode);
+            }
             PlayerPageMsg::Navigate(app_route) => {
                 sender
                     .output(PlayerPageOutput::Navigate(app_route))
@@ -491,6 +749,45 @@ impl Component for PlayerPage {
     }
 }
 
+impl PlayerPage {
+    fn title(&self) -> &str {
+        self.mv.as_ref().map_or(&self.song.name, |mv| &mv.name)
+    }
+
+    fn artists(&self) -> &[Artist] {
+        self.mv
+            .as_ref()
+            .map_or(&self.song.artists, |mv| &mv.artists)
+    }
+
+    fn cover_url(&self) -> &str {
+        self.mv
+            .as_ref()
+            .map_or(&self.song.cover_url, |mv| &mv.cover)
+    }
+
+    /// 每个设备一个单选按钮，当前选择的设备处于选中状态
+    fn rebuild_output_list(&self, list: &OutputDeviceList, sender: &ComponentSender<Self>) {
+        while let Some(child) = self.output_list.first_child() {
+            self.output_list.remove(&child);
+        }
+        let mut group: Option<gtk::CheckButton> = None;
+        for (name, label) in list.choices() {
+            let button = gtk::CheckButton::with_label(&label);
+            button.set_group(group.as_ref());
+            button.set_active(name == list.selected);
+            let input = sender.input_sender().clone();
+            button.connect_toggled(move |button| {
+                if button.is_active() {
+                    input.emit(PlayerPageMsg::SelectOutputDevice(name.clone()));
+                }
+            });
+            self.output_list.append(&button);
+            group.get_or_insert(button);
+        }
+    }
+}
+
 // 辅助函数：将毫秒格式化为 mm:ss
 fn format_time(ms: u64) -> String {
     let total_sec = ms / 1000;
@@ -498,3 +795,15 @@ fn format_time(ms: u64) -> String {
     let secs = total_sec % 60;
     format!("{}:{:02}", mins, secs)
 }
+
+/// 倒计时文字：`12:34`、`剩 2 首`，淡出中显示 `淡出中`
+fn sleep_status_text(status: SleepTimerStatus) -> String {
+    if status.fading {
+        return "淡出中".to_string();
+    }
+    match (status.remaining_secs, status.tracks_left) {
+        (Some(secs), _) => format!("{}:{:02}", secs / 60, secs % 60),
+        (None, Some(left)) => format!("剩 {} 首", left),
+        (None, None) => String::new(),
+    }
+}
@@ -2,19 +2,30 @@ use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use futures::FutureExt;
 use log::trace;
+use rand::Rng;
+use relm4::adw;
+use relm4::adw::prelude::{AdwDialogExt, AlertDialogExt};
 use relm4::gtk::prelude::{AdjustmentExt, BoxExt, ButtonExt, OrientableExt, WidgetExt};
 use relm4::{ComponentParts, ComponentSender, gtk, prelude::*, typed_view::list::TypedListView};
 
+use crate::api::cache::{self, key, ttl};
 use crate::api::{
-    PlaylistDetail as PlaylistDetailModel, Song, album_subscribe, get_album_detail,
+    CommentType, PlaylistDetail as PlaylistDetailModel, Song, album_subscribe, get_album_detail,
     get_home_category_daily_song_list, get_playlist_detail, get_playlist_track_all,
-    get_recommend_song, playlist_subscribe,
+    get_recommend_song, get_song_detail, playlist_delete, playlist_subscribe,
 };
 use crate::db::{CollectType, Db};
 use crate::ui::components::image::AsyncImage;
+use crate::ui::components::playlist_edit_dialog::{
+    PlaylistEditDialog, PlaylistEditInit, PlaylistEditOutput,
+};
+use crate::ui::components::track_editor::{TrackEditorDialog, TrackEditorInit, TrackEditorOutput};
 use crate::ui::components::track_row::TrackListItem;
 use crate::ui::model::{DetailView, PlaylistType};
+use crate::ui::route::AppRoute;
+use crate::utils::share;
 
 #[derive(Debug)]
 pub enum PlaylistDetailMsg {
@@ -29,10 +40,27 @@ pub enum PlaylistDetailMsg {
         cover: String,
     },
     PlayAllClicked,
+    IntelligenceClicked,
     LikeClicked,
+    CommentsClicked,
+    CreatorClicked,
+    CopyShareLink,
     TrackPlayClicked(u64),
     TrackMoreClicked(u64),
     LoadNextPage,
+    // ----- 自建歌单管理 -----
+    EditInfoClicked,
+    EditTracksClicked,
+    DeleteClicked,
+    DeleteConfirmed,
+    InfoSaved {
+        name: String,
+        description: String,
+        tags: Vec<String>,
+        cover_url: Option<String>,
+    },
+    TracksChanged,
+    ShowToast(String),
 }
 
 #[derive(Debug)]
@@ -43,12 +71,24 @@ pub enum PlaylistDetailOutput {
         start_index: usize,
         playlist: crate::api::Playlist,
     },
+    /// 从「我喜欢的音乐」开启心动模式
+    PlayIntelligence {
+        playlist_id: u64,
+        seed: Song,
+    },
     ShowToast(String),
+    Navigate(AppRoute),
+    /// 自建歌单的名称/封面等发生变化，需要刷新“我的歌单”
+    PlaylistsChanged,
+    /// 歌单已删除（携带歌单名）
+    PlaylistDeleted(String),
 }
 
 #[derive(Debug)]
 pub enum PlaylistDetailCmdMsg {
     PlaylistLoaded(PlaylistDetailModel),
+    /// 既没有缓存、联网又失败
+    PlaylistLoadFailed(u64),
     AlbumLoaded(crate::api::AlbumDetail),
     DailyRecommendLoaded(Vec<Song>),
     DailyCategoryLoaded {
@@ -62,6 +102,14 @@ pub enum PlaylistDetailCmdMsg {
         name: String,
     },
     NextPageLoaded(Vec<Song>),
+    IntelligenceSeedLoaded {
+        playlist_id: u64,
+        seed: Song,
+    },
+    DeleteResult {
+        success: bool,
+        name: String,
+    },
 }
 
 #[tracker::track]
@@ -77,6 +125,8 @@ pub struct PlaylistDetail {
     is_loading: bool,
     is_collected: bool,
     is_own: bool,
+    /// 自己的「我喜欢的音乐」，可开启心动模式
+    is_liked_songs: bool,
     #[do_not_track]
     user_id: u64,
     #[do_not_track]
@@ -93,6 +143,10 @@ pub struct PlaylistDetail {
     on_play: Option<Rc<dyn Fn(u64)>>,
     #[do_not_track]
     on_more: Option<Rc<dyn Fn(u64)>>,
+    #[do_not_track]
+    edit_dialog: Option<Controller<PlaylistEditDialog>>,
+    #[do_not_track]
+    track_editor: Option<Controller<TrackEditorDialog>>,
 }
 
 #[relm4::component(pub)]
@@ -184,12 +238,18 @@ impl Component for PlaylistDetail {
                                 .and_then(|d| d.creator.as_deref())
                                 .unwrap_or(""),
                             add_css_class: "dim-label",
+                            add_css_class: "link",
                             set_halign: gtk::Align::Start,
                             set_wrap: true,
                             set_wrap_mode: gtk::pango::WrapMode::WordChar,
                             set_ellipsize: gtk::pango::EllipsizeMode::End,
                             set_max_width_chars: 40,
                             set_lines: 2,
+                            add_controller = gtk::GestureClick {
+                                connect_released[sender] => move |_, _, _, _| {
+                                    sender.input(PlaylistDetailMsg::CreatorClicked);
+                                }
+                            },
                         },
                         gtk::Label {
                             #[watch]
@@ -220,6 +280,17 @@ impl Component for PlaylistDetail {
                                 add_css_class: "pill",
                                 connect_clicked => PlaylistDetailMsg::PlayAllClicked
                             },
+                            gtk::Button {
+                                #[wrap(Some)]
+                                set_child = &adw::ButtonContent {
+                                    set_label: "心动模式",
+                                    set_icon_name: "heart-filled",
+                                },
+                                add_css_class: "pill",
+                                #[watch]
+                                set_visible: model.is_liked_songs,
+                                connect_clicked => PlaylistDetailMsg::IntelligenceClicked
+                            },
                             gtk::Button {
                                 #[track = "model.changed(PlaylistDetail::is_collected())"]
                                 set_icon_name: if model.is_collected { "heart-filled" } else { "plus-large-symbolic" },
@@ -230,6 +301,52 @@ impl Component for PlaylistDetail {
                                 #[watch]
                                 set_sensitive: !model.is_own && !matches!(model.playlist_type, PlaylistType::DailyRecommend | PlaylistType::DailyCategory {..}),
                                 connect_clicked => PlaylistDetailMsg::LikeClicked
+                            },
+                            gtk::Button {
+                                set_icon_name: "chat-bubble-text",
+                                set_tooltip_text: Some("评论"),
+                                set_size_request: (46, 46),
+                                add_css_class: "circular",
+                                set_visible: matches!(model.playlist_type, PlaylistType::Playlist(_) | PlaylistType::Album(_)),
+                                connect_clicked => PlaylistDetailMsg::CommentsClicked
+                            },
+                            gtk::Button {
+                                set_icon_name: "edit-copy-symbolic",
+                                set_tooltip_text: Some("复制分享链接"),
+                                set_size_request: (46, 46),
+                                add_css_class: "circular",
+                                set_visible: matches!(model.playlist_type, PlaylistType::Playlist(_) | PlaylistType::Album(_)),
+                                connect_clicked => PlaylistDetailMsg::CopyShareLink
+                            },
+
+                            // 仅自建歌单可编辑
+                            gtk::Button {
+                                set_icon_name: "document-edit-symbolic",
+                                set_tooltip_text: Some("编辑歌单信息"),
+                                set_size_request: (46, 46),
+                                add_css_class: "circular",
+                                #[watch]
+                                set_visible: model.is_own,
+                                connect_clicked => PlaylistDetailMsg::EditInfoClicked
+                            },
+                            gtk::Button {
+                                set_icon_name: "view-list-symbolic",
+                                set_tooltip_text: Some("排序 / 删除歌曲"),
+                                set_size_request: (46, 46),
+                                add_css_class: "circular",
+                                #[watch]
+                                set_visible: model.is_own,
+                                connect_clicked => PlaylistDetailMsg::EditTracksClicked
+                            },
+                            gtk::Button {
+                                set_icon_name: "user-trash-symbolic",
+                                set_tooltip_text: Some("删除歌单"),
+                                set_size_request: (46, 46),
+                                add_css_class: "circular",
+                                add_css_class: "destructive-action",
+                                #[watch]
+                                set_visible: model.is_own,
+                                connect_clicked => PlaylistDetailMsg::DeleteClicked
                             }
                         }
                     }
@@ -274,6 +391,7 @@ impl Component for PlaylistDetail {
             is_loading: true,
             is_collected,
             is_own: false,
+            is_liked_songs: false,
             user_id,
             db,
             tracks_list: TypedListView::new(),
@@ -282,6 +400,8 @@ impl Component for PlaylistDetail {
             page_offset: 0,
             on_play: None,
             on_more: None,
+            edit_dialog: None,
+            track_editor: None,
             tracker: 0,
         };
 
@@ -325,15 +445,25 @@ impl Component for PlaylistDetail {
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         self.reset();
         trace!("PlaylistDetail Msg: {:?}", message);
         match message {
             PlaylistDetailMsg::LoadPlaylist(id) => {
                 self.set_is_loading(true);
                 sender.command(move |out, _shutdown| async move {
-                    if let Ok(detail) = get_playlist_detail(id).await {
-                        let _ = out.send(PlaylistDetailCmdMsg::PlaylistLoaded(detail));
+                    let result = cache::swr(
+                        &key::playlist_detail(id),
+                        ttl::PLAYLIST_DETAIL,
+                        || get_playlist_detail(id),
+                        |detail| {
+                            let _ = out.send(PlaylistDetailCmdMsg::PlaylistLoaded(detail));
+                        },
+                    )
+                    .await;
+                    if let Err(e) = result {
+                        log::error!("加载歌单 {id} 失败: {e}");
+                        let _ = out.send(PlaylistDetailCmdMsg::PlaylistLoadFailed(id));
                     }
                 });
             }
@@ -347,11 +477,47 @@ impl Component for PlaylistDetail {
                             tracks: tracks_arc.clone(),
                             track_ids: ids_arc.clone(),
                             start_index: 0,
-                            playlist: self.detail.clone().unwrap().into(),
+                            playlist: self.source_playlist(),
                         })
                         .unwrap();
                 }
             }
+            PlaylistDetailMsg::IntelligenceClicked => {
+                let PlaylistType::Playlist(playlist_id) = self.playlist_type else {
+                    return;
+                };
+                let Some(ids) = self.ids_arc.clone().filter(|ids| !ids.is_empty()) else {
+                    return;
+                };
+                // 与官方客户端一致：从整个歌单里随机挑一首作为种子，还没翻到的歌先取详情
+                let index = rand::thread_rng().gen_range(0..ids.len());
+                let id = ids[index];
+                let loaded = self
+                    .tracks_arc
+                    .as_ref()
+                    .and_then(|tracks| tracks.get(index))
+                    .filter(|song| song.id == id)
+                    .cloned();
+                if let Some(seed) = loaded {
+                    sender
+                        .output(PlaylistDetailOutput::PlayIntelligence { playlist_id, seed })
+                        .ok();
+                    return;
+                }
+                sender.command(move |out, _shutdown| async move {
+                    match get_song_detail(vec![id]).await {
+                        Ok(songs) => {
+                            if let Some(seed) = songs.into_iter().next() {
+                                let _ = out.send(PlaylistDetailCmdMsg::IntelligenceSeedLoaded {
+                                    playlist_id,
+                                    seed,
+                                });
+                            }
+                        }
+                        Err(e) => log::error!("获取心动模式种子歌曲失败: {e}"),
+                    }
+                });
+            }
             PlaylistDetailMsg::LikeClicked => {
                 if self.is_own
                     || matches!(
@@ -362,14 +528,9 @@ impl Component for PlaylistDetail {
                     return;
                 }
                 let new_collected = !self.is_collected;
-                let id = match &self.playlist_type {
-                    PlaylistType::Playlist(id) => *id,
-                    PlaylistType::Album(id) => *id,
-                    PlaylistType::DailyRecommend | PlaylistType::DailyCategory { .. } => return,
-                };
-                let collect_type = match &self.playlist_type {
-                    PlaylistType::Playlist(_) => CollectType::Playlist,
-                    PlaylistType::Album(_) => CollectType::Album,
+                let subscribe = match &self.playlist_type {
+                    PlaylistType::Playlist(id) => playlist_subscribe(*id, new_collected).boxed(),
+                    PlaylistType::Album(id) => album_subscribe(*id, new_collected).boxed(),
                     PlaylistType::DailyRecommend | PlaylistType::DailyCategory { .. } => return,
                 };
                 let name = self
@@ -378,10 +539,7 @@ impl Component for PlaylistDetail {
                     .map(|d| d.name.clone())
                     .unwrap_or_default();
                 sender.command(move |out, _shutdown| async move {
-                    let result = match collect_type {
-                        CollectType::Playlist => playlist_subscribe(id, new_collected).await,
-                        CollectType::Album => album_subscribe(id, new_collected).await,
-                    };
+                    let result = subscribe.await;
                     let _ = out.send(PlaylistDetailCmdMsg::SubscribeResult {
                         success: result.is_ok(),
                         collected: new_collected,
@@ -389,6 +547,40 @@ impl Component for PlaylistDetail {
                     });
                 });
             }
+            PlaylistDetailMsg::CommentsClicked => {
+                let route = match &self.playlist_type {
+                    PlaylistType::Playlist(id) => AppRoute::Comments(CommentType::Playlist, *id),
+                    PlaylistType::Album(id) => AppRoute::Comments(CommentType::Album, *id),
+                    PlaylistType::DailyRecommend | PlaylistType::DailyCategory { .. } => return,
+                };
+                sender.output(PlaylistDetailOutput::Navigate(route)).ok();
+            }
+            PlaylistDetailMsg::CopyShareLink => {
+                let Some(url) = share::playlist_share_url(&self.playlist_type) else {
+                    return;
+                };
+                root.clipboard().set_text(&url);
+                sender
+                    .output(PlaylistDetailOutput::ShowToast(
+                        "已复制分享链接".to_string(),
+                    ))
+                    .ok();
+            }
+            PlaylistDetailMsg::CreatorClicked => {
+                let Some(creator_id) = self.detail.as_ref().map(|d| d.creator_id) else {
+                    return;
+                };
+                if creator_id == 0 {
+                    return;
+                }
+                // 歌单的创建者是用户，专辑的“创建者”是第一位歌手
+                let route = match &self.playlist_type {
+                    PlaylistType::Playlist(_) => AppRoute::User(creator_id),
+                    PlaylistType::Album(_) => AppRoute::Artist(creator_id),
+                    PlaylistType::DailyRecommend | PlaylistType::DailyCategory { .. } => return,
+                };
+                sender.output(PlaylistDetailOutput::Navigate(route)).ok();
+            }
             PlaylistDetailMsg::TrackPlayClicked(track_id) => {
                 if let (Some(_detail), Some(tracks_arc), Some(ids_arc)) =
                     (&self.detail, &self.tracks_arc, &self.ids_arc)
@@ -401,7 +593,7 @@ impl Component for PlaylistDetail {
                             tracks: tracks_arc.clone(),
                             track_ids: ids_arc.clone(),
                             start_index: index,
-                            playlist: self.detail.clone().unwrap().into(),
+                            playlist: self.source_playlist(),
                         })
                         .unwrap();
                 }
@@ -433,6 +625,116 @@ impl Component for PlaylistDetail {
                     }
                 });
             }
+            PlaylistDetailMsg::EditInfoClicked => {
+                let Some(detail) = self.detail.as_ref().filter(|_| self.is_own) else {
+                    return;
+                };
+                let dialog = PlaylistEditDialog::builder()
+                    .launch(PlaylistEditInit {
+                        id: detail.id,
+                        name: detail.name.clone(),
+                        description: detail.description.clone().unwrap_or_default(),
+                        tags: detail.tags.clone(),
+                        cover_url: detail.cover_url.clone(),
+                    })
+                    .forward(sender.input_sender(), |output| match output {
+                        PlaylistEditOutput::Saved {
+                            name,
+                            description,
+                            tags,
+                            cover_url,
+                        } => PlaylistDetailMsg::InfoSaved {
+                            name,
+                            description,
+                            tags,
+                            cover_url,
+                        },
+                        PlaylistEditOutput::ShowToast(msg) => PlaylistDetailMsg::ShowToast(msg),
+                    });
+                dialog.widget().present(Some(root));
+                self.edit_dialog = Some(dialog);
+            }
+            PlaylistDetailMsg::EditTracksClicked => {
+                let (Some(detail), Some(tracks_arc)) = (self.detail.as_ref(), &self.tracks_arc)
+                else {
+                    return;
+                };
+                if !self.is_own {
+                    return;
+                }
+                let dialog = TrackEditorDialog::builder()
+                    .launch(TrackEditorInit {
+                        playlist_id: detail.id,
+                        track_ids: detail.track_ids.clone(),
+                        tracks: (**tracks_arc).clone(),
+                    })
+                    .forward(sender.input_sender(), |output| match output {
+                        TrackEditorOutput::Changed => PlaylistDetailMsg::TracksChanged,
+                        TrackEditorOutput::ShowToast(msg) => PlaylistDetailMsg::ShowToast(msg),
+                    });
+                dialog.widget().present(Some(root));
+                self.track_editor = Some(dialog);
+            }
+            PlaylistDetailMsg::DeleteClicked => {
+                let Some(name) = self
+                    .detail
+                    .as_ref()
+                    .filter(|_| self.is_own)
+                    .map(|d| d.name.clone())
+                else {
+                    return;
+                };
+                let alert = adw::AlertDialog::new(
+                    Some("删除歌单"),
+                    Some(&format!("确定要删除「{}」吗？此操作无法撤销。", name)),
+                );
+                alert.add_response("cancel", "取消");
+                alert.add_response("delete", "删除");
+                alert.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+                alert.set_default_response(Some("cancel"));
+                alert.set_close_response("cancel");
+                let input = sender.input_sender().clone();
+                alert.connect_response(None, move |_, response| {
+                    if response == "delete" {
+                        let _ = input.send(PlaylistDetailMsg::DeleteConfirmed);
+                    }
+                });
+                alert.present(Some(root));
+            }
+            PlaylistDetailMsg::DeleteConfirmed => {
+                let Some((id, name)) = self.detail.as_ref().map(|d| (d.id, d.name.clone())) else {
+                    return;
+                };
+                sender.command(move |out, _shutdown| async move {
+                    let success = playlist_delete(id).await.is_ok();
+                    let _ = out.send(PlaylistDetailCmdMsg::DeleteResult { success, name });
+                });
+            }
+            PlaylistDetailMsg::InfoSaved {
+                name,
+                description,
+                tags,
+                cover_url,
+            } => {
+                if let Some(detail) = self.detail.as_mut() {
+                    detail.name = name;
+                    detail.description = Some(description).filter(|d| !d.is_empty());
+                    detail.tags = tags;
+                    if let Some(url) = cover_url {
+                        detail.cover_url = url;
+                    }
+                }
+                sender.output(PlaylistDetailOutput::PlaylistsChanged).ok();
+            }
+            PlaylistDetailMsg::TracksChanged => {
+                if let PlaylistType::Playlist(id) = self.playlist_type {
+                    sender.input(PlaylistDetailMsg::LoadPlaylist(id));
+                }
+                sender.output(PlaylistDetailOutput::PlaylistsChanged).ok();
+            }
+            PlaylistDetailMsg::ShowToast(msg) => {
+                sender.output(PlaylistDetailOutput::ShowToast(msg)).ok();
+            }
             PlaylistDetailMsg::LoadAlbum(id) => {
                 self.set_is_loading(true);
                 sender.command(
@@ -487,12 +789,37 @@ impl Component for PlaylistDetail {
         _root: &Self::Root,
     ) {
         match message {
+            PlaylistDetailCmdMsg::PlaylistLoadFailed(id) => {
+                if !matches!(self.playlist_type, PlaylistType::Playlist(current) if current == id) {
+                    return;
+                }
+                self.set_is_loading(false);
+                sender
+                    .output(PlaylistDetailOutput::ShowToast("加载歌单失败".to_string()))
+                    .ok();
+            }
             PlaylistDetailCmdMsg::PlaylistLoaded(detail) => {
+                // 缓存会先后推送两次：丢掉不属于当前页面的结果
+                if !matches!(self.playlist_type, PlaylistType::Playlist(id) if id == detail.id) {
+                    return;
+                }
+                // 第二次是后台刷新的结果；用户已经往下翻页（或正在翻）时不再重置列表
+                let paged = self.is_loading_more
+                    || self
+                        .detail
+                        .as_ref()
+                        .is_some_and(|d| self.page_offset > d.tracks.len());
+                if !self.is_loading && paged {
+                    log::info!("歌单 {} 已翻页，忽略后台刷新结果", detail.id);
+                    return;
+                }
+                let liked_songs = detail.liked_songs;
                 let dv: DetailView = detail.into();
                 if matches!(self.playlist_type, PlaylistType::Playlist(_))
                     && dv.creator_id == self.user_id
                 {
                     self.set_is_own(true);
+                    self.set_is_liked_songs(liked_songs);
                 }
                 self.apply_detail(dv, &sender);
             }
@@ -515,6 +842,7 @@ impl Component for PlaylistDetail {
                     creator: Some("网易云音乐".into()),
                     creator_id: 0,
                     description: None,
+                    tags: Vec::new(),
                     tracks: songs,
                     track_ids,
                 };
@@ -589,11 +917,35 @@ impl Component for PlaylistDetail {
                 self.has_more =
                     self.page_offset < self.ids_arc.as_ref().map(|i| i.len()).unwrap_or(0);
             }
+            PlaylistDetailCmdMsg::IntelligenceSeedLoaded { playlist_id, seed } => {
+                sender
+                    .output(PlaylistDetailOutput::PlayIntelligence { playlist_id, seed })
+                    .ok();
+            }
+            PlaylistDetailCmdMsg::DeleteResult { success, name } => {
+                if success {
+                    sender
+                        .output(PlaylistDetailOutput::PlaylistDeleted(name))
+                        .ok();
+                } else {
+                    sender
+                        .output(PlaylistDetailOutput::ShowToast("删除歌单失败".to_string()))
+                        .ok();
+                }
+            }
         }
     }
 }
 
 impl PlaylistDetail {
+    /// 播放队列记录的来源歌单，带上是否为「我喜欢的音乐」供心动模式判断
+    fn source_playlist(&self) -> crate::api::Playlist {
+        let mut playlist: crate::api::Playlist =
+            self.detail.clone().map(Into::into).unwrap_or_default();
+        playlist.liked_songs = self.is_liked_songs;
+        playlist
+    }
+
     fn apply_detail(&mut self, detail: DetailView, sender: &ComponentSender<Self>) {
         self.tracks_list.clear();
 
@@ -1,5 +1,6 @@
 use relm4::gtk::prelude::{BoxExt, ButtonExt, OrientableExt, WidgetExt};
 use relm4::{ComponentParts, ComponentSender, factory::FactoryVecDeque, gtk, prelude::*};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::api::{Playlist, Song};
@@ -19,6 +20,8 @@ pub enum QueueMsg {
     },
     /// 仅更新当前播放索引 (上一首/下一首时)
     SetCurrentIndex(usize),
+    /// 标记无法播放的歌曲（置灰并显示原因）
+    MarkUnplayable { song_id: u64, reason: String },
     /// 清空队列
     Clear,
     /// 内部：工厂子组件的事件转发
@@ -40,6 +43,7 @@ pub struct QueueRowInit {
     pub index: usize,
     pub song: Arc<Song>,
     pub is_playing: bool,
+    pub unplayable: Option<String>,
 }
 
 #[derive(Debug)]
@@ -54,6 +58,8 @@ pub struct QueueRow {
     index_str: String,
     song: Arc<Song>,
     is_playing: bool,
+    /// 无法播放的原因
+    unplayable: Option<String>,
 }
 
 #[relm4::factory(pub)]
@@ -70,7 +76,10 @@ impl FactoryComponent for QueueRow {
             set_spacing: 8,
             set_margin_all: 8,
             set_valign: gtk::Align::Center,
-
+            #[watch]
+            set_opacity: if self.unplayable.is_some() { 0.5 } else { 1.0 },
+            #[watch]
+            set_tooltip_text: self.unplayable.as_deref(),
 
             add_controller = gtk::GestureClick {
                 connect_released[sender, index = self.index_str.parse::<usize>().unwrap_or(0) - 1] => move |_, _, _, _| {
@@ -134,6 +143,15 @@ impl FactoryComponent for QueueRow {
                     set_ellipsize: gtk::pango::EllipsizeMode::End,
                     add_css_class: "dim-label",
                     add_css_class: "caption",
+                },
+                gtk::Label {
+                    #[watch]
+                    set_visible: self.unplayable.is_some(),
+                    #[watch]
+                    set_label: self.unplayable.as_deref().unwrap_or_default(),
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "warning",
+                    add_css_class: "caption",
                 }
             },
 
@@ -163,6 +181,7 @@ impl FactoryComponent for QueueRow {
             index_str: (index + 1).to_string(), // 预先转为 String
             song: init.song,
             is_playing: init.is_playing,
+            unplayable: init.unplayable,
         }
     }
 }
@@ -171,6 +190,8 @@ pub struct QueuePage {
     queue: FactoryVecDeque<QueueRow>,
     current_index: usize,
     playlist: Arc<Playlist>,
+    /// 本次运行中确认无法播放的歌曲，重建队列时保留标记
+    unplayable: HashMap<u64, String>,
 }
 
 #[relm4::component(pub)]
@@ -207,6 +228,7 @@ impl Component for QueuePage {
                 .forward(sender.input_sender(), QueueMsg::RowAction),
             current_index: 0,
             playlist: Arc::new(Playlist::default()),
+            unplayable: HashMap::new(),
         };
 
         let list_box = model.queue.widget();
@@ -230,6 +252,7 @@ impl Component for QueuePage {
                         index,
                         song: Arc::new(song.clone()), // 零成本克隆指针
                         is_playing: index == start_index,
+                        unplayable: self.unplayable.get(&song.id).cloned(),
                     });
                 }
                 drop(guard); // 释放锁让 UI 更新
@@ -255,6 +278,17 @@ impl Component for QueuePage {
                 self.current_index = new_index;
             }
 
+            QueueMsg::MarkUnplayable { song_id, reason } => {
+                let mut guard = self.queue.guard();
+                for i in 0..guard.len() {
+                    if let Some(row) = guard.get_mut(i).filter(|row| row.song.id == song_id) {
+                        row.unplayable = Some(reason.clone());
+                    }
+                }
+                drop(guard);
+                self.unplayable.insert(song_id, reason);
+            }
+
             QueueMsg::Clear => {
                 self.queue.guard().clear();
                 self.current_index = 0;
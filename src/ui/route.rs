@@ -1,9 +1,16 @@
 use relm4::Controller;
 use strum::Display;
 
+use crate::api::CommentType;
 use crate::ui::{
-    artist::ArtistPage, comments::CommentsPage, model::PlaylistType, mv_player::MvPlayerPage,
+    artist::ArtistPage,
+    comments::CommentsPage,
+    explore::more::{ExploreMorePage, ExploreSection},
+    model::PlaylistType,
+    mv_player::MvPlayerPage,
     playlist_detail::PlaylistDetail,
+    user::UserPage,
+    year_report::YearReportPage,
 };
 
 #[derive(Debug, Clone, PartialEq, Display)]
@@ -21,9 +28,15 @@ pub enum AppRoute {
     #[strum(serialize = "artist")]
     Artist(u64),
     #[strum(serialize = "comments")]
-    Comments(u64),
+    Comments(CommentType, u64),
     #[strum(serialize = "mv")]
     Mv(u64),
+    #[strum(serialize = "user")]
+    User(u64),
+    #[strum(serialize = "explore-more")]
+    ExploreMore(ExploreSection),
+    #[strum(serialize = "year-report")]
+    YearReport,
 }
 
 #[derive(Debug, Clone, PartialEq, Display)]
@@ -41,4 +54,7 @@ pub enum DetailCtrl {
     Artist(Controller<ArtistPage>),
     Comments(Controller<CommentsPage>),
     Mv(Controller<MvPlayerPage>),
+    User(Controller<UserPage>),
+    ExploreMore(Controller<ExploreMorePage>),
+    YearReport(Controller<YearReportPage>),
 }
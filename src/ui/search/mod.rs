@@ -9,7 +9,9 @@ use crate::api::{
     Album, Artist, Playlist, SearchSuggest, Song, search_albums, search_artists, search_playlists,
     search_songs, search_suggest,
 };
-use crate::ui::components::artist_card::{ArtistCard, ArtistCardInit, ArtistCardOutput};
+use crate::ui::components::artist_card::{
+    ArtistCard, ArtistCardInit, ArtistCardOutput, artist_row_flow_box,
+};
 use crate::ui::components::playlist_card::{BoxPlaylistCard, PlaylistCardInit, PlaylistCardOutput};
 use crate::ui::components::scrollable_row::ScrollableRow;
 use crate::ui::components::song_list::{
@@ -40,6 +42,7 @@ pub enum SearchMsg {
     AlbumCardClicked(PlaylistCardOutput),
     /// 结果区的歌手圆形卡片被点击
     ArtistCardClicked(ArtistCardOutput),
+    Noop,
 }
 
 #[derive(Debug)]
@@ -148,6 +151,8 @@ impl Component for Search {
             .launch(SongListScrollInit::new("单曲", 220, 220))
             .forward(sender.input_sender(), |out| match out {
                 SongListScrollOutput::Clicked(id) => SearchMsg::SongClicked(id),
+                // 搜索页的单曲行未开启“查看全部”
+                SongListScrollOutput::MoreClicked => SearchMsg::Noop,
             });
         let playlist_row = ScrollableRow::new("歌单", 220, 220);
         let artist_row = ScrollableRow::new("歌手", 220, 220);
@@ -159,8 +164,10 @@ impl Component for Search {
         let album_cards = FactoryVecDeque::builder()
             .launch(album_row.widgets().content_box.clone())
             .forward(sender.input_sender(), SearchMsg::AlbumCardClicked);
+        let artist_box = artist_row_flow_box();
+        artist_row.widgets().content_box.append(&artist_box);
         let artist_cards = FactoryVecDeque::builder()
-            .launch(artist_row.widgets().content_box.clone())
+            .launch(artist_box)
             .forward(sender.input_sender(), SearchMsg::ArtistCardClicked);
 
         let mut model = Self {
@@ -305,6 +312,7 @@ impl Component for Search {
                     let _ = sender.output(SearchOutput::Navigate(AppRoute::Artist(id)));
                 }
             },
+            SearchMsg::Noop => {}
         }
     }
 
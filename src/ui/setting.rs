@@ -1,41 +1,115 @@
 use relm4::adw::prelude::*;
-use relm4::gtk::gio;
-use relm4::{ComponentParts, ComponentSender, SimpleComponent, adw, gtk};
+use relm4::gtk::{gdk, gio, glib};
+use std::sync::{Arc, Mutex};
+
+use relm4::{
+    Component, ComponentController, ComponentParts, ComponentSender, Controller, SimpleComponent,
+    adw, gtk,
+};
 
 use crate::APPLICATION_ID;
+use crate::db::Db;
+use crate::player::effects::AudioEffects;
+use crate::player::messages::OutputDeviceList;
+use crate::scrobble::{self, ScrobbleConfig, ScrobbleService};
+use crate::ui::audio_effects::{AudioEffectsMsg, AudioEffectsOutput, AudioEffectsPage};
+use crate::ui::components::image::image_manager::{DISK_CACHE_LIMIT, ImageManager};
+use crate::ui::shortcuts::{self, SHORTCUTS, ShortcutGroup};
 
 mod keys {
     pub const RESTORE_ON_START: &str = "restore-on-start";
     pub const AUTO_PLAY_ON_RESTORE: &str = "auto-play-on-restore";
+    pub const TRACK_NOTIFICATIONS: &str = "track-notifications";
+    pub const REPORT_PLAYS: &str = "report-plays";
+    pub const RUN_IN_BACKGROUND: &str = "run-in-background";
+    pub const SLEEP_FADE_SECONDS: &str = "sleep-fade-seconds";
+    pub const VISUALIZER_STYLE: &str = "visualizer-style";
+    pub const VISUALIZER_BEAT_BACKGROUND: &str = "visualizer-beat-background";
     pub const COOKIE: &str = "cookie";
 }
 
+/// 频谱样式：GSettings 取值与下拉框显示文字，顺序即下拉框顺序
+const VISUALIZER_STYLES: [(&str, &str); 3] = [("off", "关闭"), ("bars", "柱状"), ("wave", "波形")];
+
+fn visualizer_style_index(settings: &gio::Settings) -> u32 {
+    let value = settings.string(keys::VISUALIZER_STYLE);
+    VISUALIZER_STYLES
+        .iter()
+        .position(|(v, _)| *v == value.as_str())
+        .unwrap_or(0) as u32
+}
+
+fn scrobble_service_index(service: Option<ScrobbleService>) -> u32 {
+    ScrobbleService::CHOICES
+        .iter()
+        .position(|(s, _, _)| *s == service)
+        .unwrap_or(0) as u32
+}
+
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    format!("{:.1} MB", bytes as f64 / MB)
+}
+
 pub struct Settings {
     settings: gio::Settings,
     restore_on_start: bool,
     auto_play_on_restore: bool,
+    track_notifications: bool,
+    report_plays: bool,
+    run_in_background: bool,
+    sleep_fade_seconds: u32,
+    visualizer_style: u32,
+    visualizer_beat_background: bool,
+    /// 图片磁盘缓存占用，打开设置时刷新
+    image_cache_size: u64,
     cookie: String,
+    /// 听歌记录同步，应用输入框内容后刷新
+    scrobble: ScrobbleConfig,
+    /// 快捷键页中每个动作对应的按键显示
+    shortcut_labels: Vec<(&'static str, adw::ShortcutLabel)>,
+    audio_effects: Controller<AudioEffectsPage>,
 }
 
 #[derive(Debug)]
 pub enum SettingsInput {
     RestoreOnStartToggled(bool),
     AutoPlayOnRestoreToggled(bool),
+    TrackNotificationsToggled(bool),
+    ReportPlaysToggled(bool),
+    RunInBackgroundToggled(bool),
+    SleepFadeSecondsChanged(u32),
+    VisualizerStyleChanged(u32),
+    VisualizerBeatBackgroundToggled(bool),
+    ClearImageCache,
     UserCookieChanged(String),
     SaveCookie(String),
+    ScrobbleServiceChanged(u32),
+    /// 听歌记录同步的输入框按下应用：(GSettings 键, 内容)
+    ScrobbleEntryApplied(&'static str, String),
     ResetSettings,
     ReloadAll,
+    /// 录制到新的快捷键（空列表表示禁用）
+    ShortcutCaptured {
+        action: &'static str,
+        accels: Vec<String>,
+    },
+    ResetShortcuts,
+    /// 播放器上报的输出设备列表，转给音效页
+    OutputDevicesChanged(OutputDeviceList),
 }
 
 #[derive(Debug)]
 pub enum SettingsOutput {
     UserCookieChanged(String),
     SaveCookie,
+    AudioEffectsChanged(AudioEffects),
+    OutputDeviceSelected(Option<String>),
 }
 
 #[relm4::component(pub)]
 impl SimpleComponent for Settings {
-    type Init = ();
+    type Init = Arc<Mutex<Db>>;
     type Input = SettingsInput;
     type Output = SettingsOutput;
 
@@ -113,6 +187,241 @@ impl SimpleComponent for Settings {
                             );
                         },
                     },
+
+                    adw::SwitchRow {
+                        set_title: "后台运行",
+                        set_subtitle: "关闭窗口后继续播放，可通过系统媒体控件或再次启动应用恢复窗口",
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("window-minimize-symbolic"),
+                        },
+
+                        #[watch]
+                        set_active: model.run_in_background,
+
+                        connect_active_notify[sender] => move |switch| {
+                            sender.input_sender().emit(
+                                SettingsInput::RunInBackgroundToggled(switch.is_active())
+                            );
+                        },
+                    },
+
+                    adw::SpinRow {
+                        set_title: "定时关闭淡出时长",
+                        set_subtitle: "定时到达后在这段时间（秒）内逐渐降低音量再暂停",
+                        set_adjustment: Some(&gtk::Adjustment::new(15.0, 0.0, 120.0, 1.0, 5.0, 0.0)),
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("alarm-symbolic"),
+                        },
+
+                        #[watch]
+                        set_value: model.sleep_fade_seconds as f64,
+
+                        connect_value_notify[sender] => move |row| {
+                            sender.input_sender().emit(
+                                SettingsInput::SleepFadeSecondsChanged(row.value() as u32)
+                            );
+                        },
+                    },
+
+                    adw::SwitchRow {
+                        set_title: "切歌通知",
+                        set_subtitle: "窗口不在前台时，切换歌曲后发送桌面通知",
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("preferences-system-notifications-symbolic"),
+                        },
+
+                        #[watch]
+                        set_active: model.track_notifications,
+
+                        connect_active_notify[sender] => move |switch| {
+                            sender.input_sender().emit(
+                                SettingsInput::TrackNotificationsToggled(switch.is_active())
+                            );
+                        },
+                    },
+                },
+
+                adw::PreferencesGroup {
+                    set_title: "可视化",
+
+                    adw::ComboRow {
+                        set_title: "频谱",
+                        set_subtitle: "在播放页和全屏歌词页显示实时频谱",
+                        set_model: Some(&gtk::StringList::new(&VISUALIZER_STYLES.map(|(_, label)| label))),
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("audio-x-generic-symbolic"),
+                        },
+
+                        #[watch]
+                        set_selected: model.visualizer_style,
+
+                        connect_selected_notify[sender] => move |row| {
+                            sender.input_sender().emit(
+                                SettingsInput::VisualizerStyleChanged(row.selected())
+                            );
+                        },
+                    },
+
+                    adw::SwitchRow {
+                        set_title: "背景随节拍律动",
+                        set_subtitle: "全屏歌词页的背景随低音加快流动",
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("weather-clear-night-symbolic"),
+                        },
+
+                        #[watch]
+                        set_active: model.visualizer_beat_background,
+
+                        connect_active_notify[sender] => move |switch| {
+                            sender.input_sender().emit(
+                                SettingsInput::VisualizerBeatBackgroundToggled(switch.is_active())
+                            );
+                        },
+                    },
+                },
+
+                adw::PreferencesGroup {
+                    set_title: "听歌记录同步",
+                    set_description: Some("同步到 Last.fm 或 ListenBrainz 时，听满一半或 4 分钟后提交，离线时暂存并在联网后补交"),
+
+                    adw::SwitchRow {
+                        set_title: "上报到网易云",
+                        set_subtitle: "在 Linn 中听的歌计入账号的听歌排行，并用于改进每日推荐",
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("send-to-symbolic"),
+                        },
+
+                        #[watch]
+                        set_active: model.report_plays,
+
+                        connect_active_notify[sender] => move |switch| {
+                            sender.input_sender().emit(
+                                SettingsInput::ReportPlaysToggled(switch.is_active())
+                            );
+                        },
+                    },
+
+                    adw::ComboRow {
+                        set_title: "同步到第三方服务",
+                        set_model: Some(&gtk::StringList::new(&ScrobbleService::CHOICES.map(|(_, _, label)| label))),
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("emblem-synchronizing-symbolic"),
+                        },
+
+                        #[watch]
+                        set_selected: scrobble_service_index(model.scrobble.service),
+
+                        connect_selected_notify[sender] => move |row| {
+                            sender.input_sender().emit(
+                                SettingsInput::ScrobbleServiceChanged(row.selected())
+                            );
+                        },
+                    },
+
+                    adw::EntryRow {
+                        set_title: "服务器地址（留空使用官方地址）",
+                        set_show_apply_button: true,
+                        #[watch]
+                        set_visible: model.scrobble.service.is_some(),
+                        #[watch]
+                        set_text: &model.scrobble.base_url,
+
+                        connect_apply[sender] => move |row| {
+                            sender.input_sender().emit(SettingsInput::ScrobbleEntryApplied(
+                                scrobble::BASE_URL_KEY,
+                                row.text().to_string(),
+                            ));
+                        },
+                    },
+
+                    adw::PasswordEntryRow {
+                        #[watch]
+                        set_title: if model.scrobble.service == Some(ScrobbleService::LastFm) {
+                            "Session Key"
+                        } else {
+                            "用户 Token"
+                        },
+                        set_show_apply_button: true,
+                        #[watch]
+                        set_visible: model.scrobble.service.is_some(),
+                        #[watch]
+                        set_text: &model.scrobble.token,
+
+                        connect_apply[sender] => move |row| {
+                            sender.input_sender().emit(SettingsInput::ScrobbleEntryApplied(
+                                scrobble::TOKEN_KEY,
+                                row.text().to_string(),
+                            ));
+                        },
+                    },
+
+                    adw::EntryRow {
+                        set_title: "Last.fm API Key",
+                        set_show_apply_button: true,
+                        #[watch]
+                        set_visible: model.scrobble.service == Some(ScrobbleService::LastFm),
+                        #[watch]
+                        set_text: &model.scrobble.api_key,
+
+                        connect_apply[sender] => move |row| {
+                            sender.input_sender().emit(SettingsInput::ScrobbleEntryApplied(
+                                scrobble::LASTFM_API_KEY_KEY,
+                                row.text().to_string(),
+                            ));
+                        },
+                    },
+
+                    adw::PasswordEntryRow {
+                        set_title: "Last.fm API Secret",
+                        set_show_apply_button: true,
+                        #[watch]
+                        set_visible: model.scrobble.service == Some(ScrobbleService::LastFm),
+                        #[watch]
+                        set_text: &model.scrobble.api_secret,
+
+                        connect_apply[sender] => move |row| {
+                            sender.input_sender().emit(SettingsInput::ScrobbleEntryApplied(
+                                scrobble::LASTFM_API_SECRET_KEY,
+                                row.text().to_string(),
+                            ));
+                        },
+                    },
+                },
+
+                adw::PreferencesGroup {
+                    set_title: "存储",
+
+                    adw::ActionRow {
+                        set_title: "图片缓存",
+                        #[watch]
+                        set_subtitle: &format!(
+                            "已使用 {}，上限 {}，超出后自动清理最久未查看的图片",
+                            format_size(model.image_cache_size),
+                            format_size(DISK_CACHE_LIMIT)
+                        ),
+
+                        add_prefix = &gtk::Image {
+                            set_icon_name: Some("folder-pictures-symbolic"),
+                        },
+
+                        add_suffix = &gtk::Button {
+                            set_label: "清除",
+                            set_valign: gtk::Align::Center,
+                            #[watch]
+                            set_sensitive: model.image_cache_size > 0,
+
+                            connect_clicked[sender] => move |_| {
+                                sender.input_sender().emit(SettingsInput::ClearImageCache);
+                            },
+                        },
+                    },
                 },
 
                 adw::PreferencesGroup {
@@ -139,6 +448,12 @@ impl SimpleComponent for Settings {
                 },
             },
 
+            #[name(shortcuts_page)]
+            add = &adw::PreferencesPage {
+                set_title: "快捷键",
+                set_icon_name: Some("input-keyboard-symbolic"),
+            },
+
             connect_map[sender] => move |_| {
                 sender.input_sender().emit(SettingsInput::ReloadAll);
             },
@@ -146,7 +461,7 @@ impl SimpleComponent for Settings {
     }
 
     fn init(
-        _init: Self::Init,
+        db: Self::Init,
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
@@ -154,15 +469,83 @@ impl SimpleComponent for Settings {
         let cookie = settings.string(keys::COOKIE).to_string();
         let restore_on_start = settings.boolean(keys::RESTORE_ON_START);
         let auto_play_on_restore = settings.boolean(keys::AUTO_PLAY_ON_RESTORE);
+        let track_notifications = settings.boolean(keys::TRACK_NOTIFICATIONS);
+        let report_plays = settings.boolean(keys::REPORT_PLAYS);
+        let run_in_background = settings.boolean(keys::RUN_IN_BACKGROUND);
+        let sleep_fade_seconds = settings.uint(keys::SLEEP_FADE_SECONDS);
+        let visualizer_style = visualizer_style_index(&settings);
+        let visualizer_beat_background = settings.boolean(keys::VISUALIZER_BEAT_BACKGROUND);
+        let scrobble = ScrobbleConfig::from_settings(&settings);
+
+        let audio_effects =
+            AudioEffectsPage::builder()
+                .launch(db)
+                .forward(sender.output_sender(), |output| match output {
+                    AudioEffectsOutput::Changed(effects) => {
+                        SettingsOutput::AudioEffectsChanged(effects)
+                    }
+                    AudioEffectsOutput::OutputDeviceSelected(name) => {
+                        SettingsOutput::OutputDeviceSelected(name)
+                    }
+                });
 
-        let model = Self {
+        let mut model = Self {
             settings,
             restore_on_start,
             auto_play_on_restore,
+            track_notifications,
+            report_plays,
+            run_in_background,
+            sleep_fade_seconds,
+            visualizer_style,
+            visualizer_beat_background,
+            image_cache_size: ImageManager::global().disk_usage(),
             cookie,
+            scrobble,
+            shortcut_labels: Vec::new(),
+            audio_effects,
         };
 
         let widgets = view_output!();
+        widgets.dialog.add(model.audio_effects.widget());
+
+        for group in ShortcutGroup::ALL {
+            let pref_group = adw::PreferencesGroup::builder()
+                .title(group.title())
+                .build();
+            for spec in SHORTCUTS.iter().filter(|s| s.group == group) {
+                let label =
+                    adw::ShortcutLabel::new(&shortcuts::current_accels(spec.action).join(" "));
+                label.set_disabled_text("已禁用");
+                label.set_valign(gtk::Align::Center);
+                let row = adw::ActionRow::builder()
+                    .title(spec.title)
+                    .activatable(true)
+                    .build();
+                row.add_suffix(&label);
+                let input = sender.input_sender().clone();
+                let (action, title) = (spec.action, spec.title);
+                row.connect_activated(move |row| {
+                    present_capture_dialog(row, action, title, input.clone());
+                });
+                pref_group.add(&row);
+                model.shortcut_labels.push((spec.action, label));
+            }
+            widgets.shortcuts_page.add(&pref_group);
+        }
+
+        let reset_group = adw::PreferencesGroup::new();
+        let reset_row = adw::ButtonRow::builder()
+            .title("恢复默认快捷键")
+            .start_icon_name("view-refresh-symbolic")
+            .build();
+        let input = sender.input_sender().clone();
+        reset_row.connect_activated(move |_| {
+            let _ = input.send(SettingsInput::ResetShortcuts);
+        });
+        reset_group.add(&reset_row);
+        widgets.shortcuts_page.add(&reset_group);
+
         ComponentParts { model, widgets }
     }
 
@@ -180,6 +563,44 @@ impl SimpleComponent for Settings {
                     .set_boolean(keys::AUTO_PLAY_ON_RESTORE, active)
                     .ok();
             }
+            SettingsInput::RunInBackgroundToggled(active) => {
+                self.run_in_background = active;
+                self.settings
+                    .set_boolean(keys::RUN_IN_BACKGROUND, active)
+                    .ok();
+            }
+            SettingsInput::SleepFadeSecondsChanged(secs) => {
+                if self.sleep_fade_seconds != secs {
+                    self.sleep_fade_seconds = secs;
+                    self.settings.set_uint(keys::SLEEP_FADE_SECONDS, secs).ok();
+                }
+            }
+            SettingsInput::VisualizerStyleChanged(index) => {
+                if let Some((value, _)) = VISUALIZER_STYLES.get(index as usize) {
+                    self.visualizer_style = index;
+                    self.settings.set_string(keys::VISUALIZER_STYLE, value).ok();
+                }
+            }
+            SettingsInput::VisualizerBeatBackgroundToggled(active) => {
+                self.visualizer_beat_background = active;
+                self.settings
+                    .set_boolean(keys::VISUALIZER_BEAT_BACKGROUND, active)
+                    .ok();
+            }
+            SettingsInput::ClearImageCache => {
+                ImageManager::global().clear();
+                self.image_cache_size = ImageManager::global().disk_usage();
+            }
+            SettingsInput::TrackNotificationsToggled(active) => {
+                self.track_notifications = active;
+                self.settings
+                    .set_boolean(keys::TRACK_NOTIFICATIONS, active)
+                    .ok();
+            }
+            SettingsInput::ReportPlaysToggled(active) => {
+                self.report_plays = active;
+                self.settings.set_boolean(keys::REPORT_PLAYS, active).ok();
+            }
 
             SettingsInput::UserCookieChanged(_text) => {}
 
@@ -188,10 +609,37 @@ impl SimpleComponent for Settings {
                 self.settings.set_string(keys::COOKIE, &text).ok();
                 sender.output(SettingsOutput::SaveCookie).ok();
             }
+            SettingsInput::ScrobbleServiceChanged(index) => {
+                if let Some((service, value, _)) = ScrobbleService::CHOICES.get(index as usize) {
+                    self.scrobble.service = *service;
+                    self.settings.set_string(scrobble::SERVICE_KEY, value).ok();
+                }
+            }
+            SettingsInput::ScrobbleEntryApplied(key, text) => {
+                self.settings.set_string(key, text.trim()).ok();
+                self.scrobble = ScrobbleConfig::from_settings(&self.settings);
+            }
             SettingsInput::ResetSettings => {
                 self.restore_on_start = true;
                 self.auto_play_on_restore = false;
+                self.track_notifications = true;
+                self.report_plays = true;
+                self.run_in_background = false;
+                self.sleep_fade_seconds = 15;
+                self.visualizer_style = 0;
+                self.visualizer_beat_background = false;
                 self.cookie = String::new();
+                // 输入框不会自动写回 GSettings，直接重置对应的键
+                for key in [
+                    scrobble::SERVICE_KEY,
+                    scrobble::BASE_URL_KEY,
+                    scrobble::TOKEN_KEY,
+                    scrobble::LASTFM_API_KEY_KEY,
+                    scrobble::LASTFM_API_SECRET_KEY,
+                ] {
+                    self.settings.reset(key);
+                }
+                self.scrobble = ScrobbleConfig::default();
                 sender
                     .output(SettingsOutput::UserCookieChanged(String::new()))
                     .ok();
@@ -199,8 +647,96 @@ impl SimpleComponent for Settings {
             SettingsInput::ReloadAll => {
                 self.restore_on_start = self.settings.boolean(keys::RESTORE_ON_START);
                 self.auto_play_on_restore = self.settings.boolean(keys::AUTO_PLAY_ON_RESTORE);
+                self.track_notifications = self.settings.boolean(keys::TRACK_NOTIFICATIONS);
+                self.report_plays = self.settings.boolean(keys::REPORT_PLAYS);
+                self.run_in_background = self.settings.boolean(keys::RUN_IN_BACKGROUND);
+                self.sleep_fade_seconds = self.settings.uint(keys::SLEEP_FADE_SECONDS);
+                self.visualizer_style = visualizer_style_index(&self.settings);
+                self.visualizer_beat_background =
+                    self.settings.boolean(keys::VISUALIZER_BEAT_BACKGROUND);
                 self.cookie = self.settings.string(keys::COOKIE).to_string();
+                self.scrobble = ScrobbleConfig::from_settings(&self.settings);
+                self.image_cache_size = ImageManager::global().disk_usage();
             }
+            SettingsInput::ShortcutCaptured { action, accels } => {
+                shortcuts::save_custom_accel(action, accels);
+                self.refresh_shortcuts();
+            }
+            SettingsInput::ResetShortcuts => {
+                shortcuts::reset_custom_accels();
+                self.refresh_shortcuts();
+            }
+            SettingsInput::OutputDevicesChanged(list) => {
+                self.audio_effects
+                    .emit(AudioEffectsMsg::OutputDevicesChanged(list));
+            }
+        }
+    }
+}
+
+impl Settings {
+    /// 重新注册快捷键并刷新快捷键页的按键显示
+    fn refresh_shortcuts(&self) {
+        shortcuts::apply_accels(&relm4::main_adw_application());
+        for (action, label) in &self.shortcut_labels {
+            label.set_accelerator(&shortcuts::current_accels(action).join(" "));
         }
     }
 }
+
+/// 弹出“按下新快捷键”对话框：Esc 取消，Backspace 禁用该快捷键
+fn present_capture_dialog(
+    parent: &adw::ActionRow,
+    action: &'static str,
+    title: &str,
+    input: relm4::Sender<SettingsInput>,
+) {
+    let dialog = adw::AlertDialog::new(
+        Some("设置快捷键"),
+        Some(&format!(
+            "为「{}」按下新的组合键\nEsc 取消，Backspace 禁用该快捷键",
+            title
+        )),
+    );
+    dialog.add_response("cancel", "取消");
+    dialog.set_close_response("cancel");
+
+    let controller = gtk::EventControllerKey::new();
+    controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    controller.connect_key_pressed(glib::clone!(
+        #[weak]
+        dialog,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, keyval, _, state| {
+            let accels = match keyval {
+                gdk::Key::Escape => {
+                    dialog.close();
+                    return glib::Propagation::Stop;
+                }
+                gdk::Key::BackSpace => Vec::new(),
+                // 单独按下修饰键时继续等待
+                gdk::Key::Shift_L
+                | gdk::Key::Shift_R
+                | gdk::Key::Control_L
+                | gdk::Key::Control_R
+                | gdk::Key::Alt_L
+                | gdk::Key::Alt_R
+                | gdk::Key::Super_L
+                | gdk::Key::Super_R
+                | gdk::Key::Meta_L
+                | gdk::Key::Meta_R
+                | gdk::Key::ISO_Level3_Shift => return glib::Propagation::Proceed,
+                _ => {
+                    let mods = state & gtk::accelerator_get_default_mod_mask();
+                    vec![gtk::accelerator_name(keyval.to_lower(), mods).to_string()]
+                }
+            };
+            let _ = input.send(SettingsInput::ShortcutCaptured { action, accels });
+            dialog.close();
+            glib::Propagation::Stop
+        }
+    ));
+    dialog.add_controller(controller);
+    dialog.present(Some(parent));
+}
@@ -0,0 +1,267 @@
+//! 键盘快捷键：动作清单、默认按键、用户自定义按键的持久化以及快捷键帮助窗口
+//!
+//! 除 `app.quit` 外，所有快捷键都挂在 `win` 动作组上（见 `window.rs` 的 `WindowActionGroup`）。
+//! 用户自定义的按键以 `a{sas}`（动作名 → 按键列表）保存在 GSettings 的 `custom-accels` 中，
+//! 没有自定义的动作使用 [`SHORTCUTS`] 里的默认按键。
+
+use std::collections::HashMap;
+
+use relm4::gtk::gio::prelude::SettingsExt;
+use relm4::gtk::glib::prelude::ToVariant;
+use relm4::gtk::prelude::{GtkApplicationExt, IsA};
+use relm4::gtk::{self, gdk, gio};
+
+use crate::APPLICATION_ID;
+
+const CUSTOM_ACCELS_KEY: &str = "custom-accels";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutGroup {
+    Playback,
+    Navigation,
+    General,
+}
+
+impl ShortcutGroup {
+    pub fn title(self) -> &'static str {
+        match self {
+            ShortcutGroup::Playback => "播放",
+            ShortcutGroup::Navigation => "导航",
+            ShortcutGroup::General => "通用",
+        }
+    }
+
+    pub const ALL: [ShortcutGroup; 3] = [
+        ShortcutGroup::Playback,
+        ShortcutGroup::Navigation,
+        ShortcutGroup::General,
+    ];
+}
+
+pub struct ShortcutSpec {
+    /// 完整动作名，如 `win.play-pause`
+    pub action: &'static str,
+    pub title: &'static str,
+    pub group: ShortcutGroup,
+    pub default_accels: &'static [&'static str],
+}
+
+pub const SHORTCUTS: &[ShortcutSpec] = &[
+    ShortcutSpec {
+        action: "win.play-pause",
+        title: "播放 / 暂停",
+        group: ShortcutGroup::Playback,
+        default_accels: &["space"],
+    },
+    ShortcutSpec {
+        action: "win.seek-forward",
+        title: "快进 5 秒",
+        group: ShortcutGroup::Playback,
+        default_accels: &["Right"],
+    },
+    ShortcutSpec {
+        action: "win.seek-backward",
+        title: "快退 5 秒",
+        group: ShortcutGroup::Playback,
+        default_accels: &["Left"],
+    },
+    ShortcutSpec {
+        action: "win.volume-up",
+        title: "增大音量",
+        group: ShortcutGroup::Playback,
+        default_accels: &["Up"],
+    },
+    ShortcutSpec {
+        action: "win.volume-down",
+        title: "减小音量",
+        group: ShortcutGroup::Playback,
+        default_accels: &["Down"],
+    },
+    ShortcutSpec {
+        action: "win.next",
+        title: "下一首",
+        group: ShortcutGroup::Playback,
+        default_accels: &["<Ctrl>Right"],
+    },
+    ShortcutSpec {
+        action: "win.previous",
+        title: "上一首",
+        group: ShortcutGroup::Playback,
+        default_accels: &["<Ctrl>Left"],
+    },
+    ShortcutSpec {
+        action: "win.like",
+        title: "喜欢 / 取消喜欢当前歌曲",
+        group: ShortcutGroup::Playback,
+        default_accels: &["<Ctrl>L"],
+    },
+    ShortcutSpec {
+        action: "win.search",
+        title: "搜索",
+        group: ShortcutGroup::Navigation,
+        default_accels: &["<Ctrl>F"],
+    },
+    ShortcutSpec {
+        action: "win.go-back",
+        title: "返回",
+        group: ShortcutGroup::Navigation,
+        default_accels: &["<Alt>Left"],
+    },
+    ShortcutSpec {
+        action: "win.toggle-fullscreen",
+        title: "全屏歌词",
+        group: ShortcutGroup::Navigation,
+        default_accels: &["F11"],
+    },
+    ShortcutSpec {
+        action: "win.toggle-sidebar",
+        title: "显示 / 隐藏侧栏",
+        group: ShortcutGroup::Navigation,
+        default_accels: &["<Ctrl>K"],
+    },
+    ShortcutSpec {
+        action: "win.show-shortcuts",
+        title: "键盘快捷键",
+        group: ShortcutGroup::General,
+        default_accels: &["<Ctrl>question"],
+    },
+    ShortcutSpec {
+        action: "win.close",
+        title: "关闭窗口",
+        group: ShortcutGroup::General,
+        default_accels: &["<Ctrl>W"],
+    },
+    ShortcutSpec {
+        action: "app.quit",
+        title: "退出",
+        group: ShortcutGroup::General,
+        default_accels: &["<Ctrl>Q"],
+    },
+];
+
+fn load_custom_accels(settings: &gio::Settings) -> HashMap<String, Vec<String>> {
+    settings
+        .value(CUSTOM_ACCELS_KEY)
+        .get::<HashMap<String, Vec<String>>>()
+        .unwrap_or_default()
+}
+
+/// 某个动作当前生效的按键（自定义优先，否则为默认值）
+pub fn current_accels(action: &str) -> Vec<String> {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    resolve_accels(&load_custom_accels(&settings), action)
+}
+
+fn resolve_accels(custom: &HashMap<String, Vec<String>>, action: &str) -> Vec<String> {
+    if let Some(accels) = custom.get(action) {
+        return accels.clone();
+    }
+    SHORTCUTS
+        .iter()
+        .find(|s| s.action == action)
+        .map(|s| s.default_accels.iter().map(|a| a.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// 按键是否不带 Ctrl / Alt / Super 修饰（空格、方向键等）。
+///
+/// 应用级快捷键在捕获阶段处理，这类按键会抢走输入框的输入，
+/// 所以窗口在输入框获得焦点时要临时禁用绑定了它们的动作。
+pub fn is_plain_accel(accel: &str) -> bool {
+    let modifiers = gdk::ModifierType::CONTROL_MASK
+        | gdk::ModifierType::ALT_MASK
+        | gdk::ModifierType::SUPER_MASK
+        | gdk::ModifierType::META_MASK;
+    gtk::accelerator_parse(accel).is_some_and(|(_, mods)| !mods.intersects(modifiers))
+}
+
+/// 当前绑定了无修饰单键的动作
+pub fn plain_key_actions() -> Vec<&'static str> {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    let custom = load_custom_accels(&settings);
+    SHORTCUTS
+        .iter()
+        .filter(|spec| {
+            resolve_accels(&custom, spec.action)
+                .iter()
+                .any(|accel| is_plain_accel(accel))
+        })
+        .map(|spec| spec.action)
+        .collect()
+}
+
+/// 把所有快捷键（含自定义）注册到应用上，启动时和修改后调用
+pub fn apply_accels(app: &impl IsA<gtk::Application>) {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    let custom = load_custom_accels(&settings);
+    for spec in SHORTCUTS {
+        let accels = resolve_accels(&custom, spec.action);
+        let accels: Vec<&str> = accels.iter().map(String::as_str).collect();
+        app.set_accels_for_action(spec.action, &accels);
+    }
+}
+
+/// 保存某个动作的自定义按键；空列表表示禁用该快捷键
+pub fn save_custom_accel(action: &str, accels: Vec<String>) {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    let mut custom = load_custom_accels(&settings);
+    custom.insert(action.to_string(), accels);
+    if let Err(e) = settings.set_value(CUSTOM_ACCELS_KEY, &custom.to_variant()) {
+        log::error!("保存自定义快捷键失败: {e}");
+    }
+}
+
+/// 清除全部自定义按键，恢复默认
+pub fn reset_custom_accels() {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    settings.reset(CUSTOM_ACCELS_KEY);
+}
+
+/// 构建快捷键帮助窗口（按当前生效的按键）
+#[allow(deprecated)]
+pub fn build_shortcuts_window(parent: &impl IsA<gtk::Window>) -> gtk::ShortcutsWindow {
+    let settings = gio::Settings::new(APPLICATION_ID);
+    let custom = load_custom_accels(&settings);
+
+    let section = gtk::ShortcutsSection::builder()
+        .section_name("shortcuts")
+        .max_height(12)
+        .build();
+    for group in ShortcutGroup::ALL {
+        let shortcuts_group = gtk::ShortcutsGroup::builder().title(group.title()).build();
+        for spec in SHORTCUTS.iter().filter(|s| s.group == group) {
+            let accels = resolve_accels(&custom, spec.action);
+            if accels.is_empty() {
+                continue;
+            }
+            let shortcut = gtk::ShortcutsShortcut::builder()
+                .title(spec.title)
+                .accelerator(accels.join(" "))
+                .build();
+            shortcuts_group.add_shortcut(&shortcut);
+        }
+        section.add_group(&shortcuts_group);
+    }
+
+    let window = gtk::ShortcutsWindow::builder()
+        .modal(true)
+        .transient_for(parent)
+        .build();
+    window.add_section(&section);
+    window
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_accels_override_defaults() {
+        let mut custom = HashMap::new();
+        custom.insert("win.like".to_string(), vec!["<Ctrl>J".to_string()]);
+        custom.insert("win.search".to_string(), Vec::new());
+        assert_eq!(resolve_accels(&custom, "win.like"), vec!["<Ctrl>J"]);
+        assert!(resolve_accels(&custom, "win.search").is_empty());
+        assert_eq!(resolve_accels(&custom, "win.next"), vec!["<Ctrl>Right"]);
+    }
+}
@@ -6,7 +6,7 @@ use relm4::prelude::*;
 use relm4::{ComponentParts, ComponentSender, adw, gtk};
 
 use crate::api::Artist;
-use crate::player::messages::{PlaybackState, PlayerCommand, PlayerEvent};
+use crate::player::messages::{NowPlaying, PlaybackState, PlayerCommand, PlayerEvent};
 use crate::ui::lyric::{LyricPage, LyricsMsg, LyricsOutput};
 use crate::ui::player::{PlayerPage, PlayerPageMsg, PlayerPageOutput};
 use crate::ui::queue::{QueueMsg, QueuePage, QueuePageOutput};
@@ -18,6 +18,8 @@ pub struct Sidebar {
     player_page: Controller<PlayerPage>,
     lyrics_page: Controller<LyricPage>,
     queue_page: Controller<QueuePage>,
+    /// 正在播放 MV：进度来自视频，不驱动歌词
+    mv_active: bool,
 }
 
 // 在 window.rs 或单独的 route.rs 里定义
@@ -42,6 +44,7 @@ pub enum SidebarOutput {
     NavigateTo(AppRoute),
     OpenArtistDialog(Vec<Artist>),
     CollectSong(u64),
+    ShowToast(String),
 }
 
 #[relm4::component(pub)]
@@ -142,6 +145,7 @@ impl SimpleComponent for Sidebar {
             player_page: player_page,
             lyrics_page: lyric_page,
             queue_page: queue_page,
+            mv_active: false,
         };
 
         let widgets = view_output!();
@@ -262,6 +266,31 @@ impl SimpleComponent for Sidebar {
                     PlayerPageOutput::CollectSong(id) => {
                         sender.output(SidebarOutput::CollectSong(id)).ok();
                     }
+                    PlayerPageOutput::ShowToast(msg) => {
+                        sender.output(SidebarOutput::ShowToast(msg)).ok();
+                    }
+                    PlayerPageOutput::SetSleepTimer { mode, fade_secs } => {
+                        sender
+                            .output(SidebarOutput::PlayerCommand(PlayerCommand::SetSleepTimer {
+                                mode,
+                                fade_secs,
+                            }))
+                            .ok();
+                    }
+                    PlayerPageOutput::CancelSleepTimer => {
+                        sender
+                            .output(SidebarOutput::PlayerCommand(
+                                PlayerCommand::CancelSleepTimer,
+                            ))
+                            .ok();
+                    }
+                    PlayerPageOutput::SetOutputDevice(name) => {
+                        sender
+                            .output(SidebarOutput::PlayerCommand(
+                                PlayerCommand::SetOutputDevice(name),
+                            ))
+                            .ok();
+                    }
                 }
             }
 
@@ -277,8 +306,16 @@ impl SimpleComponent for Sidebar {
                         duration: duration,
                     });
 
-                    self.lyrics_page.emit(LyricsMsg::GstTick(position));
+                    if !self.mv_active {
+                        self.lyrics_page.emit(LyricsMsg::GstTick(position));
+                    }
                 }
+                PlayerEvent::NowPlayingChanged(now_playing) => {
+                    self.mv_active = matches!(now_playing, NowPlaying::Mv(_));
+                    self.player_page
+                        .emit(PlayerPageMsg::NowPlayingChanged(now_playing));
+                }
+                PlayerEvent::MvControl(_) => {} // 由 Window 转给 MV 管线
                 PlayerEvent::TrackChanged {
                     song,
                     current_index,
@@ -293,7 +330,32 @@ impl SimpleComponent for Sidebar {
                 }
                 PlayerEvent::EndOfQueue => {}
                 PlayerEvent::Error(_) => {}
+                PlayerEvent::TrackUnplayable { song_id, reason } => {
+                    self.queue_page
+                        .emit(QueueMsg::MarkUnplayable { song_id, reason });
+                }
                 PlayerEvent::ShowToast(_) => {} // 由 Window 处理
+                PlayerEvent::RaiseWindow | PlayerEvent::QuitRequested => {}
+                PlayerEvent::SleepTimerChanged(status) => {
+                    self.player_page
+                        .emit(PlayerPageMsg::SleepTimerChanged(status));
+                }
+                PlayerEvent::Spectrum(bands) => {
+                    self.player_page.emit(PlayerPageMsg::Spectrum(bands));
+                }
+                PlayerEvent::OutputDevicesChanged(list) => {
+                    self.player_page
+                        .emit(PlayerPageMsg::OutputDevicesChanged(list));
+                }
+                PlayerEvent::VolumeChanged(volume) => {
+                    self.player_page.emit(PlayerPageMsg::VolumeChanged(volume));
+                }
+                PlayerEvent::PlayModeChanged(mode) => {
+                    self.player_page.emit(PlayerPageMsg::SetPlayMode(mode));
+                }
+                PlayerEvent::LikeChanged { liked, .. } => {
+                    self.player_page.emit(PlayerPageMsg::SetLiked(liked));
+                }
                 PlayerEvent::SetQueue {
                     tracks,
                     playlist,
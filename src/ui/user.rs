@@ -0,0 +1,380 @@
+use relm4::gtk::prelude::*;
+use relm4::prelude::FactoryVecDeque;
+use std::sync::Arc;
+
+use relm4::{
+    Component, ComponentController, ComponentParts, ComponentSender, Controller, RelmWidgetExt, gtk,
+};
+
+use crate::api::{
+    Playlist, Song, UserDetails, get_user_detail, get_user_playlist_collected,
+    get_user_playlist_created,
+};
+use crate::ui::components::image::AsyncImage;
+use crate::ui::components::play_ranking::{PlayRanking, PlayRankingMsg, PlayRankingOutput};
+use crate::ui::components::playlist_card::{PlaylistCard, PlaylistCardInit, PlaylistCardOutput};
+use crate::ui::model::PlaylistType;
+
+#[tracker::track]
+pub struct UserPage {
+    #[do_not_track]
+    user_id: u64,
+    #[do_not_track]
+    user_details: Option<UserDetails>,
+    is_loading: bool,
+    has_created: bool,
+    has_collected: bool,
+    #[do_not_track]
+    created_playlists: FactoryVecDeque<PlaylistCard>,
+    #[do_not_track]
+    collected_playlists: FactoryVecDeque<PlaylistCard>,
+    #[do_not_track]
+    ranking: Controller<PlayRanking>,
+}
+
+#[derive(Debug)]
+pub enum UserMsg {
+    LoadUser(u64),
+    CardAction(PlaylistCardOutput),
+    RankingAction(PlayRankingOutput),
+}
+
+#[derive(Debug)]
+pub enum UserCmdMsg {
+    UserDetailLoaded(UserDetails),
+    CreatedLoaded(Vec<Playlist>),
+    CollectedLoaded(Vec<Playlist>),
+    LoadFailed,
+}
+
+#[derive(Debug)]
+pub enum UserPageOutput {
+    OpenPlaylistDetail(PlaylistType),
+    Playlist(PlaylistType),
+    PlayTracks(Arc<Vec<Song>>, usize),
+}
+
+#[relm4::component(pub)]
+impl Component for UserPage {
+    type Init = u64;
+    type Input = UserMsg;
+    type CommandOutput = UserCmdMsg;
+    type Output = UserPageOutput;
+
+    view! {
+        #[root]
+        gtk::Stack {
+            set_transition_type: gtk::StackTransitionType::Crossfade,
+            #[watch]
+            set_visible_child_name: if model.is_loading { "loading" } else { "content" },
+
+            add_named[Some("loading")] = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_halign: gtk::Align::Center,
+                set_valign: gtk::Align::Center,
+                set_spacing: 16,
+
+                gtk::Spinner {
+                    set_spinning: true,
+                    set_width_request: 48,
+                    set_height_request: 48,
+                },
+                gtk::Label {
+                    set_label: "正在加载用户信息...",
+                    add_css_class: "dim-label",
+                }
+            },
+
+            add_named[Some("content")] = &gtk::ScrolledWindow {
+                set_hscrollbar_policy: gtk::PolicyType::Never,
+                set_vexpand: true,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 24,
+                    set_margin_all: 32,
+
+                    // 头像、昵称、签名与关注/粉丝数
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 16,
+
+                        AsyncImage {
+                            set_width_request: 160,
+                            set_height_request: 160,
+                            #[watch]
+                            set_url: model.user_details.as_ref()
+                                .map(|d| format!("{}?param=200y200", d.avatar_url))
+                                .unwrap_or_default(),
+                            set_placeholder_icon: "avatar-default-symbolic",
+                            set_corner_radius: 80.0,
+                        },
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_valign: gtk::Align::Center,
+                            set_spacing: 4,
+
+                            gtk::Label {
+                                #[watch]
+                                set_label: model.user_details.as_ref()
+                                    .map(|d| d.name.as_str())
+                                    .unwrap_or_default(),
+                                set_xalign: 0.0,
+                                add_css_class: "title-1",
+                            },
+                            gtk::Label {
+                                #[watch]
+                                set_label: &model.user_details.as_ref()
+                                    .map(|d| format!("Lv.{}", d.level))
+                                    .unwrap_or_default(),
+                                set_xalign: 0.0,
+                                add_css_class: "caption-heading",
+                            },
+                            gtk::Label {
+                                #[watch]
+                                set_label: model.user_details.as_ref()
+                                    .map(|d| d.signature.as_str())
+                                    .filter(|s| !s.is_empty())
+                                    .unwrap_or("这个人很懒，什么都没有留下"),
+                                set_xalign: 0.0,
+                                set_wrap: true,
+                                set_wrap_mode: gtk::pango::WrapMode::WordChar,
+                                set_max_width_chars: 50,
+                                set_lines: 2,
+                                set_ellipsize: gtk::pango::EllipsizeMode::End,
+                                add_css_class: "dim-label",
+                            },
+                            gtk::Box {
+                                set_orientation: gtk::Orientation::Horizontal,
+                                set_spacing: 8,
+
+                                gtk::Label {
+                                    #[watch]
+                                    set_label: &model.user_details.as_ref()
+                                        .map(|d| format!("关注: {}", d.follows))
+                                        .unwrap_or_else(|| "关注: --".to_string()),
+                                    set_xalign: 0.0,
+                                    add_css_class: "dim-label",
+                                },
+
+                                gtk::Label {
+                                    #[watch]
+                                    set_label: &model.user_details.as_ref()
+                                        .map(|d| format!("粉丝: {}", d.followeds))
+                                        .unwrap_or_else(|| "粉丝: --".to_string()),
+                                    set_xalign: 0.0,
+                                    add_css_class: "dim-label",
+                                }
+                            }
+                        }
+                    },
+
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 16,
+
+                        gtk::StackSwitcher {
+                            set_stack: Some(&view_stack),
+                            set_halign: gtk::Align::Start,
+                        },
+
+                        #[name(view_stack)]
+                        gtk::Stack {
+                            set_vexpand: true,
+                            set_transition_type: gtk::StackTransitionType::Crossfade,
+
+                            // ----- Tab 1: 创建的歌单 -----
+                            add_titled[Some("created"), "创建的歌单"] = &gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_margin_top: 12,
+
+                                gtk::Label {
+                                    #[watch]
+                                    set_visible: !model.has_created,
+                                    set_label: "还没有创建任何歌单",
+                                    set_margin_top: 24,
+                                    add_css_class: "dim-label",
+                                },
+
+                                #[local_ref]
+                                created_flow_box -> gtk::FlowBox {
+                                    set_orientation: gtk::Orientation::Horizontal,
+                                    set_homogeneous: true,
+                                    set_row_spacing: 16,
+                                    set_column_spacing: 16,
+                                    set_min_children_per_line: 1,
+                                    set_max_children_per_line: 100,
+                                    set_selection_mode: gtk::SelectionMode::None,
+                                }
+                            },
+
+                            // ----- Tab 2: 收藏的歌单 -----
+                            add_titled[Some("collected"), "收藏的歌单"] = &gtk::Box {
+                                set_orientation: gtk::Orientation::Vertical,
+                                set_margin_top: 12,
+
+                                gtk::Label {
+                                    #[watch]
+                                    set_visible: !model.has_collected,
+                                    set_label: "还没有收藏任何歌单",
+                                    set_margin_top: 24,
+                                    add_css_class: "dim-label",
+                                },
+
+                                #[local_ref]
+                                collected_flow_box -> gtk::FlowBox {
+                                    set_orientation: gtk::Orientation::Horizontal,
+                                    set_homogeneous: true,
+                                    set_row_spacing: 16,
+                                    set_column_spacing: 16,
+                                    set_min_children_per_line: 1,
+                                    set_max_children_per_line: 100,
+                                    set_selection_mode: gtk::SelectionMode::None,
+                                }
+                            },
+
+                            // ----- Tab 3: 听歌排行 -----
+                            #[local_ref]
+                            add_titled[Some("ranking"), "听歌排行"] = ranking_widget -> gtk::Box {},
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn init(
+        user_id: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let created_playlists = FactoryVecDeque::builder()
+            .launch(gtk::FlowBox::default())
+            .forward(sender.input_sender(), UserMsg::CardAction);
+        let collected_playlists = FactoryVecDeque::builder()
+            .launch(gtk::FlowBox::default())
+            .forward(sender.input_sender(), UserMsg::CardAction);
+
+        let model = Self {
+            user_id,
+            user_details: None,
+            is_loading: true,
+            has_created: false,
+            has_collected: false,
+            created_playlists,
+            collected_playlists,
+            ranking: PlayRanking::builder()
+                .launch(user_id)
+                .forward(sender.input_sender(), UserMsg::RankingAction),
+            tracker: 0,
+        };
+
+        let created_flow_box = model.created_playlists.widget();
+        let collected_flow_box = model.collected_playlists.widget();
+        let ranking_widget = model.ranking.widget();
+        let widgets = view_output!();
+
+        sender.input(UserMsg::LoadUser(user_id));
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        self.reset();
+        match message {
+            UserMsg::LoadUser(user_id) => {
+                // 排行组件创建时已按初始用户加载过
+                if user_id != self.user_id {
+                    self.ranking.emit(PlayRankingMsg::Load(user_id));
+                }
+                self.user_id = user_id;
+                self.set_is_loading(true);
+                self.user_details = None;
+
+                sender.command(move |out, _shutdown| async move {
+                    match get_user_detail(user_id).await {
+                        Ok(details) => {
+                            let _ = out.send(UserCmdMsg::UserDetailLoaded(details));
+                        }
+                        Err(_) => {
+                            let _ = out.send(UserCmdMsg::LoadFailed);
+                        }
+                    }
+                });
+                sender.command(move |out, _shutdown| async move {
+                    if let Ok(playlists) = get_user_playlist_created(user_id).await {
+                        let _ = out.send(UserCmdMsg::CreatedLoaded(playlists));
+                    }
+                });
+                sender.command(move |out, _shutdown| async move {
+                    if let Ok(playlists) = get_user_playlist_collected(user_id).await {
+                        let _ = out.send(UserCmdMsg::CollectedLoaded(playlists));
+                    }
+                });
+            }
+            UserMsg::CardAction(output) => match output {
+                PlaylistCardOutput::Clicked(id) => {
+                    sender
+                        .output(UserPageOutput::OpenPlaylistDetail(PlaylistType::Playlist(
+                            id,
+                        )))
+                        .ok();
+                }
+                PlaylistCardOutput::ClickedPlaylist(id) => {
+                    sender
+                        .output(UserPageOutput::Playlist(PlaylistType::Playlist(id)))
+                        .ok();
+                }
+            },
+            UserMsg::RankingAction(PlayRankingOutput::Play { songs, start_index }) => {
+                sender
+                    .output(UserPageOutput::PlayTracks(songs, start_index))
+                    .ok();
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        _sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        self.reset();
+        match message {
+            UserCmdMsg::UserDetailLoaded(details) => {
+                if details.id != self.user_id {
+                    return;
+                }
+                self.user_details = Some(details);
+                self.set_is_loading(false);
+            }
+            UserCmdMsg::CreatedLoaded(playlists) => {
+                self.set_has_created(!playlists.is_empty());
+                fill_playlists(&mut self.created_playlists, &playlists);
+            }
+            UserCmdMsg::CollectedLoaded(playlists) => {
+                self.set_has_collected(!playlists.is_empty());
+                fill_playlists(&mut self.collected_playlists, &playlists);
+            }
+            UserCmdMsg::LoadFailed => {
+                self.set_is_loading(false);
+            }
+        }
+    }
+}
+
+fn fill_playlists(factory: &mut FactoryVecDeque<PlaylistCard>, playlists: &[Playlist]) {
+    let mut guard = factory.guard();
+    guard.clear();
+    for playlist in playlists {
+        guard.push_back(PlaylistCardInit {
+            id: playlist.id,
+            cover_url: format!("{}?param=200y200", playlist.cover_url),
+            title: playlist.name.clone(),
+            subtitle: None,
+            show_play_button: true,
+        });
+    }
+}
@@ -1,48 +1,82 @@
 //! Main component of the application.
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Mutex;
 
 use flume::Sender;
-use relm4::actions::{AccelsPlus, RelmAction, RelmActionGroup};
+use relm4::actions::{RelmAction, RelmActionGroup};
 use relm4::adw::prelude::{AdwApplicationWindowExt, AdwDialogExt};
-use relm4::gtk::gio::prelude::SettingsExt;
-use relm4::gtk::prelude::{BoxExt, GtkWindowExt, OrientableExt, WidgetExt};
+use relm4::gtk::gio::prelude::{
+    ActionExt, ActionMapExt, ApplicationExt, SettingsExt, SettingsExtManual,
+};
+use relm4::gtk::prelude::{BoxExt, GtkWindowExt, ObjectExt, OrientableExt, WidgetExt};
 use relm4::gtk::{self, Box, Orientation, Stack, StackTransitionType, gio, glib};
 use relm4::{
-    ComponentController, ComponentParts, ComponentSender, Controller, SimpleComponent, adw,
+    ComponentController, ComponentParts, ComponentSender, Controller, MessageBroker,
+    SimpleComponent, adw,
 };
 
 use relm4::Component;
 
 use crate::APPLICATION_ID;
-use crate::api::{Artist, Playlist, UserInfo, get_user_info};
+use crate::api::{Artist, Playlist, UserInfo, cache, get_song_detail, get_user_info};
 use crate::db::{Db, SessionState};
-use crate::player::messages::{PlayerCommand, PlayerEvent};
+use crate::player::messages::{NowPlaying, PlayerCommand, PlayerEvent};
 use crate::player::{PlayerEventBus, PlayerFacade};
+use crate::remote::{self, RemoteCommand};
+use crate::scrobble;
 use crate::ui::artist::{ArtistPage, ArtistPageOutput};
 use crate::ui::collection::{Collection, CollectionMsg, CollectionOutput};
-use crate::ui::comments::CommentsPage;
+use crate::ui::comments::{CommentsOutput, CommentsPage};
 use crate::ui::components::artist_dialog::ArtistDialog;
 use crate::ui::components::collect_dialog::CollectDialog;
+use crate::ui::components::spectrum::{self, SpectrumStyle};
+use crate::ui::explore::more::{ExploreMoreOutput, ExploreMorePage};
 use crate::ui::explore::{Explore, ExploreOutput};
 use crate::ui::fullscreen_lyric::{FullscreenLyricMsg, FullscreenLyricOutput, FullscreenLyricPage};
 use crate::ui::header::{Header, HeaderMsg, HeaderOutput};
 use crate::ui::home::{Home, HomeOutput};
 use crate::ui::model::{PlaySource, PlaylistType};
-use crate::ui::mv_player::{MvPlayerOutput, MvPlayerPage};
+use crate::ui::mv_pip::{MvPipOutput, MvPipWindow};
+use crate::ui::mv_playback::MvPlayback;
+use crate::ui::mv_player::{MvPlayerMsg, MvPlayerOutput, MvPlayerPage};
+use crate::ui::notification;
 use crate::ui::playlist_detail::{PlaylistDetail, PlaylistDetailOutput};
 use crate::ui::route::{AppRoute, DetailCtrl};
 use crate::ui::search::{Search, SearchMsg, SearchOutput};
-use crate::ui::setting::{Settings, SettingsOutput};
+use crate::ui::setting::{Settings, SettingsInput, SettingsOutput};
+use crate::ui::shortcuts;
 use crate::ui::sidebar::{Sidebar, SidebarMsg, SidebarOutput};
+use crate::ui::user::{UserPage, UserPageOutput};
+use crate::ui::year_report::{YearReportOutput, YearReportPage};
 use crate::utils::animate::Fade;
+use crate::utils::share;
 
 relm4::new_action_group!(pub WindowActionGroup, "win");
 relm4::new_stateless_action!(pub CloseAction, WindowActionGroup, "close");
 relm4::new_stateless_action!(pub ToggleSidebarAction, WindowActionGroup, "toggle-sidebar");
+relm4::new_stateless_action!(pub PlayPauseAction, WindowActionGroup, "play-pause");
+relm4::new_stateless_action!(pub SeekForwardAction, WindowActionGroup, "seek-forward");
+relm4::new_stateless_action!(pub SeekBackwardAction, WindowActionGroup, "seek-backward");
+relm4::new_stateless_action!(pub VolumeUpAction, WindowActionGroup, "volume-up");
+relm4::new_stateless_action!(pub VolumeDownAction, WindowActionGroup, "volume-down");
+relm4::new_stateless_action!(pub NextAction, WindowActionGroup, "next");
+relm4::new_stateless_action!(pub PreviousAction, WindowActionGroup, "previous");
+relm4::new_stateless_action!(pub LikeAction, WindowActionGroup, "like");
+relm4::new_stateless_action!(pub SearchAction, WindowActionGroup, "search");
+relm4::new_stateless_action!(pub GoBackAction, WindowActionGroup, "go-back");
+relm4::new_stateless_action!(pub ToggleFullscreenAction, WindowActionGroup, "toggle-fullscreen");
+relm4::new_stateless_action!(pub ShowShortcutsAction, WindowActionGroup, "show-shortcuts");
 
 /// 全屏歌词页淡入/淡出动画时长（ms）
 const FULLSCREEN_FADE_MS: u64 = 350;
+/// 方向键快进/快退的步长（ms）
+pub(crate) const SEEK_STEP_MS: i64 = 5_000;
+/// 方向键调节音量的步长
+pub(crate) const VOLUME_STEP: f64 = 0.05;
+/// 退出时等待播放线程停止管线的最长时间
+const SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
 
 #[derive(Debug)]
 pub enum WindowMsg {
@@ -61,15 +95,37 @@ pub enum WindowMsg {
     UserInfoLoaded(UserInfo),
 
     CollectSong(u64),
+    /// 歌手关注状态变化：刷新收藏页的关注歌手
+    FollowedArtistsChanged,
+    /// 自建歌单增删改：刷新收藏页的我的歌单
+    UserPlaylistsChanged,
+    /// 当前详情页的歌单已被删除：提示并返回上一页
+    PlaylistDeleted(String),
 
     ShowToast(String),
 
+    /// 空格：MV 页时控制视频，否则控制音乐
+    TogglePlayPause,
     /// Ctrl+K：切换侧栏显示/隐藏
     ToggleSidebar,
+    /// 左/右方向键：相对当前位置快退/快进（ms）
+    SeekBy(i64),
+    /// 上/下方向键：调节音量
+    ChangeVolume(f64),
+    /// Ctrl+L：喜欢/取消喜欢当前歌曲
+    ToggleLikeCurrent,
+    /// Ctrl+F：聚焦搜索框
+    FocusSearch,
+    /// Ctrl+?：显示快捷键帮助窗口
+    ShowShortcuts,
     /// header 全屏按钮：进入/退出全屏歌词页
     ToggleFullscreen,
     /// 全屏页淡出结束后的清理信号
     FullscreenFadedOut,
+    /// MV 页转入画中画；管线不能跨线程，经 `mv_pip_pending` 交接
+    OpenMvPip,
+    /// 画中画窗口输出
+    MvPipEvent(MvPipOutput),
     /// 在搜索页提交了搜索词
     /// 提交搜索（回车，默认单曲搜索）
     SearchSubmit(String),
@@ -77,8 +133,17 @@ pub enum WindowMsg {
     SearchSuggestQuery(String),
     /// 全屏歌词页输出
     FullscreenLyricEvent(FullscreenLyricOutput),
+    /// 来自命令行 / ncm:// 链接的远程控制
+    Remote(RemoteCommand),
+    /// 打开网易云分享链接（搜索框粘贴、命令行参数）
+    OpenShareLink(String),
+    /// Ctrl+Q / MPRIS Quit / 非后台模式下关闭窗口：保存会话、停止播放后退出
+    Quit,
 }
 
+/// 供命令行处理函数向主窗口发消息（见 `remote.rs`）
+pub static WINDOW_BROKER: MessageBroker<WindowMsg> = MessageBroker::new();
+
 pub struct Window {
     main_window: adw::ApplicationWindow,
     overlay_split_view: adw::OverlaySplitView,
@@ -124,8 +189,16 @@ pub struct Window {
     current_position: u64,
     /// 缓存当前歌曲时长
     current_duration: u64,
-    /// 进入 MV 页时暂停了音乐，离开时是否需要恢复
-    should_resume_music: bool,
+    /// 缓存当前音量（0.0 ~ 1.0）
+    current_volume: f64,
+    /// 缓存当前歌曲的喜欢状态
+    current_is_liked: bool,
+    /// 正在播放 MV（音乐已由播放器暂停保留）
+    mv_active: bool,
+    /// MV 画中画窗口；打开期间离开 MV 页视频继续播放
+    mv_pip: Option<Controller<MvPipWindow>>,
+    /// MV 页交给画中画的管线，处理 `OpenMvPip` 时取出
+    mv_pip_pending: Rc<RefCell<Option<Rc<MvPlayback>>>>,
 
     /// 上次播放会话镜像，用于持久化与启动恢复
     session: SessionState,
@@ -202,8 +275,7 @@ impl SimpleComponent for Window {
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let app = relm4::main_adw_application();
-        app.set_accelerators_for_action::<CloseAction>(&["<Ctrl>W"]);
-        app.set_accelerators_for_action::<ToggleSidebarAction>(&["<Ctrl>k"]);
+        shortcuts::apply_accels(&app);
 
         let mut action_group = RelmActionGroup::<WindowActionGroup>::new();
         let close_action = RelmAction::<CloseAction>::new_stateless(glib::clone!(
@@ -211,10 +283,105 @@ impl SimpleComponent for Window {
             root,
             move |_| root.close()
         ));
-        let window_sender = sender.input_sender().clone();
-        let toggle_sidebar_action = RelmAction::<ToggleSidebarAction>::new_stateless(move |_| {
-            let _ = window_sender.send(WindowMsg::ToggleSidebar);
+        // 其余动作都只是把对应的 WindowMsg 发回给自己
+        let send = |msg: fn() -> WindowMsg| {
+            let window_sender = sender.input_sender().clone();
+            move |_: &gio::SimpleAction| {
+                let _ = window_sender.send(msg());
+            }
+        };
+        let toggle_sidebar_action =
+            RelmAction::<ToggleSidebarAction>::new_stateless(send(|| WindowMsg::ToggleSidebar));
+        let play_pause_action =
+            RelmAction::<PlayPauseAction>::new_stateless(send(|| WindowMsg::TogglePlayPause));
+        let seek_forward_action = RelmAction::<SeekForwardAction>::new_stateless(send(|| {
+            WindowMsg::SeekBy(SEEK_STEP_MS)
+        }));
+        let seek_backward_action = RelmAction::<SeekBackwardAction>::new_stateless(send(|| {
+            WindowMsg::SeekBy(-SEEK_STEP_MS)
+        }));
+        let volume_up_action = RelmAction::<VolumeUpAction>::new_stateless(send(|| {
+            WindowMsg::ChangeVolume(VOLUME_STEP)
+        }));
+        let volume_down_action = RelmAction::<VolumeDownAction>::new_stateless(send(|| {
+            WindowMsg::ChangeVolume(-VOLUME_STEP)
+        }));
+        let next_action = RelmAction::<NextAction>::new_stateless(send(|| {
+            WindowMsg::PlayerCommandReceived(PlayerCommand::Next)
+        }));
+        let previous_action = RelmAction::<PreviousAction>::new_stateless(send(|| {
+            WindowMsg::PlayerCommandReceived(PlayerCommand::Previous)
+        }));
+        let like_action =
+            RelmAction::<LikeAction>::new_stateless(send(|| WindowMsg::ToggleLikeCurrent));
+        let search_action =
+            RelmAction::<SearchAction>::new_stateless(send(|| WindowMsg::FocusSearch));
+        let go_back_action = RelmAction::<GoBackAction>::new_stateless(send(|| WindowMsg::GoBack));
+        let toggle_fullscreen_action =
+            RelmAction::<ToggleFullscreenAction>::new_stateless(send(|| {
+                WindowMsg::ToggleFullscreen
+            }));
+        let show_shortcuts_action =
+            RelmAction::<ShowShortcutsAction>::new_stateless(send(|| WindowMsg::ShowShortcuts));
+
+        // 输入框获得焦点时，临时禁用绑定了无修饰单键（空格/方向键）的动作，避免吞掉输入
+        let plain_key_actions: Vec<(String, gio::SimpleAction)> = [
+            play_pause_action.gio_action(),
+            seek_forward_action.gio_action(),
+            seek_backward_action.gio_action(),
+            volume_up_action.gio_action(),
+            volume_down_action.gio_action(),
+            next_action.gio_action(),
+            previous_action.gio_action(),
+            like_action.gio_action(),
+            search_action.gio_action(),
+            go_back_action.gio_action(),
+            toggle_fullscreen_action.gio_action(),
+            toggle_sidebar_action.gio_action(),
+            show_shortcuts_action.gio_action(),
+        ]
+        .into_iter()
+        .map(|action| (format!("win.{}", action.name()), action.clone()))
+        .collect();
+        root.connect_focus_widget_notify(move |window| {
+            let typing = window
+                .focus()
+                .is_some_and(|w| w.is::<gtk::Text>() || w.is::<gtk::TextView>());
+            let plain = shortcuts::plain_key_actions();
+            for (name, action) in &plain_key_actions {
+                action.set_enabled(!(typing && plain.contains(&name.as_str())));
+            }
+        });
+
+        // 后台运行：关闭窗口只隐藏，播放器和 MPRIS 继续工作
+        let settings = gio::Settings::new(APPLICATION_ID);
+        settings
+            .bind("run-in-background", &root, "hide-on-close")
+            .build();
+        root.connect_close_request({
+            let window_sender = sender.input_sender().clone();
+            move |window| {
+                if window.hides_on_close() {
+                    return glib::Propagation::Proceed;
+                }
+                let _ = window_sender.send(WindowMsg::Quit);
+                glib::Propagation::Stop
+            }
+        });
+        // 再次启动应用或点击通知时把隐藏的窗口带回前台
+        app.connect_activate(glib::clone!(
+            #[weak]
+            root,
+            move |_| root.present()
+        ));
+        let quit_action = gio::SimpleAction::new("quit", None);
+        quit_action.connect_activate({
+            let window_sender = sender.input_sender().clone();
+            move |_, _| {
+                let _ = window_sender.send(WindowMsg::Quit);
+            }
         });
+        app.add_action(&quit_action);
 
         let loaded_user = UserInfo::load_from_disk();
         let user_arc = loaded_user.map(Arc::new);
@@ -227,6 +394,18 @@ impl SimpleComponent for Window {
         });
         action_group.add_action(close_action);
         action_group.add_action(toggle_sidebar_action);
+        action_group.add_action(play_pause_action);
+        action_group.add_action(seek_forward_action);
+        action_group.add_action(seek_backward_action);
+        action_group.add_action(volume_up_action);
+        action_group.add_action(volume_down_action);
+        action_group.add_action(next_action);
+        action_group.add_action(previous_action);
+        action_group.add_action(like_action);
+        action_group.add_action(search_action);
+        action_group.add_action(go_back_action);
+        action_group.add_action(toggle_fullscreen_action);
+        action_group.add_action(show_shortcuts_action);
         action_group.register_for_widget(&root);
 
         let sidebar =
@@ -239,6 +418,7 @@ impl SimpleComponent for Window {
                         WindowMsg::OpenArtistDialog(artists)
                     }
                     SidebarOutput::CollectSong(id) => WindowMsg::CollectSong(id),
+                    SidebarOutput::ShowToast(msg) => WindowMsg::ShowToast(msg),
                 });
 
         let header =
@@ -251,10 +431,11 @@ impl SimpleComponent for Window {
                     HeaderOutput::OpenSettings => WindowMsg::OpenSettings,
                     HeaderOutput::SearchSubmit(query) => WindowMsg::SearchSubmit(query),
                     HeaderOutput::SearchChanged(query) => WindowMsg::SearchSuggestQuery(query),
+                    HeaderOutput::OpenShareLink(text) => WindowMsg::OpenShareLink(text),
                 });
 
         let settings_dialog = Settings::builder()
-            .launch(())
+            .launch(db.clone())
             .forward(sender.input_sender(), |output| {
                 WindowMsg::SettingEventReceived(output)
             });
@@ -279,6 +460,7 @@ impl SimpleComponent for Window {
                         })
                     }
                     HomeOutput::NavigateToArtist(id) => WindowMsg::NavigateTo(AppRoute::Artist(id)),
+                    HomeOutput::ShowToast(msg) => WindowMsg::ShowToast(msg),
                     HomeOutput::PlayDirectTracks(songs) => {
                         WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
                             source: PlaySource::DirectTracks(Arc::new(songs)),
@@ -297,6 +479,13 @@ impl SimpleComponent for Window {
                     })
                 }
                 ExploreOutput::OpenMv(id) => WindowMsg::NavigateTo(AppRoute::Mv(id)),
+                ExploreOutput::PlayPlaylist(playlist_type) => {
+                    WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                        source: PlaySource::ById(playlist_type),
+                        start_index: 0,
+                    })
+                }
+                ExploreOutput::Navigate(route) => WindowMsg::NavigateTo(route),
             });
         let collection_ctrl = Collection::builder()
             .launch((default_user.clone(), db.clone()))
@@ -310,6 +499,15 @@ impl SimpleComponent for Window {
                         start_index: 0,
                     })
                 }
+                CollectionOutput::OpenArtist(id) => WindowMsg::NavigateTo(AppRoute::Artist(id)),
+                CollectionOutput::ShowToast(msg) => WindowMsg::ShowToast(msg),
+                CollectionOutput::PlayTracks(songs, start_index) => {
+                    WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                        source: PlaySource::DirectTracks(songs),
+                        start_index,
+                    })
+                }
+                CollectionOutput::OpenYearReport => WindowMsg::NavigateTo(AppRoute::YearReport),
             });
 
         let search_ctrl = Search::builder()
@@ -329,6 +527,33 @@ impl SimpleComponent for Window {
         let player_event_sender: relm4::Sender<PlayerEvent> = event_bus.create_sender().into();
         let player_cmd_tx = PlayerFacade::start(player_event_sender, db.clone());
 
+        // 频谱只在开启可视化或背景节拍律动时计算
+        let sync_spectrum = {
+            let player_cmd_tx = player_cmd_tx.clone();
+            move |settings: &gio::Settings| {
+                let enabled = SpectrumStyle::from_settings(settings) != SpectrumStyle::Off
+                    || settings.boolean(spectrum::BEAT_BACKGROUND_KEY);
+                let _ = player_cmd_tx.send(PlayerCommand::SetSpectrumEnabled(enabled));
+            }
+        };
+        sync_spectrum(&settings);
+        settings.connect_changed(None, move |settings, key| {
+            if key == spectrum::STYLE_KEY || key == spectrum::BEAT_BACKGROUND_KEY {
+                sync_spectrum(settings);
+            }
+        });
+
+        // 向网易云上报播放记录，计入听歌排行和每日推荐
+        let _ = player_cmd_tx.send(PlayerCommand::SetPlayReport(
+            settings.boolean("report-plays"),
+        ));
+        settings.connect_changed(Some("report-plays"), {
+            let player_cmd_tx = player_cmd_tx.clone();
+            move |settings, key| {
+                let _ = player_cmd_tx.send(PlayerCommand::SetPlayReport(settings.boolean(key)));
+            }
+        });
+
         // 启动时恢复上次播放（受设置开关控制，未登录时不恢复）
         if !cookie.is_empty() {
             let settings = gio::Settings::new(APPLICATION_ID);
@@ -349,6 +574,7 @@ impl SimpleComponent for Window {
                                 creator_id: 0,
                                 description: String::new(),
                                 play_count: 0,
+                                liked_songs: false,
                             },
                         });
                     }
@@ -365,6 +591,17 @@ impl SimpleComponent for Window {
             }
         });
 
+        // 切歌通知订阅 PlayerEvent
+        notification::start(
+            &relm4::main_adw_application(),
+            &root,
+            event_bus.subscribe(),
+            player_cmd_tx.clone(),
+        );
+
+        // 听歌记录同步订阅 PlayerEvent
+        scrobble::start(event_bus.subscribe(), db.clone());
+
         // Sidebar 订阅 PlayerEvent
         let sidebar_event_rx = event_bus.subscribe();
         let sidebar_sender = sidebar.sender().clone();
@@ -403,7 +640,11 @@ impl SimpleComponent for Window {
             current_is_playing: false,
             current_position: 0,
             current_duration: 0,
-            should_resume_music: false,
+            current_volume: 1.0,
+            current_is_liked: false,
+            mv_active: false,
+            mv_pip: None,
+            mv_pip_pending: Rc::default(),
             session: SessionState::default(),
         };
 
@@ -438,13 +679,6 @@ impl SimpleComponent for Window {
                 if self.current_route == route {
                     return;
                 }
-                // 离开 MV 页 → 恢复之前暂停的音乐
-                if matches!(self.current_route, AppRoute::Mv(_)) && self.should_resume_music {
-                    self.should_resume_music = false;
-                    if let Err(e) = self.player_cmd_tx.send(PlayerCommand::Resume) {
-                        log::error!("Cannot resume music: {}", e);
-                    }
-                }
                 match route {
                     AppRoute::Home | AppRoute::Explore | AppRoute::Collection => {
                         self.history.clear();
@@ -458,13 +692,6 @@ impl SimpleComponent for Window {
                 self.render_current_route(&sender);
             }
             WindowMsg::GoBack => {
-                // 离开 MV 页 → 恢复之前暂停的音乐
-                if matches!(self.current_route, AppRoute::Mv(_)) && self.should_resume_music {
-                    self.should_resume_music = false;
-                    if let Err(e) = self.player_cmd_tx.send(PlayerCommand::Resume) {
-                        log::error!("Cannot resume music: {}", e);
-                    }
-                }
                 if let Some(prev_route) = self.history.pop() {
                     self.current_route = prev_route;
                     self.render_current_route(&sender);
@@ -476,15 +703,18 @@ impl SimpleComponent for Window {
                     PlayerEvent::TimeUpdated { position, duration } => {
                         self.current_position = *position;
                         self.current_duration = *duration;
+                        remote::set_now_playing_time(*position, *duration);
                     }
                     PlayerEvent::TrackChanged {
                         song,
                         current_index,
-                        ..
+                        is_liked,
                     } => {
+                        self.current_is_liked = *is_liked;
                         // 从"无歌"进入"有歌"：侧栏动画弹出
                         let was_empty = self.current_song.is_none();
                         self.current_song = Some(song.clone());
+                        remote::set_now_playing_track(song);
                         if was_empty && !self.sidebar_visible {
                             self.set_sidebar_visible(true);
                         }
@@ -494,10 +724,37 @@ impl SimpleComponent for Window {
                     PlayerEvent::StateChanged(state) => {
                         self.current_is_playing =
                             *state == crate::player::messages::PlaybackState::Playing;
+                        remote::set_now_playing_state(self.current_is_playing);
+                    }
+                    PlayerEvent::VolumeChanged(volume) => {
+                        self.current_volume = *volume;
+                    }
+                    PlayerEvent::LikeChanged { song_id, liked } => {
+                        if self.current_song.as_ref().is_some_and(|s| s.id == *song_id) {
+                            self.current_is_liked = *liked;
+                        }
                     }
                     PlayerEvent::ShowToast(msg) => {
                         self.toast_overlay.add_toast(adw::Toast::new(msg));
                     }
+                    PlayerEvent::RaiseWindow => {
+                        self.main_window.present();
+                    }
+                    PlayerEvent::OutputDevicesChanged(list) => {
+                        self.settings_dialog
+                            .emit(SettingsInput::OutputDevicesChanged(list.clone()));
+                    }
+                    PlayerEvent::QuitRequested => {
+                        sender.input(WindowMsg::Quit);
+                    }
+                    PlayerEvent::NowPlayingChanged(now_playing) => {
+                        self.mv_active = matches!(now_playing, NowPlaying::Mv(_));
+                    }
+                    PlayerEvent::MvControl(control) => {
+                        if let Some(playback) = self.mv_playback() {
+                            playback.control(*control);
+                        }
+                    }
                     PlayerEvent::SetQueue {
                         tracks,
                         playlist,
@@ -514,10 +771,10 @@ impl SimpleComponent for Window {
                     _ => {}
                 }
 
-                // 如果全屏歌词页打开，转发给它
+                // 如果全屏歌词页打开，转发给它（MV 的进度和状态除外）
                 if let Some(ref fl) = self.fullscreen_lyric {
                     match &player_event {
-                        PlayerEvent::TimeUpdated { position, duration } => {
+                        PlayerEvent::TimeUpdated { position, duration } if !self.mv_active => {
                             fl.emit(FullscreenLyricMsg::TimeUpdated {
                                 position: *position,
                                 duration: *duration,
@@ -526,11 +783,17 @@ impl SimpleComponent for Window {
                         PlayerEvent::TrackChanged { song, .. } => {
                             fl.emit(FullscreenLyricMsg::LoadTrack(song.clone()));
                         }
-                        PlayerEvent::StateChanged(state) => {
+                        PlayerEvent::StateChanged(state) if !self.mv_active => {
                             fl.emit(FullscreenLyricMsg::UpdatePlayback(
                                 *state == crate::player::messages::PlaybackState::Playing,
                             ));
                         }
+                        PlayerEvent::LikeChanged { liked, .. } => {
+                            fl.emit(FullscreenLyricMsg::SetLiked(*liked));
+                        }
+                        PlayerEvent::Spectrum(bands) => {
+                            fl.emit(FullscreenLyricMsg::Spectrum(bands.clone()));
+                        }
                         _ => {}
                     }
                 }
@@ -540,6 +803,56 @@ impl SimpleComponent for Window {
                     log::error!("Cannot send command to player: {}", e);
                 }
             }
+            WindowMsg::Remote(cmd) => match cmd {
+                RemoteCommand::TogglePlayPause => {
+                    sender.input(WindowMsg::PlayerCommandReceived(
+                        PlayerCommand::TogglePlayPause,
+                    ));
+                }
+                RemoteCommand::Next => {
+                    sender.input(WindowMsg::PlayerCommandReceived(PlayerCommand::Next));
+                }
+                RemoteCommand::Previous => {
+                    sender.input(WindowMsg::PlayerCommandReceived(PlayerCommand::Previous));
+                }
+                RemoteCommand::PlayPlaylist(id) => {
+                    sender.input(WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                        source: PlaySource::ById(PlaylistType::Playlist(id)),
+                        start_index: 0,
+                    }));
+                }
+                RemoteCommand::PlaySong(id) => {
+                    let sender_clone = sender.clone();
+                    gtk::glib::MainContext::default().spawn_local(async move {
+                        match get_song_detail(vec![id]).await {
+                            Ok(songs) if !songs.is_empty() => {
+                                sender_clone.input(WindowMsg::PlayerCommandReceived(
+                                    PlayerCommand::Play {
+                                        source: PlaySource::DirectTracks(Arc::new(songs)),
+                                        start_index: 0,
+                                    },
+                                ));
+                            }
+                            Ok(_) => log::error!("歌曲 {id} 不存在"),
+                            Err(e) => log::error!("获取歌曲详情失败: {e}"),
+                        }
+                    });
+                }
+                RemoteCommand::Open(route) => {
+                    self.main_window.present();
+                    sender.input(WindowMsg::NavigateTo(route));
+                }
+            },
+            WindowMsg::OpenShareLink(text) => {
+                let sender_clone = sender.clone();
+                relm4::spawn(async move {
+                    match share::resolve_share_text(&text).await {
+                        Some(target) => sender_clone.input(WindowMsg::Remote(target.into())),
+                        None => sender_clone
+                            .input(WindowMsg::ShowToast("无法识别的分享链接".to_string())),
+                    }
+                });
+            }
             WindowMsg::OpenSettings => {
                 self.settings_dialog
                     .widget()
@@ -548,6 +861,19 @@ impl SimpleComponent for Window {
             WindowMsg::SettingEventReceived(output) => match output {
                 SettingsOutput::UserCookieChanged(_) => {}
                 SettingsOutput::SaveCookie => {}
+                SettingsOutput::OutputDeviceSelected(name) => {
+                    sender.input(WindowMsg::PlayerCommandReceived(
+                        PlayerCommand::SetOutputDevice(name),
+                    ));
+                }
+                SettingsOutput::AudioEffectsChanged(effects) => {
+                    if let Err(e) = self
+                        .player_cmd_tx
+                        .send(PlayerCommand::SetAudioEffects(effects))
+                    {
+                        log::error!("发送音效设置失败: {}", e);
+                    }
+                }
             },
             WindowMsg::LoadUserInfo => {
                 let sender_clone = sender.clone();
@@ -558,6 +884,10 @@ impl SimpleComponent for Window {
                 });
             }
             WindowMsg::UserInfoLoaded(user_info) => {
+                // 各种登录方式最终都走到这里：账号变了就清掉上个账号缓存的个人数据（收藏、推荐等）
+                if self.user_info.as_ref().map(|u| u.id) != Some(user_info.id) {
+                    cache::clear();
+                }
                 let new_arc = Arc::new(user_info);
                 self.user_info = Some(new_arc.clone());
                 self.user_info.as_ref().unwrap().save_to_disk();
@@ -584,6 +914,22 @@ impl SimpleComponent for Window {
                 dialog.widget().present(Some(&self.main_window));
                 self.collect_dialog = Some(dialog);
             }
+            WindowMsg::FollowedArtistsChanged => {
+                self.collection_ctrl.emit(CollectionMsg::LoadUserSubArtists);
+            }
+            WindowMsg::UserPlaylistsChanged => {
+                self.collection_ctrl.emit(CollectionMsg::LoadUserPlaylist);
+            }
+            WindowMsg::PlaylistDeleted(name) => {
+                self.toast_overlay
+                    .add_toast(adw::Toast::new(&format!("已删除歌单「{}」", name)));
+                self.collection_ctrl.emit(CollectionMsg::LoadUserPlaylist);
+                if self.history.is_empty() {
+                    sender.input(WindowMsg::NavigateTo(AppRoute::Collection));
+                } else {
+                    sender.input(WindowMsg::GoBack);
+                }
+            }
             WindowMsg::ShowToast(msg) => {
                 self.toast_overlay.add_toast(adw::Toast::new(&msg));
             }
@@ -606,9 +952,85 @@ impl SimpleComponent for Window {
                 let target = !self.sidebar_visible;
                 self.set_sidebar_visible(target);
             }
+            WindowMsg::TogglePlayPause => {
+                if let Some(mv) = self.active_mv_page() {
+                    mv.emit(MvPlayerMsg::TogglePlay);
+                } else if let Err(e) = self.player_cmd_tx.send(PlayerCommand::TogglePlayPause) {
+                    log::error!("Cannot send play/pause command: {}", e);
+                }
+            }
+            WindowMsg::SeekBy(delta_ms) => {
+                if let Some(mv) = self.active_mv_page() {
+                    mv.emit(MvPlayerMsg::SeekBy(delta_ms));
+                    return;
+                }
+                if self.current_song.is_none() || self.current_duration == 0 {
+                    return;
+                }
+                let target = (self.current_position as i64 + delta_ms)
+                    .clamp(0, self.current_duration as i64) as u64;
+                self.current_position = target;
+                if let Err(e) = self.player_cmd_tx.send(PlayerCommand::Seek(target)) {
+                    log::error!("Cannot send seek command: {}", e);
+                }
+            }
+            WindowMsg::ChangeVolume(delta) => {
+                if let Some(mv) = self.active_mv_page() {
+                    mv.emit(MvPlayerMsg::ChangeVolume(delta));
+                    return;
+                }
+                let volume = (self.current_volume + delta).clamp(0.0, 1.0);
+                if let Err(e) = self.player_cmd_tx.send(PlayerCommand::SetVolume(volume)) {
+                    log::error!("Cannot send volume command: {}", e);
+                }
+            }
+            WindowMsg::ToggleLikeCurrent => {
+                let Some(song_id) = self.current_song.as_ref().map(|s| s.id) else {
+                    return;
+                };
+                let liked = !self.current_is_liked;
+                if let Err(e) = self
+                    .player_cmd_tx
+                    .send(PlayerCommand::LikeSong { song_id, liked })
+                {
+                    log::error!("Cannot send like command: {}", e);
+                }
+            }
+            WindowMsg::FocusSearch => {
+                if self.current_route == AppRoute::Search {
+                    self.header.emit(HeaderMsg::FocusSearch);
+                } else {
+                    sender.input(WindowMsg::NavigateTo(AppRoute::Search));
+                }
+            }
+            WindowMsg::ShowShortcuts => {
+                shortcuts::build_shortcuts_window(&self.main_window).present();
+            }
+            WindowMsg::Quit => {
+                self.db.lock().unwrap().save_session(&self.session);
+                // 等播放线程停掉管线再退出，避免 GStreamer 在进程退出时仍在输出。
+                // 在主循环里异步等待回复，界面不会卡住
+                let (done_tx, done_rx) = flume::bounded(1);
+                let sent = self
+                    .player_cmd_tx
+                    .send(PlayerCommand::Shutdown { done: done_tx })
+                    .is_ok();
+                gtk::glib::spawn_future_local(async move {
+                    if sent {
+                        let timeout = gtk::glib::timeout_future(SHUTDOWN_TIMEOUT);
+                        let done = futures::future::select(done_rx.into_recv_async(), timeout);
+                        if !matches!(done.await, futures::future::Either::Left((Ok(()), _))) {
+                            log::warn!("播放器未能及时停止，直接退出");
+                        }
+                    }
+                    relm4::main_adw_application().quit();
+                });
+            }
 
             WindowMsg::ToggleFullscreen => {
-                if self.fullscreen_lyric.is_some() {
+                if let Some(mv) = self.active_mv_page() {
+                    mv.emit(MvPlayerMsg::ToggleFullscreen);
+                } else if self.fullscreen_lyric.is_some() {
                     self.close_fullscreen_lyric(&sender);
                 } else if self.current_song.is_none() {
                     self.toast_overlay
@@ -622,6 +1044,35 @@ impl SimpleComponent for Window {
                 self.finish_fullscreen_cleanup();
             }
 
+            WindowMsg::OpenMvPip => {
+                let Some(playback) = self.mv_pip_pending.take() else {
+                    return;
+                };
+                if let Some(old) = self.mv_pip.take() {
+                    old.widget().close();
+                }
+                let pip = MvPipWindow::builder()
+                    .launch(playback)
+                    .forward(sender.input_sender(), WindowMsg::MvPipEvent);
+                pip.widget().set_transient_for(Some(&self.main_window));
+                pip.widget().present();
+                self.mv_pip = Some(pip);
+
+                // 离开 MV 页继续浏览，视频在画中画里播放
+                if self.history.is_empty() {
+                    sender.input(WindowMsg::NavigateTo(AppRoute::Home));
+                } else {
+                    sender.input(WindowMsg::GoBack);
+                }
+            }
+            WindowMsg::MvPipEvent(MvPipOutput::Restore(id)) => {
+                sender.input(WindowMsg::NavigateTo(AppRoute::Mv(id)));
+            }
+            WindowMsg::MvPipEvent(MvPipOutput::Closed) => {
+                // 释放管线，播放器随之恢复音乐；回到 MV 页时画中画已被接管
+                self.mv_pip = None;
+            }
+
             WindowMsg::FullscreenLyricEvent(output) => match output {
                 FullscreenLyricOutput::Close => {
                     self.close_fullscreen_lyric(&sender);
@@ -713,7 +1164,18 @@ impl Window {
                             },
                             start_index,
                         }),
+                        PlaylistDetailOutput::PlayIntelligence { playlist_id, seed } => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::Intelligence { playlist_id, seed },
+                                start_index: 0,
+                            })
+                        }
                         PlaylistDetailOutput::ShowToast(text) => WindowMsg::ShowToast(text),
+                        PlaylistDetailOutput::Navigate(route) => WindowMsg::NavigateTo(route),
+                        PlaylistDetailOutput::PlaylistsChanged => WindowMsg::UserPlaylistsChanged,
+                        PlaylistDetailOutput::PlaylistDeleted(name) => {
+                            WindowMsg::PlaylistDeleted(name)
+                        }
                     });
 
                 self.detail_container.append(detail.widget());
@@ -725,9 +1187,9 @@ impl Window {
                     self.detail_container.remove(&child);
                 }
 
-                let detail = ArtistPage::builder().launch(*id).forward(
-                    sender.input_sender(),
-                    |msg| match msg {
+                let detail = ArtistPage::builder()
+                    .launch((*id, self.db.clone()))
+                    .forward(sender.input_sender(), |msg| match msg {
                         ArtistPageOutput::PlayQueue {
                             artist_id,
                             artist_name,
@@ -742,24 +1204,25 @@ impl Window {
                             start_index: start_index,
                         }),
                         ArtistPageOutput::Navigate(app_route) => WindowMsg::NavigateTo(app_route),
-                    },
-                );
+                        ArtistPageOutput::FollowChanged => WindowMsg::FollowedArtistsChanged,
+                        ArtistPageOutput::ShowToast(text) => WindowMsg::ShowToast(text),
+                    });
 
                 self.detail_container.append(detail.widget());
                 self.content_stack.set_visible_child_name("detail");
                 self.detail_ctrl = Some(DetailCtrl::Artist(detail));
             }
-            AppRoute::Comments(song_id) => {
-                eprintln!("Comments: {}", song_id);
+            AppRoute::Comments(resource, id) => {
                 while let Some(child) = self.detail_container.first_child() {
                     self.detail_container.remove(&child);
                 }
 
-                let detail = CommentsPage::builder()
-                    .launch(*song_id)
-                    .forward(sender.input_sender(), |_msg| {
-                        WindowMsg::ShowToast(String::new())
-                    });
+                let detail = CommentsPage::builder().launch((*resource, *id)).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        CommentsOutput::Navigate(route) => WindowMsg::NavigateTo(route),
+                    },
+                );
 
                 self.detail_container.append(detail.widget());
                 self.content_stack.set_visible_child_name("detail");
@@ -770,26 +1233,114 @@ impl Window {
                     self.detail_container.remove(&child);
                 }
 
-                // 暂停正在播放的音乐（进入 MV 页时）
-                if self.current_is_playing {
-                    self.should_resume_music = true;
-                    if let Err(e) = self.player_cmd_tx.send(PlayerCommand::Pause) {
-                        log::error!("Cannot pause music: {}", e);
+                // 沿用画中画或上一个 MV 页的管线：同一个 MV 不中断，
+                // 换 MV 时也不会先结束会话、让音乐短暂响起
+                let playback = match (self.mv_pip.take(), &self.detail_ctrl) {
+                    (Some(pip), _) => {
+                        let playback = pip.model().playback();
+                        pip.widget().close();
+                        playback
                     }
-                }
+                    (None, Some(DetailCtrl::Mv(page))) => page.model().playback(),
+                    (None, _) => MvPlayback::new(self.player_cmd_tx.clone()),
+                };
 
-                let detail =
-                    MvPlayerPage::builder()
-                        .launch(*id)
-                        .forward(sender.input_sender(), |msg| match msg {
-                            MvPlayerOutput::Navigate(route) => WindowMsg::NavigateTo(route),
-                            MvPlayerOutput::ShowToast(text) => WindowMsg::ShowToast(text),
-                        });
+                let pip_pending = self.mv_pip_pending.clone();
+                let detail = MvPlayerPage::builder().launch((*id, playback)).forward(
+                    sender.input_sender(),
+                    move |msg| match msg {
+                        MvPlayerOutput::Navigate(route) => WindowMsg::NavigateTo(route),
+                        MvPlayerOutput::ShowToast(text) => WindowMsg::ShowToast(text),
+                        MvPlayerOutput::OpenPip(playback) => {
+                            pip_pending.replace(Some(playback));
+                            WindowMsg::OpenMvPip
+                        }
+                    },
+                );
 
                 self.detail_container.append(detail.widget());
                 self.content_stack.set_visible_child_name("detail");
                 self.detail_ctrl = Some(DetailCtrl::Mv(detail));
             }
+            AppRoute::User(id) => {
+                while let Some(child) = self.detail_container.first_child() {
+                    self.detail_container.remove(&child);
+                }
+
+                let detail = UserPage::builder().launch(*id).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        UserPageOutput::OpenPlaylistDetail(playlist_type) => {
+                            WindowMsg::NavigateTo(AppRoute::PlaylistDetail(playlist_type))
+                        }
+                        UserPageOutput::Playlist(playlist_type) => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::ById(playlist_type),
+                                start_index: 0,
+                            })
+                        }
+                        UserPageOutput::PlayTracks(songs, start_index) => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::DirectTracks(songs),
+                                start_index,
+                            })
+                        }
+                    },
+                );
+
+                self.detail_container.append(detail.widget());
+                self.content_stack.set_visible_child_name("detail");
+                self.detail_ctrl = Some(DetailCtrl::User(detail));
+            }
+            AppRoute::ExploreMore(section) => {
+                while let Some(child) = self.detail_container.first_child() {
+                    self.detail_container.remove(&child);
+                }
+
+                let detail = ExploreMorePage::builder().launch(*section).forward(
+                    sender.input_sender(),
+                    |msg| match msg {
+                        ExploreMoreOutput::Navigate(route) => WindowMsg::NavigateTo(route),
+                        ExploreMoreOutput::PlayTracks(songs, start_index) => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::DirectTracks(Arc::new(songs)),
+                                start_index,
+                            })
+                        }
+                        ExploreMoreOutput::PlayPlaylist(playlist_type) => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::ById(playlist_type),
+                                start_index: 0,
+                            })
+                        }
+                    },
+                );
+
+                self.detail_container.append(detail.widget());
+                self.content_stack.set_visible_child_name("detail");
+                self.detail_ctrl = Some(DetailCtrl::ExploreMore(detail));
+            }
+            AppRoute::YearReport => {
+                while let Some(child) = self.detail_container.first_child() {
+                    self.detail_container.remove(&child);
+                }
+
+                let user_id = self.user_info.as_ref().map(|u| u.id).unwrap_or(0);
+                let detail = YearReportPage::builder()
+                    .launch((user_id, self.db.clone()))
+                    .forward(sender.input_sender(), |msg| match msg {
+                        YearReportOutput::PlayTracks(songs, start_index) => {
+                            WindowMsg::PlayerCommandReceived(PlayerCommand::Play {
+                                source: PlaySource::DirectTracks(songs),
+                                start_index,
+                            })
+                        }
+                    });
+
+                self.detail_container.append(detail.widget());
+                self.content_stack.set_visible_child_name("detail");
+                self.detail_ctrl = Some(DetailCtrl::YearReport(detail));
+            }
         }
 
         let can_go_back = !self.history.is_empty();
@@ -814,6 +1365,7 @@ impl Window {
             fl.emit(FullscreenLyricMsg::LoadTrack(song.clone()));
         }
         fl.emit(FullscreenLyricMsg::UpdatePlayback(self.current_is_playing));
+        fl.emit(FullscreenLyricMsg::SetLiked(self.current_is_liked));
         fl.emit(FullscreenLyricMsg::TimeUpdated {
             position: self.current_position,
             duration: self.current_duration,
@@ -863,6 +1415,25 @@ impl Window {
         self.fullscreen_fade = None;
     }
 
+    /// 当前在 MV 页时返回它的控制器，播放相关快捷键改为控制视频
+    fn active_mv_page(&self) -> Option<&Controller<MvPlayerPage>> {
+        match &self.detail_ctrl {
+            Some(DetailCtrl::Mv(mv)) if matches!(self.current_route, AppRoute::Mv(_)) => Some(mv),
+            _ => None,
+        }
+    }
+
+    /// 正在使用的 MV 管线：MV 页或画中画
+    fn mv_playback(&self) -> Option<Rc<MvPlayback>> {
+        if let Some(pip) = &self.mv_pip {
+            return Some(pip.model().playback());
+        }
+        match &self.detail_ctrl {
+            Some(DetailCtrl::Mv(page)) => Some(page.model().playback()),
+            _ => None,
+        }
+    }
+
     /// 侧栏显示/隐藏（OverlaySplitView 原生 show_sidebar 动画：侧栏与内容同一平面内滑入/滑出）
     fn set_sidebar_visible(&mut self, visible: bool) {
         self.sidebar_visible = visible;
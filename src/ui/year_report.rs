@@ -0,0 +1,595 @@
+//! 年度报告：按年汇总本地播放历史（听歌时长、最常听的歌曲 / 歌手 / 月份），
+//! 并附上网易云「所有时间」的听歌排行
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use chrono::{Datelike, Local, TimeZone};
+use relm4::factory::FactoryVecDeque;
+use relm4::gtk::prelude::*;
+use relm4::prelude::*;
+
+use crate::api::{Artist, PlayRecord, RecordHidden, RecordRange, Song, get_user_record};
+use crate::db::{Db, PlayHistoryEntry};
+use crate::ui::components::play_ranking::{RankingRow, RankingRowOutput};
+
+/// 听满这么多秒才计入「最常听」，快速切过的歌不算
+const MIN_COUNTED_SECS: u64 = 30;
+const TOP_COUNT: usize = 10;
+
+#[derive(Debug, Default)]
+struct YearSummary {
+    total_secs: u64,
+    play_count: usize,
+    top_songs: Vec<(Song, usize)>,
+    top_artists: Vec<(String, usize)>,
+    /// 听歌时长最长的月份（1–12）
+    top_month: Option<u32>,
+}
+
+/// 次数相同的按第一次出现的先后排
+fn count_by<K: Eq + Hash>(keys: impl Iterator<Item = K>) -> Vec<(K, usize)> {
+    // 值为（第一次出现的位置, 次数）
+    let mut counts: HashMap<K, (usize, usize)> = HashMap::new();
+    for (index, key) in keys.enumerate() {
+        counts.entry(key).or_insert((index, 0)).1 += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.1.cmp(&a.1.1).then(a.1.0.cmp(&b.1.0)));
+    counts
+        .into_iter()
+        .take(TOP_COUNT)
+        .map(|(key, (_, count))| (key, count))
+        .collect()
+}
+
+fn summarize(plays: &[PlayHistoryEntry]) -> YearSummary {
+    let counted: Vec<&PlayHistoryEntry> = plays
+        .iter()
+        .filter(|p| p.played_secs >= MIN_COUNTED_SECS)
+        .collect();
+
+    let top_songs = count_by(counted.iter().map(|p| p.song.id))
+        .into_iter()
+        .filter_map(|(id, count)| {
+            let play = counted.iter().find(|p| p.song.id == id)?;
+            Some((play.song.clone(), count))
+        })
+        .collect();
+    // 按 id 计数，免得同名歌手被合并；未收录的歌手 id 为 0，只能靠名字区分
+    let artists: Vec<&Artist> = counted.iter().flat_map(|p| p.song.artists.iter()).collect();
+    let top_artists = count_by(
+        artists
+            .iter()
+            .map(|a| (a.id, if a.id == 0 { a.name.as_str() } else { "" })),
+    )
+    .into_iter()
+    .filter_map(|((id, name), count)| {
+        let artist = artists
+            .iter()
+            .find(|a| a.id == id && (id != 0 || a.name == name))?;
+        Some((artist.name.clone(), count))
+    })
+    .collect();
+
+    let mut month_secs = [0u64; 12];
+    for play in plays {
+        if let Some(time) = Local.timestamp_opt(play.played_at, 0).single() {
+            month_secs[time.month0() as usize] += play.played_secs;
+        }
+    }
+    let top_month = month_secs
+        .iter()
+        .enumerate()
+        .filter(|(_, secs)| **secs > 0)
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+        .map(|(month0, _)| month0 as u32 + 1);
+
+    YearSummary {
+        total_secs: plays.iter().map(|p| p.played_secs).sum(),
+        play_count: counted.len(),
+        top_songs,
+        top_artists,
+        top_month,
+    }
+}
+
+/// 本地时间下某一年的 `[开始, 结束)`（Unix 秒）
+fn year_bounds(year: i32) -> (i64, i64) {
+    let start = |year: i32| {
+        Local
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .earliest()
+            .map(|t| t.timestamp())
+            .unwrap_or_default()
+    };
+    (start(year), start(year + 1))
+}
+
+/// 条形长度按第一名的比例
+fn ranking_records(top_songs: &[(Song, usize)]) -> Vec<PlayRecord> {
+    let max = top_songs.first().map(|(_, c)| *c).unwrap_or(1).max(1);
+    top_songs
+        .iter()
+        .map(|(song, count)| PlayRecord {
+            song: song.clone(),
+            play_count: *count as u64,
+            score: (*count * 100 / max) as u64,
+        })
+        .collect()
+}
+
+fn artist_row(rank: usize, name: &str, count: usize, max: usize) -> gtk::Box {
+    let row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    row.set_margin_all(8);
+
+    let rank_label = gtk::Label::new(Some(&(rank + 1).to_string()));
+    rank_label.set_width_chars(3);
+    rank_label.add_css_class("numeric");
+    rank_label.add_css_class(if rank < 3 { "heading" } else { "dim-label" });
+
+    let name_label = gtk::Label::new(Some(name));
+    name_label.set_hexpand(true);
+    name_label.set_halign(gtk::Align::Start);
+    name_label.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+    let bar = gtk::LevelBar::for_interval(0.0, max.max(1) as f64);
+    bar.set_value(count as f64);
+    bar.set_valign(gtk::Align::Center);
+    bar.set_width_request(160);
+
+    let count_label = gtk::Label::new(Some(&format!("{count} 次")));
+    count_label.set_width_chars(6);
+    count_label.set_xalign(1.0);
+    count_label.add_css_class("caption");
+    count_label.add_css_class("dim-label");
+
+    row.append(&rank_label);
+    row.append(&name_label);
+    row.append(&bar);
+    row.append(&count_label);
+    row
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CloudState {
+    Loading,
+    Loaded,
+    Hidden,
+    Failed,
+}
+
+pub struct YearReportPage {
+    db: Arc<Mutex<Db>>,
+    year: i32,
+    summary: YearSummary,
+    local_songs: Arc<Vec<Song>>,
+    local_rows: FactoryVecDeque<RankingRow>,
+    artist_list: gtk::ListBox,
+    cloud_state: CloudState,
+    cloud_songs: Arc<Vec<Song>>,
+    cloud_rows: FactoryVecDeque<RankingRow>,
+}
+
+#[derive(Debug)]
+pub enum YearReportMsg {
+    PrevYear,
+    NextYear,
+    PlayLocal(usize),
+    PlayCloud(usize),
+}
+
+#[derive(Debug)]
+pub enum YearReportCmd {
+    CloudLoaded(Vec<PlayRecord>),
+    CloudHidden,
+    CloudFailed,
+}
+
+#[derive(Debug)]
+pub enum YearReportOutput {
+    PlayTracks(Arc<Vec<Song>>, usize),
+}
+
+#[relm4::component(pub)]
+impl Component for YearReportPage {
+    type Init = (u64, Arc<Mutex<Db>>);
+    type Input = YearReportMsg;
+    type Output = YearReportOutput;
+    type CommandOutput = YearReportCmd;
+
+    view! {
+        #[root]
+        gtk::ScrolledWindow {
+            set_vexpand: true,
+            set_hscrollbar_policy: gtk::PolicyType::Never,
+
+            gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 24,
+                set_margin_all: 32,
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 8,
+
+                    gtk::Button {
+                        set_icon_name: "go-previous-symbolic",
+                        set_valign: gtk::Align::Center,
+                        add_css_class: "circular",
+                        add_css_class: "flat",
+                        set_tooltip_text: Some("上一年"),
+                        connect_clicked => YearReportMsg::PrevYear,
+                    },
+                    gtk::Label {
+                        #[watch]
+                        set_label: &format!("{} 年度报告", model.year),
+                        add_css_class: "title-1",
+                    },
+                    gtk::Button {
+                        set_icon_name: "go-next-symbolic",
+                        set_valign: gtk::Align::Center,
+                        add_css_class: "circular",
+                        add_css_class: "flat",
+                        set_tooltip_text: Some("下一年"),
+                        #[watch]
+                        set_sensitive: model.year < Local::now().year(),
+                        connect_clicked => YearReportMsg::NextYear,
+                    },
+                },
+
+                gtk::Label {
+                    #[watch]
+                    set_visible: model.summary.total_secs == 0,
+                    set_label: "这一年还没有在本机听过歌",
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "dim-label",
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Vertical,
+                    set_spacing: 24,
+                    #[watch]
+                    set_visible: model.summary.total_secs > 0,
+
+                    // 概览
+                    gtk::Box {
+                        set_orientation: gtk::Orientation::Horizontal,
+                        set_spacing: 12,
+                        set_homogeneous: true,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 4,
+                            add_css_class: "card",
+
+                            gtk::Label {
+                                #[watch]
+                                set_label: &format!("{:.1}", model.summary.total_secs as f64 / 3600.0),
+                                set_margin_top: 16,
+                                add_css_class: "title-2",
+                            },
+                            gtk::Label {
+                                set_label: "听歌小时",
+                                set_margin_bottom: 16,
+                                add_css_class: "dim-label",
+                            },
+                        },
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 4,
+                            add_css_class: "card",
+
+                            gtk::Label {
+                                #[watch]
+                                set_label: &model.summary.play_count.to_string(),
+                                set_margin_top: 16,
+                                add_css_class: "title-2",
+                            },
+                            gtk::Label {
+                                set_label: "播放次数",
+                                set_margin_bottom: 16,
+                                add_css_class: "dim-label",
+                            },
+                        },
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 4,
+                            add_css_class: "card",
+
+                            gtk::Label {
+                                #[watch]
+                                set_label: &model.summary.top_month
+                                    .map(|m| format!("{m} 月"))
+                                    .unwrap_or_else(|| "--".to_string()),
+                                set_margin_top: 16,
+                                add_css_class: "title-2",
+                            },
+                            gtk::Label {
+                                set_label: "听得最多的月份",
+                                set_margin_bottom: 16,
+                                add_css_class: "dim-label",
+                            },
+                        },
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 4,
+                            add_css_class: "card",
+
+                            gtk::Label {
+                                #[watch]
+                                set_label: model.summary.top_artists
+                                    .first()
+                                    .map(|(name, _)| name.as_str())
+                                    .unwrap_or("--"),
+                                set_margin_top: 16,
+                                set_margin_start: 8,
+                                set_margin_end: 8,
+                                set_ellipsize: gtk::pango::EllipsizeMode::End,
+                                add_css_class: "title-2",
+                            },
+                            gtk::Label {
+                                set_label: "最爱歌手",
+                                set_margin_bottom: 16,
+                                add_css_class: "dim-label",
+                            },
+                        },
+                    },
+
+                    gtk::Label {
+                        set_label: "最常听的歌曲",
+                        set_halign: gtk::Align::Start,
+                        add_css_class: "title-3",
+                    },
+                    #[local_ref]
+                    local_list -> gtk::ListBox {
+                        set_selection_mode: gtk::SelectionMode::None,
+                        add_css_class: "boxed-list",
+                    },
+
+                    gtk::Label {
+                        set_label: "最常听的歌手",
+                        set_halign: gtk::Align::Start,
+                        add_css_class: "title-3",
+                    },
+                    #[local_ref]
+                    artist_list -> gtk::ListBox {
+                        set_selection_mode: gtk::SelectionMode::None,
+                        add_css_class: "boxed-list",
+                    },
+                },
+
+                gtk::Label {
+                    set_label: "网易云累计排行",
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "title-3",
+                },
+                gtk::Label {
+                    #[watch]
+                    set_visible: model.cloud_state != CloudState::Loaded,
+                    #[watch]
+                    set_label: match model.cloud_state {
+                        CloudState::Loading => "正在加载…",
+                        CloudState::Hidden => "听歌排行未公开",
+                        _ => "听歌排行加载失败",
+                    },
+                    set_halign: gtk::Align::Start,
+                    add_css_class: "dim-label",
+                },
+                #[local_ref]
+                cloud_list -> gtk::ListBox {
+                    #[watch]
+                    set_visible: model.cloud_state == CloudState::Loaded,
+                    set_selection_mode: gtk::SelectionMode::None,
+                    add_css_class: "boxed-list",
+                },
+            }
+        }
+    }
+
+    fn init(
+        (user_id, db): Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let local_rows = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::new())
+            .forward(sender.input_sender(), |RankingRowOutput::Play(index)| {
+                YearReportMsg::PlayLocal(index)
+            });
+        let cloud_rows = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::new())
+            .forward(sender.input_sender(), |RankingRowOutput::Play(index)| {
+                YearReportMsg::PlayCloud(index)
+            });
+
+        let mut model = Self {
+            db,
+            year: Local::now().year(),
+            summary: YearSummary::default(),
+            local_songs: Arc::new(Vec::new()),
+            local_rows,
+            artist_list: gtk::ListBox::new(),
+            cloud_state: CloudState::Loading,
+            cloud_songs: Arc::new(Vec::new()),
+            cloud_rows,
+        };
+        model.load_local();
+
+        let local_list = model.local_rows.widget();
+        let artist_list = &model.artist_list;
+        let cloud_list = model.cloud_rows.widget();
+        let widgets = view_output!();
+
+        sender.oneshot_command(async move {
+            match get_user_record(user_id, RecordRange::AllTime).await {
+                Ok(mut records) => {
+                    records.truncate(TOP_COUNT);
+                    YearReportCmd::CloudLoaded(records)
+                }
+                Err(e) if e.downcast_ref::<RecordHidden>().is_some() => YearReportCmd::CloudHidden,
+                Err(e) => {
+                    log::warn!("加载听歌排行失败: {e}");
+                    YearReportCmd::CloudFailed
+                }
+            }
+        });
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+        match message {
+            YearReportMsg::PrevYear => {
+                self.year -= 1;
+                self.load_local();
+            }
+            YearReportMsg::NextYear => {
+                if self.year < Local::now().year() {
+                    self.year += 1;
+                    self.load_local();
+                }
+            }
+            YearReportMsg::PlayLocal(index) => {
+                sender
+                    .output(YearReportOutput::PlayTracks(
+                        self.local_songs.clone(),
+                        index,
+                    ))
+                    .ok();
+            }
+            YearReportMsg::PlayCloud(index) => {
+                sender
+                    .output(YearReportOutput::PlayTracks(
+                        self.cloud_songs.clone(),
+                        index,
+                    ))
+                    .ok();
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        _sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            YearReportCmd::CloudLoaded(records) => {
+                self.cloud_state = CloudState::Loaded;
+                self.cloud_songs = Arc::new(records.iter().map(|r| r.song.clone()).collect());
+                let mut guard = self.cloud_rows.guard();
+                for (rank, record) in records.into_iter().enumerate() {
+                    guard.push_back((rank, record));
+                }
+            }
+            YearReportCmd::CloudHidden => self.cloud_state = CloudState::Hidden,
+            YearReportCmd::CloudFailed => self.cloud_state = CloudState::Failed,
+        }
+    }
+}
+
+impl YearReportPage {
+    fn load_local(&mut self) {
+        let (from, to) = year_bounds(self.year);
+        let plays = self.db.lock().unwrap().plays_between(from, to);
+        self.summary = summarize(&plays);
+
+        let records = ranking_records(&self.summary.top_songs);
+        self.local_songs = Arc::new(records.iter().map(|r| r.song.clone()).collect());
+        let mut guard = self.local_rows.guard();
+        guard.clear();
+        for (rank, record) in records.into_iter().enumerate() {
+            guard.push_back((rank, record));
+        }
+        drop(guard);
+
+        self.artist_list.remove_all();
+        let max = self
+            .summary
+            .top_artists
+            .first()
+            .map(|(_, c)| *c)
+            .unwrap_or(1);
+        for (rank, (name, count)) in self.summary.top_artists.iter().enumerate() {
+            self.artist_list
+                .append(&artist_row(rank, name, *count, max));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(id: u64, artist: (u64, &str), played_at: i64, played_secs: u64) -> PlayHistoryEntry {
+        PlayHistoryEntry {
+            song: Song {
+                id,
+                name: format!("歌曲 {id}"),
+                artists: vec![Artist {
+                    id: artist.0,
+                    name: artist.1.into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            played_at,
+            played_secs,
+        }
+    }
+
+    /// 取月中，避免时区把时间推到相邻月份
+    fn mid_month(month: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(2025, month, 15, 12, 0, 0)
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn summary_counts_full_listens_and_picks_the_busiest_month() {
+        let plays = vec![
+            play(1, (11, "甲"), mid_month(3), 200),
+            play(2, (12, "乙"), mid_month(3), 10),
+            play(2, (12, "乙"), mid_month(5), 180),
+            play(2, (12, "乙"), mid_month(5), 180),
+            play(3, (11, "甲"), mid_month(5), 240),
+        ];
+        let summary = summarize(&plays);
+
+        assert_eq!(summary.total_secs, 810);
+        // 10 秒的那次不计入
+        assert_eq!(summary.play_count, 4);
+        let songs: Vec<_> = summary.top_songs.iter().map(|(s, c)| (s.id, *c)).collect();
+        assert_eq!(songs, vec![(2, 2), (1, 1), (3, 1)]);
+        assert_eq!(
+            summary.top_artists,
+            vec![("甲".to_string(), 2), ("乙".to_string(), 2)]
+        );
+        assert_eq!(summary.top_month, Some(5));
+    }
+
+    #[test]
+    fn artists_with_the_same_name_are_counted_separately() {
+        let plays = vec![
+            play(1, (21, "同名"), mid_month(1), 200),
+            play(2, (21, "同名"), mid_month(1), 200),
+            play(3, (22, "同名"), mid_month(1), 200),
+        ];
+        assert_eq!(
+            summarize(&plays).top_artists,
+            vec![("同名".to_string(), 2), ("同名".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn empty_history_has_no_top_month() {
+        let summary = summarize(&[]);
+        assert_eq!(summary.play_count, 0);
+        assert!(summary.top_songs.is_empty());
+        assert_eq!(summary.top_month, None);
+    }
+}
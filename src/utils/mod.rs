@@ -4,5 +4,6 @@
 
 pub mod animate;
 pub mod lyric_parse;
+pub mod share;
 pub mod ttml;
 pub mod utils;
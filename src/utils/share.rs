@@ -0,0 +1,215 @@
+//! 网易云分享链接的解析与生成
+//!
+//! 支持 `music.163.com/song?id=…`（含 `/#/` 前缀）、移动端 `y.music.163.com/m/song?id=…`、
+//! `music.163.com/song/…` 路径形式，以及需要联网解析跳转地址的 `163cn.tv` 短链接。
+
+use anyhow::{Context, bail};
+use reqwest::Url;
+use reqwest::header::LOCATION;
+
+use crate::api::Song;
+use crate::ui::model::PlaylistType;
+use crate::ui::route::AppRoute;
+
+const SHARE_BASE: &str = "https://music.163.com";
+const SHORT_LINK_HOST: &str = "163cn.tv";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShareTarget {
+    Song(u64),
+    Playlist(u64),
+    Album(u64),
+    Artist(u64),
+    Mv(u64),
+    User(u64),
+}
+
+impl ShareTarget {
+    /// `kind` 为链接中的类型段（`song` / `playlist` / …）
+    pub fn from_kind(kind: &str, id: u64) -> Option<Self> {
+        let target = match kind {
+            "song" => ShareTarget::Song(id),
+            "playlist" => ShareTarget::Playlist(id),
+            "album" => ShareTarget::Album(id),
+            "artist" => ShareTarget::Artist(id),
+            "mv" => ShareTarget::Mv(id),
+            "user" => ShareTarget::User(id),
+            _ => return None,
+        };
+        Some(target)
+    }
+
+    fn kind(self) -> (&'static str, u64) {
+        match self {
+            ShareTarget::Song(id) => ("song", id),
+            ShareTarget::Playlist(id) => ("playlist", id),
+            ShareTarget::Album(id) => ("album", id),
+            ShareTarget::Artist(id) => ("artist", id),
+            ShareTarget::Mv(id) => ("mv", id),
+            ShareTarget::User(id) => ("user/home", id),
+        }
+    }
+
+    pub fn id(self) -> u64 {
+        self.kind().1
+    }
+
+    /// 对应的页面；单曲没有独立页面，返回 `None`
+    pub fn route(self) -> Option<AppRoute> {
+        match self {
+            ShareTarget::Song(_) => None,
+            ShareTarget::Playlist(id) => Some(AppRoute::PlaylistDetail(PlaylistType::Playlist(id))),
+            ShareTarget::Album(id) => Some(AppRoute::PlaylistDetail(PlaylistType::Album(id))),
+            ShareTarget::Artist(id) => Some(AppRoute::Artist(id)),
+            ShareTarget::Mv(id) => Some(AppRoute::Mv(id)),
+            ShareTarget::User(id) => Some(AppRoute::User(id)),
+        }
+    }
+
+    pub fn share_url(self) -> String {
+        let (kind, id) = self.kind();
+        format!("{SHARE_BASE}/{kind}?id={id}")
+    }
+}
+
+pub fn song_share_url(song: &Song) -> String {
+    ShareTarget::Song(song.id).share_url()
+}
+
+/// 日推等本地拼出来的歌单没有分享链接
+pub fn playlist_share_url(playlist_type: &PlaylistType) -> Option<String> {
+    match playlist_type {
+        PlaylistType::Playlist(id) => Some(ShareTarget::Playlist(*id).share_url()),
+        PlaylistType::Album(id) => Some(ShareTarget::Album(*id).share_url()),
+        PlaylistType::DailyRecommend | PlaylistType::DailyCategory { .. } => None,
+    }
+}
+
+/// 从分享文案中取出第一个链接，如
+/// `分享xxx的单曲《…》: https://163cn.tv/xxxx (来自@网易云音乐)`
+pub fn extract_url(text: &str) -> Option<&str> {
+    let start = text.find("https://").or_else(|| text.find("http://"))?;
+    let rest = &text[start..];
+    let end = rest
+        .find(|c: char| c.is_whitespace() || !c.is_ascii() || matches!(c, '(' | ')' | '"'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end])
+}
+
+pub fn is_short_link(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|u| u.host_str() == Some(SHORT_LINK_HOST))
+}
+
+/// 解析完整的分享链接（不含短链接）
+pub fn parse_share_url(url: &str) -> Option<ShareTarget> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    if host != "music.163.com" && !host.ends_with(".music.163.com") {
+        return None;
+    }
+    // 网页版的 `https://music.163.com/#/song?id=1`：真正的路径在 fragment 里
+    let url = match url.fragment() {
+        Some(fragment) if fragment.starts_with('/') => {
+            Url::parse(&format!("{SHARE_BASE}{fragment}")).ok()?
+        }
+        _ => url,
+    };
+
+    let mut segments = url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .skip_while(|s| *s == "m");
+    let kind = segments.next()?;
+    let id = url
+        .query_pairs()
+        .find(|(k, _)| k == "id")
+        .and_then(|(_, v)| v.parse::<u64>().ok())
+        .or_else(|| segments.find_map(|s| s.parse::<u64>().ok()))?;
+    ShareTarget::from_kind(kind, id)
+}
+
+/// 文本里是否含有可识别的分享链接（用于搜索框粘贴等场景的快速判断）
+pub fn looks_like_share_link(text: &str) -> bool {
+    extract_url(text).is_some_and(|url| is_short_link(url) || parse_share_url(url).is_some())
+}
+
+/// 不跟随重定向，取短链接的跳转地址
+pub async fn resolve_short_link(url: &str) -> anyhow::Result<String> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let response = client.get(url).send().await?;
+    let Some(location) = response.headers().get(LOCATION) else {
+        bail!("短链接没有跳转地址: {}", response.status());
+    };
+    let location = location.to_str().context("跳转地址不是合法字符串")?;
+    Ok(Url::parse(url)?.join(location)?.to_string())
+}
+
+/// 从任意文本（完整链接、短链接或整段分享文案）解析出分享目标
+pub async fn resolve_share_text(text: &str) -> Option<ShareTarget> {
+    let url = extract_url(text)?;
+    if !is_short_link(url) {
+        return parse_share_url(url);
+    }
+    match resolve_short_link(url).await {
+        Ok(resolved) => parse_share_url(&resolved),
+        Err(e) => {
+            log::error!("解析短链接失败: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn parse_share_urls() {
+        assert_eq!(
+            parse_share_url("https://music.163.com/song?id=186016&userid=1"),
+            Some(ShareTarget::Song(186016))
+        );
+        assert_eq!(
+            parse_share_url("https://music.163.com/#/playlist?id=3778678"),
+            Some(ShareTarget::Playlist(3778678))
+        );
+        assert_eq!(
+            parse_share_url("https://y.music.163.com/m/album?id=34720827&app_version=9.0"),
+            Some(ShareTarget::Album(34720827))
+        );
+        assert_eq!(
+            parse_share_url("http://music.163.com/mv/5436712/"),
+            Some(ShareTarget::Mv(5436712))
+        );
+        assert_eq!(parse_share_url("https://example.com/song?id=1"), None);
+        assert_eq!(parse_share_url("https://music.163.com/radio?id=1"), None);
+
+        let text = "分享歌手的单曲《晴天》: https://163cn.tv/abcd (来自@网易云音乐)";
+        assert_eq!(extract_url(text), Some("https://163cn.tv/abcd"));
+        assert!(looks_like_share_link(text));
+        assert!(!looks_like_share_link("晴天 周杰伦"));
+    }
+
+    #[tokio::test]
+    async fn resolve_short_link_against_stub_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let response = "HTTP/1.1 302 Found\r\n\
+                Location: https://y.music.163.com/m/song?id=186016&uct2=x\r\n\
+                Content-Length: 0\r\n\r\n";
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let resolved = resolve_short_link(&format!("http://{addr}/abcd"))
+            .await
+            .unwrap();
+        assert_eq!(parse_share_url(&resolved), Some(ShareTarget::Song(186016)));
+    }
+}